@@ -0,0 +1,153 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Configuration file, which defines a custom color theme.
+//!
+//! Each line of the file is empty, a comment starting with `#`, or a `key = value` pair. The
+//! `theme` key selects the bundled theme to start from, and the other keys are the names of
+//! highlights (e.g. `quasi_keyword`), whose values are a color and any number of styles, e.g.
+//! `ident = #ff8700 bold underline`.
+
+use std::path::{Path, PathBuf};
+
+use c2e::color::{Highlight, StyleSpec};
+use termcolor::Color;
+
+use crate::{fmt::ColorMap, theme::Theme};
+
+/// Color and style of a highlight, as defined on a line of the configuration file
+struct HighlightSpec {
+    highlight: Highlight,
+    /// Color to use instead of the theme's, if one is given
+    color: Option<Color>,
+    style: StyleSpec,
+}
+
+/// Settings read from the configuration file
+#[derive(Default)]
+pub struct Config {
+    /// Bundled theme to start from, unless one is given with `--theme`
+    pub theme: Option<Theme>,
+    highlights: Vec<HighlightSpec>,
+}
+
+impl Config {
+    /// Reads the configuration file at the given path, or at the default path if none is given.
+    /// A missing file at the default path is treated as an empty one.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_owned(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(src) => Self::parse(&src).map_err(|err| format!("{}: {err}", path.display())),
+            Err(err) if required || err.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("{}: {err}", path.display()))
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Parses the contents of a configuration file.
+    fn parse(src: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        for (i, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| format!("line {}: expected <key> = <value>", i + 1))?;
+            if key == "theme" {
+                let theme = value
+                    .parse()
+                    .map_err(|_| format!("line {}: unknown theme '{value}'", i + 1))?;
+                config.theme = Some(theme);
+                continue;
+            }
+            let highlight = key
+                .parse()
+                .ok()
+                .filter(|highlight| *highlight != Highlight::None)
+                .ok_or_else(|| format!("line {}: unknown key '{key}'", i + 1))?;
+            let mut spec = HighlightSpec {
+                highlight,
+                color: None,
+                style: StyleSpec::PLAIN,
+            };
+            for word in value.split_whitespace() {
+                match word {
+                    "bold" => spec.style.bold = true,
+                    "italic" => spec.style.italic = true,
+                    "underline" => spec.style.underline = true,
+                    _ if spec.color.is_none() => {
+                        let color = parse_color(word).ok_or_else(|| {
+                            format!("line {}: unknown color or style '{word}'", i + 1)
+                        })?;
+                        spec.color = Some(color);
+                    }
+                    _ => return Err(format!("line {}: more than one color for {key}", i + 1)),
+                }
+            }
+            config.highlights.push(spec);
+        }
+        Ok(config)
+    }
+
+    /// Returns the colors of the given theme with the highlights defined in this configuration
+    /// applied. Each highlight defined here gets exactly the styles given for it, and keeps the
+    /// theme's color if none is given.
+    pub fn colors(&self, theme: Theme) -> ColorMap {
+        let mut colors = theme.colors();
+        for spec in &self.highlights {
+            if let Some(color) = spec.color
+                && let Some(slot) = colors.color_for_highlight_mut(spec.highlight)
+            {
+                *slot = color;
+            }
+            if let Some(style) = colors.styles.style_for_highlight_mut(spec.highlight) {
+                *style = spec.style;
+            }
+        }
+        colors
+    }
+}
+
+/// Returns the default path of the configuration file, i.e. `c2e/config` in
+/// `$XDG_CONFIG_HOME`, or in `~/.config` if it isn't set.
+fn default_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(dir.join("c2e").join("config"))
+}
+
+/// Parses a color, which is a hex truecolor value like `#ff8700`, or anything [`Color`] parses:
+/// a basic color name like `red`, an ANSI 256-color number, or red, green, and blue values
+/// separated by commas.
+fn parse_color(word: &str) -> Option<Color> {
+    if let Some(hex) = word.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    word.parse().ok()
+}
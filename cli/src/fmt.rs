@@ -1,6 +1,11 @@
 //! Formatter for printing highlighted text to a terminal.
 
-use c2e::color::{Highlight, HighlightedText};
+use std::sync::Arc;
+
+use c2e::color::{
+    Highlight, HighlightedText, StyleMap,
+    fmt::{DynFormatter, reference_url},
+};
 use termcolor::Color;
 
 #[derive(Debug, Clone, Copy)]
@@ -11,6 +16,7 @@ pub struct ColorMap {
     pub identifier: Color,
     pub number: Color,
     pub quasi_keyword: Color,
+    pub styles: StyleMap,
 }
 
 impl ColorMap {
@@ -26,37 +32,103 @@ impl ColorMap {
             _ => None,
         }
     }
+
+    /// Returns a mutable reference to the [`Color`] for the given [`Highlight`] in this color
+    /// map, or `None` if text with the highlight isn't colored.
+    pub fn color_for_highlight_mut(&mut self, highlight: Highlight) -> Option<&mut Color> {
+        match highlight {
+            Highlight::Qualifier => Some(&mut self.qualifier),
+            Highlight::PrimitiveType => Some(&mut self.primitive_type),
+            Highlight::UserDefinedType => Some(&mut self.user_defined_type),
+            Highlight::Ident => Some(&mut self.identifier),
+            Highlight::Number => Some(&mut self.number),
+            Highlight::QuasiKeyword => Some(&mut self.quasi_keyword),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct CliFormatter {
     colors: ColorMap,
+    /// URL template for hyperlinks to reference pages, if they're enabled
+    link_template: Option<String>,
+    /// Formatter to write text with instead of coloring it for the terminal
+    output: Option<Arc<dyn DynFormatter + Send + Sync>>,
 }
 
 impl CliFormatter {
     /// Creates a new [`CliFormatter`] with the given color mapping.
     #[must_use]
     pub const fn new(colors: ColorMap) -> Self {
-        Self { colors }
+        Self {
+            colors,
+            link_template: None,
+            output: None,
+        }
     }
 
-    /// Writes the given highlighted text to the destination writer, applying colors based on the
-    /// highlight type according to this formatter's color map.
+    /// Returns this formatter made to write text using the given formatter instead of coloring
+    /// it for the terminal.
+    #[must_use]
+    pub fn with_output(self, output: Box<dyn DynFormatter + Send + Sync>) -> Self {
+        Self {
+            output: Some(output.into()),
+            ..self
+        }
+    }
+
+    /// Returns this formatter made to link terms to their reference pages on terminals which
+    /// support hyperlinks, using the given URL template as described for [`reference_url()`].
+    #[must_use]
+    pub fn with_hyperlinks(self, template: String) -> Self {
+        Self {
+            link_template: Some(template),
+            ..self
+        }
+    }
+
+    /// Writes the given highlighted text to the destination writer, applying colors and styles
+    /// based on the highlight type according to this formatter's color map, and linking terms to
+    /// their reference pages if hyperlinks are enabled. If another output formatter was given, the
+    /// text is written using it instead.
     pub fn format(
         &self,
         dst: &mut impl termcolor::WriteColor,
         text: HighlightedText,
     ) -> std::io::Result<()> {
+        if let Some(output) = &self.output {
+            return write!(dst, "{}", text.format_to_string(&**output));
+        }
         for segment in text
             .0
             .into_iter()
             .filter(|segment| !segment.text.is_empty())
         {
-            if let Some(color) = self.colors.color_for_highlight(segment.highlight) {
-                dst.set_color(termcolor::ColorSpec::new().set_fg(Some(color)))?;
+            let url = self
+                .link_template
+                .as_deref()
+                .filter(|_| dst.supports_hyperlinks())
+                .and_then(|template| reference_url(template, &segment));
+            if let Some(url) = &url {
+                dst.set_hyperlink(&termcolor::HyperlinkSpec::open(url.as_bytes()))?;
+            }
+            let color = self.colors.color_for_highlight(segment.highlight);
+            let style = self.colors.styles.style_for_highlight(segment.highlight);
+            if color.is_some() || !style.is_plain() {
+                dst.set_color(
+                    termcolor::ColorSpec::new()
+                        .set_fg(color)
+                        .set_bold(style.bold)
+                        .set_italic(style.italic)
+                        .set_underline(style.underline),
+                )?;
             }
             write!(dst, "{}", segment.text)?;
             dst.reset()?;
+            if url.is_some() {
+                dst.set_hyperlink(&termcolor::HyperlinkSpec::close())?;
+            }
         }
         Ok(())
     }
@@ -12,20 +12,49 @@
  */
 
 use std::{
+    fmt::Display,
     io::{IsTerminal, Write, stderr, stdin, stdout},
+    ops::Deref,
+    path::{Path, PathBuf},
     process::ExitCode,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use c2e::{
-    explainer::explain_declaration,
-    parser::{State, parser},
+    ast::Declaration,
+    builtins::Profile,
+    cdecl::{Command, parse_command},
+    color::{
+        Highlight, HighlightedText, HighlightedTextSegment,
+        fmt::{CPPREFERENCE_URL_TEMPLATE, DynFormatter, by_name},
+    },
+    en2c::english_to_c,
+    explainer::{
+        AnnotatedExplanation, Audience, DiagramStyle, Language, Options, Verbosity, draw_diagram,
+        explain_declaration_with_footnotes, explain_declaration_with_options, explain_difference,
+        walk_through_declaration,
+    },
+    flashcards::{Deck, DeckFormat},
+    generator::Difficulty,
+    layout::{DataModel, describe_layout},
+    lint::lint,
+    notation::arrow_notation,
+    parser::{State, parse_translation_unit},
+    standard::Standard,
+    transpile::Target,
+    usage::generate_usage,
 };
-use chumsky::Parser;
-use fmt::{CliFormatter, ColorMap};
+use chumsky::error::{Rich, RichReason};
+use fmt::CliFormatter;
 use rustyline::{Config, DefaultEditor, error::ReadlineError};
-use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use theme::Theme;
 
+mod config;
 mod fmt;
+mod session;
+mod theme;
 
 // Must be a macro so it expands to a string literal
 macro_rules! copyright_header {
@@ -42,21 +71,638 @@ macro_rules! copyright_header {
     };
 }
 
-const COLOR_MAP: ColorMap = ColorMap {
-    qualifier: Color::Cyan,
-    primitive_type: Color::Yellow,
-    user_defined_type: Color::Magenta,
-    identifier: Color::Red,
-    number: Color::Blue,
-    quasi_keyword: Color::Green,
-};
+const USAGE: &str = "Usage: c2e [--lenient] [--cpp] [--terse | --verbose] \
+                     [--beginner | --expert] [--compact] [--multiline] [--spell-numbers] [--idioms] [--calls] [--mutability] [--steps] [--lint] [--glossary] [--lang <en|es>] \
+                     [--std <c89|c99|c11|c17|c23>] [--profile <stdint|posix|windows>]... \
+                     [--model <ilp32|lp64|llp64>] [--phrase <id>=<phrase>]... [--to <rust|go|zig>] [--cards <csv|tsv>] \
+                     [--theme <default|solarized|dracula|high-contrast>] [--list-themes] [--config <file>] \
+                     [--color <auto|always|never>] [--no-history] [--hyperlinks] [--link-template <url>] \
+                     [--format <plain|ansi|html|markdown|bbcode|discord|irc|json|ssml>] \
+                     [--diagram <unicode|ascii>] [-f <file>]... [<declaration>|<file>]...";
+
+/// Maximum number of lines kept in the REPL history
+const HISTORY_SIZE: usize = 1000;
+
+/// When to color output, selected with `--color`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+enum ColorWhen {
+    /// Color output written to terminals, unless `NO_COLOR` is set, or all output if
+    /// `CLICOLOR_FORCE` is set
+    #[default]
+    Auto,
+    /// Always color output
+    Always,
+    /// Never color output
+    Never,
+}
+
+impl ColorWhen {
+    /// Returns how to color a stream, given whether it's a terminal.
+    fn choice(self, is_terminal: bool) -> ColorChoice {
+        // Empty variables count as unset.
+        let env = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+        match self {
+            Self::Always => ColorChoice::Always,
+            Self::Never => ColorChoice::Never,
+            Self::Auto if env("NO_COLOR").is_some() => ColorChoice::Never,
+            Self::Auto if env("CLICOLOR_FORCE").is_some_and(|value| value != "0") => {
+                ColorChoice::Always
+            }
+            Self::Auto if is_terminal => ColorChoice::Auto,
+            Self::Auto => ColorChoice::Never,
+        }
+    }
+}
+
+/// Input given as an argument instead of on standard input
+enum Input {
+    /// Line of declarations or commands, as it would be typed in the REPL
+    Line(String),
+    /// Source file whose declarations are explained along with their line numbers
+    File(PathBuf),
+}
+
+/// Parsed command-line arguments
+struct Args {
+    /// Initial parser state
+    state: State,
+    options: Options,
+    /// Whether to walk through each declaration step by step before explaining it
+    steps: bool,
+    /// Whether to report suspicious declarations after explaining them
+    lint: bool,
+    /// Whether to define the terms used in each explanation after it
+    glossary: bool,
+    /// Whether to print compact type notations instead of explanations
+    compact: bool,
+    /// Style of diagrams to draw instead of explaining declarations
+    diagram: Option<DiagramStyle>,
+    /// Data model used to compute sizes for the `@size` command
+    model: DataModel,
+    /// Language to translate declarations to instead of explaining them
+    to: Option<Target>,
+    /// Format of flashcards to export declarations as instead of explaining them
+    cards: Option<DeckFormat>,
+    /// Theme to highlight explanations with, overriding the one in the configuration file
+    theme: Option<Theme>,
+    /// Path of the configuration file to read instead of the default one
+    config: Option<PathBuf>,
+    /// When to color output
+    color: ColorWhen,
+    /// Whether to list the available themes instead of reading declarations
+    list_themes: bool,
+    /// Whether to keep the REPL history across sessions
+    history: bool,
+    /// URL template for hyperlinks from terms to their reference pages, if they're enabled
+    link_template: Option<String>,
+    /// Formatter to write explanations with instead of coloring them for the terminal
+    format: Option<Box<dyn DynFormatter + Send + Sync>>,
+    /// Inputs given as arguments, which are read instead of standard input
+    inputs: Vec<Input>,
+}
+
+/// Parses the command-line arguments.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut state = State::default();
+    let mut options = Options::default();
+    let mut steps = false;
+    let mut lint = false;
+    let mut glossary = false;
+    let mut compact = false;
+    let mut diagram = None;
+    let mut model = DataModel::default();
+    let mut to = None;
+    let mut cards = None;
+    let mut theme = None;
+    let mut config = None;
+    let mut color = ColorWhen::default();
+    let mut list_themes = false;
+    let mut history = true;
+    let mut link_template = None;
+    let mut format = None;
+    let mut inputs = Vec::new();
+    // Phrases to override, which are applied to the phrasebook of the language given last
+    let mut phrases: Vec<(String, String)> = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--multiline" => {
+                options.multiline = true;
+                continue;
+            }
+            "--spell-numbers" => {
+                options.spell_out_numbers = true;
+                continue;
+            }
+            "--idioms" => {
+                options.idioms = true;
+                continue;
+            }
+            "--calls" => {
+                options.call_syntax = true;
+                continue;
+            }
+            "--mutability" => {
+                options.mutability = true;
+                continue;
+            }
+            "--steps" => {
+                steps = true;
+                continue;
+            }
+            "--lint" => {
+                lint = true;
+                continue;
+            }
+            "--glossary" => {
+                glossary = true;
+                continue;
+            }
+            "--compact" => {
+                compact = true;
+                continue;
+            }
+            "--list-themes" => {
+                list_themes = true;
+                continue;
+            }
+            "--no-history" => {
+                history = false;
+                continue;
+            }
+            "--hyperlinks" => {
+                link_template.get_or_insert_with(|| CPPREFERENCE_URL_TEMPLATE.to_owned());
+                continue;
+            }
+            "--beginner" => {
+                options.audience = Audience::Beginner;
+                continue;
+            }
+            "--expert" => {
+                options.audience = Audience::Expert;
+                continue;
+            }
+            "--terse" => {
+                options.verbosity = Verbosity::Terse;
+                continue;
+            }
+            "--verbose" => {
+                options.verbosity = Verbosity::Verbose;
+                continue;
+            }
+            "--lenient" => {
+                state = state.with_implicit_int(true);
+                continue;
+            }
+            "--cpp" => {
+                state = state.with_cpp(true);
+                continue;
+            }
+            // Arguments naming existing files are read as files, and others as declarations.
+            path if !path.starts_with('-') => {
+                inputs.push(if Path::new(path).is_file() {
+                    Input::File(arg.into())
+                } else {
+                    Input::Line(arg)
+                });
+                continue;
+            }
+            _ => {}
+        }
+        let (option, value) = match arg.split_once('=') {
+            Some((option, value)) => (option, Some(value.to_owned())),
+            None => (arg.as_str(), None),
+        };
+        if !matches!(
+            option,
+            "-f" | "--file"
+                | "--profile"
+                | "--std"
+                | "--lang"
+                | "--model"
+                | "--phrase"
+                | "--to"
+                | "--cards"
+                | "--theme"
+                | "--config"
+                | "--color"
+                | "--link-template"
+                | "--format"
+                | "--diagram"
+        ) {
+            return Err(format!("unrecognized argument '{arg}'"));
+        }
+        let value = match value {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| format!("missing value for {option}"))?,
+        };
+        if option == "--std" {
+            let standard: Standard = value
+                .parse()
+                .map_err(|_| format!("unknown standard '{value}'"))?;
+            state = state.with_standard(standard);
+        } else if option == "--lang" {
+            let language: Language = value
+                .parse()
+                .map_err(|_| format!("unknown language '{value}'"))?;
+            options.language = language;
+        } else if option == "--phrase" {
+            let (id, phrase) = value
+                .split_once('=')
+                .ok_or_else(|| format!("expected <id>=<phrase> for --phrase, not '{value}'"))?;
+            phrases.push((id.to_owned(), phrase.to_owned()));
+        } else if option == "--to" {
+            let target: Target = value
+                .parse()
+                .map_err(|_| format!("unknown language '{value}'"))?;
+            to = Some(target);
+        } else if option == "--cards" {
+            let format: DeckFormat = value
+                .parse()
+                .map_err(|_| format!("unknown flashcard format '{value}'"))?;
+            cards = Some(format);
+        } else if option == "--diagram" {
+            let style: DiagramStyle = value
+                .parse()
+                .map_err(|_| format!("unknown diagram style '{value}'"))?;
+            diagram = Some(style);
+        } else if option == "-f" || option == "--file" {
+            inputs.push(Input::File(value.into()));
+        } else if option == "--format" {
+            format = Some(by_name(&value).ok_or_else(|| format!("unknown format '{value}'"))?);
+        } else if option == "--link-template" {
+            if !value.contains("{topic}") {
+                return Err(format!("link template '{value}' doesn't contain {{topic}}"));
+            }
+            link_template = Some(value);
+        } else if option == "--color" {
+            color = value
+                .parse()
+                .map_err(|_| format!("unknown color choice '{value}'"))?;
+        } else if option == "--config" {
+            config = Some(value.into());
+        } else if option == "--theme" {
+            let name: Theme = value
+                .parse()
+                .map_err(|_| format!("unknown theme '{value}'"))?;
+            theme = Some(name);
+        } else if option == "--model" {
+            model = value
+                .to_uppercase()
+                .parse()
+                .map_err(|_| format!("unknown data model '{value}'"))?;
+        } else {
+            let profile: Profile = value
+                .parse()
+                .map_err(|_| format!("unknown profile '{value}'"))?;
+            state = state.with_profile(profile);
+        }
+    }
+    if !phrases.is_empty() {
+        let phrasebook = options
+            .language
+            .phrasebook()
+            .with_overrides(
+                phrases
+                    .iter()
+                    .map(|(id, phrase)| (id.as_str(), phrase.clone())),
+            )
+            .map_err(|err| err.to_string())?;
+        options.phrasebook = Some(Arc::new(phrasebook));
+    }
+    Ok(Args {
+        state,
+        options,
+        steps,
+        lint,
+        glossary,
+        compact,
+        diagram,
+        model,
+        to,
+        cards,
+        theme,
+        config,
+        color,
+        list_themes,
+        history,
+        link_template,
+        format,
+        inputs,
+    })
+}
+
+/// Prints the name of each theme followed by a sample of each kind of highlight in its colors.
+fn print_themes(out: &mut StandardStream) {
+    let sample = HighlightedText::from(vec![
+        HighlightedTextSegment::new("const", Highlight::Qualifier),
+        " ".into(),
+        HighlightedTextSegment::new("int", Highlight::PrimitiveType),
+        " ".into(),
+        HighlightedTextSegment::new("struct node", Highlight::UserDefinedType),
+        " ".into(),
+        HighlightedTextSegment::new("name", Highlight::Ident),
+        " ".into(),
+        HighlightedTextSegment::new("42", Highlight::Number),
+        " ".into(),
+        HighlightedTextSegment::new("pointer", Highlight::QuasiKeyword),
+    ]);
+    for theme in Theme::ALL {
+        write!(out, "{:<16}", theme.to_string()).unwrap();
+        CliFormatter::new(theme.colors())
+            .format(&mut *out, sample.clone())
+            .unwrap();
+        writeln!(out).unwrap();
+    }
+}
+
+/// Prints the numbered steps of a walkthrough of the declaration, one per line.
+fn walk_through(
+    formatter: &CliFormatter,
+    out: &mut StandardStream,
+    decl: &Declaration,
+    options: &Options,
+) {
+    for (i, step) in walk_through_declaration(decl, options)
+        .into_iter()
+        .enumerate()
+    {
+        write!(out, "{}. ", i + 1).unwrap();
+        formatter.format(&mut *out, step).unwrap();
+        writeln!(out).unwrap();
+    }
+}
+
+/// Prints the explanation of a declaration followed by `terminator` on one line. If `glossary` is
+/// true, the terms used in the explanation are then defined, one per line.
+fn explain(
+    formatter: &CliFormatter,
+    out: &mut StandardStream,
+    decl: &Declaration,
+    options: &Options,
+    glossary: bool,
+    terminator: &str,
+) {
+    let AnnotatedExplanation { text, footnotes } = if glossary {
+        explain_declaration_with_footnotes(decl, options)
+    } else {
+        AnnotatedExplanation {
+            text: explain_declaration_with_options(decl, options),
+            footnotes: Vec::new(),
+        }
+    };
+    formatter.format(&mut *out, text).unwrap();
+    writeln!(out, "{terminator}").unwrap();
+    for footnote in footnotes {
+        writeln!(out, "  {}: {}", footnote.term, footnote.definition).unwrap();
+    }
+}
+
+/// Explains each declaration in the source of a file on its own line, prefixed with the number of
+/// the line it starts on, then reports any errors parsing the file. Returns whether the whole file
+/// parsed.
+fn explain_file(
+    formatter: &CliFormatter,
+    out: &mut StandardStream,
+    err_out: &mut StandardStream,
+    src: &str,
+    state: &mut State,
+    options: &Options,
+    glossary: bool,
+) -> bool {
+    let (decls, errs) = parse_translation_unit(src, state).into_output_errors();
+    for (decl, span) in decls.unwrap_or_default() {
+        write!(out, "{}: ", line_of(src, span.start)).unwrap();
+        explain(formatter, out, &decl, options, glossary, "");
+    }
+    print_file_errors(err_out, src, &errs);
+    errs.is_empty()
+}
+
+/// Returns the number of the line containing the given byte offset in the source.
+fn line_of(src: &str, offset: usize) -> usize {
+    src[..offset].matches('\n').count() + 1
+}
+
+/// Reports errors parsing the source of a file in bold red to standard error, each with the
+/// number of the line it starts on.
+fn print_file_errors<'src, E>(err_out: &mut StandardStream, src: &str, errs: &[E])
+where
+    E: Display + Deref<Target = Rich<'src, char>>,
+{
+    if errs.is_empty() {
+        return;
+    }
+    err_out
+        .set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))
+        .unwrap();
+    eprintln!("Error(s) parsing declarations:");
+    for err in errs {
+        eprintln!("line {}, {err}", line_of(src, err.span().start));
+    }
+    err_out.reset().unwrap();
+}
+
+/// Returns the rest of the line if it starts with the given `@` command followed by whitespace or
+/// nothing.
+fn command_argument<'a>(line: &'a str, command: &str) -> Option<&'a str> {
+    line.strip_prefix(command)
+        .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+/// Parses the argument of the `@cards` command, i.e. a number of cards optionally followed by a
+/// difficulty.
+fn parse_cards_argument(argument: &str) -> Result<(usize, Difficulty), String> {
+    let mut words = argument.split_whitespace();
+    let count = words
+        .next()
+        .ok_or("@cards requires a number of cards")?
+        .parse()
+        .map_err(|_| "@cards requires a number of cards".to_string())?;
+    let difficulty = match words.next() {
+        Some(word) => word
+            .parse()
+            .map_err(|_| format!("unknown difficulty '{word}'"))?,
+        None => Difficulty::default(),
+    };
+    if words.next().is_some() {
+        return Err("@cards takes a number of cards and a difficulty".to_string());
+    }
+    Ok((count, difficulty))
+}
+
+/// Returns how many more parentheses, brackets, and braces the source opens than it closes,
+/// ignoring any in string or character literals and comments.
+fn bracket_depth(src: &str) -> i32 {
+    let mut depth = 0;
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => _ = chars.next(),
+                        _ if next == c => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                while let Some(next) = chars.next() {
+                    if next == '*' && chars.next_if_eq(&'/').is_some() {
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    depth
+}
+
+/// Returns whether the source ends in the middle of a declaration, i.e. it has unclosed
+/// parentheses, brackets, or braces, or it fails to parse only because the input ends too soon.
+fn is_incomplete(src: &str, state: &State) -> bool {
+    if bracket_depth(src) > 0 {
+        return true;
+    }
+    // Typedefs in the source mustn't be declared until it's complete.
+    let errs = parse_translation_unit(src, &mut state.clone()).into_errors();
+    !errs.is_empty()
+        && errs
+            .iter()
+            .all(|err| matches!(err.reason(), RichReason::ExpectedFound { found: None, .. }))
+}
+
+/// Reads a line of input in the REPL. A line of declarations which ends in the middle of one is
+/// continued on the following lines, which are read with a `... ` prompt, until it's complete or
+/// an empty line or the end of input is read.
+fn read_input(rl: &mut DefaultEditor, state: &State) -> rustyline::Result<String> {
+    let mut input = rl.readline("> ")?;
+    while !input.starts_with('@') && parse_command(&input).is_none() && is_incomplete(&input, state)
+    {
+        match rl.readline("... ") {
+            Ok(line) if !line.is_empty() => {
+                input.push('\n');
+                input.push_str(&line);
+            }
+            Ok(_) | Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(input)
+}
+
+/// Returns the path of the file the REPL history is kept in, i.e. `c2e/history` in
+/// `$XDG_DATA_HOME`, or in `~/.local/share` if it isn't set.
+fn history_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share"))
+        })?;
+    Some(dir.join("c2e").join("history"))
+}
+
+/// Prints an error message in bold red to standard error.
+fn print_error(stderr: &mut StandardStream, message: &str) {
+    stderr
+        .set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))
+        .unwrap();
+    eprintln!("Error: {message}");
+    stderr.reset().unwrap();
+}
 
 fn main() -> ExitCode {
-    let rl_config = Config::builder().auto_add_history(true).build();
+    // Persist state across input lines
+    let Args {
+        state: mut parser_state,
+        mut options,
+        steps,
+        lint: lint_enabled,
+        glossary,
+        compact,
+        diagram,
+        model,
+        to,
+        cards,
+        theme,
+        config: config_path,
+        color,
+        list_themes,
+        history,
+        link_template,
+        format,
+        inputs,
+    } = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Error: {err}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let config = match config::Config::load(config_path.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Use color according to `--color` and the environment
+    let theme = theme.or(config.theme).unwrap_or_default();
+    let mut formatter = CliFormatter::new(config.colors(theme));
+    if let Some(template) = link_template {
+        formatter = formatter.with_hyperlinks(template);
+    }
+    if let Some(format) = format {
+        formatter = formatter.with_output(format);
+    }
+    let mut stdout = StandardStream::stdout(color.choice(stdout().is_terminal()));
+    let mut stderr = StandardStream::stderr(color.choice(stderr().is_terminal()));
+
+    if list_themes {
+        print_themes(&mut stdout);
+        return ExitCode::SUCCESS;
+    }
+
+    let rl_config = Config::builder()
+        .auto_add_history(true)
+        .max_history_size(HISTORY_SIZE)
+        .unwrap()
+        .build();
     let mut rl = DefaultEditor::with_config(rl_config).unwrap();
 
+    // Inputs given as arguments are read in one shot instead of starting the REPL, and the exit
+    // status reports whether they all parsed.
+    let one_shot = !inputs.is_empty();
+    let mut inputs = inputs.into_iter();
+    let mut failed = false;
+
+    // Keep the history of interactive sessions across runs. The file doesn't exist until the
+    // first session ends, so failing to load it is expected.
+    let history_path = if history && !one_shot && stdin().is_terminal() {
+        history_path()
+    } else {
+        None
+    };
+    if let Some(path) = &history_path {
+        let _ = rl.load_history(path);
+    }
+
     // Print license information if interactive
-    if stdin().is_terminal() {
+    if !one_shot && stdin().is_terminal() {
         eprintln!(indoc::concatdoc! {
             copyright_header!(), r"
             This program comes with ABSOLUTELY NO WARRANTY.
@@ -66,24 +712,36 @@ fn main() -> ExitCode {
         });
     }
 
-    // Use color if the output is a terminal, otherwise disable it
-    let formatter = CliFormatter::new(COLOR_MAP);
-    let mut stdout = StandardStream::stdout(if stdout().is_terminal() {
-        termcolor::ColorChoice::Auto
-    } else {
-        termcolor::ColorChoice::Never
-    });
-    let mut stderr = StandardStream::stderr(if stderr().is_terminal() {
-        termcolor::ColorChoice::Auto
-    } else {
-        termcolor::ColorChoice::Never
-    });
-
-    // Persist state input lines
-    let mut parser_state = State::default();
-
-    loop {
-        match rl.readline("> ") {
+    let status = loop {
+        let line = if one_shot {
+            match inputs.next() {
+                Some(Input::Line(line)) => Ok(line),
+                Some(Input::File(path)) => {
+                    match std::fs::read_to_string(&path) {
+                        Ok(src) => {
+                            failed |= !explain_file(
+                                &formatter,
+                                &mut stdout,
+                                &mut stderr,
+                                &src,
+                                &mut parser_state,
+                                &options,
+                                glossary,
+                            );
+                        }
+                        Err(err) => {
+                            print_error(&mut stderr, &format!("{}: {err}", path.display()));
+                            failed = true;
+                        }
+                    }
+                    continue;
+                }
+                None => Err(ReadlineError::Eof),
+            }
+        } else {
+            read_input(&mut rl, &parser_state)
+        };
+        match line {
             Ok(line) => {
                 if line.is_empty() {
                     continue;
@@ -112,47 +770,293 @@ fn main() -> ExitCode {
                     continue;
                 }
 
-                match parser()
-                    .parse_with_state(&line, &mut parser_state)
-                    .into_result()
+                // `@save <file>` saves what was declared and the settings to a file, and
+                // `@restore <file>` replaces them with those saved in one.
+                if let Some(path) = command_argument(&line, "@save") {
+                    let path = path.trim();
+                    if path.is_empty() {
+                        print_error(&mut stderr, "@save requires a file");
+                    } else if let Err(err) =
+                        std::fs::write(path, session::save(&parser_state, &options))
+                    {
+                        print_error(&mut stderr, &format!("{path}: {err}"));
+                    } else {
+                        writeln!(&mut stdout, "Saved session to {path}").unwrap();
+                    }
+                    continue;
+                }
+                if let Some(path) = command_argument(&line, "@restore") {
+                    let path = path.trim();
+                    if path.is_empty() {
+                        print_error(&mut stderr, "@restore requires a file");
+                        continue;
+                    }
+                    let restored = std::fs::read_to_string(path)
+                        .map_err(|err| err.to_string())
+                        .and_then(|src| {
+                            session::restore_settings(&src, &options, &parser_state)
+                                .map(|settings| (src, settings))
+                        });
+                    match restored {
+                        Ok((src, (restored_options, restored_state))) => {
+                            options = restored_options;
+                            parser_state = restored_state;
+                            parser_state.clear_declarations();
+                            let errs =
+                                parse_translation_unit(&src, &mut parser_state).into_errors();
+                            writeln!(&mut stdout, "Restored session from {path}").unwrap();
+                            print_file_errors(&mut stderr, &src, &errs);
+                            failed |= !errs.is_empty();
+                        }
+                        Err(err) => print_error(&mut stderr, &format!("{path}: {err}")),
+                    }
+                    continue;
+                }
+
+                // `@reset` (or `@clear`) forgets the types and constants declared so far.
+                if line == "@reset" || line == "@clear" {
+                    parser_state.clear_declarations();
+                    continue;
+                }
+
+                // `@load <file>` declares the types and constants in a file, such as a header,
+                // without explaining its declarations.
+                if let Some(path) = command_argument(&line, "@load") {
+                    let path = path.trim();
+                    if path.is_empty() {
+                        print_error(&mut stderr, "@load requires a file");
+                        continue;
+                    }
+                    match std::fs::read_to_string(path) {
+                        Ok(src) => {
+                            let (decls, errs) = parse_translation_unit(&src, &mut parser_state)
+                                .into_output_errors();
+                            let count = decls.map_or(0, |decls| decls.len());
+                            writeln!(&mut stdout, "Loaded {count} declarations from {path}")
+                                .unwrap();
+                            print_file_errors(&mut stderr, &src, &errs);
+                            failed |= !errs.is_empty();
+                        }
+                        Err(err) => print_error(&mut stderr, &format!("{path}: {err}")),
+                    }
+                    continue;
+                }
+
+                // `@typedefs` explains the types declared by typedefs so far.
+                if line == "@typedefs" {
+                    let mut types = parser_state.custom_types().peekable();
+                    if types.peek().is_none() {
+                        writeln!(&mut stdout, "No types have been declared.").unwrap();
+                    }
+                    for (name, src) in types {
+                        // Parsing a copy of the state keeps the type from being declared again.
+                        let decls = parse_translation_unit(src, &mut parser_state.clone())
+                            .into_output()
+                            .unwrap_or_default();
+                        for (decl, _) in decls
+                            .iter()
+                            .filter(|(decl, _)| decl.declarator.name() == Some(name))
+                        {
+                            explain(&formatter, &mut stdout, decl, &options, false, "");
+                        }
+                    }
+                    continue;
+                }
+
+                // `@en2c <phrase>` composes a C declaration from English instead of parsing one.
+                if let Some(phrase) = command_argument(&line, "@en2c") {
+                    match english_to_c(phrase) {
+                        Ok(c) => writeln!(&mut stdout, "{c}").unwrap(),
+                        Err(err) => print_error(&mut stderr, &err.to_string()),
+                    }
+                    continue;
+                }
+
+                // `@cards <count> [<difficulty>]` exports flashcards for random declarations.
+                if let Some(argument) = command_argument(&line, "@cards") {
+                    match parse_cards_argument(argument) {
+                        Ok((count, difficulty)) => {
+                            let seed = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map_or(0, |time| time.as_secs() ^ u64::from(time.subsec_nanos()));
+                            let deck = Deck::random(seed, difficulty, count, &options);
+                            write!(&mut stdout, "{}", deck.export(cards.unwrap_or_default()))
+                                .unwrap();
+                        }
+                        Err(err) => print_error(&mut stderr, &err),
+                    }
+                    continue;
+                }
+
+                // Classic cdecl commands: `declare` and `cast` compose C code from English, and
+                // `explain` explains the rest of the line.
+                let mut explain_src = None;
+                if let Some(command) = parse_command(&line) {
+                    match command {
+                        Ok(Command::Explain(src)) => explain_src = Some(src),
+                        Ok(command) => {
+                            let c = command.to_c().unwrap_or_default();
+                            writeln!(&mut stdout, "{c}").unwrap();
+                            continue;
+                        }
+                        Err(err) => {
+                            print_error(&mut stderr, &err.to_string());
+                            continue;
+                        }
+                    }
+                }
+
+                // `@size <declaration>` describes the layouts of the declared types instead of
+                // explaining them, `@diff <declaration>; <declaration>` explains how two
+                // declarations differ, `@usage <declaration>` shows example code using the
+                // declared identifiers, and `@rust <declaration>` translates them to Rust.
+                let size_src = command_argument(&line, "@size");
+                let diff_src = command_argument(&line, "@diff");
+                let usage_src = command_argument(&line, "@usage");
+                let rust_src = command_argument(&line, "@rust");
+
+                // Explain whatever parsed successfully, then report the errors for the rest.
+                let src = size_src
+                    .or(diff_src)
+                    .or(usage_src)
+                    .or(rust_src)
+                    .or(explain_src)
+                    .unwrap_or(&line);
+                // `@rust` always translates to Rust, and `--to` translates declarations given
+                // without a command.
+                let target = if rust_src.is_some() {
+                    Some(Target::Rust)
+                } else if [size_src, diff_src, usage_src, explain_src]
+                    .iter()
+                    .all(Option::is_none)
                 {
-                    Ok(decls) => match &decls[..] {
-                        [decl] => {
-                            let explanation = explain_declaration(decl);
-                            formatter.format(&mut stdout, explanation).unwrap();
-                            writeln!(&mut stdout).unwrap();
+                    to
+                } else {
+                    None
+                };
+                let (spanned_decls, errs) =
+                    parse_translation_unit(src, &mut parser_state).into_output_errors();
+                let spanned_decls = spanned_decls.unwrap_or_default();
+                let decls: Vec<Declaration> =
+                    spanned_decls.iter().map(|(decl, _)| decl.clone()).collect();
+                match (target, decls.as_slice()) {
+                    (Some(target), decls) => {
+                        for decl in decls {
+                            writeln!(&mut stdout, "{}", target.translate(decl)).unwrap();
+                        }
+                    }
+                    (_, decls) if size_src.is_some() => {
+                        for decl in decls {
+                            match describe_layout(decl, model) {
+                                Ok(description) => writeln!(&mut stdout, "{description}").unwrap(),
+                                Err(err) => print_error(&mut stderr, &err.to_string()),
+                            }
+                        }
+                    }
+                    (_, decls) if usage_src.is_some() => {
+                        for decl in decls {
+                            writeln!(&mut stdout, "{}", generate_usage(decl)).unwrap();
+                        }
+                    }
+                    (_, [a, b]) if diff_src.is_some() => {
+                        let explanation = explain_difference(a, b, &options);
+                        formatter.format(&mut stdout, explanation).unwrap();
+                        writeln!(&mut stdout).unwrap();
+                    }
+                    _ if diff_src.is_some() => {
+                        if errs.is_empty() {
+                            print_error(&mut stderr, "@diff requires exactly two declarations");
                         }
-                        decls => {
+                    }
+                    (_, decls) if cards.is_some() => {
+                        if let Some(format) = cards {
+                            let deck = Deck::from_declarations(decls, &options);
+                            write!(&mut stdout, "{}", deck.export(format)).unwrap();
+                        }
+                    }
+                    (_, decls) if compact => {
+                        for decl in decls {
+                            writeln!(&mut stdout, "{}", arrow_notation(decl)).unwrap();
+                        }
+                    }
+                    (_, decls) if diagram.is_some() => {
+                        if let Some(style) = diagram {
                             for decl in decls {
-                                let explanation = explain_declaration(decl);
-                                formatter.format(&mut stdout, explanation).unwrap();
-                                writeln!(&mut stdout, ";").unwrap();
+                                let diagram = draw_diagram(decl, style, &options);
+                                formatter.format(&mut stdout, diagram).unwrap();
+                                writeln!(&mut stdout).unwrap();
                             }
                         }
-                    },
-                    Err(errs) => {
-                        stderr
-                            .set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))
-                            .unwrap();
-                        eprintln!("Error(s) parsing declaration:");
-                        for err in errs {
-                            eprintln!("{err}");
+                    }
+                    (_, [decl]) => {
+                        if steps {
+                            walk_through(&formatter, &mut stdout, decl, &options);
+                        }
+                        explain(&formatter, &mut stdout, decl, &options, glossary, "");
+                    }
+                    (_, decls) => {
+                        for decl in decls {
+                            if steps {
+                                walk_through(&formatter, &mut stdout, decl, &options);
+                            }
+                            explain(&formatter, &mut stdout, decl, &options, glossary, ";");
                         }
-                        stderr.reset().unwrap();
                     }
                 }
+                if !errs.is_empty() {
+                    failed = true;
+                    stderr
+                        .set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true))
+                        .unwrap();
+                    eprintln!("Error(s) parsing declaration:");
+                    for err in errs {
+                        eprintln!("{err}");
+                    }
+                    stderr.reset().unwrap();
+                }
+                let lints = if lint_enabled {
+                    lint(src, &spanned_decls)
+                } else {
+                    Vec::new()
+                };
+                if !lints.is_empty() {
+                    stderr
+                        .set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true))
+                        .unwrap();
+                    eprintln!("Warning(s):");
+                    for lint in lints {
+                        eprintln!("{lint}");
+                    }
+                    stderr.reset().unwrap();
+                }
             }
             Err(ReadlineError::Interrupted) => {
                 if stdin().is_terminal() {
                     println!("Interrupted; exiting...");
                 }
-                return ExitCode::SUCCESS;
+                break ExitCode::SUCCESS;
             }
-            Err(ReadlineError::Eof) => return ExitCode::SUCCESS,
+            Err(ReadlineError::Eof) if one_shot && failed => break ExitCode::FAILURE,
+            Err(ReadlineError::Eof) => break ExitCode::SUCCESS,
             Err(err) => {
                 eprintln!("Error reading line: {err}");
-                return ExitCode::FAILURE;
+                break ExitCode::FAILURE;
             }
         }
+    };
+
+    if let Some(path) = &history_path {
+        let saved = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .map_err(ReadlineError::from)
+            .and_then(|()| rl.save_history(path));
+        if let Err(err) = saved {
+            print_error(
+                &mut stderr,
+                &format!("couldn't save history to {}: {err}", path.display()),
+            );
+        }
     }
+    status
 }
@@ -0,0 +1,94 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Session files, which save what was declared in the REPL along with the parser and
+//! explanation settings, so the session can be resumed later.
+//!
+//! A session file is C source which declares everything declared in the session, preceded by
+//! comments of the form `// key = value` giving the settings. Since it's valid C, it can also be
+//! read with `@load` or `--file`.
+
+use c2e::{builtins::Profile, explainer::Options, parser::State};
+
+/// Returns the contents of a session file saving the given parser state and settings.
+pub fn save(state: &State, options: &Options) -> String {
+    let profiles: Vec<_> = state
+        .profiles()
+        .map(|profile| profile.to_string())
+        .collect();
+    format!(
+        "// c2e session\n\
+         // verbosity = {}\n\
+         // audience = {}\n\
+         // lang = {}\n\
+         // std = {}\n\
+         // cpp = {}\n\
+         // lenient = {}\n\
+         // profiles = {}\n\
+         {}",
+        options.verbosity,
+        options.audience,
+        options.language,
+        state.standard().to_string().to_ascii_lowercase(),
+        state.cpp(),
+        state.implicit_int(),
+        profiles.join(", "),
+        state.to_source()
+    )
+}
+
+/// Returns the given options and parser state with the settings in the leading comments of a
+/// session file applied. The declarations in the state are kept.
+pub fn restore_settings(
+    src: &str,
+    options: &Options,
+    state: &State,
+) -> Result<(Options, State), String> {
+    let mut options = options.clone();
+    let mut state = state.clone();
+    for (i, line) in src.lines().enumerate() {
+        let Some(comment) = line.strip_prefix("//") else {
+            break;
+        };
+        let Some((key, value)) = comment.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let invalid = || format!("line {}: invalid {key} '{value}'", i + 1);
+        match key {
+            "verbosity" => options.verbosity = value.parse().map_err(|_| invalid())?,
+            "audience" => options.audience = value.parse().map_err(|_| invalid())?,
+            "lang" => {
+                let language = value.parse().map_err(|_| invalid())?;
+                // Phrases overridden with `--phrase` only apply to the language they were given
+                // for.
+                if language != options.language {
+                    options.phrasebook = None;
+                }
+                options.language = language;
+            }
+            "std" => state = state.with_standard(value.parse().map_err(|_| invalid())?),
+            "cpp" => state = state.with_cpp(value.parse().map_err(|_| invalid())?),
+            "lenient" => state = state.with_implicit_int(value.parse().map_err(|_| invalid())?),
+            "profiles" => {
+                state = state.without_builtin_typedefs();
+                for profile in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let profile: Profile = profile.parse().map_err(|_| invalid())?;
+                    state = state.with_profile(profile);
+                }
+            }
+            _ => return Err(format!("line {}: unknown setting '{key}'", i + 1)),
+        }
+    }
+    Ok((options, state))
+}
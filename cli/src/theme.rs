@@ -0,0 +1,96 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Color themes for highlighting explanations.
+
+use c2e::color::{StyleMap, StyleSpec};
+use termcolor::Color;
+
+use crate::fmt::ColorMap;
+
+/// Bundled color theme, selected with `--theme`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Theme {
+    /// Basic terminal colors, which follow the terminal's own color scheme
+    #[default]
+    Default,
+    /// Truecolor theme based on Ethan Schoonover's Solarized palette
+    Solarized,
+    /// Truecolor theme based on the Dracula palette
+    Dracula,
+    /// Bright 256-color theme with bold identifiers and quasi-keywords
+    HighContrast,
+}
+
+impl Theme {
+    /// All bundled themes, in the order they're listed by `--list-themes`
+    pub const ALL: [Self; 4] = [
+        Self::Default,
+        Self::Solarized,
+        Self::Dracula,
+        Self::HighContrast,
+    ];
+
+    /// Returns the color map of this theme.
+    pub const fn colors(self) -> ColorMap {
+        match self {
+            Self::Default => ColorMap {
+                qualifier: Color::Cyan,
+                primitive_type: Color::Yellow,
+                user_defined_type: Color::Magenta,
+                identifier: Color::Red,
+                number: Color::Blue,
+                quasi_keyword: Color::Green,
+                styles: StyleMap::PLAIN,
+            },
+            Self::Solarized => ColorMap {
+                qualifier: Color::Rgb(0x2a, 0xa1, 0x98),
+                primitive_type: Color::Rgb(0xb5, 0x89, 0x00),
+                user_defined_type: Color::Rgb(0x6c, 0x71, 0xc4),
+                identifier: Color::Rgb(0xcb, 0x4b, 0x16),
+                number: Color::Rgb(0xd3, 0x36, 0x82),
+                quasi_keyword: Color::Rgb(0x26, 0x8b, 0xd2),
+                styles: StyleMap::PLAIN,
+            },
+            Self::Dracula => ColorMap {
+                qualifier: Color::Rgb(0xff, 0x79, 0xc6),
+                primitive_type: Color::Rgb(0x8b, 0xe9, 0xfd),
+                user_defined_type: Color::Rgb(0x50, 0xfa, 0x7b),
+                identifier: Color::Rgb(0xff, 0xb8, 0x6c),
+                number: Color::Rgb(0xbd, 0x93, 0xf9),
+                quasi_keyword: Color::Rgb(0xf1, 0xfa, 0x8c),
+                styles: StyleMap {
+                    primitive_type: StyleSpec::PLAIN.italic(),
+                    user_defined_type: StyleSpec::PLAIN.italic(),
+                    ..StyleMap::PLAIN
+                },
+            },
+            Self::HighContrast => ColorMap {
+                qualifier: Color::Ansi256(51),
+                primitive_type: Color::Ansi256(226),
+                user_defined_type: Color::Ansi256(201),
+                identifier: Color::Ansi256(196),
+                number: Color::Ansi256(39),
+                quasi_keyword: Color::Ansi256(46),
+                styles: StyleMap {
+                    identifier: StyleSpec::PLAIN.bold(),
+                    quasi_keyword: StyleSpec::PLAIN.bold(),
+                    ..StyleMap::PLAIN
+                },
+            },
+        }
+    }
+}
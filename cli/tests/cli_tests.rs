@@ -13,7 +13,7 @@
 
 use std::{
     io::Write,
-    process::{Command, Stdio},
+    process::{Command, Output, Stdio},
     time::Duration,
 };
 
@@ -27,6 +27,7 @@ use pretty_assertions::assert_eq;
 fn spawn(color: bool) -> PtySession {
     let path = env!("CARGO_BIN_EXE_c2e");
     let mut cmd = Command::new(path);
+    cmd.arg("--no-history");
     if color {
         cmd.env("TERM", "xterm-256color");
     }
@@ -40,6 +41,23 @@ fn spawn(color: bool) -> PtySession {
     .unwrap()
 }
 
+/// Runs c2e with the given arguments and standard input, returning its output once it exits.
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut c = Command::new(env!("CARGO_BIN_EXE_c2e"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    c.stdin
+        .as_mut()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    c.wait_with_output().unwrap()
+}
+
 fn kill(mut c: PtySession) {
     c.send_control('d').unwrap();
     c.exp_eof().unwrap();
@@ -81,7 +99,18 @@ fn test_send_empty_line() {
 fn test_parse_error() {
     let mut c = spawn(false);
     c.exp_string("> ").unwrap();
-    c.send_line("int x = 5;").unwrap();
+    c.send_line("int x y;").unwrap();
+    c.exp_string("Error(s) parsing declaration:\r\n").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+}
+
+#[test]
+fn test_partial_explanation() {
+    let mut c = spawn(false);
+    c.exp_string("> ").unwrap();
+    c.send_line("int x y; float z;").unwrap();
+    c.exp_string("a float named z\r\n").unwrap();
     c.exp_string("Error(s) parsing declaration:\r\n").unwrap();
     c.exp_string("> ").unwrap();
     kill(c);
@@ -122,14 +151,7 @@ fn test_interactive_license_header() {
 
 #[test]
 fn test_non_interactive_no_license() {
-    let mut c = Command::new(env!("CARGO_BIN_EXE_c2e"))
-        .stdin(Stdio::piped())
-        .stderr(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .unwrap();
-    c.stdin.as_mut().unwrap().write_all(b"int foo\n").unwrap();
-    let output = c.wait_with_output().unwrap();
+    let output = run(&[], "int foo\n");
     let out_str = str::from_utf8(&output.stdout).unwrap();
     assert_eq!(out_str, "an int named foo\n", "wrong output on stdout");
     assert!(output.stderr.is_empty(), "expected stderr to be empty");
@@ -142,6 +164,10 @@ fn test_multiple_declarations() {
     c.send_line("int x; float y;").unwrap();
     c.exp_string("an int named x;\r\na float named y;").unwrap();
     c.exp_string("> ").unwrap();
+    c.send_line("int a, *b;").unwrap();
+    c.exp_string("an int named a;\r\na pointer named b to an int;")
+        .unwrap();
+    c.exp_string("> ").unwrap();
     kill(c);
 }
 
@@ -192,7 +218,7 @@ fn test_colors() {
 fn test_error_color() {
     let mut c = spawn(true);
     c.exp_string("> ").unwrap();
-    c.send_line("int x = 5;").unwrap();
+    c.send_line("int x y;").unwrap();
     c.exp_string("\x1b[31m").unwrap(); // Error color
     c.exp_string("Error(s) parsing declaration:\r\n").unwrap();
     c.exp_string("\r\n").unwrap();
@@ -200,3 +226,910 @@ fn test_error_color() {
     c.exp_string("> ").unwrap();
     kill(c);
 }
+
+#[test]
+fn test_profile_flag() {
+    let output = run(
+        &["--profile", "posix", "--profile=windows"],
+        "pid_t pid\nDWORD flags\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "a pid_t named pid\na DWORD named flags\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_unknown_profile() {
+    let output = run(&["--profile", "plan9"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("Error: unknown profile 'plan9'"));
+}
+
+#[test]
+fn test_std_flag() {
+    let output = run(&["--std", "c89"], "int x\n_Bool b\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    // The explanation is still printed along with the error.
+    assert_eq!(
+        out_str, "an int named x\na _Bool named b\n",
+        "wrong output on stdout"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("_Bool is not available before C99"));
+
+    let output = run(&["--std=c2y"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("Error: unknown standard 'c2y'"));
+}
+
+#[test]
+fn test_lenient_flag() {
+    let output = run(&["--lenient"], "static x\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "a static int named x (implicitly an int, with internal linkage)\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_cpp_flag() {
+    let output = run(&["--cpp"], "std::string &s\nint (C::*f)(int) const\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a reference named s to a std::string\n\
+         a pointer named f to a member of C that is a const function that takes (an int) and \
+         returns an int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_verbosity_flags() {
+    for (flag, input, expected) in [
+        ("--terse", "int (*p)[10]\n", "pointer to array of 10 int\n"),
+        (
+            "--verbose",
+            "int (*const p)[10]\n",
+            "a const pointer named p to an array of 10 ints \
+             (the pointer itself cannot be changed to point elsewhere)\n",
+        ),
+    ] {
+        let output = run(&[flag], input);
+        let out_str = str::from_utf8(&output.stdout).unwrap();
+        assert_eq!(out_str, expected, "wrong output on stdout for {flag}");
+        assert!(output.stderr.is_empty(), "expected stderr to be empty");
+    }
+}
+
+#[test]
+fn test_lang_flag() {
+    let output = run(&["--lang", "es"], "int *p\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "un puntero llamado p a un int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+
+    let output = run(&["--lang=tlh"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("Error: unknown language 'tlh'"));
+}
+
+#[test]
+fn test_steps_flag() {
+    let output = run(&["--steps"], "int (*fp)(void)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "1. start at `fp`\n\
+         2. go left: `*`, a pointer to\n\
+         3. leave the parentheses and go right: `(void)`, a function that takes no parameters \
+         and returns\n\
+         4. go left to the type: `int`, an int\n\
+         a pointer named fp to a function that takes no parameters and returns an int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_spell_numbers_flag() {
+    let output = run(&["--spell-numbers"], "char buf[10]\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "an array named buf of ten chars\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_idioms_flag() {
+    let output = run(&["--idioms"], "int main(int argc, char **argv)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a function named main that takes (an int named argc and a pointer named argv to a \
+         pointer to a char (commonly an array of strings)) and returns an int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_calls_flag() {
+    let output = run(&["--calls"], "int (**fpp)(void)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a pointer named fpp to a pointer to a function that takes no parameters and returns an \
+         int; call it as `(**fpp)()` or simply `(*fpp)()`, and assign it with `fpp = &value;`\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_cards_option() {
+    let output = run(
+        &["--cards", "csv"],
+        "int *p; char (*fp)(int, int)\n@cards 3 arrays\n@cards many\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    let lines: Vec<&str> = out_str.lines().collect();
+    assert_eq!(
+        lines[..2],
+        [
+            "int *p,a pointer named p to an int",
+            "\"char (*fp)(int, int)\",a pointer named fp to a function that takes (an int and an \
+             int) and returns a char",
+        ],
+        "wrong output on stdout"
+    );
+    assert_eq!(lines.len(), 5, "expected three random cards");
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert_eq!(
+        err_str, "Error: @cards requires a number of cards\n",
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_mutability_flag() {
+    let output = run(&["--mutability"], "char *const p\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "a const pointer named p to a char (*p may be modified, but p may not)\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_compact_flag() {
+    let output = run(&["--compact"], "int (*p)[10]; char *(*fp)(int)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "p: ptr -> array[10] -> int\nfp: ptr -> fn(int) -> ptr -> char\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_multiline_flag() {
+    let output = run(&["--multiline"], "int *(*fp)(int)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a pointer named fp to\n  \
+         a function that takes (an int) and returns\n    \
+         a pointer to\n      \
+         an int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty(), "expected stderr to be empty");
+}
+
+#[test]
+fn test_audience_flags() {
+    for (flag, expected) in [
+        (
+            "--beginner",
+            "a pointer (a variable that stores a memory address) named p to an int\n",
+        ),
+        ("--expert", "pointer to int\n"),
+    ] {
+        let output = run(&[flag], "int *p\n");
+        let out_str = str::from_utf8(&output.stdout).unwrap();
+        assert_eq!(out_str, expected, "wrong output on stdout for {flag}");
+        assert!(output.stderr.is_empty(), "expected stderr to be empty");
+    }
+}
+
+#[test]
+fn test_size_command() {
+    let output = run(
+        &["--model", "ilp32"],
+        "@size int *arr[10]\n@size void f(void)\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "arr occupies 40 bytes and is aligned to 4 bytes on ILP32\n",
+        "wrong output on stdout"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert_eq!(err_str, "Error: functions have no size\n");
+
+    let output = run(&["--model=lp32"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("Error: unknown data model 'lp32'"));
+}
+
+#[test]
+fn test_diff_command() {
+    let output = run(&[], "@diff int *a[10]; int (*a)[10]\n@diff int x\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "the first is an array of pointers; the second is a pointer to an array\n",
+        "wrong output on stdout"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert_eq!(err_str, "Error: @diff requires exactly two declarations\n");
+}
+
+#[test]
+fn test_impossible_declarator() {
+    let output = run(&[], "int f(void)[3]\n");
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("a function cannot return an array; return a pointer to an array instead"),
+        "wrong output on stderr: {err_str}"
+    );
+}
+
+#[test]
+fn test_lint_flag() {
+    let output = run(&["--lint"], "const int f(void)\nint x\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a function named f that takes no parameters and returns a const int\nan int named x\n",
+        "wrong output on stdout"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert_eq!(
+        err_str,
+        "Warning(s):\nat 0..17: `const` on a return type has no effect; remove `const` from the \
+         return type\n"
+    );
+}
+
+#[test]
+fn test_usage_command() {
+    let output = run(&[], "@usage int (*cmp)(const void *, const void *)\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "cmp = my_cmp;\nint result = cmp(a, b);\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_rust_command() {
+    let output = run(&[], "@rust int (*cb)(int); volatile char buf[]\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "pub static mut cb: Option<extern \"C\" fn(c_int) -> c_int>;\n\
+         pub static mut buf: [c_char; 0];\n\
+         // Note: Rust has no volatile types, so volatile objects must be accessed with \
+         `read_volatile()` and `write_volatile()`\n\
+         // Note: arrays of unknown size are translated to arrays of length 0, so their elements \
+         must be accessed through pointers\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_to_option() {
+    let output = run(
+        &["--to", "zig"],
+        "int (*cb)(int)\n@rust int *p\nexplain int x\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "pub extern var cb: ?*const fn (c_int) callconv(.c) c_int;\n\
+         pub static mut p: *mut c_int;\n\
+         an int named x\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty());
+
+    let output = run(&["--to=cobol"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(err_str.contains("Error: unknown language 'cobol'"));
+}
+
+#[test]
+fn test_en2c_command() {
+    let output = run(
+        &[],
+        "@en2c pointer to array of 10 const char\n@en2c pointer int\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(out_str, "const char (*)[10]\n", "wrong output on stdout");
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("expected 'to', but found 'int'"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_cdecl_commands() {
+    let output = run(
+        &[],
+        "explain char *(*fptab[])(int)\n\
+              declare x as pointer to function (void) returning int\n\
+              cast p into pointer to int\n\
+              declare x pointer to int\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "an array named fptab of pointers to functions that take (an int) and return a pointer \
+         to a char\nint (*x)(void)\n(int *)p\n",
+        "wrong output on stdout"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("expected 'as', but found 'pointer'"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_glossary_flag() {
+    let output = run(&["--glossary"], "int *p[2]\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "an array named p of 2 pointers to ints\n  \
+         array: a sequence of elements stored next to each other in memory\n  \
+         pointers: a variable that stores a memory address\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_phrase_overrides() {
+    let output = run(
+        &[
+            "--phrase",
+            "that-takes= which accepts ",
+            "--phrase=and-returns= and yields ",
+        ],
+        "int f(char)\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str, "a function named f which accepts (a char) and yields an int\n",
+        "wrong output on stdout"
+    );
+    assert!(output.stderr.is_empty());
+
+    let output = run(&["--phrase", "returns=yields"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("unknown phrase 'returns'"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_list_themes() {
+    let output = run(&["--list-themes"], "");
+    assert!(output.status.success());
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    let names: Vec<&str> = out_str
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(names, ["default", "solarized", "dracula", "high-contrast"]);
+
+    let output = run(&["--theme", "neon"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("unknown theme 'neon'"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_theme_colors() {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_c2e"));
+    cmd.args(["--no-history", "--theme", "dracula"]);
+    cmd.env("TERM", "xterm-256color");
+    let mut c = spawn_with_options(
+        cmd,
+        Options {
+            timeout_ms: Some(Duration::from_secs(10).as_millis() as u64),
+            strip_ansi_escape_codes: false,
+        },
+    )
+    .unwrap();
+    c.exp_string("> ").unwrap();
+    c.send_line("int *p").unwrap();
+    c.exp_string("\x1b[38;2;241;250;140m").unwrap();
+    c.exp_string("pointer").unwrap();
+    c.exp_string("\x1b[38;2;255;184;108m").unwrap();
+    c.exp_string("p").unwrap();
+    c.exp_string("\x1b[3m").unwrap();
+    c.exp_string("\x1b[38;2;139;233;253m").unwrap();
+    c.exp_string("int").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+}
+
+#[test]
+fn test_hyperlinks() {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_c2e"));
+    cmd.args([
+        "--no-history",
+        "--link-template",
+        "https://example.com/{topic}",
+    ]);
+    cmd.env("TERM", "xterm-256color");
+    let mut c = spawn_with_options(
+        cmd,
+        Options {
+            timeout_ms: Some(Duration::from_secs(10).as_millis() as u64),
+            strip_ansi_escape_codes: false,
+        },
+    )
+    .unwrap();
+    c.exp_string("> ").unwrap();
+    c.send_line("int *p").unwrap();
+    c.exp_string("\x1b]8;;https://example.com/pointer\x1b\\")
+        .unwrap();
+    c.exp_string("pointer").unwrap();
+    c.exp_string("\x1b]8;;\x1b\\").unwrap();
+    c.exp_string("\x1b]8;;https://example.com/arithmetic_types\x1b\\")
+        .unwrap();
+    c.exp_string("int").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+
+    let output = run(&["--link-template", "https://example.com/"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("doesn't contain {topic}"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_format_option() {
+    let output = run(&["--format", "markdown"], "int *p\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(out_str, "a pointer named `p` to an `int`\n");
+
+    let output = run(&["--format", "html", "char c"], "");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a <span class=\"primitive_type\">char</span> named <span class=\"ident\">c</span>\n"
+    );
+
+    let output = run(&["--format", "rtf"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("unknown format 'rtf'"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_diagram_option() {
+    let output = run(&["--diagram", "ascii"], "int *p[2]\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "int *p[2]\n     ^--- p is\n      ^^^ an array of 2\n    ^---- pointers to\n^^^------ ints\n"
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_one_shot_arguments() {
+    // Each argument is explained in turn, sharing typedefs, without starting the REPL.
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_c2e"));
+    cmd.args(["typedef int T", "T (*fp)(void)"]);
+    let mut c = spawn_with_options(
+        cmd,
+        Options {
+            timeout_ms: Some(Duration::from_secs(10).as_millis() as u64),
+            strip_ansi_escape_codes: true,
+        },
+    )
+    .unwrap();
+    let output = c.exp_eof().unwrap();
+    assert!(!output.contains("> "), "REPL prompt was printed");
+    assert!(
+        !output.contains("NO WARRANTY"),
+        "license header was printed"
+    );
+    assert!(output.contains("a type named T defined as an int"));
+    assert!(
+        output
+            .contains("a pointer named fp to a function that takes no parameters and returns a T")
+    );
+
+    let output = run(&["int x", "int (*"], "");
+    assert!(!output.status.success());
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(out_str, "an int named x\n");
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("Error(s) parsing declaration"),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_file_input() {
+    let path = std::env::temp_dir().join(format!("c2e-test-{}.h", std::process::id()));
+    std::fs::write(
+        &path,
+        "#include <stddef.h>\n\n/* Copies memory */\nvoid *memcpy(void *dst, const void *src, unsigned long n);\nint (*y;\nint x;\n",
+    )
+    .unwrap();
+    for args in [
+        vec!["-f", path.to_str().unwrap()],
+        vec![path.to_str().unwrap()],
+    ] {
+        let output = run(&args, "");
+        assert!(!output.status.success());
+        let out_str = str::from_utf8(&output.stdout).unwrap();
+        assert_eq!(
+            out_str,
+            "4: a function named memcpy that takes (a pointer named dst to a void, a pointer named src to a const void, and an unsigned long named n) and returns a pointer to a void\n\
+             6: an int named x\n"
+        );
+        let err_str = str::from_utf8(&output.stderr).unwrap();
+        assert!(err_str.contains("line 5, "), "wrong output on stderr");
+    }
+    std::fs::remove_file(&path).unwrap();
+
+    let output = run(&["--file", "/nonexistent/decls.h"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("/nonexistent/decls.h: "),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_color_option() {
+    let run_with_env = |args: &[&str], env: &[(&str, &str)]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_c2e"))
+            .args(args)
+            .arg("int x")
+            .env_remove("NO_COLOR")
+            .env_remove("CLICOLOR_FORCE")
+            .envs(env.iter().copied())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let plain = "an int named x\n";
+    assert_eq!(run_with_env(&[], &[]), plain);
+    assert_ne!(run_with_env(&["--color=always"], &[]), plain);
+    assert_eq!(
+        run_with_env(&["--color", "always"], &[("NO_COLOR", "1")]),
+        run_with_env(&["--color=always"], &[])
+    );
+    assert_ne!(run_with_env(&[], &[("CLICOLOR_FORCE", "1")]), plain);
+    assert_eq!(run_with_env(&[], &[("CLICOLOR_FORCE", "0")]), plain);
+    assert_eq!(
+        run_with_env(&[], &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]),
+        plain
+    );
+    assert_eq!(
+        run_with_env(&["--color=never"], &[("CLICOLOR_FORCE", "1")]),
+        plain
+    );
+
+    // Output to a terminal is colored unless disabled.
+    for (args, env) in [
+        (["--color", "never"], None),
+        (["--color", "auto"], Some("1")),
+    ] {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_c2e"));
+        cmd.arg("--no-history")
+            .args(args)
+            .env("TERM", "xterm-256color");
+        if let Some(value) = env {
+            cmd.env("NO_COLOR", value);
+        }
+        let mut c = spawn_with_options(
+            cmd,
+            Options {
+                timeout_ms: Some(Duration::from_secs(10).as_millis() as u64),
+                strip_ansi_escape_codes: false,
+            },
+        )
+        .unwrap();
+        c.exp_string("> ").unwrap();
+        c.send_line("int x").unwrap();
+        // Colored output would have escape codes around "int" and "x".
+        c.exp_string("an int named x").unwrap();
+        kill(c);
+    }
+}
+
+#[test]
+fn test_config_theme() {
+    let dir = std::env::temp_dir().join(format!("c2e-test-config-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("c2e")).unwrap();
+    std::fs::write(
+        dir.join("c2e").join("config"),
+        "# Custom colors\ntheme = high-contrast\nident = #ff8700 italic\nnumber = 33\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_c2e"))
+        .args(["--color=always", "int x[2]"])
+        .env("XDG_CONFIG_HOME", &dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    // The identifier is italic instead of bold, and the number and array keep the theme's style.
+    assert!(
+        out_str.contains("\x1b[3m\x1b[38;2;255;135;0mx"),
+        "{out_str:?}"
+    );
+    assert!(out_str.contains("\x1b[38;5;33m2"), "{out_str:?}");
+    assert!(out_str.contains("\x1b[1m\x1b[38;5;46marray"), "{out_str:?}");
+
+    let bad = dir.join("bad");
+    std::fs::write(&bad, "theme = dracula\nident = bold red blue\n").unwrap();
+    let output = run(&["--config", bad.to_str().unwrap(), "int x"], "");
+    assert!(!output.status.success());
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("line 2: more than one color for ident"),
+        "wrong output on stderr"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_history() {
+    let dir = std::env::temp_dir().join(format!("c2e-test-history-{}", std::process::id()));
+    let spawn_in = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_c2e"));
+        cmd.args(args).env("XDG_DATA_HOME", &dir);
+        spawn_with_options(
+            cmd,
+            Options {
+                timeout_ms: Some(Duration::from_secs(10).as_millis() as u64),
+                strip_ansi_escape_codes: true,
+            },
+        )
+        .unwrap()
+    };
+
+    let mut c = spawn_in(&[]);
+    c.exp_string("> ").unwrap();
+    c.send_line("char **argv").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+    let history = std::fs::read_to_string(dir.join("c2e").join("history")).unwrap();
+    assert!(history.contains("char **argv"), "history wasn't saved");
+
+    // The previous session's line is recalled with the up arrow.
+    let mut c = spawn_in(&[]);
+    c.exp_string("> ").unwrap();
+    c.send("\x1b[A").unwrap();
+    c.send_line("").unwrap();
+    c.exp_string("named argv").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+
+    // With --no-history, lines are neither recalled nor saved.
+    std::fs::remove_dir_all(&dir).unwrap();
+    let mut c = spawn_in(&["--no-history"]);
+    c.exp_string("> ").unwrap();
+    c.send_line("int x").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+    assert!(!dir.exists(), "history was saved with --no-history");
+}
+
+#[test]
+fn test_multiline_input() {
+    let mut c = spawn(false);
+    c.exp_string("> ").unwrap();
+    c.send_line("typedef void (*handler_t)(int sig,").unwrap();
+    c.exp_string("... ").unwrap();
+    c.send_line("  const char *name)").unwrap();
+    c.exp_string(
+        "a type named handler_t defined as a pointer to a function that takes (an int named sig \
+         and a pointer named name to a const char) and returns a void",
+    )
+    .unwrap();
+    c.exp_string("> ").unwrap();
+
+    // A line without any unclosed parentheses may still end too soon.
+    c.send_line("const").unwrap();
+    c.exp_string("... ").unwrap();
+    c.send_line("char *s").unwrap();
+    c.exp_string("a pointer named s to a const char").unwrap();
+    c.exp_string("> ").unwrap();
+
+    // Brackets in literals and comments aren't counted.
+    c.send_line("char c = '(';").unwrap();
+    c.exp_string("a char named c initialized to '('").unwrap();
+    c.exp_string("> ").unwrap();
+    c.send_line("int x; /* [ */").unwrap();
+    c.exp_string("an int named x").unwrap();
+    c.exp_string("> ").unwrap();
+
+    // An empty line ends the input even if it's incomplete.
+    c.send_line("long (*fp)(").unwrap();
+    c.exp_string("... ").unwrap();
+    c.send_line("").unwrap();
+    c.exp_string("Error(s) parsing declaration").unwrap();
+    c.exp_string("> ").unwrap();
+    kill(c);
+}
+
+#[test]
+fn test_typedefs_command() {
+    let output = run(
+        &[],
+        "@typedefs\n\
+              typedef struct { int x, y; } point, *point_ptr;\n\
+              typedef point_ptr (*getter)(void);\n\
+              typedef int point;\n\
+              @typedefs\n",
+    );
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(out_str.lines().next(), Some("No types have been declared."));
+    // Each type is explained with its most recent declaration, in the order they were declared.
+    assert_eq!(
+        out_str.lines().rev().take(3).collect::<Vec<_>>(),
+        [
+            "a type named point defined as an int",
+            "a type named getter defined as a pointer to a function that takes no parameters and returns a point_ptr",
+            "a type named point_ptr defined as a pointer to a struct containing an int named x and an int named y",
+        ]
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_reset_command() {
+    let output = run(&[], "typedef char T\nT x\n@reset\n@typedefs\nT y\n@clear\n");
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        "a type named T defined as a char\n\
+         a T named x\n\
+         No types have been declared.\n"
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("Error(s) parsing declaration"),
+        "T was still known after @reset"
+    );
+}
+
+#[test]
+fn test_load_command() {
+    let path = std::env::temp_dir().join(format!("c2e-test-load-{}.h", std::process::id()));
+    std::fs::write(
+        &path,
+        "#pragma once\n#define MAX 8\ntypedef struct list { int v; } list_t;\nenum color { RED = 2, GREEN };\n",
+    )
+    .unwrap();
+    let output = run(
+        &[],
+        &format!(
+            "@load {}\nconst list_t *items[MAX + GREEN]\n@load /nonexistent/c2e.h\n",
+            path.display()
+        ),
+    );
+    std::fs::remove_file(&path).unwrap();
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        format!(
+            "Loaded 2 declarations from {}\n\
+             an array named items of 11 pointers to const list_ts\n",
+            path.display()
+        )
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("/nonexistent/c2e.h: "),
+        "wrong output on stderr"
+    );
+}
+
+#[test]
+fn test_session_commands() {
+    let path = std::env::temp_dir().join(format!("c2e-test-session-{}.h", std::process::id()));
+    let output = run(
+        &["--lang", "es", "--std", "c99", "--profile", "posix"],
+        &format!(
+            "#define N 3\ntypedef struct {{ int x; }} point;\ntypedef point tri[N];\n@save {}\n",
+            path.display()
+        ),
+    );
+    assert!(output.stderr.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "// c2e session\n\
+         // verbosity = normal\n\
+         // audience = standard\n\
+         // lang = es\n\
+         // std = c99\n\
+         // cpp = false\n\
+         // lenient = false\n\
+         // profiles = stdint, posix\n\
+         #define N 3\n\
+         typedef struct { int x; } point;\n\
+         typedef point tri[N];\n"
+    );
+
+    // Restoring replaces the declared types and the settings.
+    let output = run(
+        &[],
+        &format!(
+            "typedef int T;\n@restore {}\ntri t\nssize_t n\nT u\n",
+            path.display()
+        ),
+    );
+    std::fs::remove_file(&path).unwrap();
+    let out_str = str::from_utf8(&output.stdout).unwrap();
+    assert_eq!(
+        out_str,
+        format!(
+            "a type named T defined as an int\n\
+             Restored session from {}\n\
+             un tri llamado t\n\
+             un ssize_t llamado n\n",
+            path.display()
+        )
+    );
+    let err_str = str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        err_str.contains("Error(s) parsing declaration"),
+        "T was still known after @restore"
+    );
+}
@@ -0,0 +1,141 @@
+# English phrasebook for c2e.
+#
+# Message IDs are the phrase IDs listed in `c2e::explainer::PHRASE_IDS`. Leading and trailing
+# spaces are significant, so they are written as {" "}. Messages which are left out are taken from
+# the base language the phrasebook is loaded on top of, which also provides the grammar rules for
+# articles, plurals, and agreement.
+
+# Whether adjectives and qualifiers follow the nouns they describe ("true" or "false")
+adjectives-after-nouns = false
+
+# Introduces the name of a noun, with a `feminine` form for languages with grammatical gender
+named = {" "}named{" "}
+    .feminine = {" "}named{" "}
+
+and = {" "}and{" "}
+final-and = , and{" "}
+
+# Nouns, with their plural forms as the `plural` attribute
+pointer = pointer
+    .plural = pointers
+reference = reference
+    .plural = references
+block = block
+    .plural = blocks
+array = array
+    .plural = arrays
+function = function
+    .plural = functions
+value = {" "}value
+    .plural = {" "}values
+type = type
+unnamed = unnamed
+thread-local = thread-local
+forward-declaration-of = forward declaration of{" "}
+
+to = {" "}to{" "}
+to-member-of = {" "}to a member of{" "}
+    .plural = {" "}to members of{" "}
+that-is = {" "}that is{" "}
+    .plural = {" "}that are{" "}
+of = {" "}of{" "}
+at-least = at least{" "}
+that-takes = {" "}that takes{" "}
+    .plural = {" "}that take{" "}
+and-returns = {" "}and returns{" "}
+    .plural = {" "}and return{" "}
+taking = {" "}taking{" "}
+and-returning = {" "}and returning{" "}
+that-takes-no-parameters = {" "}that takes no parameters
+    .plural = {" "}that take no parameters
+taking-no-parameters = {" "}taking no parameters
+variable-arguments = a variable number of arguments
+further-arguments = a variable number of further arguments
+parameters-start = (
+parameters-end = )
+defined-as = {" "}defined as{" "}
+variable-length = {" "}(variable length)
+with-the-same-type-as = {" "}with the same type as{" "}
+
+variable = variable
+signed-integer = signed integer
+unsigned-integer = unsigned integer
+
+# Widths of bit-precise integers and bit-fields, with the text before and after the width as
+# the `before` and `after` attributes
+bits =
+    .before = {""}
+    .after = -bit
+bit-field = bit-field
+in = {" "}in{" "}
+that-can-hold-values = {" "}that can hold values{" "}
+
+initialized-to = {" "}initialized to{" "}
+initialized-with-an-empty-list = {" "}initialized with an empty list
+initialized-with-a-list-of = {" "}initialized with a list of{" "}
+with-designators-for = , with designators for{" "}
+
+implicitly = implicitly{" "}
+with-internal-linkage = with internal linkage
+defined-elsewhere = defined elsewhere
+stored-in-register = stored in register{" "}
+with-assembler-name = with assembler name{" "}
+commonly-a-string = commonly a string
+commonly-an-array-of-strings = commonly an array of strings
+commonly-a-callback = commonly a callback with a context pointer
+call-it-as = ; call it as{" "}
+or-simply = {" "}or simply{" "}
+and-assign-it-with = , and assign it with{" "}
+may-be-modified = {" "}may be modified
+    .plural = {" "}may be modified
+but = , but{" "}
+may-not = {" "}may not
+    .plural = {" "}may not
+may-not-be-modified = {" "}may not be modified
+    .plural = {" "}may not be modified
+
+containing = {" "}containing{" "}
+containing-no-members = {" "}containing no members
+with-values = {" "}with values{" "}
+
+# Descriptions of attributes, with the keyword as the value and the text around it as the
+# `before` and `after` attributes
+packed = packed
+    .before = {""}
+    .after = {""}
+aligned-to = aligned
+    .before = {""}
+    .after = {" "}to{" "}
+bytes = {" "}bytes
+maximally-aligned = aligned
+    .before = maximally{" "}
+    .after = {""}
+deprecated = deprecated
+    .before = {""}
+    .after = {""}
+possibly-unused = unused
+    .before = possibly{" "}
+    .after = {""}
+non-returning = non-returning
+    .before = {""}
+    .after = {""}
+result-must-be-used = used
+    .before = result must be{" "}
+    .after = {""}
+weakly-linked = linked
+    .before = weakly{" "}
+    .after = {""}
+imported-from-a-dll = imported
+    .before = {""}
+    .after = {" "}from a DLL
+exported-from-a-dll = exported
+    .before = {""}
+    .after = {" "}from a DLL
+
+# Asides of verbose explanations
+target-cannot-be-modified = the value it points to cannot be modified through it
+pointer-cannot-be-changed = the pointer itself cannot be changed to point elsewhere
+value-cannot-be-modified = its value cannot be modified after initialization
+no-other-pointer-accesses-target = no other pointer is used to access the object it points to
+accesses-are-never-optimized-away = accesses are never optimized away, since the value may change unexpectedly
+address-cannot-be-taken = its address cannot be taken
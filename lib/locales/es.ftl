@@ -0,0 +1,128 @@
+# Spanish phrasebook for c2e. See en.ftl for a description of the format.
+
+adjectives-after-nouns = true
+
+named = {" "}llamado{" "}
+    .feminine = {" "}llamada{" "}
+
+and = {" "}y{" "}
+final-and = {" "}y{" "}
+
+pointer = puntero
+    .plural = punteros
+reference = referencia
+    .plural = referencias
+block = bloque
+    .plural = bloques
+array = arreglo
+    .plural = arreglos
+function = función
+    .plural = funciones
+value = {" "}valor
+    .plural = {" "}valores
+type = tipo
+unnamed = anónimo
+thread-local = local al hilo
+forward-declaration-of = declaración adelantada de{" "}
+
+to = {" "}a{" "}
+to-member-of = {" "}a un miembro de{" "}
+    .plural = {" "}a miembros de{" "}
+that-is = {" "}que es{" "}
+    .plural = {" "}que son{" "}
+of = {" "}de{" "}
+at-least = al menos{" "}
+that-takes = {" "}que recibe{" "}
+    .plural = {" "}que reciben{" "}
+and-returns = {" "}y devuelve{" "}
+    .plural = {" "}y devuelven{" "}
+taking = {" "}que recibe{" "}
+and-returning = {" "}y devuelve{" "}
+that-takes-no-parameters = {" "}que no recibe parámetros
+    .plural = {" "}que no reciben parámetros
+taking-no-parameters = {" "}que no recibe parámetros
+variable-arguments = un número variable de argumentos
+further-arguments = un número variable de argumentos adicionales
+parameters-start = (
+parameters-end = )
+defined-as = {" "}definido como{" "}
+variable-length = {" "}(de longitud variable)
+with-the-same-type-as = {" "}con el mismo tipo que{" "}
+
+variable = variable
+signed-integer = entero con signo
+unsigned-integer = entero sin signo
+
+bits =
+    .before = de{" "}
+    .after = {" "}bits
+bit-field = campo de bits
+in = {" "}en{" "}
+that-can-hold-values = {" "}que puede contener valores{" "}
+
+initialized-to = {" "}con valor inicial{" "}
+initialized-with-an-empty-list = {" "}con una lista vacía como valor inicial
+initialized-with-a-list-of = {" "}con valor inicial una lista de{" "}
+with-designators-for = , con designadores para{" "}
+
+implicitly = implícitamente{" "}
+with-internal-linkage = con enlace interno
+defined-elsewhere = definido en otro lugar
+stored-in-register = almacenado en el registro{" "}
+with-assembler-name = con nombre de ensamblador{" "}
+commonly-a-string = normalmente una cadena
+commonly-an-array-of-strings = normalmente un arreglo de cadenas
+commonly-a-callback = normalmente una función de retrollamada con un puntero de contexto
+call-it-as = ; se llama como{" "}
+or-simply = {" "}o simplemente{" "}
+and-assign-it-with = , y se asigna con{" "}
+may-be-modified = {" "}puede modificarse
+    .plural = {" "}pueden modificarse
+but = , pero{" "}
+may-not = {" "}no
+    .plural = {" "}no
+may-not-be-modified = {" "}no puede modificarse
+    .plural = {" "}no pueden modificarse
+
+containing = {" "}que contiene{" "}
+containing-no-members = {" "}sin miembros
+with-values = {" "}con valores{" "}
+
+packed = sin relleno
+    .before = {""}
+    .after = {""}
+aligned-to = alineación
+    .before = con{" "}
+    .after = {" "}de{" "}
+bytes = {" "}bytes
+maximally-aligned = alineación
+    .before = con{" "}
+    .after = {" "}máxima
+deprecated = en desuso
+    .before = {""}
+    .after = {""}
+possibly-unused = sin usar
+    .before = posiblemente{" "}
+    .after = {""}
+non-returning = sin retorno
+    .before = {""}
+    .after = {""}
+result-must-be-used = usarse
+    .before = el resultado debe{" "}
+    .after = {""}
+weakly-linked = enlace débil
+    .before = con{" "}
+    .after = {""}
+imported-from-a-dll = importación
+    .before = con{" "}
+    .after = {" "}desde una DLL
+exported-from-a-dll = exportación
+    .before = con{" "}
+    .after = {" "}desde una DLL
+
+target-cannot-be-modified = el valor al que apunta no puede modificarse a través de él
+pointer-cannot-be-changed = el propio puntero no puede cambiarse para apuntar a otro lugar
+value-cannot-be-modified = su valor no puede modificarse después de la inicialización
+no-other-pointer-accesses-target = ningún otro puntero se usa para acceder al objeto al que apunta
+accesses-are-never-optimized-away = los accesos nunca se eliminan al optimizar, ya que el valor puede cambiar inesperadamente
+address-cannot-be-taken = no se puede obtener su dirección
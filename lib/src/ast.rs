@@ -21,34 +21,153 @@ use core::{
 use alloc::{boxed::Box, vec::Vec};
 use enumflags2::BitFlags;
 
+// Each flag corresponds to an independent specifier or property of the declaration.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Declaration<'src> {
     pub base_type: QualifiedType<'src>,
+    pub storage_class: Option<StorageClass>,
+    /// Whether the declaration has the `_Thread_local` storage duration specifier.
+    pub thread_local: bool,
+    /// Whether the declaration has the `inline` function specifier.
+    pub inline: bool,
     pub declarator: Declarator<'src>,
+    pub initializer: Option<Initializer<'src>>,
+    /// Source text of the function body, including the braces, if this is a function definition
+    pub body: Option<&'src str>,
+    pub attributes: Vec<Attribute<'src>>,
+    /// Name given by a GNU asm label, like `asm("r11")`. This is the register of an explicit
+    /// register variable, or the assembler symbol name of anything else.
+    pub asm_label: Option<&'src str>,
+    /// Whether the type was omitted and defaults to `int`, as for a K&R-style parameter which
+    /// is named in the identifier list but has no declaration.
+    pub implicit_int: bool,
+    /// Whether this is a forward declaration of a record's tag, like `struct node;`, which
+    /// declares nothing but the tag.
+    pub forward_declaration: bool,
 }
 
-// Convert from a tuple `(Type, Declarator)` to a `Declaration`
-impl<'src> From<(QualifiedType<'src>, Declarator<'src>)> for Declaration<'src> {
-    fn from((base_type, declarator): (QualifiedType<'src>, Declarator<'src>)) -> Self {
-        Declaration {
+impl<'src> Declaration<'src> {
+    /// Creates a new declaration with no storage class.
+    #[must_use]
+    pub fn new(base_type: QualifiedType<'src>, declarator: Declarator<'src>) -> Self {
+        Self {
             base_type,
+            storage_class: None,
+            thread_local: false,
+            inline: false,
             declarator,
+            initializer: None,
+            body: None,
+            attributes: Vec::new(),
+            asm_label: None,
+            implicit_int: false,
+            forward_declaration: false,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, parse_display::Display)]
+// Convert from a tuple `(Type, Declarator)` to a `Declaration`
+impl<'src> From<(QualifiedType<'src>, Declarator<'src>)> for Declaration<'src> {
+    fn from((base_type, declarator): (QualifiedType<'src>, Declarator<'src>)) -> Self {
+        Declaration::new(base_type, declarator)
+    }
+}
+
+/// Attribute attached to a declaration or type, such as `__attribute__((packed))`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute<'src> {
+    /// Name of the attribute as written, e.g. `aligned` or `__aligned__`
+    pub name: &'src str,
+    /// Source text of the attribute's arguments, excluding the parentheses, if any were given
+    pub args: Option<&'src str>,
+}
+
+impl<'src> Attribute<'src> {
+    /// Creates an attribute with no arguments.
+    #[must_use]
+    pub fn new(name: &'src str) -> Self {
+        Self { name, args: None }
+    }
+}
+
+/// Initial value given to a declared object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Initializer<'src> {
+    /// A single expression, stored as its source text
+    Expr(&'src str),
+    /// A brace-enclosed list of initializers, e.g. `{1, 2, 3}` or `{.x = 1, .y = 2}`
+    List(Vec<ListInitializer<'src>>),
+}
+
+/// Element of a brace-enclosed initializer list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListInitializer<'src> {
+    /// Designators of the element, e.g. `.x` and `[2]` in `.x[2] = 0`. Empty if the element is not
+    /// designated.
+    pub designators: Vec<Designator<'src>>,
+    pub initializer: Initializer<'src>,
+}
+
+/// Designator in an initializer list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Designator<'src> {
+    /// `.member`
+    Member(&'src str),
+    /// `[index]`, with the index stored as its source text
+    Index(&'src str),
+}
+
+/// Storage class specifier of a declaration
+#[derive(Debug, Copy, Clone, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "title case")]
+pub enum StorageClass {
+    /// `auto`
+    Auto,
+    /// `register`
+    Register,
+    /// `static`
+    Static,
+    /// `extern`
+    Extern,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, parse_display::Display)]
 pub enum Type<'src> {
     #[display("{0}")]
     Primitive(PrimitiveType),
-    #[display("{0} {1}")]
-    Record(RecordKind, &'src str),
+    #[display("{0}")]
+    Record(Record<'src>),
     /// Custom type, i.e. those defined by a `typedef` declaration.
     #[display("{0}")]
     Custom(&'src str),
+    /// `typeof` specifier, storing the source text of its operand, which may be an expression or
+    /// a type name.
+    #[display("typeof({0})")]
+    Typeof(&'src str),
+    #[display("{0}")]
+    BitInt(BitInt),
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, parse_display::Display)]
+/// Bit-precise integer type, i.e. `_BitInt(N)` or `unsigned _BitInt(N)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BitInt {
+    /// Width in bits
+    pub width: usize,
+    pub unsigned: bool,
+}
+
+/// Formats the type as it would be written in C, e.g. `unsigned _BitInt(24)`.
+impl Display for BitInt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.unsigned {
+            write!(f, "unsigned ")?;
+        }
+        write!(f, "_BitInt({})", self.width)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, parse_display::Display)]
 #[display("{0}{1}")]
 pub struct QualifiedType<'src>(pub TypeQualifiers, pub Type<'src>);
 
@@ -76,6 +195,8 @@ pub enum TypeQualifier {
     Volatile,
     /// `restrict`
     Restrict,
+    /// `_Atomic`
+    Atomic,
     /// `typedef`
     Typedef,
 }
@@ -142,6 +263,88 @@ pub enum RecordKind {
     Enum,
 }
 
+/// A `struct`, `union`, or `enum` type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'src> {
+    pub kind: RecordKind,
+    /// Tag of the record, or `None` if the record is anonymous.
+    pub name: Option<&'src str>,
+    /// Body of the record, if this is a definition rather than a reference.
+    pub body: Option<RecordBody<'src>>,
+    /// Attributes given between the `struct`, `union`, or `enum` keyword and the tag
+    pub attributes: Vec<Attribute<'src>>,
+}
+
+impl<'src> Record<'src> {
+    /// Creates a reference to a named record with no body, like `struct foo`.
+    #[must_use]
+    pub fn named(kind: RecordKind, name: &'src str) -> Self {
+        Self {
+            kind,
+            name: Some(name),
+            body: None,
+            attributes: Vec::new(),
+        }
+    }
+}
+
+/// Formats the record as its kind followed by its tag, if any, e.g. `struct foo`.
+impl Display for Record<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{} {name}", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+/// Body of a record definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordBody<'src> {
+    /// Member declarations of a `struct` or `union`
+    Members(Vec<Member<'src>>),
+    /// Enumeration constants of an `enum`
+    Enumerators(Vec<Enumerator<'src>>),
+}
+
+/// Member declaration in the body of a `struct` or `union`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member<'src> {
+    pub declaration: Declaration<'src>,
+    /// Width in bits, if the member is a bit-field
+    pub bit_width: Option<usize>,
+}
+
+impl<'src> From<Declaration<'src>> for Member<'src> {
+    fn from(declaration: Declaration<'src>) -> Self {
+        Self {
+            declaration,
+            bit_width: None,
+        }
+    }
+}
+
+/// Enumeration constant declared in the body of an `enum`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Enumerator<'src> {
+    pub name: &'src str,
+    /// Explicitly assigned value, if any
+    pub value: Option<i64>,
+}
+
+impl Enumerator<'_> {
+    /// Returns each of the given enumerators, which are declared in that order, along with its
+    /// value. An enumerator without an explicit value is one greater than the previous one, or 0
+    /// if it is the first.
+    pub fn values(enumerators: &[Self]) -> impl Iterator<Item = (&Self, i64)> {
+        enumerators.iter().scan(0, |next, enumerator| {
+            let value = enumerator.value.unwrap_or(*next);
+            *next = value.wrapping_add(1);
+            Some((enumerator, value))
+        })
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, parse_display::Display)]
 pub struct PrimitiveType(pub(crate) &'static str);
 
@@ -158,13 +361,75 @@ pub enum Declarator<'src> {
     Anonymous,
     Ident(&'src str),
     Ptr(Box<Declarator<'src>>, TypeQualifiers),
-    Array(Box<Declarator<'src>>, Option<usize>),
+    /// C++ lvalue reference, e.g. `int &r`
+    Reference(Box<Declarator<'src>>),
+    /// Clang block pointer, e.g. `void (^handler)(int)`
+    Block(Box<Declarator<'src>>, TypeQualifiers),
+    /// C++ pointer to a member of the named class, e.g. `int C::*m`
+    MemberPtr(Box<Declarator<'src>>, &'src str, TypeQualifiers),
+    Array(Box<Declarator<'src>>, ArraySize<'src>, ArrayQualifiers),
     Function {
         func: Box<Declarator<'src>>,
         params: Vec<Declaration<'src>>,
+        /// Whether the parameter list ends with `...`, meaning the function takes a variable
+        /// number of further arguments
+        variadic: bool,
+        /// Calling convention given by a keyword such as `__stdcall`, if any
+        calling_convention: Option<CallingConvention>,
+        /// Qualifiers of a C++ member function, e.g. the `const` in `int (C::*f)(int) const`
+        member_qualifiers: TypeQualifiers,
     },
 }
 
+/// Calling convention of a function, as specified by an MSVC keyword
+#[derive(Debug, Copy, Clone, PartialEq, Eq, parse_display::Display)]
+#[display(style = "lowercase")]
+pub enum CallingConvention {
+    /// `__cdecl`
+    Cdecl,
+    /// `__stdcall`
+    Stdcall,
+    /// `__fastcall`
+    Fastcall,
+}
+
+/// Size of an array declarator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArraySize<'src> {
+    /// No size given, i.e. `[]`
+    #[default]
+    Unspecified,
+    /// Size given by a constant expression which could be evaluated
+    Fixed(usize),
+    /// Size given by an expression which could not be evaluated, stored as its source text
+    Expr(&'src str),
+    /// Size given by a variable only known at runtime, i.e. a variable-length array
+    Variable(&'src str),
+    /// Variable-length array of unspecified size, i.e. `[*]`. Only valid in function prototypes.
+    VariableUnspecified,
+}
+
+impl From<usize> for ArraySize<'_> {
+    fn from(size: usize) -> Self {
+        ArraySize::Fixed(size)
+    }
+}
+
+impl From<Option<usize>> for ArraySize<'_> {
+    fn from(size: Option<usize>) -> Self {
+        size.map_or(ArraySize::Unspecified, ArraySize::Fixed)
+    }
+}
+
+/// Qualifiers which may appear inside the brackets of an array function parameter, such as
+/// `int arr[static const 10]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ArrayQualifiers {
+    pub qualifiers: TypeQualifiers,
+    /// Whether `static` is present, meaning the array has at least the given number of elements
+    pub is_static: bool,
+}
+
 impl Declarator<'_> {
     /// Returns the name of the identifier being declared, if any.
     #[must_use]
@@ -172,10 +437,49 @@ impl Declarator<'_> {
         match self {
             Declarator::Anonymous => None,
             Declarator::Ident(name) => Some(name),
-            Declarator::Ptr(decl, _) | Declarator::Array(decl, _) => decl.name(),
+            Declarator::Ptr(decl, _)
+            | Declarator::Reference(decl)
+            | Declarator::Block(decl, _)
+            | Declarator::MemberPtr(decl, ..)
+            | Declarator::Array(decl, ..) => decl.name(),
             Declarator::Function { func, .. } => func.name(),
         }
     }
+
+    /// Returns `true` if the declared identifier is a function, i.e. if the declarator closest to
+    /// the identifier is a function declarator.
+    #[must_use]
+    pub fn is_function(&self) -> bool {
+        match self {
+            Declarator::Anonymous | Declarator::Ident(_) => false,
+            Declarator::Ptr(decl, _)
+            | Declarator::Reference(decl)
+            | Declarator::Block(decl, _)
+            | Declarator::MemberPtr(decl, ..)
+            | Declarator::Array(decl, ..) => decl.is_function(),
+            Declarator::Function { func, .. } => {
+                matches!(**func, Declarator::Anonymous | Declarator::Ident(_)) || func.is_function()
+            }
+        }
+    }
+
+    /// Returns `true` if the declarator declares a variable-length array or a type derived
+    /// from one, such as a pointer to a variable-length array.
+    #[must_use]
+    pub fn is_variable_length(&self) -> bool {
+        match self {
+            Declarator::Anonymous | Declarator::Ident(_) => false,
+            Declarator::Array(_, ArraySize::Variable(_) | ArraySize::VariableUnspecified, _) => {
+                true
+            }
+            Declarator::Ptr(decl, _)
+            | Declarator::Reference(decl)
+            | Declarator::Block(decl, _)
+            | Declarator::MemberPtr(decl, ..)
+            | Declarator::Array(decl, ..) => decl.is_variable_length(),
+            Declarator::Function { func, .. } => func.is_variable_length(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +506,21 @@ mod tests {
         assert_eq!(qualifiers.to_string(), "const volatile");
     }
 
+    #[test]
+    fn record_display() {
+        assert_eq!(
+            Record::named(RecordKind::Struct, "foo").to_string(),
+            "struct foo"
+        );
+        let anonymous = Record {
+            kind: RecordKind::Union,
+            name: None,
+            body: Some(RecordBody::Members(vec![])),
+            attributes: vec![],
+        };
+        assert_eq!(anonymous.to_string(), "union");
+    }
+
     #[test]
     fn declarator_name() {
         let decl = Declarator::Ident("myVar");
@@ -213,12 +532,19 @@ mod tests {
         );
         assert_eq!(decl.name(), Some("ptrVar"));
 
-        let decl = Declarator::Array(Box::new(Declarator::Ident("arrVar")), Some(10));
+        let decl = Declarator::Array(
+            Box::new(Declarator::Ident("arrVar")),
+            ArraySize::Fixed(10),
+            ArrayQualifiers::default(),
+        );
         assert_eq!(decl.name(), Some("arrVar"));
 
         let decl = Declarator::Function {
             func: Box::new(Declarator::Ident("funcVar")),
             params: vec![],
+            variadic: false,
+            calling_convention: None,
+            member_qualifiers: TypeQualifiers::default(),
         };
         assert_eq!(decl.name(), Some("funcVar"));
 
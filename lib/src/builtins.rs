@@ -0,0 +1,164 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Typedefs provided by standard headers, which are known to the parser without having to be
+//! declared.
+
+/// Type defined by a standard header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinTypedef {
+    /// Name of the type, e.g. `uint8_t`
+    pub name: &'static str,
+    /// Description of the underlying type as a singular noun phrase, e.g. "unsigned 8-bit integer"
+    pub description: &'static str,
+}
+
+/// Shorthand for constructing a list of [`BuiltinTypedef`]s.
+macro_rules! typedefs {
+    ( $( $name:ident => $description:literal, )* ) => {
+        &[ $( BuiltinTypedef { name: stringify!($name), description: $description }, )* ]
+    };
+}
+
+/// Fixed-width integer types from `<stdint.h>`
+pub const STDINT: &[BuiltinTypedef] = typedefs![
+    int8_t => "signed 8-bit integer",
+    int16_t => "signed 16-bit integer",
+    int32_t => "signed 32-bit integer",
+    int64_t => "signed 64-bit integer",
+    uint8_t => "unsigned 8-bit integer",
+    uint16_t => "unsigned 16-bit integer",
+    uint32_t => "unsigned 32-bit integer",
+    uint64_t => "unsigned 64-bit integer",
+    int_least8_t => "minimum-width signed 8-bit integer",
+    int_least16_t => "minimum-width signed 16-bit integer",
+    int_least32_t => "minimum-width signed 32-bit integer",
+    int_least64_t => "minimum-width signed 64-bit integer",
+    uint_least8_t => "minimum-width unsigned 8-bit integer",
+    uint_least16_t => "minimum-width unsigned 16-bit integer",
+    uint_least32_t => "minimum-width unsigned 32-bit integer",
+    uint_least64_t => "minimum-width unsigned 64-bit integer",
+    int_fast8_t => "fastest minimum-width signed 8-bit integer",
+    int_fast16_t => "fastest minimum-width signed 16-bit integer",
+    int_fast32_t => "fastest minimum-width signed 32-bit integer",
+    int_fast64_t => "fastest minimum-width signed 64-bit integer",
+    uint_fast8_t => "fastest minimum-width unsigned 8-bit integer",
+    uint_fast16_t => "fastest minimum-width unsigned 16-bit integer",
+    uint_fast32_t => "fastest minimum-width unsigned 32-bit integer",
+    uint_fast64_t => "fastest minimum-width unsigned 64-bit integer",
+    intptr_t => "pointer-sized signed integer",
+    uintptr_t => "pointer-sized unsigned integer",
+    intmax_t => "greatest-width signed integer",
+    uintmax_t => "greatest-width unsigned integer",
+];
+
+/// Types from the standard C library headers other than `<stdint.h>`
+pub const STDC: &[BuiltinTypedef] = typedefs![
+    size_t => "unsigned object size",
+    ptrdiff_t => "signed pointer difference",
+    max_align_t => "maximally aligned type",
+    FILE => "file stream",
+    fpos_t => "file position",
+    va_list => "variable argument list",
+    time_t => "calendar time",
+    clock_t => "processor time",
+    sig_atomic_t => "atomically accessible integer",
+    wint_t => "wide character integer",
+    mbstate_t => "multibyte conversion state",
+    jmp_buf => "non-local jump buffer",
+];
+
+/// Types from POSIX headers
+pub const POSIX: &[BuiltinTypedef] = typedefs![
+    ssize_t => "signed object size",
+    off_t => "file offset",
+    pid_t => "process ID",
+    uid_t => "user ID",
+    gid_t => "group ID",
+    mode_t => "file mode",
+    dev_t => "device ID",
+    ino_t => "inode number",
+    nlink_t => "link count",
+    blksize_t => "block size",
+    blkcnt_t => "block count",
+    socklen_t => "socket address length",
+    pthread_t => "thread ID",
+    pthread_mutex_t => "mutex",
+    DIR => "directory stream",
+];
+
+/// Types from the Windows API headers
+pub const WINDOWS: &[BuiltinTypedef] = typedefs![
+    BYTE => "unsigned 8-bit integer",
+    WORD => "unsigned 16-bit integer",
+    DWORD => "unsigned 32-bit integer",
+    QWORD => "unsigned 64-bit integer",
+    BOOL => "boolean-valued signed 32-bit integer",
+    CHAR => "narrow character",
+    WCHAR => "UTF-16 code unit",
+    INT => "signed integer",
+    UINT => "unsigned integer",
+    LONG => "signed 32-bit integer",
+    ULONG => "unsigned 32-bit integer",
+    LONGLONG => "signed 64-bit integer",
+    ULONGLONG => "unsigned 64-bit integer",
+    SIZE_T => "unsigned object size",
+    HRESULT => "COM result code",
+    HANDLE => "object handle",
+    HWND => "window handle",
+    HINSTANCE => "instance handle",
+    HMODULE => "module handle",
+    LPVOID => "untyped pointer",
+    LPSTR => "narrow string pointer",
+    LPCSTR => "constant narrow string pointer",
+    LPWSTR => "wide string pointer",
+    LPCWSTR => "constant wide string pointer",
+];
+
+/// All lists of builtin typedefs
+const ALL: &[&[BuiltinTypedef]] = &[STDINT, STDC, POSIX, WINDOWS];
+
+/// Set of builtin typedefs which can be loaded into the parser's [`State`][crate::parser::State]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "lowercase")]
+pub enum Profile {
+    /// Fixed-width integer types from `<stdint.h>`
+    Stdint,
+    /// Types from the standard C library and POSIX headers
+    Posix,
+    /// Types from the standard C library and Windows API headers
+    Windows,
+}
+
+impl Profile {
+    /// All profiles, in the order they're listed in
+    pub const ALL: [Profile; 3] = [Profile::Stdint, Profile::Posix, Profile::Windows];
+
+    /// Returns the lists of typedefs provided by the profile.
+    #[must_use]
+    pub fn typedefs(self) -> &'static [&'static [BuiltinTypedef]] {
+        match self {
+            Profile::Stdint => &[STDINT],
+            Profile::Posix => &[STDC, POSIX],
+            Profile::Windows => &[STDC, WINDOWS],
+        }
+    }
+}
+
+/// Returns the builtin typedef with the given name, if there is one.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static BuiltinTypedef> {
+    ALL.iter()
+        .flat_map(|typedefs| typedefs.iter())
+        .find(|typedef| typedef.name == name)
+}
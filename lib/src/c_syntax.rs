@@ -0,0 +1,170 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Formatting of types and declarators as they're written in C
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::ast::{
+    ArrayQualifiers, ArraySize, Declaration, Declarator, QualifiedType, TypeQualifier,
+    TypeQualifiers,
+};
+
+/// Formats qualifiers followed by a space, or nothing if there are none.
+pub(crate) fn c_qualifiers(qualifiers: TypeQualifiers) -> String {
+    if qualifiers.is_empty() {
+        String::new()
+    } else {
+        format!("{qualifiers} ")
+    }
+}
+
+/// Formats the qualifiers of a C++ member function as they're written after its parameters,
+/// e.g. ` const`.
+pub(crate) fn c_member_qualifiers(qualifiers: TypeQualifiers) -> String {
+    if qualifiers.is_empty() {
+        String::new()
+    } else {
+        format!(" {qualifiers}")
+    }
+}
+
+/// Formats a qualified type as it's written in C, e.g. `const char`. The `typedef` keyword is
+/// left out, since it isn't part of the type.
+pub(crate) fn c_type(ty: &QualifiedType) -> String {
+    let mut qualifiers = ty.0;
+    qualifiers.remove(TypeQualifier::Typedef);
+    format!("{}{}", c_qualifiers(qualifiers), ty.1)
+}
+
+/// Formats the contents of an array declarator's brackets, e.g. `static const 10`.
+pub(crate) fn c_array_contents(size: &ArraySize, qualifiers: ArrayQualifiers) -> String {
+    let size = match size {
+        ArraySize::Unspecified => String::new(),
+        ArraySize::Fixed(len) => len.to_string(),
+        ArraySize::Expr(expr) | ArraySize::Variable(expr) => (*expr).to_string(),
+        ArraySize::VariableUnspecified => "*".to_string(),
+    };
+    let prefix = if qualifiers.is_static { "static " } else { "" };
+    format!("{prefix}{}{size}", c_qualifiers(qualifiers.qualifiers))
+        .trim_end()
+        .to_string()
+}
+
+/// Formats a function's parameter list, including the parentheses, e.g. `(int a, char *b)`, or
+/// `(const char *fmt, ...)` if the function is variadic. A function without parameters takes
+/// `(void)`, since `()` doesn't declare the parameters before C23.
+pub(crate) fn c_params(params: &[Declaration], variadic: bool) -> String {
+    if params.is_empty() && !variadic {
+        return "(void)".to_string();
+    }
+    let mut params: Vec<String> = params
+        .iter()
+        .map(|param| {
+            let ty = c_type(&param.base_type);
+            match c_declarator(&param.declarator).0 {
+                declarator if declarator.is_empty() => ty,
+                declarator => format!("{ty} {declarator}"),
+            }
+        })
+        .collect();
+    if variadic {
+        params.push("...".to_string());
+    }
+    format!("({})", params.join(", "))
+}
+
+/// Formats a declarator as it's written in C, e.g. `(*fp)(int)`. Also returns whether the
+/// outermost part of the declarator is a prefix like `*`, which must be parenthesized when
+/// followed by a suffix like `[10]`.
+pub(crate) fn c_declarator(declarator: &Declarator) -> (String, bool) {
+    match declarator {
+        Declarator::Anonymous => (String::new(), false),
+        Declarator::Ident(name) => ((*name).to_string(), false),
+        Declarator::Ptr(..)
+        | Declarator::Reference(..)
+        | Declarator::Block(..)
+        | Declarator::MemberPtr(..) => {
+            let (inner, prefix) = declarator_token(declarator);
+            let inner = c_declarator(inner).0;
+            (format!("{prefix}{inner}").trim_end().to_string(), true)
+        }
+        Declarator::Array(inner, ..) | Declarator::Function { func: inner, .. } => {
+            let (inner_text, is_prefix) = c_declarator(inner);
+            let inner_text = if is_prefix {
+                format!("({inner_text})")
+            } else {
+                inner_text
+            };
+            (
+                format!(
+                    "{}{inner_text}{}",
+                    calling_convention_token(declarator),
+                    declarator_token(declarator).1
+                ),
+                false,
+            )
+        }
+    }
+}
+
+/// Returns the declarator inside the outermost part of the given declarator, along with that
+/// part as it's written in C, e.g. `*const ` or `[10]`. Identifiers have no parts, so they're
+/// returned along with an empty string.
+pub(crate) fn declarator_token<'a, 'src>(
+    declarator: &'a Declarator<'src>,
+) -> (&'a Declarator<'src>, String) {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => (declarator, String::new()),
+        Declarator::Ptr(inner, qualifiers) => (inner, format!("*{}", c_qualifiers(*qualifiers))),
+        Declarator::Reference(inner) => (inner, "&".to_string()),
+        Declarator::Block(inner, qualifiers) => (inner, format!("^{}", c_qualifiers(*qualifiers))),
+        Declarator::MemberPtr(inner, class, qualifiers) => {
+            (inner, format!("{class}::*{}", c_qualifiers(*qualifiers)))
+        }
+        Declarator::Array(inner, size, qualifiers) => {
+            (inner, format!("[{}]", c_array_contents(size, *qualifiers)))
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            member_qualifiers,
+            ..
+        } => (
+            func,
+            format!(
+                "{}{}",
+                c_params(params, *variadic),
+                c_member_qualifiers(*member_qualifiers)
+            ),
+        ),
+    }
+}
+
+/// Returns the calling convention of a function declarator as it's written in C, before the
+/// function's name or parenthesized inner declarator, e.g. `__stdcall `. Other declarators have
+/// no calling convention, so an empty string is returned for them.
+pub(crate) fn calling_convention_token(declarator: &Declarator) -> String {
+    match declarator {
+        Declarator::Function {
+            calling_convention: Some(convention),
+            ..
+        } => format!("__{convention} "),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,277 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Compatibility with the commands of the classic `cdecl` program, such as
+//! `declare x as pointer to function (void) returning int`
+
+use alloc::{boxed::Box, format, string::String};
+
+use crate::{
+    ast::{Declaration, Declarator, Record, RecordKind, Type},
+    en2c::{En2cError, format_declaration, parse_english},
+    parser::check_name,
+    semantics::check_declaration,
+    standard::Standard,
+};
+
+/// Command in the syntax of `cdecl`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command<'src> {
+    /// `explain <declaration>`, which explains C source code as if it had been entered alone
+    Explain(&'src str),
+    /// `declare <name> as <phrase>`, which composes the declaration of a name
+    Declare(Declaration<'src>),
+    /// `cast <expression> into <phrase>`, which composes a cast of an expression to a type
+    Cast {
+        expression: &'src str,
+        type_name: Declaration<'src>,
+    },
+}
+
+impl Command<'_> {
+    /// Returns the C code composed by the command, e.g. `int (*x)(void)` for
+    /// `declare x as pointer to function returning int`, or [`None`] for
+    /// [`Command::Explain`], whose source code should be explained instead.
+    #[must_use]
+    pub fn to_c(&self) -> Option<String> {
+        match self {
+            Self::Explain(_) => None,
+            Self::Declare(decl) => Some(format_declaration(decl)),
+            Self::Cast {
+                expression,
+                type_name,
+            } => Some(format!("({}){expression}", format_declaration(type_name))),
+        }
+    }
+}
+
+/// Returns a copy of the declarator with its innermost, anonymous, declarator replaced by the
+/// given name.
+fn named<'src>(declarator: &Declarator<'src>, name: &'src str) -> Declarator<'src> {
+    let inner = |inner: &Declarator<'src>| Box::new(named(inner, name));
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => Declarator::Ident(name),
+        Declarator::Ptr(decl, qualifiers) => Declarator::Ptr(inner(decl), *qualifiers),
+        Declarator::Reference(decl) => Declarator::Reference(inner(decl)),
+        Declarator::Block(decl, qualifiers) => Declarator::Block(inner(decl), *qualifiers),
+        Declarator::MemberPtr(decl, class, qualifiers) => {
+            Declarator::MemberPtr(inner(decl), class, *qualifiers)
+        }
+        Declarator::Array(decl, size, qualifiers) => {
+            Declarator::Array(inner(decl), *size, *qualifiers)
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => Declarator::Function {
+            func: inner(func),
+            params: params.clone(),
+            variadic: *variadic,
+            calling_convention: *calling_convention,
+            member_qualifiers: *member_qualifiers,
+        },
+    }
+}
+
+/// Returns the part of a declarator which derives the declared type itself, i.e. the one nearest
+/// the name, or `None` if the declarator derives nothing.
+fn outermost_derivation<'a, 'src>(
+    declarator: &'a Declarator<'src>,
+) -> Option<&'a Declarator<'src>> {
+    let inner = match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => return None,
+        Declarator::Ptr(inner, _)
+        | Declarator::Reference(inner)
+        | Declarator::Block(inner, _)
+        | Declarator::MemberPtr(inner, ..)
+        | Declarator::Array(inner, ..)
+        | Declarator::Function { func: inner, .. } => inner,
+    };
+    outermost_derivation(inner).or(Some(declarator))
+}
+
+/// Returns `true` if a value can be cast to the given type, i.e. if it is `void` or a scalar
+/// type. Types declared by typedefs are assumed to be scalar.
+fn is_cast_target(type_name: &Declaration) -> bool {
+    match outermost_derivation(&type_name.declarator) {
+        None => !matches!(
+            type_name.base_type.1,
+            Type::Record(Record {
+                kind: RecordKind::Struct | RecordKind::Union,
+                ..
+            })
+        ),
+        Some(Declarator::Array(..) | Declarator::Function { .. }) => false,
+        Some(_) => true,
+    }
+}
+
+/// Splits the argument of a command into its first word, the keyword which must follow it, and
+/// the rest, returning the first word and the rest. `expected` describes the keyword in errors.
+fn split_at_keyword<'src>(
+    argument: &'src str,
+    keyword: &str,
+    expected: &'static str,
+) -> Result<(&'src str, &'src str), En2cError> {
+    let (first, rest) = argument
+        .split_once(char::is_whitespace)
+        .ok_or(En2cError::UnexpectedEnd(expected))?;
+    let rest = rest.trim_start();
+    let (second, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if second != keyword {
+        return Err(En2cError::Unexpected {
+            expected,
+            found: second.into(),
+        });
+    }
+    Ok((first, rest.trim_start()))
+}
+
+/// Parses a line as a `cdecl` command, if it starts with `explain`, `declare`, or `cast`.
+///
+/// Returns [`None`] if the line isn't a command, so it should be parsed as C source code.
+///
+/// # Errors
+///
+/// Returns an error if the line is a `declare` or `cast` command whose arguments are malformed
+/// or whose phrase doesn't describe a type, or describes one which can't exist, such as an array
+/// of functions. The name to declare must be an identifier which isn't a keyword, and the type to
+/// cast to must be `void` or a scalar type.
+pub fn parse_command(line: &str) -> Option<Result<Command<'_>, En2cError>> {
+    let line = line.trim();
+    let (keyword, argument) = line.split_once(char::is_whitespace)?;
+    let argument = argument.trim_start();
+    Some(match keyword {
+        "explain" => Ok(Command::Explain(argument)),
+        "declare" => split_at_keyword(argument, "as", "'as'").and_then(|(name, phrase)| {
+            check_name(name, Standard::default()).map_err(En2cError::InvalidName)?;
+            let mut decl = parse_english(phrase)?;
+            decl.declarator = named(&decl.declarator, name);
+            check_declaration(&decl)?;
+            Ok(Command::Declare(decl))
+        }),
+        "cast" => split_at_keyword(argument, "into", "'into'").and_then(|(expression, phrase)| {
+            let type_name = parse_english(phrase)?;
+            check_declaration(&type_name)?;
+            if !is_cast_target(&type_name) {
+                return Err(En2cError::NonScalarCast);
+            }
+            Ok(Command::Cast {
+                expression,
+                type_name,
+            })
+        }),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn commands() {
+        for (line, expected) in [
+            (
+                "declare x as pointer to function (void) returning int",
+                "int (*x)(void)",
+            ),
+            (
+                "declare fptab as array of pointer to function returning pointer to char",
+                "char *(*fptab[])(void)",
+            ),
+            (
+                "declare s as const pointer to const char",
+                "const char *const s",
+            ),
+            ("cast p into pointer to int", "(int *)p"),
+            ("cast p into void", "(void)p"),
+            ("cast e into enum color", "(enum color)e"),
+            (
+                "cast f into pointer to function taking int returning void",
+                "(void (*)(int))f",
+            ),
+        ] {
+            let command = parse_command(line).unwrap().unwrap();
+            assert_eq!(command.to_c().unwrap(), expected, "Wrong output for {line}");
+        }
+        assert_eq!(
+            parse_command("explain char *(*fptab[])(int)"),
+            Some(Ok(Command::Explain("char *(*fptab[])(int)")))
+        );
+        assert_eq!(parse_command("int explain"), None);
+        assert_eq!(parse_command("declare"), None);
+    }
+
+    #[test]
+    fn errors() {
+        for (line, expected) in [
+            (
+                "declare x pointer to int",
+                "expected 'as', but found 'pointer'",
+            ),
+            ("declare x", "expected 'as', but the phrase ended"),
+            (
+                "cast p as pointer to int",
+                "expected 'into', but found 'as'",
+            ),
+            ("declare x as pointer int", "expected 'to', but found 'int'"),
+            (
+                "declare x as array of function returning int",
+                "an array cannot contain functions; use an array of pointers to functions instead",
+            ),
+            (
+                "declare f as function returning function returning int",
+                "a function cannot return a function; return a pointer to a function instead",
+            ),
+            (
+                "declare f as function returning array of 3 int",
+                "a function cannot return an array; return a pointer to an array instead",
+            ),
+            (
+                "cast p into array of 3 void",
+                "an array cannot contain void, which has no size; use an array of pointers to void instead",
+            ),
+            (
+                "declare int as int",
+                "\"int\" is a keyword, so it cannot be used as a name",
+            ),
+            ("declare a-b as int", "\"a-b\" is not an identifier"),
+            (
+                "cast x into function returning int",
+                "a value can only be cast to void or to a scalar type, such as an integer or a pointer",
+            ),
+            (
+                "cast x into array of 3 int",
+                "a value can only be cast to void or to a scalar type, such as an integer or a pointer",
+            ),
+            (
+                "cast x into struct s",
+                "a value can only be cast to void or to a scalar type, such as an integer or a pointer",
+            ),
+        ] {
+            assert_eq!(
+                parse_command(line).unwrap().unwrap_err().to_string(),
+                expected,
+                "Wrong error for {line}"
+            );
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! Utilities for formatting highlighted text.
 
-use super::HighlightedText;
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use super::{Highlight, HighlightedText, HighlightedTextSegment, StyleMap};
+use crate::{explainer::Language, provenance::Step};
 
 pub trait Formatter {
     /// Formats the given [`HighlightedText`] into a destination writer.
@@ -11,6 +14,93 @@ pub trait Formatter {
     fn format(&self, dst: &mut impl core::fmt::Write, text: &HighlightedText) -> core::fmt::Result;
 }
 
+/// Dyn-compatible counterpart of [`Formatter`], for formatters which are chosen at runtime, e.g.
+/// as a `Box<dyn DynFormatter>`.
+///
+/// It's implemented for every [`Formatter`], and `dyn DynFormatter` is itself a [`Formatter`],
+/// so boxed formatters can be used wherever a [`Formatter`] is expected.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, explainer::explain_declaration, parser::parser};
+/// # use c2e::color::fmt::{DynFormatter, MarkdownFormatter, PlainFormatter};
+/// let markdown = false;
+/// let formatter: Box<dyn DynFormatter> = if markdown {
+///     Box::new(MarkdownFormatter::new())
+/// } else {
+///     Box::new(PlainFormatter::new())
+/// };
+/// let decls = parser().parse("int n").unwrap();
+/// assert_eq!(
+///     explain_declaration(&decls[0]).format_to_string(&*formatter),
+///     "an int named n"
+/// );
+/// ```
+pub trait DynFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the destination fails.
+    fn format_dyn(
+        &self,
+        dst: &mut dyn core::fmt::Write,
+        text: &HighlightedText,
+    ) -> core::fmt::Result;
+}
+
+impl<F: Formatter> DynFormatter for F {
+    fn format_dyn(
+        &self,
+        mut dst: &mut dyn core::fmt::Write,
+        text: &HighlightedText,
+    ) -> core::fmt::Result {
+        self.format(&mut dst, text)
+    }
+}
+
+impl Formatter for dyn DynFormatter + '_ {
+    fn format(&self, dst: &mut impl core::fmt::Write, text: &HighlightedText) -> core::fmt::Result {
+        self.format_dyn(dst, text)
+    }
+}
+
+impl Formatter for dyn DynFormatter + Send + Sync + '_ {
+    fn format(&self, dst: &mut impl core::fmt::Write, text: &HighlightedText) -> core::fmt::Result {
+        self.format_dyn(dst, text)
+    }
+}
+
+impl<F: Formatter + ?Sized> Formatter for Box<F> {
+    fn format(&self, dst: &mut impl core::fmt::Write, text: &HighlightedText) -> core::fmt::Result {
+        (**self).format(dst, text)
+    }
+}
+
+/// Names of the built-in formatters which [`by_name()`] creates
+pub const FORMATTER_NAMES: [&str; 9] = [
+    "plain", "ansi", "html", "markdown", "bbcode", "discord", "irc", "json", "ssml",
+];
+
+/// Creates the built-in formatter with the given name, which is one of [`FORMATTER_NAMES`], with
+/// its default settings. Returns `None` if there's no formatter with the name.
+#[must_use]
+pub fn by_name(name: &str) -> Option<Box<dyn DynFormatter + Send + Sync>> {
+    Some(match name {
+        "plain" => Box::new(PlainFormatter::new()),
+        "ansi" => Box::new(AnsiFormatter::new()),
+        "html" => Box::new(HtmlFormatter::new()),
+        "markdown" => Box::new(MarkdownFormatter::new()),
+        "bbcode" => Box::new(BbcodeFormatter::new()),
+        "discord" => Box::new(DiscordFormatter::new()),
+        "irc" => Box::new(IrcFormatter::new()),
+        "json" => Box::new(JsonFormatter::new()),
+        "ssml" => Box::new(SsmlFormatter::new()),
+        _ => return None,
+    })
+}
+
 /// Formatter which discards all formatting and returns plain text.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct PlainFormatter;
@@ -36,3 +126,927 @@ impl super::Formatter for PlainFormatter {
             .try_for_each(|segment| dst.write_str(&segment.text))
     }
 }
+
+/// Formatter which produces [SSML](https://www.w3.org/TR/speech-synthesis11/) for reading
+/// explanations aloud with a speech synthesizer or screen reader.
+///
+/// Identifiers are emphasized, numbers are read as cardinal numbers, and the text is paused
+/// around parameter lists and at line breaks so nested clauses are easier to follow. The
+/// characters surrounding parameter lists themselves aren't spoken.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SsmlFormatter;
+
+impl SsmlFormatter {
+    /// Creates a new `SsmlFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// Writes text with the characters which are special in XML, and therefore in HTML, escaped.
+fn write_xml_escaped(dst: &mut impl core::fmt::Write, text: &str) -> core::fmt::Result {
+    for c in text.chars() {
+        match c {
+            '&' => dst.write_str("&amp;")?,
+            '<' => dst.write_str("&lt;")?,
+            '>' => dst.write_str("&gt;")?,
+            '"' => dst.write_str("&quot;")?,
+            '\'' => dst.write_str("&apos;")?,
+            c => dst.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Writes SSML text with parentheses and line breaks replaced by pauses, and the rest escaped.
+fn write_ssml_with_pauses(dst: &mut impl core::fmt::Write, text: &str) -> core::fmt::Result {
+    let mut rest = text;
+    while let Some(i) = rest.find(['(', ')', '\n']) {
+        write_xml_escaped(dst, &rest[..i])?;
+        if rest.as_bytes()[i] == b'\n' {
+            dst.write_str(r#"<break strength="weak"/>"#)?;
+        } else {
+            dst.write_str(r#"<break strength="medium"/>"#)?;
+        }
+        rest = &rest[i + 1..];
+    }
+    write_xml_escaped(dst, rest)
+}
+
+impl super::Formatter for SsmlFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes a `<speak>` element containing the text, with identifiers in
+    /// `<emphasis>` elements, numbers in `<say-as>` elements, and `<break>` elements in place of
+    /// parentheses and line breaks.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        dst.write_str("<speak>")?;
+        for segment in text.iter() {
+            match segment.highlight {
+                super::Highlight::Ident => {
+                    dst.write_str("<emphasis>")?;
+                    write_xml_escaped(dst, &segment.text)?;
+                    dst.write_str("</emphasis>")?;
+                }
+                super::Highlight::Number if segment.text.bytes().all(|b| b.is_ascii_digit()) => {
+                    write!(
+                        dst,
+                        r#"<say-as interpret-as="cardinal">{}</say-as>"#,
+                        segment.text
+                    )?;
+                }
+                _ => write_ssml_with_pauses(dst, &segment.text)?,
+            }
+        }
+        dst.write_str("</speak>")
+    }
+}
+
+/// Formatter which produces Markdown, e.g. for pasting explanations into GitHub issues, chat
+/// messages, or course notes.
+///
+/// If the formatter was created [with a declaration][Self::with_declaration], the declaration is
+/// written in a fenced C code block before the explanation. Identifiers and types are written as
+/// inline code. Other text is written as-is.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownFormatter {
+    declaration: Option<String>,
+}
+
+impl MarkdownFormatter {
+    /// Creates a new `MarkdownFormatter` which writes only the explanation.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { declaration: None }
+    }
+
+    /// Creates a new `MarkdownFormatter` which writes the given C source code of the explained
+    /// declaration before the explanation.
+    #[must_use]
+    pub fn with_declaration(declaration: impl Into<String>) -> Self {
+        Self {
+            declaration: Some(declaration.into()),
+        }
+    }
+}
+
+/// Writes text with the characters which Markdown interprets as formatting or HTML escaped.
+fn write_markdown_text(dst: &mut impl core::fmt::Write, text: &str) -> core::fmt::Result {
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '~' | '[' | ']' | '<' | '>' | '&' | '#' | '!' | '|'
+        ) {
+            dst.write_char('\\')?;
+        }
+        dst.write_char(c)?;
+    }
+    Ok(())
+}
+
+impl super::Formatter for MarkdownFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes the declaration in a code fence, if there is one, followed by a
+    /// blank line and the text, with identifiers and types surrounded by backticks. Other text
+    /// has the characters which Markdown interprets as formatting escaped.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        if let Some(declaration) = &self.declaration {
+            // The fence must be longer than any run of backticks in the code.
+            let longest_run = declaration
+                .split(|c| c != '`')
+                .map(str::len)
+                .max()
+                .unwrap_or_default();
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            writeln!(dst, "{fence}c\n{}\n{fence}\n", declaration.trim_end())?;
+        }
+        for segment in text.iter() {
+            match segment.highlight {
+                super::Highlight::Ident
+                | super::Highlight::PrimitiveType
+                | super::Highlight::UserDefinedType => write!(dst, "`{}`", segment.text)?,
+                _ => write_markdown_text(dst, &segment.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Color of text in a terminal which supports ANSI escape codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// Color from the 256-color palette supported by most terminals
+    Fixed(u8),
+    /// 24-bit color, for terminals which support it
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    /// Writes the parameters of the escape code which set the foreground color to this color.
+    fn write_parameters(self, dst: &mut impl core::fmt::Write) -> core::fmt::Result {
+        let basic = match self {
+            Self::Black => 0,
+            Self::Red => 1,
+            Self::Green => 2,
+            Self::Yellow => 3,
+            Self::Blue => 4,
+            Self::Magenta => 5,
+            Self::Cyan => 6,
+            Self::White => 7,
+            Self::Fixed(n) => return write!(dst, "38;5;{n}"),
+            Self::Rgb(r, g, b) => return write!(dst, "38;2;{r};{g};{b}"),
+        };
+        write!(dst, "3{basic}")
+    }
+}
+
+/// Colors and styles used by an [`AnsiFormatter`] for each kind of highlight. Text with a
+/// highlight which has no color is written without one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnsiPalette {
+    pub qualifier: Option<AnsiColor>,
+    pub primitive_type: Option<AnsiColor>,
+    pub user_defined_type: Option<AnsiColor>,
+    pub identifier: Option<AnsiColor>,
+    pub number: Option<AnsiColor>,
+    pub quasi_keyword: Option<AnsiColor>,
+    pub styles: StyleMap,
+}
+
+impl AnsiPalette {
+    /// Palette used by the c2e command-line interface
+    pub const DEFAULT: Self = Self {
+        qualifier: Some(AnsiColor::Cyan),
+        primitive_type: Some(AnsiColor::Yellow),
+        user_defined_type: Some(AnsiColor::Magenta),
+        identifier: Some(AnsiColor::Red),
+        number: Some(AnsiColor::Blue),
+        quasi_keyword: Some(AnsiColor::Green),
+        styles: StyleMap::PLAIN,
+    };
+
+    /// Returns the color for the given highlight according to this palette.
+    #[must_use]
+    pub fn color_for_highlight(&self, highlight: super::Highlight) -> Option<AnsiColor> {
+        match highlight {
+            super::Highlight::None => None,
+            super::Highlight::Qualifier => self.qualifier,
+            super::Highlight::PrimitiveType => self.primitive_type,
+            super::Highlight::UserDefinedType => self.user_defined_type,
+            super::Highlight::Ident => self.identifier,
+            super::Highlight::Number => self.number,
+            super::Highlight::QuasiKeyword => self.quasi_keyword,
+        }
+    }
+}
+
+impl Default for AnsiPalette {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Template of the URLs of pages about the C language on [cppreference](https://cppreference.com),
+/// for [`reference_url()`]
+pub const CPPREFERENCE_URL_TEMPLATE: &str = "https://en.cppreference.com/w/c/language/{topic}";
+
+/// Returns the topic of the reference page about the term in the given segment, i.e. the last
+/// component of its URL on cppreference, or `None` if the segment isn't a primitive type or a
+/// known quasi-keyword. Quasi-keywords are recognized in each of the built-in languages.
+fn reference_topic(segment: &HighlightedTextSegment) -> Option<&'static str> {
+    match segment.highlight {
+        Highlight::PrimitiveType if segment.text == "void" => Some("types"),
+        Highlight::PrimitiveType => Some("arithmetic_types"),
+        Highlight::QuasiKeyword => {
+            let text = segment.text.as_str();
+            match text {
+                "struct" | "class" => return Some("struct"),
+                "union" => return Some("union"),
+                "enum" => return Some("enum"),
+                "bit-field" => return Some("bit_field"),
+                _ => {}
+            }
+            [Language::English, Language::Spanish]
+                .into_iter()
+                .map(Language::phrasebook)
+                .find_map(|phrasebook| {
+                    [
+                        (&phrasebook.pointer, "pointer"),
+                        (&phrasebook.reference, "reference"),
+                        (&phrasebook.array, "array"),
+                        (&phrasebook.function, "function_declaration"),
+                    ]
+                    .into_iter()
+                    .find(|(word, _)| text == word.singular || text == word.plural)
+                    .map(|(_, topic)| topic)
+                })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the URL of the reference page about the term in the given segment, made by replacing
+/// `{topic}` in the given template, e.g. [`CPPREFERENCE_URL_TEMPLATE`], with the page's topic.
+/// Only primitive types and quasi-keywords like "pointer" have reference pages.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::color::{Highlight, HighlightedTextSegment};
+/// # use c2e::color::fmt::{CPPREFERENCE_URL_TEMPLATE, reference_url};
+/// let segment = HighlightedTextSegment::new("pointers", Highlight::QuasiKeyword);
+/// assert_eq!(
+///     reference_url(CPPREFERENCE_URL_TEMPLATE, &segment).as_deref(),
+///     Some("https://en.cppreference.com/w/c/language/pointer"),
+/// );
+/// ```
+#[must_use]
+pub fn reference_url(template: &str, segment: &HighlightedTextSegment) -> Option<String> {
+    reference_topic(segment).map(|topic| template.replace("{topic}", topic))
+}
+
+/// Formatter which colors text using ANSI escape codes, for terminal programs which don't have a
+/// terminal library of their own.
+///
+/// Primitive types and quasi-keywords can also be made [OSC 8
+/// hyperlinks](https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feaf) to their
+/// [reference pages][reference_url()], which supported terminals let users click.
+#[derive(Debug, Clone, Default)]
+pub struct AnsiFormatter {
+    palette: AnsiPalette,
+    /// URL template for hyperlinks to reference pages, if they're enabled
+    link_template: Option<String>,
+}
+
+impl AnsiFormatter {
+    /// Creates a new `AnsiFormatter` with the [default palette][AnsiPalette::DEFAULT].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_palette(AnsiPalette::DEFAULT)
+    }
+
+    /// Creates a new `AnsiFormatter` with the given palette.
+    #[must_use]
+    pub const fn with_palette(palette: AnsiPalette) -> Self {
+        Self {
+            palette,
+            link_template: None,
+        }
+    }
+
+    /// Returns this formatter made to link terms to their reference pages, using the given URL
+    /// template as described for [`reference_url()`].
+    #[must_use]
+    pub fn with_hyperlinks(self, template: impl Into<String>) -> Self {
+        Self {
+            link_template: Some(template.into()),
+            ..self
+        }
+    }
+}
+
+impl super::Formatter for AnsiFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes each highlighted segment between an escape code which sets its
+    /// color and style and one which resets all attributes. If hyperlinks are enabled, segments
+    /// with reference pages are also written between OSC 8 sequences which open and close a link.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        for segment in text.iter().filter(|segment| !segment.text.is_empty()) {
+            let url = self
+                .link_template
+                .as_deref()
+                .and_then(|template| reference_url(template, segment));
+            if let Some(url) = &url {
+                write!(dst, "\x1b]8;;{url}\x1b\\")?;
+            }
+            self.format_segment(dst, segment)?;
+            if url.is_some() {
+                dst.write_str("\x1b]8;;\x1b\\")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AnsiFormatter {
+    /// Writes a single segment with the escape codes for its color and style.
+    fn format_segment(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        segment: &HighlightedTextSegment,
+    ) -> core::fmt::Result {
+        let color = self.palette.color_for_highlight(segment.highlight);
+        let style = self.palette.styles.style_for_highlight(segment.highlight);
+        if color.is_none() && style.is_plain() {
+            return dst.write_str(&segment.text);
+        }
+        let mut parameters = Vec::new();
+        for (enabled, parameter) in [
+            (style.bold, "1"),
+            (style.italic, "3"),
+            (style.underline, "4"),
+        ] {
+            if enabled {
+                parameters.push(String::from(parameter));
+            }
+        }
+        if let Some(color) = color {
+            let mut parameter = String::new();
+            color.write_parameters(&mut parameter)?;
+            parameters.push(parameter);
+        }
+        write!(dst, "\x1b[{}m{}\x1b[0m", parameters.join(";"), segment.text)
+    }
+}
+
+/// Formatter which produces [BBCode](https://www.bbcode.org/), for posting explanations on forums.
+///
+/// Highlighted text is colored with `[color]` tags, using colors which are readable on both
+/// light and dark themes, and identifiers are also made bold.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BbcodeFormatter;
+
+impl BbcodeFormatter {
+    /// Creates a new `BbcodeFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl super::Formatter for BbcodeFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes highlighted segments in `[color]` tags, with identifiers in
+    /// `[b]` tags as well.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        for segment in text.iter().filter(|segment| !segment.text.is_empty()) {
+            let color = match segment.highlight {
+                super::Highlight::None => {
+                    dst.write_str(&segment.text)?;
+                    continue;
+                }
+                super::Highlight::Qualifier => "#0087af",
+                super::Highlight::PrimitiveType => "#af8700",
+                super::Highlight::UserDefinedType => "#af00af",
+                super::Highlight::Ident => "#d70000",
+                super::Highlight::Number => "#005fd7",
+                super::Highlight::QuasiKeyword => "#008700",
+            };
+            if segment.highlight == super::Highlight::Ident {
+                write!(dst, "[b][color={color}]{}[/color][/b]", segment.text)?;
+            } else {
+                write!(dst, "[color={color}]{}[/color]", segment.text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formatter which produces HTML, for embedding explanations in web pages.
+///
+/// Highlighted text is wrapped in `<span>` elements whose class is the name of the highlight in
+/// `snake_case`, e.g. `<span class="primitive_type">`, so it can be styled with CSS. Characters
+/// which are special in HTML are escaped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlFormatter;
+
+impl HtmlFormatter {
+    /// Creates a new `HtmlFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl super::Formatter for HtmlFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes highlighted segments in `<span>` elements with classes named
+    /// after their highlights.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        for segment in text.iter().filter(|segment| !segment.text.is_empty()) {
+            if segment.highlight == super::Highlight::None {
+                write_xml_escaped(dst, &segment.text)?;
+            } else {
+                write!(dst, r#"<span class="{}">"#, segment.highlight)?;
+                write_xml_escaped(dst, &segment.text)?;
+                dst.write_str("</span>")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formatter which produces Markdown as rendered by Discord, for bots which post explanations.
+///
+/// Discord doesn't support colored text, so highlights are approximated: identifiers are bold,
+/// types are inline code, qualifiers are italic, and quasi-keywords like `pointer` are
+/// underlined. Characters which Discord would interpret as formatting are escaped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiscordFormatter;
+
+impl DiscordFormatter {
+    /// Creates a new `DiscordFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// Writes text with the characters which Discord interprets as formatting escaped.
+fn write_discord_text(dst: &mut impl core::fmt::Write, text: &str) -> core::fmt::Result {
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>') {
+            dst.write_char('\\')?;
+        }
+        dst.write_char(c)?;
+    }
+    Ok(())
+}
+
+impl super::Formatter for DiscordFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation surrounds highlighted segments with the markers of the formatting
+    /// which approximates their highlights.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        for segment in text.iter().filter(|segment| !segment.text.is_empty()) {
+            let marker = match segment.highlight {
+                super::Highlight::None | super::Highlight::Number => "",
+                super::Highlight::PrimitiveType | super::Highlight::UserDefinedType => {
+                    // Text in inline code is shown as-is, so it isn't escaped.
+                    write!(dst, "`{}`", segment.text)?;
+                    continue;
+                }
+                super::Highlight::Qualifier => "*",
+                super::Highlight::Ident => "**",
+                super::Highlight::QuasiKeyword => "__",
+            };
+            dst.write_str(marker)?;
+            write_discord_text(dst, &segment.text)?;
+            dst.write_str(marker)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formatter which produces text with mIRC color codes, for IRC bots.
+///
+/// Each highlighted segment is preceded by a color code and followed by the code which ends the
+/// color. Colors are always written with two digits, so numbers in the text aren't mistaken for
+/// part of the code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IrcFormatter;
+
+impl IrcFormatter {
+    /// Creates a new `IrcFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl super::Formatter for IrcFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes highlighted segments between a color code (`\x03` followed by
+    /// the color's number) and a bare `\x03`.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        for segment in text.iter().filter(|segment| !segment.text.is_empty()) {
+            // Colors of the standard mIRC palette which are readable on light and dark themes
+            let color = match segment.highlight {
+                super::Highlight::None => {
+                    dst.write_str(&segment.text)?;
+                    continue;
+                }
+                super::Highlight::Qualifier => 10,      // Cyan
+                super::Highlight::PrimitiveType => 7,   // Orange
+                super::Highlight::UserDefinedType => 6, // Magenta
+                super::Highlight::Ident => 4,           // Red
+                super::Highlight::Number => 12,         // Blue
+                super::Highlight::QuasiKeyword => 3,    // Green
+            };
+            write!(dst, "\x03{color:02}{}\x03", segment.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formatter which produces a JSON array of segments, for programs which apply their own styling
+/// to explanations.
+///
+/// Each segment is an object with its `text` and `highlight`, which is one of `none`,
+/// `qualifier`, `primitive_type`, `user_defined_type`, `ident`, `number`, and `quasi_keyword`.
+/// Segments with an [origin][super::HighlightedTextSegment::origin] also have an `origin`
+/// member, which is an array of steps, e.g. `["declarator", {"parameter": 0}, "base_type"]`.
+/// Segments which are part of [groups][super::HighlightedTextSegment::groups] have a `groups`
+/// member, e.g. `[{"level": 1}, {"parameter": 0}]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormatter;
+
+impl JsonFormatter {
+    /// Creates a new `JsonFormatter`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// Writes a JSON string containing the given text.
+fn write_json_string(dst: &mut impl core::fmt::Write, text: &str) -> core::fmt::Result {
+    dst.write_char('"')?;
+    for c in text.chars() {
+        match c {
+            '"' => dst.write_str("\\\"")?,
+            '\\' => dst.write_str("\\\\")?,
+            '\n' => dst.write_str("\\n")?,
+            '\r' => dst.write_str("\\r")?,
+            '\t' => dst.write_str("\\t")?,
+            c if c.is_control() => write!(dst, "\\u{:04x}", u32::from(c))?,
+            c => dst.write_char(c)?,
+        }
+    }
+    dst.write_char('"')
+}
+
+impl super::Formatter for JsonFormatter {
+    /// Formats the given [`HighlightedText`] into a destination writer.
+    ///
+    /// This implementation writes a JSON array with an object for each segment.
+    fn format(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        text: &super::HighlightedText,
+    ) -> core::fmt::Result {
+        dst.write_char('[')?;
+        for (i, segment) in text.iter().enumerate() {
+            if i > 0 {
+                dst.write_char(',')?;
+            }
+            dst.write_str("{\"text\":")?;
+            write_json_string(dst, &segment.text)?;
+            let highlight = match segment.highlight {
+                super::Highlight::None => "none",
+                super::Highlight::Qualifier => "qualifier",
+                super::Highlight::PrimitiveType => "primitive_type",
+                super::Highlight::UserDefinedType => "user_defined_type",
+                super::Highlight::Ident => "ident",
+                super::Highlight::Number => "number",
+                super::Highlight::QuasiKeyword => "quasi_keyword",
+            };
+            write!(dst, ",\"highlight\":\"{highlight}\"")?;
+            if let Some(origin) = &segment.origin {
+                dst.write_str(",\"origin\":[")?;
+                for (i, step) in origin.0.iter().enumerate() {
+                    if i > 0 {
+                        dst.write_char(',')?;
+                    }
+                    match step {
+                        Step::Declarator => dst.write_str("\"declarator\"")?,
+                        Step::Inner => dst.write_str("\"inner\"")?,
+                        Step::BaseType => dst.write_str("\"base_type\"")?,
+                        Step::Initializer => dst.write_str("\"initializer\"")?,
+                        Step::Parameter(index) => write!(dst, "{{\"parameter\":{index}}}")?,
+                        Step::Member(index) => write!(dst, "{{\"member\":{index}}}")?,
+                        Step::Enumerator(index) => write!(dst, "{{\"enumerator\":{index}}}")?,
+                    }
+                }
+                dst.write_char(']')?;
+            }
+            if !segment.groups.is_empty() {
+                dst.write_str(",\"groups\":[")?;
+                for (i, group) in segment.groups.iter().enumerate() {
+                    if i > 0 {
+                        dst.write_char(',')?;
+                    }
+                    match group {
+                        super::Group::Level(index) => write!(dst, "{{\"level\":{index}}}")?,
+                        super::Group::Parameter(index) => {
+                            write!(dst, "{{\"parameter\":{index}}}")?;
+                        }
+                    }
+                }
+                dst.write_char(']')?;
+            }
+            dst.write_char('}')?;
+        }
+        dst.write_char(']')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        color::{Group, Highlight, HighlightedTextSegment, StyleSpec},
+        explainer::explain_declaration,
+    };
+
+    fn ssml(src: &str) -> alloc::string::String {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        explain_declaration(&decls[0]).format_to_string(&SsmlFormatter::new())
+    }
+
+    #[test]
+    fn ssml_pauses_and_emphasis() {
+        assert_eq!(
+            ssml("int (*fp)(char c)"),
+            r#"<speak>a pointer named <emphasis>fp</emphasis> to a function that takes <break strength="medium"/>a char named <emphasis>c</emphasis><break strength="medium"/> and returns an int</speak>"#
+        );
+        assert_eq!(
+            ssml("char buf[10]"),
+            r#"<speak>an array named <emphasis>buf</emphasis> of <say-as interpret-as="cardinal">10</say-as> chars</speak>"#
+        );
+    }
+
+    #[test]
+    fn ssml_escaping() {
+        let text = HighlightedText::from(alloc::vec![
+            HighlightedTextSegment::new("a <b> & 'c'\n", Highlight::None),
+            HighlightedTextSegment::new("d&e", Highlight::Ident),
+        ]);
+        assert_eq!(
+            text.format_to_string(&SsmlFormatter::new()),
+            r#"<speak>a &lt;b&gt; &amp; &apos;c&apos;<break strength="weak"/><emphasis>d&amp;e</emphasis></speak>"#
+        );
+    }
+    #[test]
+    fn markdown() {
+        let src = "const char *names[4]";
+        let decls = crate::parser::parser().parse(src).unwrap();
+        let explanation = explain_declaration(&decls[0]);
+        assert_eq!(
+            explanation.format_to_string(&MarkdownFormatter::with_declaration(src)),
+            "```c\nconst char *names[4]\n```\n\nan array named `names` of 4 pointers to \
+             const `char`s"
+        );
+        assert_eq!(
+            explanation.format_to_string(&MarkdownFormatter::new()),
+            "an array named `names` of 4 pointers to const `char`s"
+        );
+        assert_eq!(
+            HighlightedText::new().format_to_string(&MarkdownFormatter::with_declaration("a ```b")),
+            "````c\na ```b\n````\n\n"
+        );
+        let decls = crate::parser::parser()
+            .parse(r#"char *my_var = "<b>&*_[u]`x`""#)
+            .unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&MarkdownFormatter::new()),
+            r#"a pointer named `my_var` to a `char` initialized to "\<b\>\&\*\_\[u\]\`x\`""#
+        );
+    }
+    #[test]
+    fn dyn_formatters() {
+        let decls = crate::parser::parser().parse("int *p").unwrap();
+        let explanation = explain_declaration(&decls[0]);
+        for name in FORMATTER_NAMES {
+            let formatter = by_name(name).unwrap();
+            let expected = match name {
+                "plain" => explanation.format_to_string(&PlainFormatter),
+                "ansi" => explanation.format_to_string(&AnsiFormatter::new()),
+                "html" => explanation.format_to_string(&HtmlFormatter),
+                "markdown" => explanation.format_to_string(&MarkdownFormatter::new()),
+                "bbcode" => explanation.format_to_string(&BbcodeFormatter),
+                "discord" => explanation.format_to_string(&DiscordFormatter),
+                "irc" => explanation.format_to_string(&IrcFormatter),
+                "json" => explanation.format_to_string(&JsonFormatter),
+                "ssml" => explanation.format_to_string(&SsmlFormatter),
+                _ => unreachable!("unexpected formatter {name}"),
+            };
+            assert_eq!(
+                explanation.format_to_string(&*formatter),
+                expected,
+                "{name}"
+            );
+            assert_eq!(explanation.format_to_string(&formatter), expected, "{name}");
+        }
+        assert!(by_name("rtf").is_none());
+    }
+
+    #[test]
+    fn ansi_hyperlinks() {
+        let decls = crate::parser::parser().parse("void *p").unwrap();
+        let formatter = AnsiFormatter::with_palette(AnsiPalette {
+            quasi_keyword: None,
+            ..AnsiPalette::DEFAULT
+        })
+        .with_hyperlinks("https://example.com/{topic}.html");
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&formatter),
+            "a \x1b]8;;https://example.com/pointer.html\x1b\\pointer\x1b]8;;\x1b\\ named \
+             \x1b[31mp\x1b[0m to a \x1b]8;;https://example.com/types.html\x1b\\\x1b[33mvoid\x1b[0m\
+             \x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn reference_urls() {
+        for (text, highlight, topic) in [
+            (
+                "unsigned long",
+                Highlight::PrimitiveType,
+                Some("arithmetic_types"),
+            ),
+            ("arrays", Highlight::QuasiKeyword, Some("array")),
+            (
+                "función",
+                Highlight::QuasiKeyword,
+                Some("function_declaration"),
+            ),
+            ("struct", Highlight::QuasiKeyword, Some("struct")),
+            ("packed", Highlight::QuasiKeyword, None),
+            ("pointer", Highlight::Ident, None),
+        ] {
+            let segment = HighlightedTextSegment::new(text, highlight);
+            assert_eq!(
+                reference_url("{topic}", &segment).as_deref(),
+                topic,
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn ansi() {
+        let decls = crate::parser::parser().parse("const int n").unwrap();
+        let explanation = explain_declaration(&decls[0]);
+        assert_eq!(
+            explanation.format_to_string(&AnsiFormatter::new()),
+            "a \x1b[36mconst\x1b[0m \x1b[33mint\x1b[0m named \x1b[31mn\x1b[0m"
+        );
+        let palette = AnsiPalette {
+            qualifier: None,
+            primitive_type: Some(AnsiColor::Fixed(208)),
+            identifier: Some(AnsiColor::Rgb(255, 0, 128)),
+            ..AnsiPalette::DEFAULT
+        };
+        assert_eq!(
+            explanation.format_to_string(&AnsiFormatter::with_palette(palette)),
+            "a const \x1b[38;5;208mint\x1b[0m named \x1b[38;2;255;0;128mn\x1b[0m"
+        );
+        let palette = AnsiPalette {
+            qualifier: None,
+            styles: StyleMap {
+                qualifier: StyleSpec::PLAIN.underline(),
+                identifier: StyleSpec::PLAIN.bold().italic(),
+                ..StyleMap::PLAIN
+            },
+            ..AnsiPalette::DEFAULT
+        };
+        assert_eq!(
+            explanation.format_to_string(&AnsiFormatter::with_palette(palette)),
+            "a \x1b[4mconst\x1b[0m \x1b[33mint\x1b[0m named \x1b[1;3;31mn\x1b[0m"
+        );
+    }
+    #[test]
+    fn bbcode() {
+        let decls = crate::parser::parser().parse("const int *p").unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&BbcodeFormatter::new()),
+            "a [color=#008700]pointer[/color] named [b][color=#d70000]p[/color][/b] to a \
+             [color=#0087af]const[/color] [color=#af8700]int[/color]"
+        );
+    }
+
+    #[test]
+    fn html() {
+        let decls = crate::parser::parser().parse("struct a_b *p[2]").unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&HtmlFormatter::new()),
+            r#"an <span class="quasi_keyword">array</span> named <span class="ident">p</span> of <span class="number">2</span> <span class="quasi_keyword">pointers</span> to <span class="user_defined_type">struct a_b</span>"#
+        );
+        let text = HighlightedText(alloc::vec![
+            HighlightedTextSegment::new("a < b && ", Highlight::None),
+            HighlightedTextSegment::new("\"c\"", Highlight::Ident),
+        ]);
+        assert_eq!(
+            text.format_to_string(&HtmlFormatter::new()),
+            r#"a &lt; b &amp;&amp; <span class="ident">&quot;c&quot;</span>"#
+        );
+    }
+
+    #[test]
+    fn discord() {
+        let decls = crate::parser::parser()
+            .parse("const struct point *my_points[2]")
+            .unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&DiscordFormatter::new()),
+            "an __array__ named **my\\_points** of 2 __pointers__ to *const* `struct point`"
+        );
+    }
+    #[test]
+    fn irc() {
+        let decls = crate::parser::parser().parse("unsigned x[8]").unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&IrcFormatter::new()),
+            "an \x0303array\x03 named \x0304x\x03 of \x03128\x03 \x0307unsigned\x03s"
+        );
+    }
+    #[test]
+    fn json() {
+        let decls = crate::parser::parser().parse("int *p").unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).format_to_string(&JsonFormatter::new()),
+            r#"[{"text":"a ","highlight":"none"},{"text":"pointer","highlight":"quasi_keyword"},{"text":" named ","highlight":"none"},{"text":"p","highlight":"ident"},{"text":" to an ","highlight":"none"},{"text":"int","highlight":"primitive_type"}]"#
+        );
+        let mut text = HighlightedText::from(alloc::vec![HighlightedTextSegment::new(
+            "\"a\\b\"\n\u{1}",
+            Highlight::Ident
+        )]);
+        text[0].origin = Some(crate::provenance::NodePath(alloc::vec![
+            crate::provenance::Step::Declarator,
+            crate::provenance::Step::Parameter(1),
+            crate::provenance::Step::BaseType,
+        ]));
+        text[0].groups = alloc::vec![Group::Level(1), Group::Parameter(1)];
+        assert_eq!(
+            text.format_to_string(&JsonFormatter::new()),
+            r#"[{"text":"\"a\\b\"\n\u0001","highlight":"ident","origin":["declarator",{"parameter":1},"base_type"],"groups":[{"level":1},{"parameter":1}]}]"#
+        );
+    }
+}
@@ -3,10 +3,18 @@ use core::ops::{Deref, DerefMut};
 use alloc::{string::String, vec::Vec};
 use fmt::Formatter;
 
+use crate::provenance::NodePath;
+
 pub mod fmt;
 
 /// Defines types of highlights that can be applied to parts of the explanation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "snake_case")]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 #[non_exhaustive]
 pub enum Highlight {
     /// No highlight
@@ -25,10 +33,139 @@ pub enum Highlight {
     QuasiKeyword,
 }
 
+/// Text attributes besides color which a formatter applies to highlighted text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleSpec {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl StyleSpec {
+    /// Style without any attributes
+    pub const PLAIN: Self = Self {
+        bold: false,
+        italic: false,
+        underline: false,
+    };
+
+    /// Returns this style made bold.
+    #[must_use]
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Returns this style made italic.
+    #[must_use]
+    pub const fn italic(self) -> Self {
+        Self {
+            italic: true,
+            ..self
+        }
+    }
+
+    /// Returns this style made underlined.
+    #[must_use]
+    pub const fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// Returns whether the style has no attributes.
+    #[must_use]
+    pub const fn is_plain(self) -> bool {
+        !(self.bold || self.italic || self.underline)
+    }
+}
+
+/// Styles which a formatter applies to each kind of highlight, e.g. bold quasi-keywords and
+/// italic identifiers. Text without a highlight is never styled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StyleMap {
+    pub qualifier: StyleSpec,
+    pub primitive_type: StyleSpec,
+    pub user_defined_type: StyleSpec,
+    pub identifier: StyleSpec,
+    pub number: StyleSpec,
+    pub quasi_keyword: StyleSpec,
+}
+
+impl StyleMap {
+    /// Map which doesn't style any highlights
+    pub const PLAIN: Self = Self {
+        qualifier: StyleSpec::PLAIN,
+        primitive_type: StyleSpec::PLAIN,
+        user_defined_type: StyleSpec::PLAIN,
+        identifier: StyleSpec::PLAIN,
+        number: StyleSpec::PLAIN,
+        quasi_keyword: StyleSpec::PLAIN,
+    };
+
+    /// Returns the style for the given highlight according to this map.
+    #[must_use]
+    pub fn style_for_highlight(&self, highlight: Highlight) -> StyleSpec {
+        match highlight {
+            Highlight::None => StyleSpec::PLAIN,
+            Highlight::Qualifier => self.qualifier,
+            Highlight::PrimitiveType => self.primitive_type,
+            Highlight::UserDefinedType => self.user_defined_type,
+            Highlight::Ident => self.identifier,
+            Highlight::Number => self.number,
+            Highlight::QuasiKeyword => self.quasi_keyword,
+        }
+    }
+
+    /// Returns a mutable reference to the style for the given highlight, or `None` for
+    /// [`Highlight::None`], which can't be styled.
+    pub fn style_for_highlight_mut(&mut self, highlight: Highlight) -> Option<&mut StyleSpec> {
+        Some(match highlight {
+            Highlight::None => return None,
+            Highlight::Qualifier => &mut self.qualifier,
+            Highlight::PrimitiveType => &mut self.primitive_type,
+            Highlight::UserDefinedType => &mut self.user_defined_type,
+            Highlight::Ident => &mut self.identifier,
+            Highlight::Number => &mut self.number,
+            Highlight::QuasiKeyword => &mut self.quasi_keyword,
+        })
+    }
+}
+
+/// Logical group of segments of an explanation, which a formatter can wrap in a container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Group {
+    /// Explanation of the declarator level with the given index, counting from the level nearest
+    /// the declared name, e.g. "a pointer named p to" in "a pointer named p to an int"
+    Level(usize),
+    /// Explanation of the function parameter with the given index
+    Parameter(usize),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HighlightedTextSegment {
     pub text: String,
     pub highlight: Highlight,
+    /// Path to the node of the explained declaration which the segment explains, if the
+    /// explanation was generated with [provenance][crate::explainer::Options::provenance]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub origin: Option<NodePath>,
+    /// Groups which the segment is part of, outermost first, if the explanation was generated
+    /// with [groups][crate::explainer::Options::groups]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub groups: Vec<Group>,
 }
 
 /// Represents a piece of text with a single highlight type.
@@ -39,6 +176,8 @@ impl HighlightedTextSegment {
         Self {
             text: text.into(),
             highlight,
+            origin: None,
+            groups: Vec::new(),
         }
     }
 }
@@ -52,6 +191,11 @@ impl<T: Into<String>> From<T> for HighlightedTextSegment {
 
 /// Represents a piece of text made up of multiple segments, each with its own highlight type.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct HighlightedText(pub Vec<HighlightedTextSegment>);
 
 impl Deref for HighlightedText {
@@ -90,11 +234,16 @@ impl HighlightedText {
     }
 
     /// Pushes the given string as a new segment with [`Highlight::None`].
-    /// If the last existing segment has the same highlight, it appends to that segment instead of
-    /// creating a new one.
+    /// If the last existing segment has the same highlight and isn't part of any
+    /// [groups][HighlightedTextSegment::groups], it appends to that segment instead of creating a
+    /// new one. Empty strings are ignored.
     pub fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
         if let Some(last) = self.0.last_mut()
             && last.highlight == Highlight::None
+            && last.groups.is_empty()
         {
             last.text.push_str(text);
         } else {
@@ -108,7 +257,7 @@ impl HighlightedText {
     ///
     /// Panics if the formatter fails to write to the string.
     #[must_use]
-    pub fn format_to_string(&self, formatter: &impl Formatter) -> String {
+    pub fn format_to_string(&self, formatter: &(impl Formatter + ?Sized)) -> String {
         let mut output = String::new();
         formatter.format(&mut output, self).unwrap();
         output
@@ -121,7 +270,7 @@ impl HighlightedText {
         let mut coalesced: Vec<HighlightedTextSegment> = Vec::new();
         for segment in self.0 {
             if let Some(last) = coalesced.last_mut() {
-                if last.highlight == segment.highlight {
+                if last.highlight == segment.highlight && last.groups == segment.groups {
                     last.text.push_str(&segment.text);
                 } else {
                     coalesced.push(segment);
@@ -197,4 +346,63 @@ mod tests {
             "this is an int named foo"
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use serde_test::{Token, assert_tokens};
+
+        use crate::provenance::Step;
+
+        let mut ident = HighlightedTextSegment::new("p", Highlight::Ident);
+        ident.origin = Some(NodePath(vec![Step::Declarator, Step::Parameter(0)]));
+        let text = HighlightedText(vec![
+            HighlightedTextSegment::new("a ", Highlight::None),
+            ident,
+        ]);
+        assert_tokens(
+            &text,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::Struct {
+                    name: "HighlightedTextSegment",
+                    len: 2,
+                },
+                Token::Str("text"),
+                Token::Str("a "),
+                Token::Str("highlight"),
+                Token::UnitVariant {
+                    name: "Highlight",
+                    variant: "none",
+                },
+                Token::StructEnd,
+                Token::Struct {
+                    name: "HighlightedTextSegment",
+                    len: 3,
+                },
+                Token::Str("text"),
+                Token::Str("p"),
+                Token::Str("highlight"),
+                Token::UnitVariant {
+                    name: "Highlight",
+                    variant: "ident",
+                },
+                Token::Str("origin"),
+                Token::Some,
+                Token::Seq { len: Some(2) },
+                Token::UnitVariant {
+                    name: "Step",
+                    variant: "declarator",
+                },
+                Token::NewtypeVariant {
+                    name: "Step",
+                    variant: "parameter",
+                },
+                Token::U64(0),
+                Token::SeqEnd,
+                Token::StructEnd,
+                Token::SeqEnd,
+            ],
+        );
+    }
 }
@@ -0,0 +1,655 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Checking whether declarations denote compatible types, as defined by C11 section 6.2.7
+//!
+//! The parser treats an empty parameter list like `(void)`, as C23 does, so every function type
+//! has a prototype. Typedef names are compared by name, since their definitions aren't known.
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::{
+    ast::{
+        ArrayQualifiers, ArraySize, Declaration, Declarator, Record, Type, TypeQualifier,
+        TypeQualifiers,
+    },
+    c_syntax::c_array_contents,
+};
+
+/// Kind of a type, as far as compatibility is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeKind {
+    /// A type which isn't derived from another, like `int` or `struct foo`
+    Base,
+    Pointer,
+    /// C++ lvalue reference
+    Reference,
+    /// Clang block pointer
+    Block,
+    /// C++ pointer to a member of a class
+    MemberPointer,
+    Array,
+    Function,
+}
+
+/// Formats the kind with an indefinite article, e.g. "an array".
+impl Display for TypeKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            TypeKind::Base => "a non-derived type",
+            TypeKind::Pointer => "a pointer",
+            TypeKind::Reference => "a reference",
+            TypeKind::Block => "a block pointer",
+            TypeKind::MemberPointer => "a member pointer",
+            TypeKind::Array => "an array",
+            TypeKind::Function => "a function",
+        })
+    }
+}
+
+/// Part of a type which contains another type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    /// Type pointed to by a pointer, block pointer, or member pointer
+    Pointee,
+    /// Type referred to by a reference
+    Referent,
+    /// Element type of an array
+    Element,
+    /// Return type of a function
+    Return,
+    /// Type of the function parameter with the given zero-based index
+    Parameter(usize),
+}
+
+/// Formats the component as a noun phrase, e.g. "the element type" or "parameter 2".
+impl Display for Component {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Component::Pointee => f.write_str("the pointed-to type"),
+            Component::Referent => f.write_str("the referenced type"),
+            Component::Element => f.write_str("the element type"),
+            Component::Return => f.write_str("the return type"),
+            Component::Parameter(index) => write!(f, "parameter {}", index + 1),
+        }
+    }
+}
+
+/// Reason why two types aren't compatible
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Reason {
+    #[error("{0} is not compatible with {1}")]
+    Kinds(TypeKind, TypeKind),
+    #[error("`{0}` is not compatible with `{1}`")]
+    BaseTypes(String, String),
+    #[error(
+        "the types are qualified differently ({} and {})",
+        describe_qualifiers(*.0),
+        describe_qualifiers(*.1)
+    )]
+    Qualifiers(TypeQualifiers, TypeQualifiers),
+    #[error("pointers to members of `{0}` and `{1}` are not compatible")]
+    Classes(String, String),
+    #[error("arrays of {0} and {1} elements are not compatible")]
+    ArraySizes(usize, usize),
+    /// Array sizes which differ when [`check_same_type`] requires them to match, e.g. `[10]`
+    /// and `[]`
+    #[error("arrays declared with `[{0}]` and `[{1}]` are not the same type")]
+    ArraySpellings(String, String),
+    #[error(
+        "the functions take {} and {}",
+        count(*.0, "parameter"),
+        count(*.1, "parameter")
+    )]
+    ParameterCounts(usize, usize),
+    #[error("only one of the functions takes a variable number of arguments")]
+    Variadic,
+}
+
+/// Formats type qualifiers for a [`Reason`], e.g. "`const volatile`" or "unqualified".
+fn describe_qualifiers(qualifiers: TypeQualifiers) -> String {
+    if qualifiers.is_empty() {
+        "unqualified".to_string()
+    } else {
+        format!("`{qualifiers}`")
+    }
+}
+
+/// Formats a number of things, e.g. "1 parameter" or "2 parameters".
+fn count(n: usize, noun: &str) -> String {
+    if n == 1 {
+        format!("{n} {noun}")
+    } else {
+        format!("{n} {noun}s")
+    }
+}
+
+/// First incompatibility found between two types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    /// Path from the outermost types to the incompatible ones, e.g. `[Pointee, Parameter(0)]`
+    /// if the types of the first parameters of two pointed-to functions are incompatible.
+    /// Empty if the outermost types themselves are incompatible.
+    pub location: Vec<Component>,
+    pub reason: Reason,
+}
+
+impl Incompatibility {
+    /// Prepends a component to the location of the incompatibility.
+    fn within(mut self, component: Component) -> Self {
+        self.location.insert(0, component);
+        self
+    }
+}
+
+/// Formats the incompatibility as a sentence fragment, e.g. "in parameter 1 of the pointed-to
+/// type, `int` is not compatible with `char`".
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, component) in self.location.iter().rev().enumerate() {
+            f.write_str(if i == 0 { "in " } else { " of " })?;
+            write!(f, "{component}")?;
+        }
+        if !self.location.is_empty() {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl core::error::Error for Incompatibility {}
+
+/// Type declared by a declaration, from the outermost derivation inwards
+#[derive(Debug, Clone)]
+enum CType<'a> {
+    Base(TypeQualifiers, &'a Type<'a>),
+    Pointer(TypeQualifiers, Box<CType<'a>>),
+    Reference(Box<CType<'a>>),
+    Block(TypeQualifiers, Box<CType<'a>>),
+    /// Member pointer, with the name of the class
+    MemberPointer(TypeQualifiers, &'a str, Box<CType<'a>>),
+    Array(ArraySize<'a>, Box<CType<'a>>),
+    /// Function type, with whether it's variadic and its member function qualifiers
+    Function(Box<CType<'a>>, Vec<CType<'a>>, bool, TypeQualifiers),
+}
+
+impl CType<'_> {
+    fn kind(&self) -> TypeKind {
+        match self {
+            CType::Base(..) => TypeKind::Base,
+            CType::Pointer(..) => TypeKind::Pointer,
+            CType::Reference(_) => TypeKind::Reference,
+            CType::Block(..) => TypeKind::Block,
+            CType::MemberPointer(..) => TypeKind::MemberPointer,
+            CType::Array(..) => TypeKind::Array,
+            CType::Function(..) => TypeKind::Function,
+        }
+    }
+}
+
+/// Removes the `typedef` pseudo-qualifier, which doesn't affect the type.
+fn without_typedef(mut qualifiers: TypeQualifiers) -> TypeQualifiers {
+    qualifiers.remove(TypeQualifier::Typedef);
+    qualifiers
+}
+
+/// Returns the type declared by a declarator, given the type it derives from.
+fn derive<'a>(declarator: &'a Declarator<'a>, ty: CType<'a>) -> CType<'a> {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => ty,
+        Declarator::Ptr(inner, qualifiers) => {
+            derive(inner, CType::Pointer(*qualifiers, Box::new(ty)))
+        }
+        Declarator::Reference(inner) => derive(inner, CType::Reference(Box::new(ty))),
+        Declarator::Block(inner, qualifiers) => {
+            derive(inner, CType::Block(*qualifiers, Box::new(ty)))
+        }
+        Declarator::MemberPtr(inner, class, qualifiers) => derive(
+            inner,
+            CType::MemberPointer(*qualifiers, class, Box::new(ty)),
+        ),
+        Declarator::Array(inner, size, _) => derive(inner, CType::Array(*size, Box::new(ty))),
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            member_qualifiers,
+            ..
+        } => derive(
+            func,
+            CType::Function(
+                Box::new(ty),
+                params.iter().map(parameter_type).collect(),
+                *variadic,
+                *member_qualifiers,
+            ),
+        ),
+    }
+}
+
+/// Returns the type declared by a declaration.
+fn declared_type<'a>(decl: &'a Declaration<'a>) -> CType<'a> {
+    let base = CType::Base(without_typedef(decl.base_type.0), &decl.base_type.1);
+    derive(&decl.declarator, base)
+}
+
+/// Returns the type of a parameter after adjustment, which turns arrays and functions into
+/// pointers and removes top-level qualifiers, which don't affect the function's type.
+fn parameter_type<'a>(decl: &'a Declaration<'a>) -> CType<'a> {
+    match declared_type(decl) {
+        CType::Base(_, ty) => CType::Base(TypeQualifiers::default(), ty),
+        CType::Pointer(_, pointee) | CType::Array(_, pointee) => {
+            CType::Pointer(TypeQualifiers::default(), pointee)
+        }
+        CType::Block(_, pointee) => CType::Block(TypeQualifiers::default(), pointee),
+        CType::MemberPointer(_, class, pointee) => {
+            CType::MemberPointer(TypeQualifiers::default(), class, pointee)
+        }
+        function @ CType::Function(..) => {
+            CType::Pointer(TypeQualifiers::default(), Box::new(function))
+        }
+        reference @ CType::Reference(_) => reference,
+    }
+}
+
+/// Returns a primitive type's name in a canonical form, so that e.g. `signed long int` and
+/// `long` are the same. The first element is whether the type is unsigned.
+fn canonical_primitive(name: &str) -> (bool, &str) {
+    // Plain `char` is distinct from both `signed char` and `unsigned char`.
+    if name.ends_with("char") {
+        return (false, name);
+    }
+    if name == "bool" {
+        return (false, "_Bool");
+    }
+    let (unsigned, rest) = match name.strip_prefix("unsigned") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (
+            false,
+            name.strip_prefix("signed").map_or(name, str::trim_start),
+        ),
+    };
+    match rest.strip_suffix(" int").unwrap_or(rest) {
+        "" => (unsigned, "int"),
+        base => (unsigned, base),
+    }
+}
+
+/// Returns whether two types which aren't derived from others are compatible.
+fn base_types_compatible(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Primitive(a), Type::Primitive(b)) => {
+            canonical_primitive(a.as_ref()) == canonical_primitive(b.as_ref())
+        }
+        // Records with the same tag are the same type, and untagged records are only compatible
+        // if they're defined identically.
+        (Type::Record(a), Type::Record(b)) => match (a, b) {
+            (
+                Record {
+                    name: Some(a_name), ..
+                },
+                Record {
+                    name: Some(b_name), ..
+                },
+            ) => a.kind == b.kind && a_name == b_name,
+            _ => a.kind == b.kind && a.name == b.name && a.body == b.body,
+        },
+        _ => a == b,
+    }
+}
+
+/// Formats a type which isn't derived from another for a [`Reason`], showing untagged records
+/// as e.g. `struct { ... }`.
+fn describe_base_type(ty: &Type) -> String {
+    match ty {
+        Type::Record(Record {
+            kind, name: None, ..
+        }) => format!("{kind} {{ ... }}"),
+        _ => ty.to_string(),
+    }
+}
+
+/// Checks whether two qualified types are identically qualified.
+fn check_qualifiers(a: TypeQualifiers, b: TypeQualifiers) -> Result<(), Incompatibility> {
+    if a == b {
+        Ok(())
+    } else {
+        Err(Incompatibility {
+            location: Vec::new(),
+            reason: Reason::Qualifiers(a, b),
+        })
+    }
+}
+
+/// Checks whether two types are compatible, returning the first incompatibility found. If
+/// `exact_sizes` is set, array sizes must be spelled the same, even if one of them is unknown.
+fn check_types(a: &CType, b: &CType, exact_sizes: bool) -> Result<(), Incompatibility> {
+    let incompatible = |reason| {
+        Err(Incompatibility {
+            location: Vec::new(),
+            reason,
+        })
+    };
+    match (a, b) {
+        (CType::Base(a_qualifiers, a_type), CType::Base(b_qualifiers, b_type)) => {
+            check_qualifiers(*a_qualifiers, *b_qualifiers)?;
+            if base_types_compatible(a_type, b_type) {
+                Ok(())
+            } else {
+                incompatible(Reason::BaseTypes(
+                    describe_base_type(a_type),
+                    describe_base_type(b_type),
+                ))
+            }
+        }
+        (CType::Pointer(a_qualifiers, a_pointee), CType::Pointer(b_qualifiers, b_pointee))
+        | (CType::Block(a_qualifiers, a_pointee), CType::Block(b_qualifiers, b_pointee)) => {
+            check_qualifiers(*a_qualifiers, *b_qualifiers)?;
+            check_types(a_pointee, b_pointee, exact_sizes)
+                .map_err(|err| err.within(Component::Pointee))
+        }
+        (
+            CType::MemberPointer(a_qualifiers, a_class, a_pointee),
+            CType::MemberPointer(b_qualifiers, b_class, b_pointee),
+        ) => {
+            check_qualifiers(*a_qualifiers, *b_qualifiers)?;
+            if a_class != b_class {
+                return incompatible(Reason::Classes(
+                    (*a_class).to_string(),
+                    (*b_class).to_string(),
+                ));
+            }
+            check_types(a_pointee, b_pointee, exact_sizes)
+                .map_err(|err| err.within(Component::Pointee))
+        }
+        (CType::Reference(a_referent), CType::Reference(b_referent)) => {
+            check_types(a_referent, b_referent, exact_sizes)
+                .map_err(|err| err.within(Component::Referent))
+        }
+        // Sizes only matter if both are known constants, unless they must match exactly.
+        (CType::Array(a_size, a_element), CType::Array(b_size, b_element)) => {
+            if let (ArraySize::Fixed(a_len), ArraySize::Fixed(b_len)) = (a_size, b_size)
+                && a_len != b_len
+            {
+                return incompatible(Reason::ArraySizes(*a_len, *b_len));
+            }
+            if exact_sizes && a_size != b_size {
+                return incompatible(Reason::ArraySpellings(
+                    c_array_contents(a_size, ArrayQualifiers::default()),
+                    c_array_contents(b_size, ArrayQualifiers::default()),
+                ));
+            }
+            check_types(a_element, b_element, exact_sizes)
+                .map_err(|err| err.within(Component::Element))
+        }
+        (
+            CType::Function(a_return, a_params, a_variadic, a_qualifiers),
+            CType::Function(b_return, b_params, b_variadic, b_qualifiers),
+        ) => {
+            check_qualifiers(*a_qualifiers, *b_qualifiers)?;
+            check_types(a_return, b_return, exact_sizes)
+                .map_err(|err| err.within(Component::Return))?;
+            if a_params.len() != b_params.len() {
+                return incompatible(Reason::ParameterCounts(a_params.len(), b_params.len()));
+            }
+            if a_variadic != b_variadic {
+                return incompatible(Reason::Variadic);
+            }
+            a_params
+                .iter()
+                .zip(b_params)
+                .enumerate()
+                .try_for_each(|(i, (a_param, b_param))| {
+                    check_types(a_param, b_param, exact_sizes)
+                        .map_err(|err| err.within(Component::Parameter(i)))
+                })
+        }
+        _ => incompatible(Reason::Kinds(a.kind(), b.kind())),
+    }
+}
+
+/// Checks whether two declarations denote compatible types, ignoring their names, storage
+/// classes, and everything else which isn't part of the type. Parameter names and the top-level
+/// qualifiers of parameters are ignored too, since they don't affect a function's type.
+///
+/// # Errors
+///
+/// Returns the first incompatibility found if the types aren't compatible.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, compatibility::check_compatibility};
+/// let decls = c2e::parser::parser()
+///     .parse("void f(const int n, int a[]); void g(int, int *p)")
+///     .unwrap();
+/// assert!(check_compatibility(&decls[0], &decls[1]).is_ok());
+/// ```
+pub fn check_compatibility(a: &Declaration, b: &Declaration) -> Result<(), Incompatibility> {
+    check_types(&declared_type(a), &declared_type(b), false)
+}
+
+/// Checks whether two declarations denote the same type. This is stricter than
+/// [`check_compatibility`] in that array sizes must match exactly, so `int a[]` and `int a[10]`
+/// are different types. Parameters are still adjusted, so `int a[]` and `int *a` are the same
+/// parameter type.
+///
+/// # Errors
+///
+/// Returns the first difference found if the types aren't the same.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, compatibility::check_same_type};
+/// let decls = c2e::parser::parser()
+///     .parse("int a[10]; int b[]; void f(int a[10]); void g(int *p)")
+///     .unwrap();
+/// assert!(check_same_type(&decls[0], &decls[1]).is_err());
+/// assert!(check_same_type(&decls[2], &decls[3]).is_ok());
+/// ```
+pub fn check_same_type(a: &Declaration, b: &Declaration) -> Result<(), Incompatibility> {
+    check_types(&declared_type(a), &declared_type(b), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Checks the compatibility of the two declarations in the source, returning the
+    /// incompatibility as a string.
+    fn check(src: &str) -> Result<(), String> {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 2, "Expected two declarations in {src}");
+        check_compatibility(&decls[0], &decls[1]).map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn compatible() {
+        for src in [
+            "int x; int y",
+            "long a; signed long int b",
+            "unsigned u; unsigned int v",
+            "bool b; _Bool c",
+            "int a[10]; int b[]",
+            "int a[]; int b[n]",
+            "const char *s; const char *t",
+            "struct foo *a; struct foo *b",
+            "void f(const int n, int a[]); void g(int, int *p)",
+            "void f(int g(void)); void h(int (*)(void))",
+            "typedef int T; int x",
+            "uint8_t a; uint8_t b",
+            "int f(const char *, ...); int g(const char *fmt, ...)",
+        ] {
+            assert_eq!(check(src), Ok(()), "Wrong result for {src}");
+        }
+    }
+
+    #[test]
+    fn incompatible() {
+        for (src, expected) in [
+            ("int x; char y", "`int` is not compatible with `char`"),
+            (
+                "char c; signed char s",
+                "`char` is not compatible with `signed char`",
+            ),
+            (
+                "unsigned u; int i",
+                "`unsigned` is not compatible with `int`",
+            ),
+            (
+                "const int a; int b",
+                "the types are qualified differently (`const` and unqualified)",
+            ),
+            (
+                "int *a[10]; int (*a)[10]",
+                "an array is not compatible with a pointer",
+            ),
+            (
+                "int a[10]; int b[20]",
+                "arrays of 10 and 20 elements are not compatible",
+            ),
+            (
+                "int *const p; int *q",
+                "the types are qualified differently (`const` and unqualified)",
+            ),
+            (
+                "const int *p; int *q",
+                "in the pointed-to type, the types are qualified differently (`const` and \
+                 unqualified)",
+            ),
+            (
+                "int f(int); int g(int, int)",
+                "the functions take 1 parameter and 2 parameters",
+            ),
+            (
+                "int f(int, ...); int g(int)",
+                "only one of the functions takes a variable number of arguments",
+            ),
+            (
+                "int (*f)(int); int (*g)(long)",
+                "in parameter 1 of the pointed-to type, `int` is not compatible with `long`",
+            ),
+            (
+                "int f(void); char *g(void)",
+                "in the return type, a non-derived type is not compatible with a pointer",
+            ),
+            (
+                "struct foo a; union foo b",
+                "`struct foo` is not compatible with `union foo`",
+            ),
+            (
+                "struct { int x; } a; struct { long x; } b",
+                "`struct { ... }` is not compatible with `struct { ... }`",
+            ),
+            (
+                "uint8_t a; uint16_t b",
+                "`uint8_t` is not compatible with `uint16_t`",
+            ),
+        ] {
+            assert_eq!(
+                check(src),
+                Err(expected.to_string()),
+                "Wrong result for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn location() {
+        let decls = crate::parser::parser()
+            .parse("void (*a[2])(int *); void (*b[2])(char *)")
+            .unwrap();
+        let err = check_compatibility(&decls[0], &decls[1]).unwrap_err();
+        assert_eq!(
+            err.location,
+            [
+                Component::Element,
+                Component::Pointee,
+                Component::Parameter(0),
+                Component::Pointee
+            ]
+        );
+        assert_eq!(
+            err.reason,
+            Reason::BaseTypes("int".to_string(), "char".to_string())
+        );
+    }
+
+    #[test]
+    fn member_pointers() {
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let decls = crate::parser::parser()
+            .parse_with_state(
+                "int C::*a; int C::*b; int D::*c; int (C::*f)(void) const; int (C::*g)(void)",
+                &mut state,
+            )
+            .unwrap();
+        assert_eq!(check_compatibility(&decls[0], &decls[1]), Ok(()));
+        assert_eq!(
+            check_compatibility(&decls[0], &decls[2]).map_err(|err| err.reason),
+            Err(Reason::Classes("C".to_string(), "D".to_string()))
+        );
+        let err = check_compatibility(&decls[3], &decls[4]).unwrap_err();
+        assert_eq!(err.location, [Component::Pointee]);
+        assert_eq!(
+            err.reason,
+            Reason::Qualifiers(
+                TypeQualifiers(TypeQualifier::Const.into()),
+                TypeQualifiers::default()
+            )
+        );
+    }
+    #[test]
+    fn same_type() {
+        for (src, expected) in [
+            ("int a[10]; int b[10]", Ok(())),
+            ("void f(int a[]); void g(int *p)", Ok(())),
+            ("void f(int a[10]); void g(int a[])", Ok(())),
+            (
+                "int a[10]; int b[]",
+                Err("arrays declared with `[10]` and `[]` are not the same type"),
+            ),
+            (
+                "int (*p)[]; int (*q)[3]",
+                Err(
+                    "in the pointed-to type, arrays declared with `[]` and `[3]` are not the same \
+                     type",
+                ),
+            ),
+            (
+                "void f(int a[][3]); void g(int (*p)[4])",
+                Err(
+                    "in the pointed-to type of parameter 1, arrays of 3 and 4 elements are not \
+                     compatible",
+                ),
+            ),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            assert_eq!(
+                check_same_type(&decls[0], &decls[1]).map_err(|err| err.to_string()),
+                expected.map_err(ToString::to_string),
+                "Wrong result for {src}"
+            );
+        }
+    }
+}
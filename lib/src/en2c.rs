@@ -0,0 +1,713 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Composition of C declarations from controlled English, the reverse of the
+//! [explainer](crate::explainer)
+//!
+//! Phrases describe a type from the outside in, like "pointer to array of 10 const char" or
+//! "function taking int and char pointer returning void". Articles are optional, nouns may be
+//! plural, and the declared identifier may be given with "named", so the English explanations
+//! produced by this crate can be read back in, e.g. "a pointer named p to a const char".
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    ast::{
+        ArrayQualifiers, ArraySize, Declaration, Declarator, PrimitiveType, QualifiedType, Record,
+        RecordKind, Type, TypeQualifier, TypeQualifiers,
+    },
+    c_syntax::{c_declarator, c_type},
+    parser::check_name,
+    semantics::{SemanticError, check_declaration},
+    standard::Standard,
+};
+
+/// Spellings of the primitive types, as accepted by the parser
+const PRIMITIVE_TYPES: &[&str] = &[
+    "unsigned long long int",
+    "unsigned long long",
+    "unsigned long int",
+    "unsigned short int",
+    "unsigned short",
+    "unsigned long",
+    "unsigned int",
+    "unsigned char",
+    "unsigned",
+    "signed long long int",
+    "signed long long",
+    "signed long int",
+    "signed long",
+    "signed short int",
+    "signed short",
+    "signed char",
+    "signed int",
+    "signed",
+    "long long int",
+    "long double _Complex",
+    "long double",
+    "long long",
+    "long int",
+    "long",
+    "short int",
+    "short",
+    "float _Complex",
+    "float",
+    "double _Complex",
+    "double",
+    "void",
+    "char",
+    "int",
+    "_Bool",
+    "bool",
+    "nullptr_t",
+    "char8_t",
+    "wchar_t",
+    "char16_t",
+    "char32_t",
+    "_Decimal32",
+    "_Decimal64",
+    "_Decimal128",
+];
+
+/// Error composing a declaration from English
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum En2cError {
+    #[error("expected {expected}, but found '{found}'")]
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("expected {0}, but the phrase ended")]
+    UnexpectedEnd(&'static str),
+    /// The phrase describes a type which can't exist, e.g. an array of functions
+    #[error(transparent)]
+    Semantic(#[from] SemanticError),
+    /// The name to declare isn't an identifier, or is a keyword
+    #[error("{0}")]
+    InvalidName(String),
+    /// The phrase describes a C++ reference
+    #[error("references only exist in C++; use a pointer instead")]
+    Reference,
+    /// The type to cast to is neither `void` nor a scalar type, e.g. a function
+    #[error(
+        "a value can only be cast to void or to a scalar type, such as an integer or a pointer"
+    )]
+    NonScalarCast,
+}
+
+/// Part of a type which derives from another, listed from the name outwards
+#[derive(Debug, Clone)]
+enum Part<'src> {
+    Pointer(TypeQualifiers),
+    Block(TypeQualifiers),
+    Array(ArraySize<'src>),
+    /// Parameters, and whether the function is variadic
+    Function(Vec<Declaration<'src>>, bool),
+}
+
+/// Type described by a phrase, along with the name given to it, if any
+struct Phrase<'src> {
+    base: QualifiedType<'src>,
+    parts: Vec<Part<'src>>,
+    name: Option<&'src str>,
+}
+
+impl<'src> Phrase<'src> {
+    /// Converts the phrase into a declaration.
+    fn into_declaration(self) -> Declaration<'src> {
+        let mut declarator = self.name.map_or(Declarator::Anonymous, Declarator::Ident);
+        for part in self.parts {
+            declarator = match part {
+                Part::Pointer(qualifiers) => Declarator::Ptr(Box::new(declarator), qualifiers),
+                Part::Block(qualifiers) => Declarator::Block(Box::new(declarator), qualifiers),
+                Part::Array(size) => {
+                    Declarator::Array(Box::new(declarator), size, ArrayQualifiers::default())
+                }
+                Part::Function(params, variadic) => Declarator::Function {
+                    func: Box::new(declarator),
+                    params,
+                    variadic,
+                    calling_convention: None,
+                    member_qualifiers: TypeQualifiers::default(),
+                },
+            };
+        }
+        Declaration::new(self.base, declarator)
+    }
+}
+
+/// Returns whether the word is the given noun, or its plural if `plural` is true.
+fn is_noun(word: &str, noun: &str, plural: bool) -> bool {
+    word.eq_ignore_ascii_case(noun)
+        || (plural
+            && word
+                .strip_suffix('s')
+                .is_some_and(|word| word.eq_ignore_ascii_case(noun)))
+}
+
+/// Returns the qualifier named by the word, if any.
+fn qualifier(word: &str) -> Option<TypeQualifier> {
+    match word.to_ascii_lowercase().as_str() {
+        "const" => Some(TypeQualifier::Const),
+        "volatile" => Some(TypeQualifier::Volatile),
+        "restrict" => Some(TypeQualifier::Restrict),
+        "atomic" | "_atomic" => Some(TypeQualifier::Atomic),
+        _ => None,
+    }
+}
+
+/// Recursive-descent parser over the words of a phrase
+struct Parser<'src> {
+    words: Vec<&'src str>,
+    pos: usize,
+}
+
+impl<'src> Parser<'src> {
+    /// Splits a phrase into words, treating parentheses and commas as separate words.
+    fn new(phrase: &'src str) -> Self {
+        let mut words = Vec::new();
+        for chunk in phrase.split_whitespace() {
+            let mut rest = chunk;
+            while let Some(i) = rest.find(['(', ')', ',']) {
+                if i > 0 {
+                    words.push(&rest[..i]);
+                }
+                words.push(&rest[i..=i]);
+                rest = &rest[i + 1..];
+            }
+            if !rest.is_empty() {
+                words.push(rest);
+            }
+        }
+        Self { words, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&'src str> {
+        self.words.get(self.pos).copied()
+    }
+
+    fn peek_is(&self, word: &str) -> bool {
+        self.peek().is_some_and(|w| w.eq_ignore_ascii_case(word))
+    }
+
+    /// Consumes the next word if it is the given one.
+    fn eat(&mut self, word: &str) -> bool {
+        let matched = self.peek_is(word);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Consumes the next word, returning an error mentioning what was expected if there isn't
+    /// one.
+    fn next(&mut self, expected: &'static str) -> Result<&'src str, En2cError> {
+        let word = self.peek().ok_or(En2cError::UnexpectedEnd(expected))?;
+        self.pos += 1;
+        Ok(word)
+    }
+
+    /// Returns an error for the next word, which isn't what was expected.
+    fn unexpected<T>(&self, expected: &'static str) -> Result<T, En2cError> {
+        Err(match self.peek() {
+            Some(found) => En2cError::Unexpected {
+                expected,
+                found: found.to_string(),
+            },
+            None => En2cError::UnexpectedEnd(expected),
+        })
+    }
+
+    fn skip_articles(&mut self) {
+        while self.eat("a") || self.eat("an") || self.eat("the") {}
+    }
+
+    fn qualifiers(&mut self) -> TypeQualifiers {
+        let mut qualifiers = TypeQualifiers::default();
+        while let Some(qualifier) = self.peek().and_then(qualifier) {
+            qualifiers.insert(qualifier);
+            self.pos += 1;
+        }
+        qualifiers
+    }
+
+    /// Parses "named <name>", if present, recording the name if none has been given yet.
+    fn name(&mut self, name: &mut Option<&'src str>) -> Result<(), En2cError> {
+        if self.eat("named") {
+            let word = self.next("a name")?;
+            check_name(word, Standard::default()).map_err(En2cError::InvalidName)?;
+            name.get_or_insert(word);
+        }
+        Ok(())
+    }
+
+    /// Returns whether the words starting at the given position introduce a function's return
+    /// type.
+    fn at_return(&self, mut pos: usize) -> bool {
+        while self
+            .words
+            .get(pos)
+            .is_some_and(|w| w.eq_ignore_ascii_case("and") || w.eq_ignore_ascii_case("that"))
+        {
+            pos += 1;
+        }
+        self.words.get(pos).is_some_and(|w| {
+            ["returning", "returns", "return"]
+                .iter()
+                .any(|r| w.eq_ignore_ascii_case(r))
+        })
+    }
+
+    /// Parses a phrase describing a type. If `plural` is true, nouns may be plural, as in the
+    /// element type of an array.
+    fn phrase(&mut self, plural: bool) -> Result<Phrase<'src>, En2cError> {
+        self.skip_articles();
+        let qualifiers = self.qualifiers();
+        let Some(word) = self.peek() else {
+            return self.unexpected("a type");
+        };
+        let mut name = None;
+        if is_noun(word, "reference", true) {
+            return Err(En2cError::Reference);
+        }
+        let mut phrase = if is_noun(word, "pointer", true) {
+            self.pos += 1;
+            let plural = plural || word.ends_with('s');
+            self.name(&mut name)?;
+            if !self.eat("to") {
+                return self.unexpected("'to'");
+            }
+            let mut phrase = self.phrase(plural)?;
+            phrase.parts.insert(0, Part::Pointer(qualifiers));
+            phrase
+        } else if is_noun(word, "array", true) {
+            self.pos += 1;
+            self.name(&mut name)?;
+            self.eat("of");
+            let size = match self.peek().map(str::parse::<usize>) {
+                Some(Ok(size)) => {
+                    self.pos += 1;
+                    ArraySize::Fixed(size)
+                }
+                _ => ArraySize::Unspecified,
+            };
+            self.eat("of");
+            let mut phrase = self.phrase(true)?;
+            phrase.parts.insert(0, Part::Array(size));
+            phrase
+        } else if is_noun(word, "function", true) || is_noun(word, "block", true) {
+            self.pos += 1;
+            let plural = plural || word.ends_with('s');
+            self.name(&mut name)?;
+            let (params, variadic) = self.params()?;
+            while self.eat("and") || self.eat("that") {}
+            if !(self.eat("returning") || self.eat("returns") || self.eat("return")) {
+                return self.unexpected("'returning'");
+            }
+            let mut phrase = self.phrase(plural)?;
+            phrase.parts.insert(0, Part::Function(params, variadic));
+            if is_noun(word, "block", true) {
+                phrase.parts.insert(0, Part::Block(qualifiers));
+            }
+            phrase
+        } else {
+            let ty = self.base_type(plural)?;
+            Phrase {
+                base: QualifiedType(qualifiers, ty),
+                parts: Vec::new(),
+                name: None,
+            }
+        };
+        self.name(&mut name)?;
+        // Postfix pointers, like "char pointer"
+        while let Some(word) = self.peek()
+            && is_noun(word, "pointer", true)
+            && !self
+                .words
+                .get(self.pos + 1)
+                .is_some_and(|w| w.eq_ignore_ascii_case("to"))
+        {
+            self.pos += 1;
+            phrase
+                .parts
+                .insert(0, Part::Pointer(TypeQualifiers::default()));
+            self.name(&mut name)?;
+        }
+        if let Some(name) = name {
+            phrase.name = Some(name);
+        }
+        Ok(phrase)
+    }
+
+    /// Parses "a variable number of further arguments" or "variable arguments", which end the
+    /// parameters of a variadic function. Returns whether they were parsed.
+    fn variable_arguments(&mut self) -> bool {
+        let start = self.pos;
+        self.skip_articles();
+        let parsed = self.eat("variable") && (!self.eat("number") || self.eat("of")) && {
+            let _ = self.eat("further") || self.eat("additional");
+            self.eat("arguments") || self.eat("parameters")
+        };
+        if !parsed {
+            self.pos = start;
+        }
+        parsed
+    }
+
+    /// Parses the parameters of a function, if any are given, such as "taking int and char",
+    /// "that takes (an int named a, and a double)", or "taking no parameters". Also returns
+    /// whether the function is variadic, which is given by ending the parameters with "a
+    /// variable number of further arguments".
+    fn params(&mut self) -> Result<(Vec<Declaration<'src>>, bool), En2cError> {
+        self.eat("that");
+        let introduced = ["taking", "takes", "take", "accepting", "with"]
+            .iter()
+            .any(|word| self.eat(word));
+        if self.eat("no") {
+            if !(self.eat("parameters") || self.eat("arguments")) {
+                return self.unexpected("'parameters'");
+            }
+            return Ok((Vec::new(), false));
+        }
+        if self.eat("nothing") {
+            return Ok((Vec::new(), false));
+        }
+        let parenthesized = self.eat("(");
+        if !introduced && !parenthesized {
+            return Ok((Vec::new(), false));
+        }
+        let mut params = Vec::new();
+        if parenthesized && self.eat(")") {
+            return Ok((params, false));
+        }
+        // `void` alone means there are no parameters.
+        if self.peek_is("void") {
+            let alone = if parenthesized {
+                self.words.get(self.pos + 1).is_some_and(|w| *w == ")")
+            } else {
+                self.at_return(self.pos + 1)
+            };
+            if alone {
+                self.pos += 1;
+                if parenthesized {
+                    self.eat(")");
+                }
+                return Ok((params, false));
+            }
+        }
+        let mut variadic = false;
+        loop {
+            // Variable arguments must come last.
+            if self.variable_arguments() {
+                variadic = true;
+                if parenthesized && !self.eat(")") {
+                    return self.unexpected("')'");
+                }
+                if !parenthesized && !self.at_return(self.pos) {
+                    return self.unexpected("'returning'");
+                }
+                break;
+            }
+            params.push(self.phrase(false)?.into_declaration());
+            if parenthesized && self.eat(")") {
+                break;
+            }
+            if !parenthesized && self.at_return(self.pos) {
+                break;
+            }
+            let comma = self.eat(",");
+            let and = self.eat("and");
+            if !comma && !and {
+                return self.unexpected(if parenthesized { "')'" } else { "'returning'" });
+            }
+        }
+        Ok((params, variadic))
+    }
+
+    /// Parses a type which isn't derived from another, like "unsigned long", "struct foo", or a
+    /// typedef name.
+    fn base_type(&mut self, plural: bool) -> Result<Type<'src>, En2cError> {
+        let word = self.next("a type")?;
+        if let Ok(kind) = word.parse::<RecordKind>()
+            && word.chars().all(|c| c.is_ascii_lowercase())
+        {
+            // Tags are taken as written, since a tag like "status" may itself end in "s".
+            let tag = self.next("a tag")?;
+            return Ok(Type::Record(Record::named(kind, tag)));
+        }
+        self.pos -= 1;
+
+        // Find the longest primitive type matching the following words.
+        for primitive in PRIMITIVE_TYPES {
+            let len = primitive.split(' ').count();
+            let Some(words) = self.words.get(self.pos..self.pos + len) else {
+                continue;
+            };
+            let matches = primitive
+                .split(' ')
+                .zip(words)
+                .enumerate()
+                .all(|(i, (p, w))| {
+                    if i == len - 1 {
+                        is_noun(w, p, plural)
+                    } else {
+                        w.eq_ignore_ascii_case(p)
+                    }
+                });
+            if matches {
+                self.pos += len;
+                return Ok(Type::Primitive(PrimitiveType(primitive)));
+            }
+        }
+
+        // Anything else is a typedef name.
+        if word.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && word.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+            // Only the plural of a "_t" name is recognised, as in "uint8_ts"; other names may
+            // end in "s" on their own.
+            let word = if plural && word.ends_with("_ts") {
+                &word[..word.len() - 1]
+            } else {
+                word
+            };
+            Ok(Type::Custom(word))
+        } else {
+            self.unexpected("a type")
+        }
+    }
+}
+
+/// Parses a controlled-English phrase into the declaration it describes. If no name is given
+/// with "named", the declaration is abstract, like a type name.
+///
+/// # Errors
+///
+/// Returns an error if the phrase doesn't describe a type.
+pub fn parse_english(phrase: &str) -> Result<Declaration<'_>, En2cError> {
+    let mut parser = Parser::new(phrase);
+    // Typedefs are described as "a type named T defined as ...".
+    parser.skip_articles();
+    let decl = if parser.eat("type") && parser.eat("named") {
+        let name = parser.next("a name")?;
+        check_name(name, Standard::default()).map_err(En2cError::InvalidName)?;
+        if !(parser.eat("defined") && parser.eat("as")) {
+            return parser.unexpected("'defined as'");
+        }
+        let mut phrase = parser.phrase(false)?;
+        phrase.name = Some(name);
+        phrase.base.0.insert(TypeQualifier::Typedef);
+        phrase.into_declaration()
+    } else {
+        parser.pos = 0;
+        parser.phrase(false)?.into_declaration()
+    };
+    match parser.peek() {
+        None => Ok(decl),
+        Some(_) => parser.unexpected("the end of the phrase"),
+    }
+}
+
+/// Formats a declaration as it's written in C, e.g. `char (*x)[10]`.
+#[must_use]
+pub fn format_declaration(decl: &Declaration) -> String {
+    let typedef = if decl.base_type.0.contains(TypeQualifier::Typedef) {
+        "typedef "
+    } else {
+        ""
+    };
+    let ty = c_type(&decl.base_type);
+    match c_declarator(&decl.declarator).0 {
+        declarator if declarator.is_empty() => format!("{typedef}{ty}"),
+        declarator => format!("{typedef}{ty} {declarator}"),
+    }
+}
+
+/// Composes the C declaration described by a controlled-English phrase, e.g. `const char (*)[10]`
+/// for "pointer to array of 10 const char".
+///
+/// # Errors
+///
+/// Returns an error if the phrase doesn't describe a type, or describes one which can't exist,
+/// such as an array of functions.
+pub fn english_to_c(phrase: &str) -> Result<String, En2cError> {
+    let decl = parse_english(phrase)?;
+    check_declaration(&decl)?;
+    Ok(format_declaration(&decl))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn phrases() {
+        for (phrase, expected) in [
+            ("int", "int"),
+            ("pointer to array of 10 const char", "const char (*)[10]"),
+            (
+                "function taking int and char pointer returning void",
+                "void (int, char *)",
+            ),
+            ("const pointer to volatile int", "volatile int *const"),
+            ("array of 3 arrays of 4 doubles", "double [3][4]"),
+            ("array of 2 uint8_ts", "uint8_t [2]"),
+            ("array of 3 struct status", "struct status [3]"),
+            ("array of 4 address", "address [4]"),
+            (
+                "array of pointers to functions returning int",
+                "int (*[])(void)",
+            ),
+            ("pointer to function (void) returning int", "int (*)(void)"),
+            ("unsigned long long int", "unsigned long long int"),
+            ("pointer to struct node", "struct node *"),
+            ("block taking int returning void", "void (^)(int)"),
+            (
+                "function taking int and variable arguments returning void",
+                "void (int, ...)",
+            ),
+        ] {
+            assert_eq!(
+                english_to_c(phrase).as_deref(),
+                Ok(expected),
+                "Wrong output for {phrase}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        use chumsky::Parser;
+
+        for src in [
+            "int x",
+            "const char *const p",
+            "char *(*(**foo[][8])())[]",
+            "int f(int a, char *b, double)",
+            "void (^blk)(int)",
+            "struct foo *s[3]",
+            "unsigned long long int x",
+            "int main(void)",
+            "volatile int (*const fp)(const char *s)",
+            "typedef int (*handler_t)(int)",
+            "int printf(const char *fmt, ...)",
+            "int f(...)",
+        ] {
+            let decl = &crate::parser::parser().parse(src).unwrap()[0];
+            let english = crate::explainer::explain_declaration(decl)
+                .format_to_string(&crate::color::fmt::PlainFormatter);
+            assert_eq!(
+                parse_english(&english).as_ref(),
+                Ok(decl),
+                "Wrong round trip for {src} via '{english}'"
+            );
+        }
+    }
+
+    #[test]
+    fn format_round_trip() {
+        use chumsky::Parser;
+
+        for src in [
+            "int main(void)",
+            "int (*fp)(void)",
+            "void (^blk)(void)",
+            "char *(*fptab[])(void)",
+            "int printf(const char *fmt, ...)",
+            "int __stdcall f(void)",
+            "void __cdecl (*handlers[2])(int)",
+        ] {
+            let decl = &crate::parser::parser().parse(src).unwrap()[0];
+            assert_eq!(format_declaration(decl), src, "Wrong formatting of {src}");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        for (phrase, expected) in [
+            ("pointer int", "expected 'to', but found 'int'"),
+            ("pointer to", "expected a type, but the phrase ended"),
+            (
+                "function taking int",
+                "expected 'returning', but the phrase ended",
+            ),
+            ("int int", "expected the end of the phrase, but found 'int'"),
+            ("array of 10 %", "expected a type, but found '%'"),
+            ("x as int", "expected the end of the phrase, but found 'as'"),
+            ("int named 123", "\"123\" is not an identifier"),
+            (
+                "pointer named int to char",
+                "\"int\" is a keyword, so it cannot be used as a name",
+            ),
+            (
+                "type named while defined as int",
+                "\"while\" is a keyword, so it cannot be used as a name",
+            ),
+            (
+                "reference to int",
+                "references only exist in C++; use a pointer instead",
+            ),
+            (
+                "pointer to reference to int",
+                "references only exist in C++; use a pointer instead",
+            ),
+        ] {
+            assert_eq!(
+                english_to_c(phrase).unwrap_err().to_string(),
+                expected,
+                "Wrong error for {phrase}"
+            );
+        }
+    }
+
+    #[test]
+    fn impossible_types() {
+        for (phrase, expected) in [
+            (
+                "array of 10 function returning int",
+                SemanticError::ArrayOfFunctions,
+            ),
+            (
+                "function returning array of 3 int",
+                SemanticError::FunctionReturningArray,
+            ),
+            (
+                "function returning function returning int",
+                SemanticError::FunctionReturningFunction,
+            ),
+            ("array of 3 void", SemanticError::ArrayOfVoid),
+            (
+                "array of array of int",
+                SemanticError::ArrayOfIncompleteArrays,
+            ),
+            (
+                "array named x of function returning int",
+                SemanticError::ArrayOfFunctions,
+            ),
+        ] {
+            assert_eq!(
+                english_to_c(phrase),
+                Err(En2cError::Semantic(expected)),
+                "Wrong error for {phrase}"
+            );
+        }
+    }
+}
@@ -14,30 +14,188 @@
 //! Convert ASTs to a human-readable explanations
 
 use alloc::{
+    format,
     string::{String, ToString},
+    sync::Arc,
     vec,
+    vec::Vec,
 };
 
 use crate::{
-    ast::{Declaration, Declarator, QualifiedType, Type, TypeQualifier},
-    color::{Highlight, HighlightedText, HighlightedTextSegment},
+    ast::{
+        ArraySize, Attribute, BitInt, Declaration, Declarator, Designator, Enumerator, Initializer,
+        ListInitializer, Member, QualifiedType, Record, RecordBody, RecordKind, StorageClass, Type,
+        TypeQualifier, TypeQualifiers,
+    },
+    builtins,
+    color::{Group, Highlight, HighlightedText, HighlightedTextSegment},
+    provenance::{NodePath, Step},
+    usage::call_syntax,
 };
 
+/// Builds a slice of highlighted text segments for tests, e.g. `hltext!["an " n "int" pt]`.
+#[cfg(test)]
+macro_rules! hltext {
+    ( $($text:literal $hl:ident)+ ) => {
+        &[
+            $(hltext!(line $text $hl)),+
+        ]
+    };
+    ( line $text:literal n ) => {
+        HighlightedTextSegment::new($text, Highlight::None)
+    };
+    ( line $text:literal pt ) => {
+        HighlightedTextSegment::new($text, Highlight::PrimitiveType)
+    };
+    ( line $text:literal i ) => {
+        HighlightedTextSegment::new($text, Highlight::Ident)
+    };
+    ( line $text:literal q ) => {
+        HighlightedTextSegment::new($text, Highlight::Qualifier)
+    };
+    ( line $text:literal qk ) => {
+        HighlightedTextSegment::new($text, Highlight::QuasiKeyword)
+    };
+    ( line $text:literal num ) => {
+        HighlightedTextSegment::new($text, Highlight::Number)
+    };
+    ( line $text:literal udt ) => {
+        HighlightedTextSegment::new($text, Highlight::UserDefinedType)
+    };
+}
+
+mod diagram;
+mod difference;
+#[cfg(feature = "fluent")]
+mod fluent;
+mod numbers;
+mod phrasebook;
+mod pronunciation;
+mod walkthrough;
+
+pub use diagram::{DiagramStyle, draw_diagram};
+pub use difference::explain_difference;
+#[cfg(feature = "fluent")]
+pub use fluent::PhrasebookError;
+pub use phrasebook::{
+    Affixes, Gendered, KeywordPhrase, Language, PHRASE_IDS, Phrasebook, UnknownPhrase, Word,
+};
+pub use walkthrough::walk_through_declaration;
+
+/// Options controlling how declarations are explained
+// The flags are independent options, so they wouldn't be clearer as an enum.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Whether to describe [builtin typedefs][crate::builtins] by their underlying types, e.g.
+    /// "an unsigned 8-bit integer" instead of "a `uint8_t`".
+    pub expand_builtin_typedefs: bool,
+    /// How many levels of record definitions nested inside a record body to describe along with
+    /// their members. With the default of 0, nested definitions are only named, e.g. "a struct
+    /// point named origin".
+    pub nested_record_depth: usize,
+    /// How much detail to include in explanations.
+    pub verbosity: Verbosity,
+    /// Language to explain declarations in.
+    pub language: Language,
+    /// Phrasebook to use instead of the one for [`language`][Options::language], e.g. one loaded
+    /// from a Fluent resource.
+    pub phrasebook: Option<Arc<Phrasebook>>,
+    /// Whether to break explanations across lines, one per declarator level, with each level
+    /// indented further than the one it's part of. Parameters and members are explained on a
+    /// single line.
+    pub multiline: bool,
+    /// Who explanations are written for.
+    pub audience: Audience,
+    /// Whether to write array sizes as words, e.g. "an array of ten ints" instead of "an array of
+    /// 10 ints". The words are still highlighted as [numbers][Highlight::Number].
+    pub spell_out_numbers: bool,
+    /// Whether to add hints about well-known idioms which declarations follow, e.g. "commonly a
+    /// string" for a `char *`, after the literal explanation.
+    pub idioms: bool,
+    /// Whether to show how to call functions through declared function pointers, e.g. "call it
+    /// as `(*handler)(code)` or simply `handler(code)`", and how to assign to the pointers.
+    pub call_syntax: bool,
+    /// Whether to state what may and may not be modified through the declared name when `const`
+    /// is involved, e.g. "p may be modified, but *p may not" for `const char *p`.
+    pub mutability: bool,
+    /// Whether to record the [origin][HighlightedTextSegment::origin] of each highlighted segment
+    /// of explanations, i.e. the part of the declaration which it explains.
+    pub provenance: bool,
+    /// Whether to record the logical [groups][HighlightedTextSegment::groups] which the segments
+    /// of explanations are part of, i.e. declarator levels and function parameters, so
+    /// formatters can wrap each group in a container.
+    pub groups: bool,
+}
+
+impl Options {
+    /// Returns whether explanations should be [terse][Verbosity::Terse].
+    fn is_terse(&self) -> bool {
+        self.verbosity == Verbosity::Terse || self.audience == Audience::Expert
+    }
+
+    /// Returns the phrasebook of the language to explain declarations in.
+    fn phrasebook(&self) -> &Phrasebook {
+        self.phrasebook
+            .as_deref()
+            .unwrap_or_else(|| self.language.phrasebook())
+    }
+
+    /// Returns the appropriate article for the given noun in the explanation's language, or
+    /// nothing if explanations are terse.
+    fn article_for(&self, noun: &HighlightedTextSegment) -> &'static str {
+        if self.is_terse() {
+            ""
+        } else {
+            self.phrasebook().article(noun)
+        }
+    }
+
+    /// Returns a copy of the options which explains declarations on a single line.
+    fn single_line(&self) -> Options {
+        Options {
+            multiline: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// Level of detail of explanations
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum Verbosity {
+    /// Omits articles, names, and plurals, e.g. "pointer to array of 10 int".
+    Terse,
+    #[default]
+    Normal,
+    /// Adds asides explaining what qualifiers and storage classes mean, e.g. that the value a
+    /// pointer to `const` points to cannot be modified through it.
+    Verbose,
+}
+
+/// Audience of explanations
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum Audience {
+    /// Adds clarifications from the [glossary][Phrasebook::glossary] after the first use of
+    /// each term, e.g. "a pointer (a variable that stores a memory address)".
+    Beginner,
+    #[default]
+    Standard,
+    /// Keeps explanations [terse][Verbosity::Terse].
+    Expert,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Plurality {
     Singular,
     Plural,
 }
 
-/// Returns the appropriate article ("a" or "an") for the given noun, followed by a space.
-fn article_for(noun: &HighlightedTextSegment) -> &'static str {
-    match noun.text.chars().next() {
-        Some('a' | 'e' | 'i' | 'o' | 'u') => "an ",
-        Some(_) => "a ",
-        None => "",
-    }
-}
-
 /// Naively returns the plural suffix for a noun.
 fn plural_suffix_for(noun: &HighlightedTextSegment) -> &'static str {
     match noun.text.chars().last() {
@@ -47,46 +205,973 @@ fn plural_suffix_for(noun: &HighlightedTextSegment) -> &'static str {
     }
 }
 
+/// Returns the adjective describing a storage class.
+fn storage_class_adjective(class: StorageClass) -> &'static str {
+    match class {
+        StorageClass::Auto => "automatic",
+        StorageClass::Register => "register",
+        StorageClass::Static => "static",
+        StorageClass::Extern => "externally-linked",
+    }
+}
+
+/// Returns the first noun of an explanation, i.e. its first highlighted segment.
+fn first_noun(msg: &HighlightedText) -> HighlightedTextSegment {
+    msg.iter()
+        .find(|segment| segment.highlight != Highlight::None)
+        .cloned()
+        .unwrap_or_else(|| "".into())
+}
+
+/// Inserts an adjective before the first noun of an explanation, replacing the leading article
+/// with one which agrees with the adjective. In languages where adjectives follow nouns, the
+/// adjective is inserted after the first noun instead.
+fn prepend_adjective(
+    msg: &mut HighlightedText,
+    adjective: HighlightedTextSegment,
+    options: &Options,
+) {
+    if options.phrasebook().adjectives_after_nouns {
+        let index = msg
+            .iter()
+            .position(|segment| segment.highlight != Highlight::None)
+            .map_or(msg.len(), |i| i + 1);
+        msg.splice(index..index, [" ".into(), adjective]);
+        return;
+    }
+    if let Some(first) = msg.first_mut()
+        && first.highlight == Highlight::None
+        && let Some(rest) = first
+            .text
+            .strip_prefix("an ")
+            .or_else(|| first.text.strip_prefix("a "))
+    {
+        if rest.is_empty() {
+            msg.remove(0);
+        } else {
+            first.text = rest.to_string();
+        }
+    }
+    msg.splice(0..0, [adjective, " ".into()]);
+    let article = options.article_for(&msg[0]);
+    if !article.is_empty() {
+        msg.insert(0, article.into());
+    }
+}
+
+/// Records the given path as the origin of the highlighted segments of an explanation which
+/// don't have one yet, if explanations record their [provenance][Options::provenance].
+fn claim(msg: &mut HighlightedText, path: &[Step], options: &Options) {
+    if !options.provenance {
+        return;
+    }
+    for segment in msg.iter_mut() {
+        if segment.highlight != Highlight::None && segment.origin.is_none() {
+            segment.origin = Some(NodePath(path.to_vec()));
+        }
+    }
+}
+
+/// Position in an explanation, after which the text explained later can be made part of a group
+#[derive(Debug, Clone, Copy, Default)]
+struct Mark {
+    /// Number of segments before the position
+    segments: usize,
+    /// Length of the text of the last segment before the position
+    text_len: usize,
+}
+
+impl Mark {
+    /// Returns the position at the end of the given explanation.
+    fn end_of(msg: &HighlightedText) -> Self {
+        Self {
+            segments: msg.len(),
+            text_len: msg.last().map_or(0, |segment| segment.text.len()),
+        }
+    }
+}
+
+/// Makes the segments of an explanation after the given mark part of a group, if explanations
+/// record their [groups][Options::groups]. Text appended to the segment before the mark is split
+/// off into a segment of its own first.
+fn group_after(msg: &mut HighlightedText, mark: Mark, group: Group, options: &Options) {
+    if !options.groups {
+        return;
+    }
+    if let Some(last) = mark.segments.checked_sub(1).map(|i| &mut msg[i])
+        && last.text.len() > mark.text_len
+    {
+        let mut rest = last.clone();
+        rest.text = last.text.split_off(mark.text_len);
+        msg.insert(mark.segments, rest);
+    }
+    for segment in &mut msg[mark.segments..] {
+        segment.groups.insert(0, group);
+    }
+}
+
+/// Prepends a step to the origins of the segments of an explanation, once it's made part of the
+/// explanation of the step's parent node.
+fn nest(msg: &mut HighlightedText, step: Step) {
+    for segment in msg.iter_mut() {
+        if let Some(origin) = segment.origin.take() {
+            segment.origin = Some(origin.within(step));
+        }
+    }
+}
+
+/// Pushes the given items as a list, e.g. "a", "a and b", or "a, b, and c" in English.
+fn push_list(
+    msg: &mut HighlightedText,
+    items: impl IntoIterator<Item = HighlightedText>,
+    options: &Options,
+) {
+    let phrasebook = options.phrasebook();
+    let items: Vec<HighlightedText> = items.into_iter().collect();
+    let count = items.len();
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            msg.push_str(match (count, i + 1 == count) {
+                (2, _) => &phrasebook.and,
+                (_, true) => &phrasebook.final_and,
+                (_, false) => ", ",
+            });
+        }
+        msg.extend(item.0);
+    }
+}
+
 #[must_use]
 pub fn explain_declaration(decl: &Declaration) -> HighlightedText {
+    explain_declaration_with_options(decl, &Options::default())
+}
+
+/// Explains a declaration using the given options.
+#[must_use]
+pub fn explain_declaration_with_options(decl: &Declaration, options: &Options) -> HighlightedText {
+    let mut msg = explain_complete_declaration(decl, options);
+    push_call_syntax(&mut msg, decl, options);
+    if options.audience == Audience::Beginner {
+        clarify_terms(msg, options)
+    } else {
+        msg
+    }
+}
+
+/// Definition of a term used in an explanation, from the [glossary][Phrasebook::glossary]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footnote {
+    /// Index of the segment of the explanation in which the term first appears
+    pub segment: usize,
+    /// Term as it appears in the explanation, e.g. "pointers"
+    pub term: String,
+    pub definition: &'static str,
+}
+
+/// Explanation along with footnotes defining the terms it uses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnnotatedExplanation {
+    pub text: HighlightedText,
+    /// Footnotes for the first use of each term in the glossary, in the order they appear
+    pub footnotes: Vec<Footnote>,
+}
+
+/// Explains a declaration, returning definitions of the terms it uses as footnotes instead of
+/// adding them to the text as [beginner][Audience::Beginner] explanations do, so they can be
+/// shown as tooltips or listed after the explanation.
+#[must_use]
+pub fn explain_declaration_with_footnotes(
+    decl: &Declaration,
+    options: &Options,
+) -> AnnotatedExplanation {
+    let mut text = explain_complete_declaration(decl, options);
+    push_call_syntax(&mut text, decl, options);
+    let footnotes = glossary_footnotes(&text, options);
+    AnnotatedExplanation { text, footnotes }
+}
+
+/// Returns footnotes defining the first use of each term from the
+/// [glossary][Phrasebook::glossary] in the explanation.
+fn glossary_footnotes(msg: &HighlightedText, options: &Options) -> Vec<Footnote> {
+    let glossary = &options.phrasebook().glossary;
+    let mut defined = Vec::new();
+    let mut footnotes = Vec::new();
+    for (index, segment) in msg.iter().enumerate() {
+        if segment.highlight == Highlight::None {
+            continue;
+        }
+        for word in segment.text.split_whitespace() {
+            if let Some(i) = glossary
+                .iter()
+                .position(|(term, _)| term.singular == word || term.plural == word)
+                && !defined.contains(&i)
+            {
+                defined.push(i);
+                footnotes.push(Footnote {
+                    segment: index,
+                    term: word.to_string(),
+                    definition: glossary[i].1,
+                });
+            }
+        }
+    }
+    footnotes
+}
+
+/// Adds clarifications from the [glossary][Phrasebook::glossary] after the first use of each
+/// term in the explanation, e.g. "a pointer (a variable that stores a memory address)".
+fn clarify_terms(msg: HighlightedText, options: &Options) -> HighlightedText {
+    let footnotes = glossary_footnotes(&msg, options);
+    let mut result = HighlightedText::new();
+    for (index, segment) in msg.0.into_iter().enumerate() {
+        let clarifications: Vec<&str> = footnotes
+            .iter()
+            .filter(|footnote| footnote.segment == index)
+            .map(|footnote| footnote.definition)
+            .collect();
+        result.push(segment);
+        if !clarifications.is_empty() {
+            result.push_str(" (");
+            result.push_str(&clarifications.join("; "));
+            result.push_str(")");
+        }
+    }
+    result
+}
+
+/// Explains a declaration along with its record body and initializer, if any.
+fn explain_complete_declaration(decl: &Declaration, options: &Options) -> HighlightedText {
+    let mut msg = explain_declaration_without_body(decl, options);
+    if let Type::Record(record) = &decl.base_type.1 {
+        msg.extend(explain_record_body(record, &options.single_line()).0);
+    }
+    if let Some(initializer) = &decl.initializer {
+        let mut initializer = explain_initializer(initializer, options);
+        claim(&mut initializer, &[Step::Initializer], options);
+        msg.extend(initializer.0);
+    }
+    msg
+}
+
+/// Adds how to call a function through the pointer declared by a declaration, if the options ask
+/// for it, e.g. "; call it as `(*handler)(code)` or simply `handler(code)`".
+fn push_call_syntax(msg: &mut HighlightedText, decl: &Declaration, options: &Options) {
+    let Some(call) = call_syntax(decl).filter(|_| options.call_syntax) else {
+        return;
+    };
+    let phrasebook = options.phrasebook();
+    msg.push_str(&phrasebook.call_it_as);
+    msg.push_str(&format!("`{}`", call.explicit));
+    msg.push_str(&phrasebook.or_simply);
+    msg.push_str(&format!("`{}`", call.implicit));
+    if let Some(assignment) = call.assignment {
+        msg.push_str(&phrasebook.and_assign_it_with);
+        msg.push_str(&format!("`{assignment}`"));
+    }
+}
+
+/// Explains an initializer, e.g. " initialized to 5".
+fn explain_initializer(initializer: &Initializer, options: &Options) -> HighlightedText {
+    let phrasebook = options.phrasebook();
+    let mut msg = HighlightedText::new();
+    match initializer {
+        Initializer::Expr(expr) => {
+            msg.push_str(&phrasebook.initialized_to);
+            let is_number = expr
+                .trim_start_matches(['-', '+'])
+                .starts_with(|c: char| c.is_ascii_digit() || c == '.');
+            let highlight = if is_number {
+                Highlight::Number
+            } else {
+                Highlight::None
+            };
+            msg.push(HighlightedTextSegment::new(*expr, highlight));
+        }
+        Initializer::List(elements) if elements.is_empty() => {
+            msg.push_str(&phrasebook.initialized_with_an_empty_list);
+        }
+        Initializer::List(elements) => {
+            msg.push_str(&phrasebook.initialized_with_a_list_of);
+            msg.push(HighlightedTextSegment::new(
+                elements.len().to_string(),
+                Highlight::Number,
+            ));
+            msg.push_str(phrasebook.value.get(if elements.len() == 1 {
+                Plurality::Singular
+            } else {
+                Plurality::Plural
+            }));
+            let designated: Vec<&ListInitializer> = elements
+                .iter()
+                .filter(|element| !element.designators.is_empty())
+                .collect();
+            if !designated.is_empty() {
+                msg.push_str(&phrasebook.with_designators_for);
+                push_list(
+                    &mut msg,
+                    designated
+                        .into_iter()
+                        .map(|element| explain_designators(&element.designators)),
+                    options,
+                );
+            }
+        }
+    }
+    msg
+}
+
+/// Formats a chain of designators as it appears in the source, e.g. ".y[i + 1]".
+fn explain_designators(designators: &[Designator]) -> HighlightedText {
+    let mut msg = HighlightedText::new();
+    for designator in designators {
+        match designator {
+            Designator::Member(name) => {
+                msg.push_str(".");
+                msg.push(HighlightedTextSegment::new(*name, Highlight::Ident));
+            }
+            Designator::Index(index) => {
+                msg.push_str("[");
+                msg.push(HighlightedTextSegment::new(*index, Highlight::Number));
+                msg.push_str("]");
+            }
+        }
+    }
+    msg
+}
+
+/// Explains a declaration without describing the body of its base type, if it has one.
+fn explain_declaration_without_body(decl: &Declaration, options: &Options) -> HighlightedText {
+    let mut msg = if decl.forward_declaration
+        && let Type::Record(record) = &decl.base_type.1
+    {
+        explain_forward_declaration(record, options)
+    } else if decl.base_type.0.contains(TypeQualifier::Typedef) {
+        explain_typedef(decl, options)
+    } else {
+        explain_declaration_impl(decl, false, options)
+    }
+    .msg;
+    let record_attributes = match &decl.base_type.1 {
+        Type::Record(record) => record.attributes.as_slice(),
+        _ => &[],
+    };
+    let phrasebook = options.phrasebook();
+    let mut phrases: Vec<HighlightedText> = record_attributes
+        .iter()
+        .chain(&decl.attributes)
+        .filter_map(|attribute| explain_attribute(attribute, phrasebook))
+        .collect();
+    if decl.implicit_int {
+        let int = HighlightedTextSegment::new("int", Highlight::PrimitiveType);
+        let mut phrase = HighlightedText::new();
+        phrase.push_str(&phrasebook.implicitly);
+        phrase.push_str(phrasebook.article(&int));
+        phrase.push(int);
+        phrases.insert(0, phrase);
+    }
+    if let Some(text) = linkage_phrase(decl, phrasebook) {
+        let mut phrase = HighlightedText::new();
+        phrase.push_str(text);
+        phrases.push(phrase);
+    }
+    if let Some(label) = decl.asm_label {
+        let mut phrase = HighlightedText::new();
+        // An asm label on a register variable names the register to store it in.
+        phrase.push_str(if decl.storage_class == Some(StorageClass::Register) {
+            &phrasebook.stored_in_register
+        } else {
+            &phrasebook.with_assembler_name
+        });
+        phrase.push(HighlightedTextSegment::new(label, Highlight::Ident));
+        phrases.push(phrase);
+    }
+    if options.mutability
+        && let Some(phrase) = explain_mutability(decl, options)
+    {
+        phrases.push(phrase);
+    }
+    if options.idioms
+        && let Some(hint) = idiom(decl, phrasebook)
+    {
+        let mut phrase = HighlightedText::new();
+        phrase.push_str(hint);
+        phrases.push(phrase);
+    }
+    if options.verbosity == Verbosity::Verbose {
+        phrases.extend(asides(decl, phrasebook).into_iter().map(|aside| {
+            let mut phrase = HighlightedText::new();
+            phrase.push_str(aside);
+            phrase
+        }));
+    }
+    if !phrases.is_empty() {
+        msg.push_str(" (");
+        for (i, phrase) in phrases.into_iter().enumerate() {
+            if i > 0 {
+                msg.push_str(", ");
+            }
+            msg.extend(phrase.0);
+        }
+        msg.push_str(")");
+    }
+    claim(&mut msg, &[], options);
+    msg
+}
+
+/// Returns a phrase describing what the storage class of a file-scope declaration implies, e.g.
+/// that a `static` declaration has internal linkage.
+fn linkage_phrase<'a>(decl: &Declaration, phrasebook: &'a Phrasebook) -> Option<&'a str> {
+    match decl.storage_class? {
+        StorageClass::Static => Some(&*phrasebook.with_internal_linkage),
+        // An `extern` declaration with an initializer or body is also a definition.
+        StorageClass::Extern if decl.initializer.is_none() && decl.body.is_none() => {
+            Some(&*phrasebook.defined_elsewhere)
+        }
+        _ => None,
+    }
+}
+
+/// Explains what may and may not be modified through the name declared by a declaration, e.g.
+/// "p may be modified, but *p may not" for `const char *p`. Returns `None` if everything may be
+/// modified, or if the declaration doesn't declare an object which is accessed through pointers
+/// and arrays.
+fn explain_mutability(decl: &Declaration, options: &Options) -> Option<HighlightedText> {
     if decl.base_type.0.contains(TypeQualifier::Typedef) {
-        explain_typedef(decl)
+        return None;
+    }
+    let mut expr = decl.declarator.name()?.to_string();
+    // Whether the expression ends with a prefix operator, so a postfix operator following it must
+    // be parenthesized
+    let mut is_prefix = false;
+    let mut indices = ['i', 'j', 'k', 'l', 'm', 'n'].into_iter().cycle();
+    // Each object which is accessed through the name and whether it may be modified, starting
+    // with the one the name refers to
+    let mut objects = Vec::new();
+    let mut declarator = &decl.declarator;
+    let mut parts = Vec::new();
+    loop {
+        match declarator {
+            Declarator::Anonymous | Declarator::Ident(_) => break,
+            Declarator::Ptr(inner, _)
+            | Declarator::Reference(inner)
+            | Declarator::Array(inner, ..) => {
+                parts.push(declarator);
+                declarator = inner;
+            }
+            Declarator::Block(..) | Declarator::MemberPtr(..) | Declarator::Function { .. } => {
+                return None;
+            }
+        }
+    }
+    for part in parts.into_iter().rev() {
+        match part {
+            Declarator::Ptr(_, qualifiers) => {
+                objects.push((expr.clone(), !qualifiers.contains(TypeQualifier::Const)));
+                expr = format!("*{expr}");
+                is_prefix = true;
+            }
+            // Neither references nor arrays themselves can be assigned to.
+            Declarator::Array(..) => {
+                if is_prefix {
+                    expr = format!("({expr})");
+                    is_prefix = false;
+                }
+                expr = format!("{expr}[{}]", indices.next().unwrap_or_default());
+            }
+            _ => {}
+        }
+    }
+    objects.push((expr, !decl.base_type.0.contains(TypeQualifier::Const)));
+    let (modifiable, constant): (Vec<_>, Vec<_>) =
+        objects.into_iter().partition(|(_, modifiable)| *modifiable);
+    if constant.is_empty() {
+        return None;
+    }
+    let phrasebook = options.phrasebook();
+    let plurality = |objects: &[(String, bool)]| {
+        if objects.len() > 1 {
+            Plurality::Plural
+        } else {
+            Plurality::Singular
+        }
+    };
+    let list = |msg: &mut HighlightedText, objects: &[(String, bool)]| {
+        push_list(
+            msg,
+            objects.iter().map(|(expr, _)| {
+                HighlightedText::from(vec![HighlightedTextSegment::new(
+                    expr.as_str(),
+                    Highlight::Ident,
+                )])
+            }),
+            options,
+        );
+    };
+    let mut msg = HighlightedText::new();
+    if modifiable.is_empty() {
+        list(&mut msg, &constant);
+        msg.push_str(phrasebook.may_not_be_modified.get(plurality(&constant)));
+    } else {
+        list(&mut msg, &modifiable);
+        msg.push_str(phrasebook.may_be_modified.get(plurality(&modifiable)));
+        msg.push_str(&phrasebook.but);
+        list(&mut msg, &constant);
+        msg.push_str(phrasebook.may_not.get(plurality(&constant)));
+    }
+    Some(msg)
+}
+
+/// Returns a hint naming the idiom which the type of a declaration commonly follows: a `char *`
+/// is commonly a string, a `char **` or `char *[]` an array of strings, and a pointer to a
+/// function taking a `void *` a callback with a context pointer.
+fn idiom<'a>(decl: &Declaration, phrasebook: &'a Phrasebook) -> Option<&'a str> {
+    let is_leaf = |declarator: &Declarator| {
+        matches!(declarator, Declarator::Anonymous | Declarator::Ident(_))
+    };
+    let is_primitive = |decl: &Declaration, name: &str| matches!(&decl.base_type.1, Type::Primitive(ty) if ty.as_ref() == name);
+    match &decl.declarator {
+        Declarator::Ptr(inner, _) if is_primitive(decl, "char") => match &**inner {
+            inner if is_leaf(inner) => Some(&*phrasebook.commonly_a_string),
+            Declarator::Ptr(inner, _) | Declarator::Array(inner, ..) if is_leaf(inner) => {
+                Some(&*phrasebook.commonly_an_array_of_strings)
+            }
+            _ => None,
+        },
+        Declarator::Function { func, params, .. }
+            if matches!(&**func, Declarator::Ptr(inner, _) if is_leaf(inner))
+                && params.iter().any(|param| {
+                    is_primitive(param, "void")
+                        && matches!(&param.declarator, Declarator::Ptr(inner, _) if is_leaf(inner))
+                }) =>
+        {
+            Some(&*phrasebook.commonly_a_callback)
+        }
+        _ => None,
+    }
+}
+
+/// Returns educational asides for [verbose][Verbosity::Verbose] explanations, describing what the
+/// qualifiers and storage class of a declaration mean.
+fn asides<'a>(decl: &Declaration, phrasebook: &'a Phrasebook) -> Vec<&'a str> {
+    let mut asides = Vec::new();
+    let mut const_target = false;
+    let mut const_pointer = false;
+    let mut restrict_pointer = false;
+    let mut volatile = decl.base_type.0.contains(TypeQualifier::Volatile);
+    // Qualifiers of the type which the current declarator describes the derivation of, starting
+    // with the base type. E.g. in `const char *p`, the pointer points to a `const char`.
+    let mut target_qualifiers = decl.base_type.0;
+    let mut declarator = &decl.declarator;
+    loop {
+        match declarator {
+            Declarator::Ptr(inner, qualifiers)
+            | Declarator::Block(inner, qualifiers)
+            | Declarator::MemberPtr(inner, _, qualifiers) => {
+                const_target |= target_qualifiers.contains(TypeQualifier::Const);
+                const_pointer |= qualifiers.contains(TypeQualifier::Const);
+                restrict_pointer |= qualifiers.contains(TypeQualifier::Restrict);
+                volatile |= qualifiers.contains(TypeQualifier::Volatile);
+                target_qualifiers = *qualifiers;
+                declarator = inner;
+            }
+            Declarator::Reference(inner) => {
+                const_target |= target_qualifiers.contains(TypeQualifier::Const);
+                target_qualifiers = TypeQualifiers::default();
+                declarator = inner;
+            }
+            Declarator::Array(inner, ..) => declarator = inner,
+            // The qualifiers of a function's return type don't affect the function itself.
+            Declarator::Function { func, .. } => {
+                target_qualifiers = TypeQualifiers::default();
+                declarator = func;
+            }
+            Declarator::Anonymous | Declarator::Ident(_) => break,
+        }
+    }
+    if const_target {
+        asides.push(&*phrasebook.target_cannot_be_modified);
+    }
+    if const_pointer {
+        asides.push(&*phrasebook.pointer_cannot_be_changed);
+    } else if target_qualifiers.contains(TypeQualifier::Const)
+        && !decl.base_type.0.contains(TypeQualifier::Typedef)
+    {
+        asides.push(&*phrasebook.value_cannot_be_modified);
+    }
+    if restrict_pointer {
+        asides.push(&*phrasebook.no_other_pointer_accesses_target);
+    }
+    if volatile {
+        asides.push(&*phrasebook.accesses_are_never_optimized_away);
+    }
+    if decl.storage_class == Some(StorageClass::Register) {
+        asides.push(&*phrasebook.address_cannot_be_taken);
+    }
+    asides
+}
+
+/// Pushes a phrase with its keyword highlighted, e.g. "possibly " followed by "unused".
+fn push_keyword_phrase(msg: &mut HighlightedText, phrase: &KeywordPhrase) {
+    msg.push_str(&phrase.before);
+    msg.push(HighlightedTextSegment::new(
+        &*phrase.keyword,
+        Highlight::QuasiKeyword,
+    ));
+    msg.push_str(&phrase.after);
+}
+
+/// Explains a known attribute, e.g. "aligned to 8 bytes". Returns `None` for attributes which
+/// are not understood.
+fn explain_attribute(attribute: &Attribute, phrasebook: &Phrasebook) -> Option<HighlightedText> {
+    let name = attribute
+        .name
+        .strip_prefix("gnu::")
+        .unwrap_or(attribute.name);
+    let name = name
+        .strip_prefix("__")
+        .and_then(|name| name.strip_suffix("__"))
+        .unwrap_or(name);
+    let mut msg = HighlightedText::new();
+    match (name, attribute.args) {
+        ("packed", _) => push_keyword_phrase(&mut msg, &phrasebook.packed),
+        ("aligned" | "align", Some(alignment)) => {
+            push_keyword_phrase(&mut msg, &phrasebook.aligned_to);
+            msg.push(HighlightedTextSegment::new(alignment, Highlight::Number));
+            msg.push_str(&phrasebook.bytes);
+        }
+        ("aligned", None) => push_keyword_phrase(&mut msg, &phrasebook.maximally_aligned),
+        ("deprecated", message) => {
+            push_keyword_phrase(&mut msg, &phrasebook.deprecated);
+            if let Some(message) = message {
+                msg.push_str(": ");
+                msg.push_str(message);
+            }
+        }
+        ("unused" | "maybe_unused", _) => {
+            push_keyword_phrase(&mut msg, &phrasebook.possibly_unused);
+        }
+        ("noreturn", _) => push_keyword_phrase(&mut msg, &phrasebook.non_returning),
+        ("warn_unused_result" | "nodiscard", _) => {
+            push_keyword_phrase(&mut msg, &phrasebook.result_must_be_used);
+        }
+        ("weak", _) => push_keyword_phrase(&mut msg, &phrasebook.weakly_linked),
+        ("dllimport", _) => push_keyword_phrase(&mut msg, &phrasebook.imported_from_a_dll),
+        ("dllexport", _) => push_keyword_phrase(&mut msg, &phrasebook.exported_from_a_dll),
+        _ => return None,
+    }
+    Some(msg)
+}
+
+/// Explains the body of a record definition, e.g. " containing an int named x". Returns an empty
+/// text if the record has no body.
+///
+/// Named record definitions nested in the members are described up to the depth given by
+/// [`Options::nested_record_depth`], and are only named below it.
+fn explain_record_body(record: &Record, options: &Options) -> HighlightedText {
+    let mut msg = HighlightedText::new();
+    match &record.body {
+        None => (),
+        Some(RecordBody::Members(members)) if members.is_empty() => {
+            msg.push_str(&options.phrasebook().containing_no_members);
+        }
+        Some(RecordBody::Members(members)) => {
+            msg.push_str(&options.phrasebook().containing);
+            let last = members.len() - 1;
+            push_list(
+                &mut msg,
+                members.iter().enumerate().map(|(i, member)| {
+                    let mut msg = if record.kind == RecordKind::Struct
+                        && i == last
+                        && is_flexible_array_member(member)
+                    {
+                        explain_flexible_array_member(&member.declaration, options)
+                    } else {
+                        explain_member(member, options)
+                    };
+                    claim(&mut msg, &[], options);
+                    nest(&mut msg, Step::Member(i));
+                    msg
+                }),
+                options,
+            );
+        }
+        Some(RecordBody::Enumerators(enumerators)) => {
+            msg.push_str(&options.phrasebook().with_values);
+            push_list(
+                &mut msg,
+                Enumerator::values(enumerators)
+                    .enumerate()
+                    .map(|(i, (enumerator, value))| {
+                        let mut msg = explain_enumerator(enumerator, value);
+                        claim(&mut msg, &[Step::Enumerator(i)], options);
+                        msg
+                    }),
+                options,
+            );
+        }
+    }
+    msg
+}
+
+/// Returns `true` if the member would be a flexible array member when placed last in a struct,
+/// i.e. if its identifier is directly declared as an array of unknown size.
+fn is_flexible_array_member(member: &Member) -> bool {
+    fn is_unsized_array(declarator: &Declarator) -> bool {
+        match declarator {
+            Declarator::Array(inner, ArraySize::Unspecified, _)
+                if matches!(**inner, Declarator::Ident(_)) =>
+            {
+                true
+            }
+            Declarator::Ptr(inner, _)
+            | Declarator::Reference(inner)
+            | Declarator::Block(inner, _)
+            | Declarator::MemberPtr(inner, ..)
+            | Declarator::Array(inner, ..) => is_unsized_array(inner),
+            Declarator::Function { func, .. } => is_unsized_array(func),
+            Declarator::Anonymous | Declarator::Ident(_) => false,
+        }
+    }
+    member.bit_width.is_none() && is_unsized_array(&member.declaration.declarator)
+}
+
+/// Explains a flexible array member, e.g. "a flexible array of chars named data".
+fn explain_flexible_array_member(decl: &Declaration, options: &Options) -> HighlightedText {
+    let mut msg = explain_declaration_impl(decl, true, options).msg;
+    prepend_adjective(
+        &mut msg,
+        HighlightedTextSegment::new("flexible", Highlight::QuasiKeyword),
+        options,
+    );
+    msg
+}
+
+/// Explains a member of a struct or union. Bit-fields are described by their width, e.g. "a 3-bit
+/// unsigned bit-field named flags", along with the range of values of unsigned bit-fields.
+/// Anonymous struct and union members are described along with their members, since those are
+/// accessed as if they were members of the enclosing record.
+fn explain_member(member: &Member, options: &Options) -> HighlightedText {
+    let decl = &member.declaration;
+    if let (
+        Declarator::Anonymous,
+        Type::Record(
+            record @ Record {
+                name: None,
+                body: Some(RecordBody::Members(_)),
+                ..
+            },
+        ),
+    ) = (&decl.declarator, &decl.base_type.1)
+    {
+        let ty = format_qualified_type(&decl.base_type, options);
+        let mut msg = HighlightedText::new();
+        msg.push_str(options.article_for(&ty[0]));
+        msg.extend(ty.0);
+        let adjective =
+            HighlightedTextSegment::new(&*options.phrasebook().unnamed, Highlight::None);
+        prepend_adjective(&mut msg, adjective, options);
+        msg.extend(explain_record_body(record, options).0);
+        return msg;
+    }
+    let Some(width) = member.bit_width else {
+        let mut msg = explain_declaration_without_body(decl, options);
+        if let Type::Record(record) = &decl.base_type.1
+            && options.nested_record_depth > 0
+        {
+            let options = Options {
+                nested_record_depth: options.nested_record_depth - 1,
+                ..options.clone()
+            };
+            msg.extend(explain_record_body(record, &options).0);
+        }
+        return msg;
+    };
+    let phrasebook = options.phrasebook();
+    let width_text = bit_width(width, phrasebook);
+    let noun = HighlightedTextSegment::new(&*phrasebook.bit_field, Highlight::QuasiKeyword);
+    let mut msg = HighlightedText::new();
+    if let Declarator::Ident(_) | Declarator::Anonymous = decl.declarator {
+        let mut ty = format_qualified_type(&decl.base_type, options);
+        claim(&mut ty, &[Step::BaseType], options);
+        let mut field = HighlightedText::new();
+        push_described(&mut field, vec![noun.clone()].into(), ty, phrasebook);
+        let mut bit_field = HighlightedText::new();
+        push_described(&mut bit_field, field, width_text, phrasebook);
+        msg.push_str(options.article_for(&bit_field[0]));
+        msg.extend(bit_field.0);
+        if let Some(name) = decl.declarator.name().filter(|_| !options.is_terse()) {
+            msg.push_str(phrasebook.named_for(&noun));
+            let mut name = HighlightedTextSegment::new(name, Highlight::Ident);
+            if options.provenance {
+                name.origin = Some(NodePath(vec![Step::Declarator]));
+            }
+            msg.push(name);
+        }
+        if let Some(max) = unsigned_bit_field_max(&decl.base_type.1, width) {
+            msg.push_str(&phrasebook.that_can_hold_values);
+            msg.push(HighlightedTextSegment::new("0", Highlight::Number));
+            msg.push_str("–");
+            msg.push(HighlightedTextSegment::new(
+                max.to_string(),
+                Highlight::Number,
+            ));
+        }
+    } else {
+        // Bit-fields must have integer types, but explain what we can anyway.
+        msg.extend(explain_declaration_without_body(decl, options).0);
+        msg.push_str(&phrasebook.in_);
+        let mut bit_field = HighlightedText::new();
+        push_described(&mut bit_field, vec![noun].into(), width_text, phrasebook);
+        msg.push_str(options.article_for(&bit_field[0]));
+        msg.extend(bit_field.0);
+    }
+    msg
+}
+
+/// Describes a width in bits, e.g. "24-bit".
+fn bit_width(width: usize, phrasebook: &Phrasebook) -> HighlightedText {
+    let mut msg = HighlightedText::new();
+    msg.push_str(&phrasebook.bits.before);
+    msg.push(HighlightedTextSegment::new(
+        width.to_string(),
+        Highlight::Number,
+    ));
+    msg.push_str(&phrasebook.bits.after);
+    msg
+}
+
+/// Pushes a noun along with a phrase describing it, in the order used by the explanation's
+/// language, e.g. "24-bit unsigned integer" or "entero sin signo de 24 bits".
+fn push_described(
+    msg: &mut HighlightedText,
+    noun: HighlightedText,
+    description: HighlightedText,
+    phrasebook: &Phrasebook,
+) {
+    let (first, second) = if phrasebook.adjectives_after_nouns {
+        (noun, description)
     } else {
-        explain_declaration_impl(decl)
+        (description, noun)
+    };
+    msg.extend(first.0);
+    msg.push_str(" ");
+    msg.extend(second.0);
+}
+
+/// Returns the largest value an unsigned bit-field of the given type and width can hold, or
+/// `None` if the type isn't known to be unsigned or the field has no values. The parser rejects
+/// widths wider than the type, but a `_BitInt` field may still be too wide for a `u128`.
+fn unsigned_bit_field_max(ty: &Type, width: usize) -> Option<u128> {
+    let unsigned = match ty {
+        Type::Primitive(primitive) => {
+            let name = primitive.as_ref();
+            name.starts_with("unsigned") || name == "_Bool" || name == "bool"
+        }
+        Type::BitInt(BitInt { unsigned, .. }) => *unsigned,
+        _ => false,
+    };
+    if !unsigned || width == 0 || width > 128 {
+        return None;
     }
-    .msg
+    Some(u128::MAX >> (128 - width))
+}
+
+/// Explains an enumerator as its name followed by its value, which may be implicit, e.g.
+/// "GREEN = 5".
+fn explain_enumerator(enumerator: &Enumerator, value: i64) -> HighlightedText {
+    let mut msg = HighlightedText::new();
+    msg.push(HighlightedTextSegment::new(
+        enumerator.name,
+        Highlight::Ident,
+    ));
+    msg.push_str(" = ");
+    msg.push(HighlightedTextSegment::new(
+        value.to_string(),
+        Highlight::Number,
+    ));
+    msg
 }
 
 #[derive(Debug)]
 struct Explanation {
     /// Name of the root identifier being explained
     identifier_name: Option<String>,
+    /// Path to the root identifier from the declarator being explained
+    identifier_path: Option<NodePath>,
     /// String containing English explanation
     msg: HighlightedText,
     plurality: Plurality,
+    /// Number of declarator levels explained so far
+    levels: usize,
+    /// Position in `msg` where the explanation of the current declarator level starts
+    level_start: Mark,
 }
 
 impl Explanation {
     fn new() -> Self {
         Self {
             identifier_name: None,
+            identifier_path: None,
             msg: HighlightedText::new(),
             plurality: Plurality::Singular,
+            levels: 0,
+            level_start: Mark::default(),
         }
     }
 
-    /// Sets `identifier_name` to the given name.
+    /// Sets `identifier_name` to the given name, which is that of the declarator being explained.
     fn with_identifier_name(mut self, name: String) -> Self {
         self.identifier_name = Some(name);
+        self.identifier_path = Some(NodePath::default());
         self
     }
 
+    /// Makes the explanation part of that of the parent node reached by the given step, so the
+    /// origins of its segments start from there.
+    fn nest(&mut self, step: Step) {
+        nest(&mut self.msg, step);
+        self.identifier_path = self.identifier_path.take().map(|path| path.within(step));
+    }
+
+    /// Returns a segment containing the given name of the root identifier, recording its origin if
+    /// explanations record their [provenance][Options::provenance].
+    fn name_segment(
+        &self,
+        name: impl Into<String>,
+        highlight: Highlight,
+        options: &Options,
+    ) -> HighlightedTextSegment {
+        let mut segment = HighlightedTextSegment::new(name, highlight);
+        if options.provenance {
+            segment.origin.clone_from(&self.identifier_path);
+        }
+        segment
+    }
+
     /// Sets `plurality` to [`Plurality::Singular`].
     fn singular(mut self) -> Self {
         self.plurality = Plurality::Singular;
         self
     }
 
+    /// Starts explaining the next declarator level, on a new line if it isn't the first.
+    fn begin_level(&mut self, options: &Options) {
+        break_line(&mut self.msg, self.levels, options);
+        self.level_start = Mark::end_of(&self.msg);
+        self.levels += 1;
+    }
+
+    /// Finishes explaining the current declarator level, making its explanation a
+    /// [group][Options::groups].
+    fn end_level(&mut self, options: &Options) {
+        group_after(
+            &mut self.msg,
+            self.level_start,
+            Group::Level(self.levels - 1),
+            options,
+        );
+    }
+
     /// Sets `plurality` to [`Plurality::Plural`].
     fn plural(mut self) -> Self {
         self.plurality = Plurality::Plural;
@@ -94,257 +1179,564 @@ impl Explanation {
     }
 }
 
-fn format_qualified_type(qt: &QualifiedType) -> HighlightedText {
-    let highlight = match qt.1 {
-        Type::Primitive(_) => Highlight::PrimitiveType,
-        Type::Record(_, _) | Type::Custom(_) => Highlight::UserDefinedType,
+fn format_qualified_type(qt: &QualifiedType, options: &Options) -> HighlightedText {
+    let highlighted_unqualified_type = match qt.1 {
+        Type::Primitive(_) => vec![HighlightedTextSegment::new(
+            qt.1.to_string(),
+            Highlight::PrimitiveType,
+        )],
+        Type::Custom(name)
+            if options.expand_builtin_typedefs
+                && let Some(typedef) = builtins::find(name) =>
+        {
+            vec![HighlightedTextSegment::new(
+                typedef.description,
+                Highlight::PrimitiveType,
+            )]
+        }
+        Type::Record(_) | Type::Custom(_) => vec![HighlightedTextSegment::new(
+            qt.1.to_string(),
+            Highlight::UserDefinedType,
+        )],
+        // The operand is described by `explain_typeof_operand()`.
+        Type::Typeof(_) => vec![HighlightedTextSegment::new(
+            &*options.phrasebook().variable,
+            Highlight::None,
+        )],
+        // E.g. "24-bit unsigned integer"
+        Type::BitInt(BitInt { width, unsigned }) => {
+            let phrasebook = options.phrasebook();
+            let integer = HighlightedTextSegment::new(
+                if unsigned {
+                    &*phrasebook.unsigned_integer
+                } else {
+                    &*phrasebook.signed_integer
+                },
+                Highlight::PrimitiveType,
+            );
+            let mut ty = HighlightedText::new();
+            push_described(
+                &mut ty,
+                vec![integer].into(),
+                bit_width(width, phrasebook),
+                phrasebook,
+            );
+            ty.0
+        }
     };
-    let highlighted_unqualified_type = HighlightedTextSegment::new(qt.1.to_string(), highlight);
 
     if qt.0.is_empty() {
-        vec![highlighted_unqualified_type]
+        highlighted_unqualified_type
     } else {
-        let qualifiers = qt.0.to_string();
-        vec![
-            HighlightedTextSegment::new(qualifiers, Highlight::Qualifier),
+        let qualifiers = [
+            HighlightedTextSegment::new(qt.0.to_string(), Highlight::Qualifier),
             HighlightedTextSegment::new(" ", Highlight::None),
-            highlighted_unqualified_type,
-        ]
+        ];
+        if options.phrasebook().adjectives_after_nouns {
+            let mut segments = highlighted_unqualified_type;
+            segments.extend(qualifiers.into_iter().rev());
+            segments
+        } else {
+            let mut segments = qualifiers.to_vec();
+            segments.extend(highlighted_unqualified_type);
+            segments
+        }
     }
     .into()
 }
 
-fn explain_declaration_impl(decl: &Declaration) -> Explanation {
-    let mut explanation = explain_declarator(&decl.declarator, false);
-    let highlighted_type = format_qualified_type(&decl.base_type);
-    match explanation.plurality {
-        Plurality::Singular => {
-            let article = article_for(&highlighted_type[0]);
-            explanation.msg.push_str(article);
-            explanation.msg.extend(highlighted_type.0);
-        }
-        Plurality::Plural => {
-            let suffix = plural_suffix_for(highlighted_type.last().unwrap());
-            explanation.msg.extend(highlighted_type.0);
-            explanation.msg.push_str(suffix);
-        }
+/// Appends the operand of a `typeof` specifier to the explanation, if the type is one.
+fn explain_typeof_operand(msg: &mut HighlightedText, ty: &Type, options: &Options) {
+    let Type::Typeof(operand) = ty else {
+        return;
+    };
+    msg.push_str(&options.phrasebook().with_the_same_type_as);
+    let is_identifier = operand.chars().all(|c| c == '_' || c.is_alphanumeric());
+    msg.push(HighlightedTextSegment::new(
+        *operand,
+        if is_identifier {
+            Highlight::Ident
+        } else {
+            Highlight::None
+        },
+    ));
+}
+
+/// Explains a non-typedef declaration. If `name_last` is true, the identifier's name is given at
+/// the end of the explanation rather than after the first noun. Terse explanations omit the name.
+fn explain_declaration_impl(decl: &Declaration, name_last: bool, options: &Options) -> Explanation {
+    let skip_name = name_last || options.is_terse();
+    let phrasebook = options.phrasebook();
+    let mut explanation = explain_declarator(&decl.declarator, skip_name, options);
+    explanation.nest(Step::Declarator);
+    let highlighted_type = format_qualified_type(&decl.base_type, options);
+    // The noun standing in for a `typeof` operand isn't highlighted, so it isn't found by
+    // `first_noun()`.
+    let type_noun = match decl.base_type.1 {
+        Type::Typeof(_) => HighlightedTextSegment::from(&*phrasebook.variable),
+        _ => first_noun(&highlighted_type),
+    };
+    break_line(&mut explanation.msg, explanation.levels, options);
+    push_type(
+        &mut explanation.msg,
+        highlighted_type,
+        explanation.plurality,
+        options,
+    );
+    if let Some(name) = &explanation.identifier_name
+        && !options.is_terse()
+    {
+        explanation.msg.push_str(phrasebook.named_for(&type_noun));
+        let name = explanation.name_segment(name, Highlight::Ident, options);
+        explanation.msg.push(name);
+    }
+    explain_typeof_operand(&mut explanation.msg, &decl.base_type.1, options);
+    claim(&mut explanation.msg, &[Step::BaseType], options);
+    if decl.declarator.is_variable_length() {
+        explanation.msg.push_str(&phrasebook.variable_length);
+    }
+    if decl.inline {
+        let adjective = HighlightedTextSegment::new("inline", Highlight::Qualifier);
+        prepend_adjective(&mut explanation.msg, adjective, options);
+    }
+    if decl.thread_local {
+        let adjective =
+            HighlightedTextSegment::new(&*phrasebook.thread_local, Highlight::Qualifier);
+        prepend_adjective(&mut explanation.msg, adjective, options);
     }
-    if let Some(name) = &explanation.identifier_name {
-        explanation.msg.push_str(" named ");
-        explanation
-            .msg
-            .push(HighlightedTextSegment::new(name, Highlight::Ident));
+    if let Some(class) = decl.storage_class {
+        let noun = first_noun(&explanation.msg);
+        let adjective = HighlightedTextSegment::new(
+            (phrasebook.storage_class_adjective)(class, &noun),
+            Highlight::Qualifier,
+        );
+        prepend_adjective(&mut explanation.msg, adjective, options);
     }
     explanation
 }
 
-/// Explains a declaration whose `base_type` contains a [`typedef` qualifier][TypeQualifier::Typedef].
-///
-/// # Panics
-///
-/// Panics if the declaration's `base_type` does not contain a
-/// [`typedef` qualifier][TypeQualifier::Typedef].
-fn explain_typedef(decl: &Declaration) -> Explanation {
-    assert!(decl.base_type.0.contains(TypeQualifier::Typedef));
+/// Explains a forward declaration of a record's tag, e.g. "a forward declaration of a struct
+/// named node".
+fn explain_forward_declaration(record: &Record, options: &Options) -> Explanation {
+    let phrasebook = options.phrasebook();
+    let kind = HighlightedTextSegment::new(record.kind.to_string(), Highlight::QuasiKeyword);
+    let mut explanation = Explanation::new();
+    explanation
+        .msg
+        .push_str(options.article_for(&HighlightedTextSegment::from(
+            &*phrasebook.forward_declaration_of,
+        )));
+    explanation.msg.push_str(&phrasebook.forward_declaration_of);
+    explanation.msg.push_str(options.article_for(&kind));
+    explanation.msg.push(kind.clone());
+    if let Some(name) = record.name {
+        explanation.msg.push_str(phrasebook.named_for(&kind));
+        explanation.msg.push(HighlightedTextSegment::new(
+            name,
+            Highlight::UserDefinedType,
+        ));
+    }
+    claim(&mut explanation.msg, &[Step::BaseType], options);
+    explanation
+}
+
+/// Explains a declaration whose `base_type` contains a [`typedef` qualifier][TypeQualifier::Typedef].
+///
+/// # Panics
+///
+/// Panics if the declaration's `base_type` does not contain a
+/// [`typedef` qualifier][TypeQualifier::Typedef].
+fn explain_typedef(decl: &Declaration, options: &Options) -> Explanation {
+    assert!(decl.base_type.0.contains(TypeQualifier::Typedef));
 
-    let mut new_type = decl.base_type;
+    let mut new_type = decl.base_type.clone();
     new_type.0.remove(TypeQualifier::Typedef);
-    let type_str = format_qualified_type(&new_type);
+    let type_str = format_qualified_type(&new_type, options);
+    let phrasebook = options.phrasebook();
+    let type_noun = HighlightedTextSegment::from(&*phrasebook.type_);
 
     let mut explanation = Explanation::new();
-    explanation.msg.push_str("a type");
+    explanation.msg.push_str(options.article_for(&type_noun));
+    explanation.msg.push_str(&phrasebook.type_);
 
-    let declarator_explanation = explain_declarator(&decl.declarator, true);
+    let mut declarator_explanation = explain_declarator(&decl.declarator, true, options);
+    declarator_explanation.nest(Step::Declarator);
 
-    if let Some(name) = declarator_explanation.identifier_name {
-        explanation.msg.push_str(" named ");
-        explanation.msg.push(HighlightedTextSegment::new(
+    if let Some(name) = declarator_explanation.identifier_name.take() {
+        explanation.msg.push_str(phrasebook.named_for(&type_noun));
+        explanation.msg.push(declarator_explanation.name_segment(
             name,
             Highlight::UserDefinedType,
+            options,
         ));
     }
 
-    explanation.msg.push_str(" defined as ");
+    explanation.msg.push_str(&phrasebook.defined_as);
     explanation.msg.extend(declarator_explanation.msg.0);
+    break_line(&mut explanation.msg, declarator_explanation.levels, options);
+
+    push_type(
+        &mut explanation.msg,
+        type_str,
+        declarator_explanation.plurality,
+        options,
+    );
+    explain_typeof_operand(&mut explanation.msg, &new_type.1, options);
+    claim(&mut explanation.msg, &[Step::BaseType], options);
+    if decl.declarator.is_variable_length() {
+        explanation.msg.push_str(&phrasebook.variable_length);
+    }
+
+    explanation
+}
+
+/// Starts a new line indented to the given level if explanations are
+/// [multiline][Options::multiline], replacing the whitespace at the end of the current line.
+/// Nothing is done for the first level, which shares the line of whatever precedes it.
+fn break_line(msg: &mut HighlightedText, level: usize, options: &Options) {
+    if !options.multiline || level == 0 {
+        return;
+    }
+    if let Some(last) = msg.last_mut()
+        && last.highlight == Highlight::None
+    {
+        last.text.truncate(last.text.trim_end().len());
+        if last.text.is_empty() {
+            msg.pop();
+        }
+    }
+    msg.push_str("\n");
+    msg.push_str(&"  ".repeat(level));
+}
 
-    match declarator_explanation.plurality {
+/// Pushes a formatted type along with its article, or with a plural suffix if it's plural, e.g.
+/// "an int" or "ints".
+fn push_type(
+    msg: &mut HighlightedText,
+    ty: HighlightedText,
+    plurality: Plurality,
+    options: &Options,
+) {
+    match plurality {
         Plurality::Singular => {
-            let article = article_for(&type_str[0]);
-            explanation.msg.push_str(article);
-            explanation.msg.extend(type_str.0);
+            msg.push_str(options.article_for(&ty[0]));
+            msg.extend(ty.0);
         }
         Plurality::Plural => {
-            let suffix = plural_suffix_for(type_str.last().unwrap());
-            explanation.msg.extend(type_str.0);
-            explanation.msg.push_str(suffix);
+            let last = ty.last().unwrap();
+            // Tags and typedef names are left as written, as a name like "status" can't be told
+            // apart from a plural, except for "_t" names, which read naturally as "size_ts".
+            let suffix =
+                if last.highlight == Highlight::UserDefinedType && !last.text.ends_with("_t") {
+                    ""
+                } else {
+                    (options.phrasebook().plural_suffix_for)(last)
+                };
+            msg.extend(ty.0);
+            msg.push_str(suffix);
         }
     }
+}
 
-    explanation
+/// Pushes a noun along with its article and qualifiers, if any, in the order used by the
+/// explanation's language, e.g. "a const pointer" or "const pointers".
+fn push_noun(
+    msg: &mut HighlightedText,
+    qualifiers: Option<HighlightedTextSegment>,
+    noun: HighlightedTextSegment,
+    plurality: Plurality,
+    options: &Options,
+) {
+    let adjectives_after_nouns = options.phrasebook().adjectives_after_nouns;
+    if plurality == Plurality::Singular {
+        // The article agrees with the word which follows it.
+        let next = match &qualifiers {
+            Some(qualifiers) if !adjectives_after_nouns => qualifiers,
+            _ => &noun,
+        };
+        msg.push_str(options.article_for(next));
+    }
+    match qualifiers {
+        None => msg.push(noun),
+        Some(qualifiers) if adjectives_after_nouns => {
+            msg.push(noun);
+            msg.push_str(" ");
+            msg.push(qualifiers);
+        }
+        Some(qualifiers) => {
+            msg.push(qualifiers);
+            msg.push_str(" ");
+            msg.push(noun);
+        }
+    }
+}
+
+/// Returns a segment containing the given qualifiers, or `None` if there are none.
+fn qualifiers_segment(qualifiers: TypeQualifiers) -> Option<HighlightedTextSegment> {
+    if qualifiers.is_empty() {
+        None
+    } else {
+        Some(HighlightedTextSegment::new(
+            qualifiers.to_string(),
+            Highlight::Qualifier,
+        ))
+    }
 }
 
 #[allow(clippy::too_many_lines)]
 #[must_use]
-fn explain_declarator(declarator: &Declarator, skip_name: bool) -> Explanation {
+fn explain_declarator(declarator: &Declarator, skip_name: bool, options: &Options) -> Explanation {
+    let phrasebook = options.phrasebook();
     match declarator {
         Declarator::Anonymous => Explanation::new(),
         Declarator::Ident(name) => Explanation::new().with_identifier_name((*name).to_string()),
-        Declarator::Ptr(inner, qualifiers) => {
-            let mut sub = explain_declarator(inner, skip_name);
-            let qualifiers_text = if qualifiers.is_empty() {
-                None
-            } else {
-                Some(HighlightedTextSegment::new(
-                    qualifiers.to_string(),
-                    Highlight::Qualifier,
-                ))
-            };
-            match sub.plurality {
-                Plurality::Singular => {
-                    sub.msg.push_str("a ");
-                    if let Some(qualifiers_text) = qualifiers_text {
-                        sub.msg.push(qualifiers_text);
-                        sub.msg.push_str(" ");
-                    }
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "pointer",
-                        Highlight::QuasiKeyword,
-                    ));
-                }
-                Plurality::Plural => {
-                    if let Some(qualifiers_text) = qualifiers_text {
-                        sub.msg.push(qualifiers_text);
-                        sub.msg.push_str(" ");
-                    }
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "pointers",
-                        Highlight::QuasiKeyword,
-                    ));
+        // A block pointer to a function is explained by the function case below. Block pointers
+        // to anything else are invalid, but are explained like pointers anyway.
+        Declarator::Ptr(inner, _)
+        | Declarator::Reference(inner)
+        | Declarator::Block(inner, _)
+        | Declarator::MemberPtr(inner, ..) => {
+            let (noun, qualifiers) = match declarator {
+                Declarator::Ptr(_, qualifiers) | Declarator::MemberPtr(_, _, qualifiers) => {
+                    (&phrasebook.pointer, *qualifiers)
                 }
+                Declarator::Block(_, qualifiers) => (&phrasebook.block, *qualifiers),
+                _ => (&phrasebook.reference, TypeQualifiers::default()),
+            };
+            let mut sub = explain_declarator(inner, skip_name, options);
+            sub.nest(Step::Inner);
+            sub.begin_level(options);
+            let noun =
+                HighlightedTextSegment::new(noun.get(sub.plurality), Highlight::QuasiKeyword);
+            push_noun(
+                &mut sub.msg,
+                qualifiers_segment(qualifiers),
+                noun.clone(),
+                sub.plurality,
+                options,
+            );
+            if let Some(name) = sub.identifier_name.take_if(|_| !skip_name) {
+                sub.msg.push_str(phrasebook.named_for(&noun));
+                let name = sub.name_segment(name, Highlight::Ident, options);
+                sub.msg.push(name);
             }
-            sub.msg.push_str(" ");
-            if let Some(name) = &sub.identifier_name
-                && !skip_name
-            {
-                sub.msg.push_str("named ");
-                sub.msg
-                    .push(HighlightedTextSegment::new(name, Highlight::Ident));
-                sub.msg.push_str(" ");
-                sub.identifier_name = None;
+            // E.g. "a pointer named m to a member of C that is an int"
+            if let Declarator::MemberPtr(_, class, _) = declarator {
+                sub.msg.push_str(phrasebook.to_member_of.get(sub.plurality));
+                sub.msg.push(HighlightedTextSegment::new(
+                    *class,
+                    Highlight::UserDefinedType,
+                ));
+                sub.msg.push_str(phrasebook.that_is.get(sub.plurality));
+            } else {
+                sub.msg.push_str(&phrasebook.to);
             }
-            sub.msg.push_str("to ");
+            sub.end_level(options);
+            claim(&mut sub.msg, &[], options);
             sub
         }
-        Declarator::Array(inner, len) => {
-            let mut sub = explain_declarator(inner, skip_name);
-            match sub.plurality {
-                Plurality::Singular => {
-                    sub.msg.push_str("an ");
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "array",
-                        Highlight::QuasiKeyword,
-                    ));
-                }
-                Plurality::Plural => {
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "arrays",
-                        Highlight::QuasiKeyword,
-                    ));
-                }
+        Declarator::Array(inner, len, array_qualifiers) => {
+            let mut sub = explain_declarator(inner, skip_name, options);
+            sub.nest(Step::Inner);
+            sub.begin_level(options);
+            let noun = HighlightedTextSegment::new(
+                phrasebook.array.get(sub.plurality),
+                Highlight::QuasiKeyword,
+            );
+            push_noun(
+                &mut sub.msg,
+                qualifiers_segment(array_qualifiers.qualifiers),
+                noun.clone(),
+                sub.plurality,
+                options,
+            );
+            if let Some(name) = sub.identifier_name.take_if(|_| !skip_name) {
+                sub.msg.push_str(phrasebook.named_for(&noun));
+                let name = sub.name_segment(name, Highlight::Ident, options);
+                sub.msg.push(name);
             }
-            // sub.msg.push_str(match sub.plurality {
-            //     Plurality::Singular => "an array",
-            //     Plurality::Plural => "arrays",
-            // });
-            if let Some(name) = &sub.identifier_name
-                && !skip_name
-            {
-                sub.msg.push_str(" named ");
-                sub.msg
-                    .push(HighlightedTextSegment::new(name, Highlight::Ident));
-                sub.identifier_name = None;
-            }
-            sub.msg.push_str(" of ");
-            if let Some(len) = len {
-                sub.msg.push(HighlightedTextSegment::new(
-                    len.to_string(),
-                    Highlight::Number,
-                ));
-                sub.msg.push_str(" ");
+            sub.msg.push_str(&phrasebook.of);
+            if array_qualifiers.is_static {
+                sub.msg.push_str(&phrasebook.at_least);
             }
-            sub.plural()
-        }
-        Declarator::Function { func, params } => {
-            let mut sub = explain_declarator(func, skip_name);
-            let name = if skip_name {
-                &None
-            } else {
-                &sub.identifier_name
-            };
-            match (name, sub.plurality) {
-                (None, Plurality::Singular) => {
-                    sub.msg.push_str("a ");
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "function",
-                        Highlight::QuasiKeyword,
-                    ));
-                    sub.msg.push_str(" that takes ");
+            match len {
+                ArraySize::Unspecified | ArraySize::VariableUnspecified => {}
+                ArraySize::Fixed(len) => {
+                    let len = if options.spell_out_numbers {
+                        (phrasebook.number_in_words)(*len)
+                    } else {
+                        len.to_string()
+                    };
+                    sub.msg
+                        .push(HighlightedTextSegment::new(len, Highlight::Number));
+                    sub.msg.push_str(" ");
                 }
-                (None, Plurality::Plural) => {
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "functions",
-                        Highlight::QuasiKeyword,
-                    ));
-                    sub.msg.push_str(" that take ");
+                ArraySize::Expr(expr) => {
+                    sub.msg
+                        .push(HighlightedTextSegment::new(*expr, Highlight::Number));
+                    sub.msg.push_str(" ");
                 }
-                (Some(name), Plurality::Singular) => {
-                    sub.msg.push_str("a ");
-                    sub.msg.push(HighlightedTextSegment::new(
-                        "function",
-                        Highlight::QuasiKeyword,
-                    ));
-                    sub.msg.push_str(" named ");
+                ArraySize::Variable(name) => {
                     sub.msg
-                        .push(HighlightedTextSegment::new(name, Highlight::Ident));
-                    sub.msg.push_str(" that takes ");
-                    sub.identifier_name = None;
+                        .push(HighlightedTextSegment::new(*name, Highlight::Ident));
+                    sub.msg.push_str(" ");
                 }
-                (Some(_), Plurality::Plural) => unreachable!("an identifier cannot be plural"),
             }
-            match &params[..] {
-                [] => sub.msg.push_str("no parameters"),
-                [param] => {
-                    sub.msg.push_str("(");
-                    sub.msg.extend(explain_declaration(param).0);
-                    sub.msg.push_str(")");
-                }
-                [a, b] => {
-                    sub.msg.push_str("(");
-                    sub.msg.extend(explain_declaration(a).0);
-                    sub.msg.push_str(" and ");
-                    sub.msg.extend(explain_declaration(b).0);
-                    sub.msg.push_str(")");
-                }
-                [rest @ .., last] => {
-                    sub.msg.push_str("(");
-                    for param in rest {
-                        sub.msg.extend(explain_declaration(param).0);
-                        sub.msg.push_str(", ");
-                    }
-                    sub.msg.push_str("and ");
-                    sub.msg.extend(explain_declaration(last).0);
-                    sub.msg.push_str(")");
-                }
+            sub.end_level(options);
+            claim(&mut sub.msg, &[], options);
+            // Terse explanations don't use plurals, e.g. "array of 10 int".
+            if options.is_terse() {
+                sub
+            } else {
+                sub.plural()
             }
-            sub.msg.push_str(match sub.plurality {
-                Plurality::Singular => " and returns ",
-                Plurality::Plural => " and return ",
-            });
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => {
+            if let Declarator::Block(inner, qualifiers) = &**func {
+                return explain_block(inner, *qualifiers, params, *variadic, skip_name, options);
+            }
+            let mut sub = explain_declarator(func, skip_name, options);
+            sub.nest(Step::Inner);
+            sub.begin_level(options);
+            let noun = HighlightedTextSegment::new(
+                phrasebook.function.get(sub.plurality),
+                Highlight::QuasiKeyword,
+            );
+            // E.g. "a __stdcall function" or "a const function"
+            let qualifiers = match (calling_convention, qualifiers_segment(*member_qualifiers)) {
+                (Some(convention), Some(qualifiers)) => Some(HighlightedTextSegment::new(
+                    format!("{convention} {}", qualifiers.text),
+                    Highlight::Qualifier,
+                )),
+                (Some(convention), None) => Some(HighlightedTextSegment::new(
+                    convention.to_string(),
+                    Highlight::Qualifier,
+                )),
+                (None, qualifiers) => qualifiers,
+            };
+            push_noun(
+                &mut sub.msg,
+                qualifiers,
+                noun.clone(),
+                sub.plurality,
+                options,
+            );
+            if let Some(name) = sub.identifier_name.take_if(|_| !skip_name) {
+                assert!(
+                    sub.plurality == Plurality::Singular,
+                    "an identifier cannot be plural"
+                );
+                sub.msg.push_str(phrasebook.named_for(&noun));
+                let name = sub.name_segment(name, Highlight::Ident, options);
+                sub.msg.push(name);
+            }
+            explain_params(
+                &mut sub.msg,
+                params,
+                *variadic,
+                phrasebook.that_takes.get(sub.plurality),
+                phrasebook.that_takes_no_parameters.get(sub.plurality),
+                options,
+            );
+            sub.msg.push_str(phrasebook.and_returns.get(sub.plurality));
+            sub.end_level(options);
+            claim(&mut sub.msg, &[], options);
             sub.singular()
         }
     }
 }
 
+/// Explains a function's parameters, introduced by `takes`, e.g. " that takes (an int named a)",
+/// or gives `takes_nothing` if there are none, e.g. " that takes no parameters". The parameters
+/// of a variadic function end with "a variable number of further arguments".
+fn explain_params(
+    msg: &mut HighlightedText,
+    params: &[Declaration],
+    variadic: bool,
+    takes: &str,
+    takes_nothing: &str,
+    options: &Options,
+) {
+    let options = &options.single_line();
+    let phrasebook = options.phrasebook();
+    if params.is_empty() && !variadic {
+        msg.push_str(takes_nothing);
+    } else {
+        msg.push_str(takes);
+        msg.push_str(&phrasebook.parameters_start);
+        let further_arguments = match (variadic, params.is_empty()) {
+            (false, _) => None,
+            (true, true) => Some(&*phrasebook.variable_arguments),
+            (true, false) => Some(&*phrasebook.further_arguments),
+        };
+        push_list(
+            msg,
+            params
+                .iter()
+                .enumerate()
+                .map(|(i, param)| {
+                    let mut msg = explain_complete_declaration(param, options);
+                    nest(&mut msg, Step::Parameter(i));
+                    group_after(&mut msg, Mark::default(), Group::Parameter(i), options);
+                    msg
+                })
+                .chain(further_arguments.map(|arguments| arguments.to_string().into())),
+            options,
+        );
+        msg.push_str(&phrasebook.parameters_end);
+    }
+}
+
+/// Explains a block pointer to a function type, given the declarator inside the block pointer
+/// and the function's parameters, e.g. "a block named handler taking (an int) and returning ".
+fn explain_block(
+    inner: &Declarator,
+    qualifiers: TypeQualifiers,
+    params: &[Declaration],
+    variadic: bool,
+    skip_name: bool,
+    options: &Options,
+) -> Explanation {
+    let phrasebook = options.phrasebook();
+    let mut sub = explain_declarator(inner, skip_name, options);
+    // The block pointer is the function declarator's inner declarator.
+    sub.nest(Step::Inner);
+    sub.nest(Step::Inner);
+    sub.begin_level(options);
+    let noun =
+        HighlightedTextSegment::new(phrasebook.block.get(sub.plurality), Highlight::QuasiKeyword);
+    push_noun(
+        &mut sub.msg,
+        qualifiers_segment(qualifiers),
+        noun.clone(),
+        sub.plurality,
+        options,
+    );
+    if let Some(name) = sub.identifier_name.take_if(|_| !skip_name) {
+        sub.msg.push_str(phrasebook.named_for(&noun));
+        let name = sub.name_segment(name, Highlight::Ident, options);
+        sub.msg.push(name);
+    }
+    explain_params(
+        &mut sub.msg,
+        params,
+        variadic,
+        &phrasebook.taking,
+        &phrasebook.taking_no_parameters,
+        options,
+    );
+    sub.msg.push_str(&phrasebook.and_returning);
+    sub.end_level(options);
+    claim(&mut sub.msg, &[], options);
+    sub.singular()
+}
+
 #[cfg(test)]
 mod tests {
     use chumsky::Parser;
@@ -368,35 +1760,6 @@ mod tests {
         );
     }
 
-    macro_rules! hltext {
-        ( $($text:literal $hl:ident)+ ) => {
-            &[
-                $(hltext!(line $text $hl)),+
-            ]
-        };
-        ( line $text:literal n ) => {
-            HighlightedTextSegment::new($text, Highlight::None)
-        };
-        ( line $text:literal pt ) => {
-            HighlightedTextSegment::new($text, Highlight::PrimitiveType)
-        };
-        ( line $text:literal i ) => {
-            HighlightedTextSegment::new($text, Highlight::Ident)
-        };
-        ( line $text:literal q ) => {
-            HighlightedTextSegment::new($text, Highlight::Qualifier)
-        };
-        ( line $text:literal qk ) => {
-            HighlightedTextSegment::new($text, Highlight::QuasiKeyword)
-        };
-        ( line $text:literal num ) => {
-            HighlightedTextSegment::new($text, Highlight::Number)
-        };
-        ( line $text:literal udt ) => {
-            HighlightedTextSegment::new($text, Highlight::UserDefinedType)
-        };
-    }
-
     #[test]
     fn explain_primitive_var() {
         // run("int x", "an int named x");
@@ -436,9 +1799,9 @@ mod tests {
 
     #[test]
     fn test_article_for() {
-        assert_eq!(article_for(&"int".into()), "an ");
-        assert_eq!(article_for(&"cow".into()), "a ");
-        assert_eq!(article_for(&"".into()), "");
+        assert_eq!(pronunciation::article_for(&"int".into()), "an ");
+        assert_eq!(pronunciation::article_for(&"cow".into()), "a ");
+        assert_eq!(pronunciation::article_for(&"".into()), "");
     }
 
     #[test]
@@ -500,359 +1863,2323 @@ mod tests {
     }
 
     #[test]
-    fn explain_2d_array_of_primitive() {
+    fn explain_builtin_typedefs() {
         run(
-            "int arr[10][20]",
+            "uint8_t buf[16]",
             hltext![
                 "an " n
                 "array" qk
                 " named " n
-                "arr" i
+                "buf" i
+                " of " n
+                "16" num
+                " " n
+                "uint8_t" udt
+                "s" n
+            ],
+        );
+
+        let options = Options {
+            expand_builtin_typedefs: true,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse("const uint8_t *p; intptr_t n[2]")
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 2] = [
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "p" i
+                " to a " n
+                "const" q
+                " " n
+                "unsigned 8-bit integer" pt
+            ],
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "n" i
+                " of " n
+                "2" num
+                " " n
+                "pointer-sized signed integer" pt
+                "s" n
+            ],
+        ];
+        assert_eq!(explanations[0].0, expected[0]);
+        assert_eq!(explanations[1].0, expected[1]);
+    }
+
+    #[test]
+    fn explain_c23_types() {
+        run(
+            "bool flags[8]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "flags" i
+                " of " n
+                "8" num
+                " " n
+                "bool" pt
+                "s" n
+            ],
+        );
+        run(
+            "const char8_t *s",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "s" i
+                " to a " n
+                "const" q
+                " " n
+                "char8_t" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_decimal_floating_types() {
+        run(
+            "_Decimal64 prices[3]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "prices" i
+                " of " n
+                "3" num
+                " " n
+                "_Decimal64" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_wide_character_types() {
+        run(
+            "wchar_t *title",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "title" i
+                " to a " n
+                "wchar_t" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_array_with_constant_expression_size() {
+        run(
+            "int buf[4 * 1024]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "buf" i
+                " of " n
+                "4096" num
+                " " n
+                "int" pt
+                "s" n
+            ],
+        );
+        run(
+            "char s[N + 1]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "s" i
+                " of " n
+                "N + 1" num
+                " " n
+                "char" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_variable_length_array() {
+        run(
+            "int matrix[rows][cols]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "matrix" i
+                " of " n
+                "rows" i
+                " " n
+                "arrays" qk
+                " of " n
+                "cols" i
+                " " n
+                "int" pt
+                "s (variable length)" n
+            ],
+        );
+        run(
+            "void f(int n, int (*grid)[n])",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "f" i
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "n" i
+                " and a " n
+                "pointer" qk
+                " named " n
+                "grid" i
+                " to an " n
+                "array" qk
                 " of " n
+                "n" i
+                " " n
+                "int" pt
+                "s (variable length)) and returns a " n
+                "void" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_array_parameter_qualifiers() {
+        run(
+            "void f(int arr[static 10])",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "f" i
+                " that takes (an " n
+                "array" qk
+                " named " n
+                "arr" i
+                " of at least " n
                 "10" num
                 " " n
+                "int" pt
+                "s) and returns a " n
+                "void" pt
+            ],
+        );
+        run(
+            "void g(double m[const][*])",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "g" i
+                " that takes (a " n
+                "const" q
+                " " n
+                "array" qk
+                " named " n
+                "m" i
+                " of " n
                 "arrays" qk
                 " of " n
-                "20" num
+                "double" pt
+                "s (variable length)) and returns a " n
+                "void" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_2d_array_of_primitive() {
+        run(
+            "int arr[10][20]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "arr" i
+                " of " n
+                "10" num
+                " " n
+                "arrays" qk
+                " of " n
+                "20" num
+                " " n
+                "int" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_nested_ptrs() {
+        run(
+            "char ***p",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "p" i
+                " to a " n
+                "pointer" qk
+                " to a " n
+                "pointer" qk
+                " to a " n
+                "char" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_array_of_ptrs() {
+        run(
+            "int *arr[10]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "arr" i
+                " of " n
+                "10" num
+                " " n
+                "pointers" qk
+                " to " n
+                "int" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_ptr_to_array() {
+        run(
+            "int (*p)[10]",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "p" i
+                " to an " n
+                "array" qk
+                " of " n
+                "10" num
+                " " n
+                "int" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_with_no_params() {
+        // run(
+        //     "void func()",
+        //     "a function named func that takes no parameters and returns a void",
+        // );
+        run(
+            "void func()",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "func" i
+                " that takes no parameters and returns a " n
+                "void" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_array_of_functions() {
+        run(
+            "char *(*(*bar)[5])(int)",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "bar" i
+                " to an " n
+                "array" qk
+                " of " n
+                "5" num
+                " " n
+                "pointers" qk
+                " to " n
+                "functions" qk
+                " that take (an " n
+                "int" pt
+                ") and return a " n
+                "pointer" qk
+                " to a " n
+                "char" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_qualifiers() {
+        run(
+            "const int x",
+            hltext![
+                "a " n
+                "const" q
+                " " n
+                "int" pt
+                " named " n
+                "x" i
+            ],
+        );
+        run(
+            "volatile int x",
+            hltext![
+                "a " n
+                "volatile" q
+                " " n
+                "int" pt
+                " named " n
+                "x" i
+            ],
+        );
+        run(
+            "int *const restrict x",
+            hltext![
+                "a " n
+                "const restrict" q
+                " " n
+                "pointer" qk
+                " named " n
+                "x" i
+                " to an " n
+                "int" pt
+            ],
+        );
+        run(
+            "const char *const str",
+            hltext![
+                "a " n
+                "const" q
+                " " n
+                "pointer" qk
+                " named " n
+                "str" i
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_storage_classes() {
+        run(
+            "extern int errno",
+            hltext![
+                "an " n
+                "externally-linked" q
+                " " n
+                "int" pt
+                " named " n
+                "errno" i
+                " (defined elsewhere)" n
+            ],
+        );
+        run(
+            "extern int count = 1",
+            hltext![
+                "an " n
+                "externally-linked" q
+                " " n
+                "int" pt
+                " named " n
+                "count" i
+                " initialized to " n
+                "1" num
+            ],
+        );
+        run(
+            "static const char *name",
+            hltext![
+                "a " n
+                "static" q
+                " " n
+                "pointer" qk
+                " named " n
+                "name" i
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                " (with internal linkage)" n
+            ],
+        );
+        run(
+            "auto int x[2]",
+            hltext![
+                "an " n
+                "automatic" q
+                " " n
+                "array" qk
+                " named " n
+                "x" i
+                " of " n
+                "2" num
+                " " n
+                "int" pt
+                "s" n
+            ],
+        );
+        run(
+            "int f(register int)",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "f" i
+                " that takes (a " n
+                "register" q
+                " " n
+                "int" pt
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_inline() {
+        run(
+            "static inline int max(int a, int b)",
+            hltext![
+                "a " n
+                "static" q
+                " " n
+                "inline" q
+                " " n
+                "function" qk
+                " named " n
+                "max" i
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "a" i
+                " and an " n
+                "int" pt
+                " named " n
+                "b" i
+                ") and returns an " n
+                "int" pt
+                " (with internal linkage)" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_thread_local() {
+        run(
+            "_Thread_local int counter",
+            hltext![
+                "a " n
+                "thread-local" q
+                " " n
+                "int" pt
+                " named " n
+                "counter" i
+            ],
+        );
+        run(
+            "extern _Thread_local int *counter",
+            hltext![
+                "an " n
+                "externally-linked" q
+                " " n
+                "thread-local" q
+                " " n
+                "pointer" qk
+                " named " n
+                "counter" i
+                " to an " n
+                "int" pt
+                " (defined elsewhere)" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_atomic() {
+        run(
+            "_Atomic int x",
+            hltext![
+                "an " n
+                "atomic" q
+                " " n
+                "int" pt
+                " named " n
+                "x" i
+            ],
+        );
+        run(
+            "_Atomic(int) *p",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "p" i
+                " to an " n
+                "atomic" q
+                " " n
+                "int" pt
+            ],
+        );
+        run(
+            "int *_Atomic p",
+            hltext![
+                "an " n
+                "atomic" q
+                " " n
+                "pointer" qk
+                " named " n
+                "p" i
+                " to an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_struct_body() {
+        run(
+            "struct point { int x; int y; } p",
+            hltext![
+                "a " n
+                "struct point" udt
+                " named " n
+                "p" i
+                " containing an " n
+                "int" pt
+                " named " n
+                "x" i
+                " and an " n
+                "int" pt
+                " named " n
+                "y" i
+            ],
+        );
+        run(
+            "struct { int i; char c; float f; } *u",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "u" i
+                " to a " n
+                "struct" udt
+                " containing an " n
+                "int" pt
+                " named " n
+                "i" i
+                ", a " n
+                "char" pt
+                " named " n
+                "c" i
+                ", and a " n
+                "float" pt
+                " named " n
+                "f" i
+            ],
+        );
+        run(
+            "typedef struct {} empty_t",
+            hltext![
+                "a type named " n
+                "empty_t" udt
+                " defined as a " n
+                "struct" udt
+                " containing no members" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_enum_body() {
+        run(
+            "enum color { RED, GREEN = 5, BLUE } c",
+            hltext![
+                "an " n
+                "enum color" udt
+                " named " n
+                "c" i
+                " with values " n
+                "RED" i
+                " = " n
+                "0" num
+                ", " n
+                "GREEN" i
+                " = " n
+                "5" num
+                ", and " n
+                "BLUE" i
+                " = " n
+                "6" num
+            ],
+        );
+        run(
+            "enum { ON }",
+            hltext![
+                "an " n
+                "enum" udt
+                " with values " n
+                "ON" i
+                " = " n
+                "0" num
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_bit_fields() {
+        run(
+            "struct s { unsigned flags : 3; int : 4; }",
+            hltext![
+                "a " n
+                "struct s" udt
+                " containing a " n
+                "3" num
+                "-bit " n
+                "unsigned" pt
+                " " n
+                "bit-field" qk
+                " named " n
+                "flags" i
+                " that can hold values " n
+                "0" num
+                "–" n
+                "7" num
+                " and a " n
+                "4" num
+                "-bit " n
+                "int" pt
+                " " n
+                "bit-field" qk
+            ],
+        );
+        run(
+            "struct { _Bool on : 1; unsigned long long big : 64; }",
+            hltext![
+                "a " n
+                "struct" udt
+                " containing a " n
+                "1" num
+                "-bit " n
+                "_Bool" pt
+                " " n
+                "bit-field" qk
+                " named " n
+                "on" i
+                " that can hold values " n
+                "0" num
+                "–" n
+                "1" num
+                " and a " n
+                "64" num
+                "-bit " n
+                "unsigned long long" pt
+                " " n
+                "bit-field" qk
+                " named " n
+                "big" i
+                " that can hold values " n
+                "0" num
+                "–" n
+                "18446744073709551615" num
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_anonymous_members() {
+        run(
+            "struct s { union { int i; float f; }; int tag; }",
+            hltext![
+                "a " n
+                "struct s" udt
+                " containing an unnamed " n
+                "union" udt
+                " containing an " n
+                "int" pt
+                " named " n
+                "i" i
+                " and a " n
+                "float" pt
+                " named " n
+                "f" i
+                " and an " n
+                "int" pt
+                " named " n
+                "tag" i
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_flexible_array_member() {
+        run(
+            "struct buf { unsigned long len; char data[]; }",
+            hltext![
+                "a " n
+                "struct buf" udt
+                " containing an " n
+                "unsigned long" pt
+                " named " n
+                "len" i
+                " and a " n
+                "flexible" qk
+                " " n
+                "array" qk
+                " of " n
+                "char" pt
+                "s named " n
+                "data" i
+            ],
+        );
+        run(
+            "struct argv { int argc; char *argv[]; }",
+            hltext![
+                "a " n
+                "struct argv" udt
+                " containing an " n
+                "int" pt
+                " named " n
+                "argc" i
+                " and a " n
+                "flexible" qk
+                " " n
+                "array" qk
+                " of " n
+                "pointers" qk
+                " to " n
+                "char" pt
+                "s named " n
+                "argv" i
+            ],
+        );
+        // Only the last member of a struct can be a flexible array member.
+        run(
+            "struct s { char data[]; int len; }",
+            hltext![
+                "a " n
+                "struct s" udt
+                " containing an " n
+                "array" qk
+                " named " n
+                "data" i
+                " of " n
+                "char" pt
+                "s and an " n
+                "int" pt
+                " named " n
+                "len" i
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_initializer() {
+        run(
+            "int x = 5",
+            hltext![
+                "an " n
+                "int" pt
+                " named " n
+                "x" i
+                " initialized to " n
+                "5" num
+            ],
+        );
+        run(
+            "const char *name = \"c2e\"",
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "name" i
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                " initialized to \"c2e\"" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_initializer_list() {
+        run(
+            "int a[3] = {1, 2, 3}",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "a" i
+                " of " n
+                "3" num
+                " " n
+                "int" pt
+                "s initialized with a list of " n
+                "3" num
+                " values" n
+            ],
+        );
+        run(
+            "struct p q = {.x = 1, .y[2] = 2, 3}",
+            hltext![
+                "a " n
+                "struct p" udt
+                " named " n
+                "q" i
+                " initialized with a list of " n
+                "3" num
+                " values, with designators for ." n
+                "x" i
+                " and ." n
+                "y" i
+                "[" n
+                "2" num
+                "]" n
+            ],
+        );
+        run(
+            "int x[1] = {}",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "x" i
+                " of " n
+                "1" num
+                " " n
+                "int" pt
+                "s initialized with an empty list" n
+            ],
+        );
+    }
+
+    /// By default, nested record definitions are only named, not described.
+    #[test]
+    fn explain_nested_struct_body() {
+        run(
+            "struct outer { struct inner { int x; } in; } o",
+            hltext![
+                "a " n
+                "struct outer" udt
+                " named " n
+                "o" i
+                " containing a " n
+                "struct inner" udt
+                " named " n
+                "in" i
+            ],
+        );
+    }
+
+    /// With a nested record depth, nested record definitions are described down to that depth.
+    #[test]
+    fn explain_nested_struct_body_with_depth() {
+        let options = Options {
+            nested_record_depth: 1,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse("struct a { struct b { struct c { int x; } *c; } b; } v")
+            .unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &options)
+                .coalesced()
+                .0,
+            hltext![
+                "a " n
+                "struct a" udt
+                " named " n
+                "v" i
+                " containing a " n
+                "struct b" udt
+                " named " n
+                "b" i
+                " containing a " n
+                "pointer" qk
+                " named " n
+                "c" i
+                " to a " n
+                "struct c" udt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_struct_var() {
+        run(
+            "struct point p",
+            hltext![
+                "a " n
+                "struct point" udt
+                " named " n
+                "p" i
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_one_unnamed_param() {
+        run(
+            "int foo(const char *)",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "foo" i
+                " that takes (a " n
+                "pointer" qk
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_one_named_param() {
+        run(
+            "int foo(const char *bar)",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "foo" i
+                " that takes (a " n
+                "pointer" qk
+                " named " n
+                "bar" i
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_anonymous_function() {
+        run(
+            "int (*)(const char *)",
+            hltext![
+                "a " n
+                "pointer" qk
+                " to a " n
+                "function" qk
+                " that takes (a " n
+                "pointer" qk
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_two_params() {
+        run(
+            "int add(int a, int b)",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "add" i
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "a" i
+                " and an " n
+                "int" pt
+                " named " n
+                "b" i
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_three_params() {
+        run(
+            "void print(int a, char *b, float c)",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "print" i
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "a" i
+                ", a " n
+                "pointer" qk
+                " named " n
+                "b" i
+                " to a " n
+                "char" pt
+                ", and a " n
+                "float" pt
+                " named " n
+                "c" i
+                ") and returns a " n
+                "void" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_array_of_struct() {
+        run(
+            "struct point p[]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "p" i
+                " of " n
+                "struct point" udt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_plural_qualifiers() {
+        run(
+            "char *const p[]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "p" i
+                " of " n
+                "const" q
+                " " n
+                "pointers" qk
+                " to " n
+                "char" pt
+                "s" n
+            ],
+        );
+    }
+
+    /// Anonymous typedefs are technically not valid C, but we handle them gracefully.
+    #[test]
+    fn explain_anon_typedef() {
+        run(
+            "typedef char *",
+            hltext![
+                "a type defined as a " n
+                "pointer" qk
+                " to a " n
+                "char" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_typedef() {
+        run(
+            "typedef struct point point_t",
+            hltext![
+                "a type named " n
+                "point_t" udt
+                " defined as a " n
+                "struct point" udt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_pointer_typedef() {
+        run(
+            "typedef const char *string",
+            hltext![
+                "a type named " n
+                "string" udt
+                " defined as a " n
+                "pointer" qk
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_typedef_plural_end() {
+        run(
+            "typedef int nums[]",
+            hltext![
+                "a type named " n
+                "nums" udt
+                " defined as an " n
+                "array" qk
+                " of " n
+                "int" pt
+                "s" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_function_typedef() {
+        run(
+            "typedef int (*compare_t)(const void *, const void *)",
+            hltext![
+                "a type named " n
+                "compare_t" udt
+                " defined as a " n
+                "pointer" qk
+                " to a " n
+                "function" qk
+                " that takes (a " n
+                "pointer" qk
+                " to a " n
+                "const" q
+                " " n
+                "void" pt
+                " and a " n
+                "pointer" qk
+                " to a " n
+                "const" q
+                " " n
+                "void" pt
+                ") and returns an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_typeof() {
+        run(
+            "typeof(x) y",
+            hltext![
+                "a variable named " n
+                "y" i
+                " with the same type as " n
+                "x" i
+            ],
+        );
+        run(
+            "__typeof__(int *) *p[2]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "p" i
+                " of " n
+                "2" num
+                " " n
+                "pointers" qk
+                " to variables with the same type as int *" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_cpp_references() {
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let decls = crate::parser::parser()
+            .parse_with_state("const std::string &name; int (&rows)[2]", &mut state)
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration(decl).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 2] = [
+            hltext![
+                "a " n
+                "reference" qk
+                " named " n
+                "name" i
+                " to a " n
+                "const" q
+                " " n
+                "std::string" udt
+            ],
+            hltext![
+                "a " n
+                "reference" qk
+                " named " n
+                "rows" i
+                " to an " n
+                "array" qk
+                " of " n
+                "2" num
+                " " n
+                "int" pt
+                "s" n
+            ],
+        ];
+        assert_eq!(explanations[0].0, expected[0]);
+        assert_eq!(explanations[1].0, expected[1]);
+    }
+
+    #[test]
+    fn explain_cpp_member_pointers() {
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let decls = crate::parser::parser()
+            .parse_with_state("int C::*m; int (ns::C::*fs[2])(int) const", &mut state)
+            .unwrap();
+        assert_eq!(
+            explain_declaration(&decls[0]).coalesced().0,
+            hltext![
+                "a " n
+                "pointer" qk
+                " named " n
+                "m" i
+                " to a member of " n
+                "C" udt
+                " that is an " n
+                "int" pt
+            ]
+        );
+        assert_eq!(
+            explain_declaration(&decls[1]).coalesced().0,
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "fs" i
+                " of " n
+                "2" num
+                " " n
+                "pointers" qk
+                " to members of " n
+                "ns::C" udt
+                " that are " n
+                "const" q
+                " " n
+                "functions" qk
+                " that take (an " n
+                "int" pt
+                ") and return an " n
+                "int" pt
+            ]
+        );
+    }
+
+    #[test]
+    fn explain_asm_labels() {
+        run(
+            r#"register int counter asm("r11")"#,
+            hltext![
+                "a " n
+                "register" q
+                " " n
+                "int" pt
+                " named " n
+                "counter" i
+                " (stored in register " n
+                "r11" i
+                ")" n
+            ],
+        );
+        run(
+            r#"int stat64(void) __asm__("stat")"#,
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "stat64" i
+                " that takes no parameters and returns an " n
+                "int" pt
+                " (with assembler name " n
+                "stat" i
+                ")" n
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_forward_declaration() {
+        run(
+            "struct node;",
+            hltext![
+                "a forward declaration of a " n
+                "struct" qk
+                " named " n
+                "node" udt
+            ],
+        );
+        run(
+            "enum color",
+            hltext![
+                "a forward declaration of an " n
+                "enum" qk
+                " named " n
+                "color" udt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_block_pointers() {
+        run(
+            "void (^handler)(int)",
+            hltext![
+                "a " n
+                "block" qk
+                " named " n
+                "handler" i
+                " taking (an " n
+                "int" pt
+                ") and returning a " n
+                "void" pt
+            ],
+        );
+        run(
+            "int (^const)(void)",
+            hltext![
+                "a " n
+                "const" q
+                " " n
+                "block" qk
+                " taking no parameters and returning an " n
+                "int" pt
+            ],
+        );
+    }
+
+    #[test]
+    fn explain_implicit_int() {
+        let mut state = crate::parser::State::default().with_implicit_int(true);
+        let decls = crate::parser::parser()
+            .parse_with_state("register x; foo()", &mut state)
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration(decl).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 2] = [
+            hltext![
+                "a " n
+                "register" q
+                " " n
+                "int" pt
+                " named " n
+                "x" i
+                " (implicitly an " n
+                "int" pt
+                ")" n
+            ],
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "foo" i
+                " that takes no parameters and returns an " n
+                "int" pt
+                " (implicitly an " n
+                "int" pt
+                ")" n
+            ],
+        ];
+        assert_eq!(explanations[0].0, expected[0]);
+        assert_eq!(explanations[1].0, expected[1]);
+    }
+
+    #[test]
+    fn explain_bit_int() {
+        run(
+            "unsigned _BitInt(24) x",
+            hltext![
+                "a " n
+                "24" num
+                "-bit " n
+                "unsigned integer" pt
+                " named " n
+                "x" i
+            ],
+        );
+        run(
+            "const _BitInt(128) a[2]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "a" i
+                " of " n
+                "2" num
                 " " n
-                "int" pt
+                "const" q
+                " " n
+                "128" num
+                "-bit " n
+                "signed integer" pt
                 "s" n
             ],
         );
     }
 
     #[test]
-    fn explain_nested_ptrs() {
+    fn explain_attributes() {
         run(
-            "char ***p",
+            "struct __attribute__((packed)) point p __attribute__((aligned(8)))",
             hltext![
                 "a " n
-                "pointer" qk
+                "struct point" udt
                 " named " n
                 "p" i
-                " to a " n
-                "pointer" qk
-                " to a " n
-                "pointer" qk
-                " to a " n
-                "char" pt
+                " (" n
+                "packed" qk
+                ", " n
+                "aligned" qk
+                " to " n
+                "8" num
+                " bytes)" n
+            ],
+        );
+        run(
+            "__attribute__((__warn_unused_result__, hot)) int f(void) __attribute__((deprecated(\"use g\")))",
+            hltext![
+                "a " n
+                "function" qk
+                " named " n
+                "f" i
+                " that takes no parameters and returns an " n
+                "int" pt
+                " (result must be " n
+                "used" qk
+                ", " n
+                "deprecated" qk
+                ": \"use g\")" n
             ],
         );
     }
 
     #[test]
-    fn explain_array_of_ptrs() {
+    fn explain_msvc_extensions() {
         run(
-            "int *arr[10]",
+            "__declspec(dllimport) int __stdcall Foo(int)",
+            hltext![
+                "a " n
+                "stdcall" q
+                " " n
+                "function" qk
+                " named " n
+                "Foo" i
+                " that takes (an " n
+                "int" pt
+                ") and returns an " n
+                "int" pt
+                " (" n
+                "imported" qk
+                " from a DLL)" n
+            ],
+        );
+        run(
+            "void (__fastcall *handlers[2])(void)",
             hltext![
                 "an " n
                 "array" qk
                 " named " n
-                "arr" i
+                "handlers" i
                 " of " n
-                "10" num
+                "2" num
                 " " n
                 "pointers" qk
                 " to " n
-                "int" pt
-                "s" n
+                "fastcall" q
+                " " n
+                "functions" qk
+                " that take no parameters and return a " n
+                "void" pt
             ],
         );
     }
 
     #[test]
-    fn explain_ptr_to_array() {
+    fn explain_c23_attributes() {
         run(
-            "int (*p)[10]",
+            "[[nodiscard, gnu::hot]] int compute(void)",
             hltext![
                 "a " n
-                "pointer" qk
+                "function" qk
                 " named " n
-                "p" i
-                " to an " n
-                "array" qk
-                " of " n
-                "10" num
-                " " n
+                "compute" i
+                " that takes no parameters and returns an " n
                 "int" pt
-                "s" n
+                " (result must be " n
+                "used" qk
+                ")" n
             ],
         );
-    }
-
-    #[test]
-    fn explain_function_with_no_params() {
-        // run(
-        //     "void func()",
-        //     "a function named func that takes no parameters and returns a void",
-        // );
         run(
-            "void func()",
+            "[[deprecated(\"use new_api\")]] extern int old",
             hltext![
-                "a " n
-                "function" qk
+                "an " n
+                "externally-linked" q
+                " " n
+                "int" pt
                 " named " n
-                "func" i
-                " that takes no parameters and returns a " n
-                "void" pt
+                "old" i
+                " (" n
+                "deprecated" qk
+                ": \"use new_api\", defined elsewhere)" n
             ],
         );
     }
 
     #[test]
-    fn explain_array_of_functions() {
-        run(
-            "char *(*(*bar)[5])(int)",
+    fn explain_terse() {
+        let options = Options {
+            verbosity: Verbosity::Terse,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse("int (*p)[10]; static int f(int a, char *b); typedef unsigned long size")
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 3] = [
             hltext![
-                "a " n
                 "pointer" qk
-                " named " n
-                "bar" i
-                " to an " n
+                " to " n
                 "array" qk
                 " of " n
-                "5" num
+                "10" num
                 " " n
-                "pointers" qk
-                " to " n
-                "functions" qk
-                " that take (an " n
                 "int" pt
-                ") and return a " n
+            ],
+            hltext![
+                "static" q
+                " " n
+                "function" qk
+                " that takes (" n
+                "int" pt
+                " and " n
                 "pointer" qk
-                " to a " n
+                " to " n
                 "char" pt
+                ") and returns " n
+                "int" pt
+                " (with internal linkage)" n
             ],
-        );
+            hltext![
+                "type named " n
+                "size" udt
+                " defined as " n
+                "unsigned long" pt
+            ],
+        ];
+        for (explanation, expected) in explanations.iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
     }
 
     #[test]
-    fn explain_qualifiers() {
-        run(
-            "const int x",
+    fn explain_verbose() {
+        let options = Options {
+            verbosity: Verbosity::Verbose,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse("const char *const name; const int limit; int f(register volatile int n)")
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 3] = [
+            hltext![
+                "a " n
+                "const" q
+                " " n
+                "pointer" qk
+                " named " n
+                "name" i
+                " to a " n
+                "const" q
+                " " n
+                "char" pt
+                " (the value it points to cannot be modified through it, the pointer itself \
+                 cannot be changed to point elsewhere)" n
+            ],
             hltext![
                 "a " n
                 "const" q
                 " " n
                 "int" pt
                 " named " n
-                "x" i
+                "limit" i
+                " (its value cannot be modified after initialization)" n
             ],
-        );
-        run(
-            "volatile int x",
             hltext![
                 "a " n
+                "function" qk
+                " named " n
+                "f" i
+                " that takes (a " n
+                "register" q
+                " " n
                 "volatile" q
                 " " n
                 "int" pt
                 " named " n
-                "x" i
+                "n" i
+                " (accesses are never optimized away, since the value may change \
+                 unexpectedly, its address cannot be taken)) and returns an " n
+                "int" pt
             ],
-        );
-        run(
-            "int *const restrict x",
+        ];
+        for (explanation, expected) in explanations.iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
+    }
+
+    #[test]
+    fn explain_variadic_functions() {
+        let decls = crate::parser::parser()
+            .parse("int f(int a, ...); int g(...)")
+            .unwrap();
+        let explain = |language| {
+            let options = Options {
+                language,
+                ..Options::default()
+            };
+            decls
+                .iter()
+                .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+                .collect::<Vec<_>>()
+        };
+        let expected: [&[HighlightedTextSegment]; 2] = [
             hltext![
                 "a " n
-                "const restrict" q
-                " " n
-                "pointer" qk
+                "function" qk
                 " named " n
-                "x" i
-                " to an " n
+                "f" i
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "a" i
+                " and a variable number of further arguments) and returns an " n
                 "int" pt
             ],
-        );
-        run(
-            "const char *const str",
             hltext![
                 "a " n
-                "const" q
-                " " n
-                "pointer" qk
+                "function" qk
                 " named " n
-                "str" i
-                " to a " n
-                "const" q
-                " " n
-                "char" pt
+                "g" i
+                " that takes (a variable number of arguments) and returns an " n
+                "int" pt
             ],
-        );
+        ];
+        for (explanation, expected) in explain(Language::English).iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
+        let expected: [&[HighlightedTextSegment]; 2] = [
+            hltext![
+                "una " n
+                "función" qk
+                " llamada " n
+                "f" i
+                " que recibe (un " n
+                "int" pt
+                " llamado " n
+                "a" i
+                " y un número variable de argumentos adicionales) y devuelve un " n
+                "int" pt
+            ],
+            hltext![
+                "una " n
+                "función" qk
+                " llamada " n
+                "g" i
+                " que recibe (un número variable de argumentos) y devuelve un " n
+                "int" pt
+            ],
+        ];
+        for (explanation, expected) in explain(Language::Spanish).iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
     }
 
     #[test]
-    fn explain_struct_var() {
-        run(
-            "struct point p",
+    fn explain_spanish() {
+        let options = Options {
+            language: Language::Spanish,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse(
+                "int *p; static int f(int a, char *b); const char *const names[2]; \
+                 typedef unsigned long size",
+            )
+            .unwrap();
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 4] = [
             hltext![
-                "a " n
-                "struct point" udt
-                " named " n
+                "un " n
+                "puntero" qk
+                " llamado " n
                 "p" i
+                " a un " n
+                "int" pt
             ],
-        );
+            hltext![
+                "una " n
+                "función" qk
+                " " n
+                "estática" q
+                " llamada " n
+                "f" i
+                " que recibe (un " n
+                "int" pt
+                " llamado " n
+                "a" i
+                " y un " n
+                "puntero" qk
+                " llamado " n
+                "b" i
+                " a un " n
+                "char" pt
+                ") y devuelve un " n
+                "int" pt
+                " (con enlace interno)" n
+            ],
+            hltext![
+                "un " n
+                "arreglo" qk
+                " llamado " n
+                "names" i
+                " de " n
+                "2" num
+                " " n
+                "punteros" qk
+                " " n
+                "const" q
+                " a " n
+                "char" pt
+                " " n
+                "const" q
+            ],
+            hltext![
+                "un tipo llamado " n
+                "size" udt
+                " definido como un " n
+                "unsigned long" pt
+            ],
+        ];
+        for (explanation, expected) in explanations.iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
     }
 
     #[test]
-    fn explain_function_one_unnamed_param() {
-        run(
-            "int foo(const char *)",
+    fn explain_spanish_bit_fields_attributes_and_asides() {
+        let options = Options {
+            language: Language::Spanish,
+            verbosity: Verbosity::Verbose,
+            ..Options::default()
+        };
+        for (src, expected) in [
+            (
+                "unsigned _BitInt(24) x",
+                "un entero sin signo de 24 bits llamado x",
+            ),
+            (
+                "struct s { unsigned int f : 3; int *p : 4; } v",
+                "un struct s llamado v que contiene un campo de bits unsigned int de 3 bits llamado f \
+                 que puede contener valores 0–7 y un puntero llamado p a un int en un campo de bits \
+                 de 4 bits",
+            ),
+            (
+                "struct __attribute__((packed)) s { int x; } v",
+                "un struct s llamado v (sin relleno) que contiene un int llamado x",
+            ),
+            (
+                "__attribute__((aligned(8), deprecated)) int a",
+                "un int llamado a (con alineación de 8 bytes, en desuso)",
+            ),
+            (
+                "typeof(x) y",
+                "una variable llamada y con el mismo tipo que x",
+            ),
+            (
+                "void f(void)",
+                "una función llamada f que no recibe parámetros y devuelve un void",
+            ),
+            (
+                "void (^b)(void)",
+                "un bloque llamado b que no recibe parámetros y devuelve un void",
+            ),
+            (
+                "const char *restrict const p",
+                "un puntero const restrict llamado p a un char const (el valor al que apunta no \
+                 puede modificarse a través de él, el propio puntero no puede cambiarse para \
+                 apuntar a otro lugar, ningún otro puntero se usa para acceder al objeto al que \
+                 apunta)",
+            ),
+            (
+                "register volatile int n",
+                "un int de registro volatile llamado n (los accesos nunca se eliminan al optimizar, \
+                 ya que el valor puede cambiar inesperadamente, no se puede obtener su dirección)",
+            ),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            assert_eq!(
+                explain_declaration_with_options(&decls[0], &options)
+                    .format_to_string(&crate::color::fmt::PlainFormatter),
+                expected,
+                "Wrong explanation for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn explain_with_spelled_out_numbers() {
+        let decls = crate::parser::parser()
+            .parse("int grid[3][21]; char buf[1024]")
+            .unwrap();
+        let cases: [(Language, [&[HighlightedTextSegment]; 2]); 2] = [
+            (
+                Language::English,
+                [
+                    hltext![
+                        "an " n
+                        "array" qk
+                        " named " n
+                        "grid" i
+                        " of " n
+                        "three" num
+                        " " n
+                        "arrays" qk
+                        " of " n
+                        "twenty-one" num
+                        " " n
+                        "int" pt
+                        "s" n
+                    ],
+                    hltext![
+                        "an " n
+                        "array" qk
+                        " named " n
+                        "buf" i
+                        " of " n
+                        "one thousand twenty-four" num
+                        " " n
+                        "char" pt
+                        "s" n
+                    ],
+                ],
+            ),
+            (
+                Language::Spanish,
+                [
+                    hltext![
+                        "un " n
+                        "arreglo" qk
+                        " llamado " n
+                        "grid" i
+                        " de " n
+                        "tres" num
+                        " " n
+                        "arreglos" qk
+                        " de " n
+                        "veintiuno" num
+                        " " n
+                        "int" pt
+                    ],
+                    hltext![
+                        "un " n
+                        "arreglo" qk
+                        " llamado " n
+                        "buf" i
+                        " de " n
+                        "mil veinticuatro" num
+                        " " n
+                        "char" pt
+                    ],
+                ],
+            ),
+        ];
+        for (language, expected) in cases {
+            let options = Options {
+                language,
+                spell_out_numbers: true,
+                ..Options::default()
+            };
+            for (decl, expected) in decls.iter().zip(expected) {
+                let explanation = explain_declaration_with_options(decl, &options).coalesced();
+                assert_eq!(explanation.0, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn explain_with_idioms() {
+        let decls = crate::parser::parser()
+            .parse("const char *s; char *argv[]; void (*cb)(void *ctx); int *p")
+            .unwrap();
+        let options = Options {
+            idioms: true,
+            ..Options::default()
+        };
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 4] = [
             hltext![
                 "a " n
-                "function" qk
-                " named " n
-                "foo" i
-                " that takes (a " n
                 "pointer" qk
+                " named " n
+                "s" i
                 " to a " n
                 "const" q
                 " " n
                 "char" pt
-                ") and returns an " n
-                "int" pt
+                " (commonly a string)" n
+            ],
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "argv" i
+                " of " n
+                "pointers" qk
+                " to " n
+                "char" pt
+                "s (commonly an array of strings)" n
             ],
-        );
-    }
-
-    #[test]
-    fn explain_function_one_named_param() {
-        run(
-            "int foo(const char *bar)",
             hltext![
                 "a " n
-                "function" qk
+                "pointer" qk
                 " named " n
-                "foo" i
+                "cb" i
+                " to a " n
+                "function" qk
                 " that takes (a " n
                 "pointer" qk
                 " named " n
-                "bar" i
+                "ctx" i
                 " to a " n
-                "const" q
-                " " n
-                "char" pt
-                ") and returns an " n
-                "int" pt
+                "void" pt
+                ") and returns a " n
+                "void" pt
+                " (commonly a callback with a context pointer)" n
             ],
-        );
-    }
-
-    #[test]
-    fn explain_anonymous_function() {
-        run(
-            "int (*)(const char *)",
             hltext![
                 "a " n
                 "pointer" qk
-                " to a " n
-                "function" qk
-                " that takes (a " n
-                "pointer" qk
-                " to a " n
-                "const" q
-                " " n
-                "char" pt
-                ") and returns an " n
+                " named " n
+                "p" i
+                " to an " n
                 "int" pt
             ],
-        );
+        ];
+        for (explanation, expected) in explanations.iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
     }
 
     #[test]
-    fn explain_function_two_params() {
-        run(
-            "int add(int a, int b)",
+    fn explain_with_call_syntax() {
+        let decls = crate::parser::parser()
+            .parse("void (*handler)(int code); int (*ops[2])(int, int); int *p")
+            .unwrap();
+        let options = Options {
+            call_syntax: true,
+            ..Options::default()
+        };
+        let explanations: Vec<_> = decls
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, &options).coalesced())
+            .collect();
+        let expected: [&[HighlightedTextSegment]; 3] = [
             hltext![
                 "a " n
-                "function" qk
+                "pointer" qk
                 " named " n
-                "add" i
+                "handler" i
+                " to a " n
+                "function" qk
                 " that takes (an " n
                 "int" pt
                 " named " n
-                "a" i
+                "code" i
+                ") and returns a " n
+                "void" pt
+                "; call it as `(*handler)(code)` or simply `handler(code)`, and assign it with \
+                 `handler = my_handler;`" n
+            ],
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "ops" i
+                " of " n
+                "2" num
+                " " n
+                "pointers" qk
+                " to " n
+                "functions" qk
+                " that take (an " n
+                "int" pt
                 " and an " n
                 "int" pt
+                ") and return an " n
+                "int" pt
+                "; call it as `(*ops[0])(a, b)` or simply `ops[0](a, b)`" n
+            ],
+            hltext![
+                "a " n
+                "pointer" qk
                 " named " n
-                "b" i
-                ") and returns an " n
+                "p" i
+                " to an " n
                 "int" pt
             ],
+        ];
+        for (explanation, expected) in explanations.iter().zip(expected) {
+            assert_eq!(explanation.0, expected);
+        }
+    }
+
+    #[test]
+    fn explain_with_mutability() {
+        let options = Options {
+            mutability: true,
+            ..Options::default()
+        };
+        for (src, expected) in [
+            (
+                "const char *p",
+                "a pointer named p to a const char (p may be modified, but *p may not)",
+            ),
+            (
+                "char *const p",
+                "a const pointer named p to a char (*p may be modified, but p may not)",
+            ),
+            (
+                "const char *const p",
+                "a const pointer named p to a const char (p and *p may not be modified)",
+            ),
+            (
+                "const char **pp",
+                "a pointer named pp to a pointer to a const char (pp and *pp may be modified, but \
+                 **pp may not)",
+            ),
+            (
+                "const int (*rows)[4]",
+                "a pointer named rows to an array of 4 const ints (rows may be modified, but \
+                 (*rows)[i] may not)",
+            ),
+            (
+                "const int limit",
+                "a const int named limit (limit may not be modified)",
+            ),
+            ("char *p", "a pointer named p to a char"),
+            (
+                "const char *f(void)",
+                "a function named f that takes no parameters and returns a \
+             pointer to a const char",
+            ),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            assert_eq!(
+                explain_declaration_with_options(&decls[0], &options)
+                    .format_to_string(&crate::color::fmt::PlainFormatter),
+                expected,
+                "Wrong explanation for {src}"
+            );
+        }
+        let options = Options {
+            language: Language::Spanish,
+            ..options
+        };
+        let decls = crate::parser::parser().parse("const char **pp").unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &options)
+                .format_to_string(&crate::color::fmt::PlainFormatter),
+            "un puntero llamado pp a un puntero a un char const (pp y *pp pueden modificarse, \
+             pero **pp no)"
         );
     }
 
     #[test]
-    fn explain_function_three_params() {
-        run(
-            "void print(int a, char *b, float c)",
+    fn explain_with_vocabulary_overrides() {
+        let phrasebook = Language::English
+            .phrasebook()
+            .with_overrides([
+                ("pointer", "address"),
+                ("named", " called "),
+                ("to", " of "),
+            ])
+            .unwrap();
+        let options = Options {
+            phrasebook: Some(Arc::new(phrasebook)),
+            ..Options::default()
+        };
+        let decls = crate::parser::parser().parse("int *p").unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &options)
+                .coalesced()
+                .0,
             hltext![
-                "a " n
-                "function" qk
-                " named " n
-                "print" i
-                " that takes (an " n
+                "an " n
+                "address" qk
+                " called " n
+                "p" i
+                " of an " n
                 "int" pt
-                " named " n
-                "a" i
-                ", a " n
-                "pointer" qk
-                " named " n
-                "b" i
-                " to a " n
-                "char" pt
-                ", and a " n
-                "float" pt
-                " named " n
-                "c" i
-                ") and returns a " n
-                "void" pt
             ],
         );
+
+        // Connecting phrases can be restyled, including the brackets around parameters.
+        let phrasebook = Language::English
+            .phrasebook()
+            .with_overrides([
+                ("that-takes", " which accepts "),
+                ("and-returns", " and yields "),
+                ("parameters-start", ""),
+                ("parameters-end", ""),
+            ])
+            .unwrap();
+        let options = Options {
+            phrasebook: Some(Arc::new(phrasebook)),
+            ..Options::default()
+        };
+        let decls = crate::parser::parser().parse("int f(char c)").unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &options)
+                .format_to_string(&crate::color::fmt::PlainFormatter),
+            "a function named f which accepts a char named c and yields an int"
+        );
+
+        assert_eq!(
+            Language::English
+                .phrasebook()
+                .with_overrides([("pointer.dual", "pointers")])
+                .unwrap_err(),
+            UnknownPhrase("pointer.dual".to_string())
+        );
     }
 
     #[test]
-    fn explain_array_of_struct() {
-        run(
-            "struct point p[]",
+    fn explain_for_audiences() {
+        let decls = crate::parser::parser()
+            .parse("const volatile int *p[2]")
+            .unwrap();
+        let beginner = Options {
+            audience: Audience::Beginner,
+            ..Options::default()
+        };
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &beginner)
+                .coalesced()
+                .0,
             hltext![
                 "an " n
                 "array" qk
-                " named " n
+                " (a sequence of elements stored next to each other in memory) named " n
                 "p" i
                 " of " n
-                "struct point" udt
+                "2" num
+                " " n
+                "pointers" qk
+                " (a variable that stores a memory address) to " n
+                "const volatile" q
+                " (cannot be modified; may change at any time, even if the program doesn't \
+                 change it) " n
+                "int" pt
                 "s" n
             ],
         );
-    }
 
-    #[test]
-    fn explain_plural_qualifiers() {
-        run(
-            "char *const p[]",
+        let expert = Options {
+            audience: Audience::Expert,
+            ..Options::default()
+        };
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &expert)
+                .coalesced()
+                .0,
             hltext![
-                "an " n
                 "array" qk
-                " named " n
-                "p" i
                 " of " n
-                "const" q
+                "2" num
                 " " n
-                "pointers" qk
+                "pointer" qk
                 " to " n
-                "char" pt
-                "s" n
+                "const volatile" q
+                " " n
+                "int" pt
             ],
         );
-    }
 
-    /// Anonymous typedefs are technically not valid C, but we handle them gracefully.
-    #[test]
-    fn explain_anon_typedef() {
-        run(
-            "typedef char *",
+        // Terms are only clarified the first time they're used, including in parameters.
+        let mut phrasebook = Language::English.phrasebook().clone();
+        phrasebook.glossary.to_mut().push((
+            Word {
+                singular: "char".into(),
+                plural: "chars".into(),
+            },
+            "a single byte",
+        ));
+        let options = Options {
+            audience: Audience::Beginner,
+            phrasebook: Some(Arc::new(phrasebook)),
+            ..Options::default()
+        };
+        let decls = crate::parser::parser().parse("char *f(char *s)").unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[0], &options)
+                .coalesced()
+                .0,
             hltext![
-                "a type defined as a " n
+                "a " n
+                "function" qk
+                " (a piece of code which can be called) named " n
+                "f" i
+                " that takes (a " n
+                "pointer" qk
+                " (a variable that stores a memory address) named " n
+                "s" i
+                " to a " n
+                "char" pt
+                " (a single byte)) and returns a " n
                 "pointer" qk
                 " to a " n
                 "char" pt
@@ -861,77 +4188,355 @@ mod tests {
     }
 
     #[test]
-    fn explain_typedef() {
-        run(
-            "typedef struct point point_t",
-            hltext![
-                "a type named " n
-                "point_t" udt
-                " defined as a " n
-                "struct point" udt
-            ],
+    fn explain_with_footnotes() {
+        let decls = crate::parser::parser().parse("const char *argv[]").unwrap();
+        let explanation = explain_declaration_with_footnotes(&decls[0], &Options::default());
+        assert_eq!(
+            explanation.text,
+            explain_declaration(&decls[0]),
+            "Footnotes shouldn't change the text"
+        );
+        let footnotes: Vec<(&str, &str)> = explanation
+            .footnotes
+            .iter()
+            .map(|footnote| {
+                (
+                    explanation.text[footnote.segment].text.as_str(),
+                    footnote.term.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            footnotes,
+            [
+                ("array", "array"),
+                ("pointers", "pointers"),
+                ("const", "const")
+            ]
+        );
+        assert_eq!(
+            explanation.footnotes[1].definition,
+            "a variable that stores a memory address"
         );
     }
 
     #[test]
-    fn explain_pointer_typedef() {
-        run(
-            "typedef const char *string",
-            hltext![
-                "a type named " n
-                "string" udt
-                " defined as a " n
-                "pointer" qk
-                " to a " n
-                "const" q
-                " " n
-                "char" pt
-            ],
+    fn explain_with_provenance() {
+        use Step::{BaseType, Declarator, Enumerator, Initializer, Inner, Member, Parameter};
+
+        let options = Options {
+            provenance: true,
+            ..Options::default()
+        };
+        for (src, expected) in [
+            (
+                "static void (*fp)(char c) = 0",
+                vec![
+                    ("static", vec![]),
+                    ("pointer", vec![Declarator, Inner]),
+                    ("fp", vec![Declarator, Inner, Inner]),
+                    ("function", vec![Declarator]),
+                    ("char", vec![Declarator, Parameter(0), BaseType]),
+                    ("c", vec![Declarator, Parameter(0), Declarator]),
+                    ("void", vec![BaseType]),
+                    ("0", vec![Initializer]),
+                ],
+            ),
+            (
+                "typedef int arr_t[4]",
+                vec![
+                    ("arr_t", vec![Declarator, Inner]),
+                    ("array", vec![Declarator]),
+                    ("4", vec![Declarator]),
+                    ("int", vec![BaseType]),
+                ],
+            ),
+            (
+                "struct s { int x; unsigned int flags : 3; } v",
+                vec![
+                    ("struct s", vec![BaseType]),
+                    ("v", vec![Declarator]),
+                    ("int", vec![Member(0), BaseType]),
+                    ("x", vec![Member(0), Declarator]),
+                    ("3", vec![Member(1)]),
+                    ("unsigned int", vec![Member(1), BaseType]),
+                    ("bit-field", vec![Member(1)]),
+                    ("flags", vec![Member(1), Declarator]),
+                    ("0", vec![Member(1)]),
+                    ("7", vec![Member(1)]),
+                ],
+            ),
+            (
+                "enum e { A, B } v",
+                vec![
+                    ("enum e", vec![BaseType]),
+                    ("v", vec![Declarator]),
+                    ("A", vec![Enumerator(0)]),
+                    ("0", vec![Enumerator(0)]),
+                    ("B", vec![Enumerator(1)]),
+                    ("1", vec![Enumerator(1)]),
+                ],
+            ),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            let explanation = explain_declaration_with_options(&decls[0], &options);
+            let origins: Vec<(&str, Vec<Step>)> = explanation
+                .iter()
+                .filter(|segment| segment.highlight != Highlight::None)
+                .map(|segment| {
+                    let origin = segment.origin.clone().expect("Missing origin");
+                    assert!(
+                        origin.resolve(&decls[0]).is_some(),
+                        "Unresolvable origin {origin:?} in {src}"
+                    );
+                    (segment.text.as_str(), origin.0)
+                })
+                .collect();
+            assert_eq!(origins, expected, "Wrong origins for {src}");
+            assert!(
+                explain_declaration(&decls[0])
+                    .iter()
+                    .all(|segment| segment.origin.is_none()),
+                "Origins recorded without provenance for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn explain_with_groups() {
+        use Group::{Level, Parameter};
+
+        let decls = crate::parser::parser()
+            .parse("static void (*fp)(char *s, int n)")
+            .unwrap();
+        let options = Options {
+            groups: true,
+            ..Options::default()
+        };
+        let explanation = explain_declaration_with_options(&decls[0], &options);
+        let groups: Vec<(&str, Vec<Group>)> = explanation
+            .iter()
+            .map(|segment| (segment.text.as_str(), segment.groups.clone()))
+            .collect();
+        let param = |i| vec![Level(1), Parameter(i)];
+        let pointer_param = vec![Level(1), Parameter(0), Level(0)];
+        assert_eq!(
+            groups,
+            vec![
+                ("a ", vec![]),
+                ("static", vec![]),
+                (" ", vec![]),
+                ("pointer", vec![Level(0)]),
+                (" named ", vec![Level(0)]),
+                ("fp", vec![Level(0)]),
+                (" to ", vec![Level(0)]),
+                ("a ", vec![Level(1)]),
+                ("function", vec![Level(1)]),
+                (" that takes (", vec![Level(1)]),
+                ("a ", pointer_param.clone()),
+                ("pointer", pointer_param.clone()),
+                (" named ", pointer_param.clone()),
+                ("s", pointer_param.clone()),
+                (" to ", pointer_param),
+                ("a ", param(0)),
+                ("char", param(0)),
+                (" and ", vec![Level(1)]),
+                ("an ", param(1)),
+                ("int", param(1)),
+                (" named ", param(1)),
+                ("n", param(1)),
+                (") and returns ", vec![Level(1)]),
+                ("a ", vec![]),
+                ("void", vec![]),
+                (" (", vec![]),
+                ("with internal linkage)", vec![]),
+            ]
         );
+
+        // Groups don't change the text of explanations.
+        for src in [
+            "const char *const names[4]",
+            "int (*(*fp)(int))[3]",
+            "void (^handler)(int code)",
+            "extern struct s *(*table[2])(void)",
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            for options in [
+                Options::default(),
+                Options {
+                    multiline: true,
+                    ..Options::default()
+                },
+                Options {
+                    verbosity: Verbosity::Verbose,
+                    ..Options::default()
+                },
+                Options {
+                    language: Language::Spanish,
+                    ..Options::default()
+                },
+            ] {
+                let grouped = Options {
+                    groups: true,
+                    ..options.clone()
+                };
+                assert_eq!(
+                    explain_declaration_with_options(&decls[0], &grouped)
+                        .format_to_string(&crate::color::fmt::PlainFormatter),
+                    explain_declaration_with_options(&decls[0], &options)
+                        .format_to_string(&crate::color::fmt::PlainFormatter),
+                    "{src}"
+                );
+            }
+            assert!(
+                explain_declaration(&decls[0])
+                    .iter()
+                    .all(|segment| segment.groups.is_empty()),
+                "Groups recorded without being requested for {src}"
+            );
+        }
     }
 
     #[test]
-    fn explain_typedef_plural_end() {
-        run(
-            "typedef int nums[]",
+    fn explain_multiline() {
+        let options = Options {
+            multiline: true,
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse(
+                "char *(*(**foo[][8])())[]; static int x; \
+                 typedef void (*handler)(int sig, int *(*info)[2])",
+            )
+            .unwrap();
+        let expected: [&[HighlightedTextSegment]; 3] = [
             hltext![
-                "a type named " n
-                "nums" udt
-                " defined as an " n
+                "an " n
                 "array" qk
+                " named " n
+                "foo" i
+                " of\n  " n
+                "arrays" qk
                 " of " n
-                "int" pt
+                "8" num
+                "\n    " n
+                "pointers" qk
+                " to\n      " n
+                "pointers" qk
+                " to\n        " n
+                "functions" qk
+                " that take no parameters and return\n          a " n
+                "pointer" qk
+                " to\n            an " n
+                "array" qk
+                " of\n              " n
+                "pointers" qk
+                " to\n                " n
+                "char" pt
                 "s" n
             ],
-        );
-    }
-
-    #[test]
-    fn explain_function_typedef() {
-        run(
-            "typedef int (*compare_t)(const void *, const void *)",
+            hltext![
+                "a " n
+                "static" q
+                " " n
+                "int" pt
+                " named " n
+                "x" i
+                " (with internal linkage)" n
+            ],
             hltext![
                 "a type named " n
-                "compare_t" udt
+                "handler" udt
                 " defined as a " n
                 "pointer" qk
-                " to a " n
+                " to\n  a " n
                 "function" qk
-                " that takes (a " n
+                " that takes (an " n
+                "int" pt
+                " named " n
+                "sig" i
+                " and a " n
                 "pointer" qk
-                " to a " n
-                "const" q
+                " named " n
+                "info" i
+                " to an " n
+                "array" qk
+                " of " n
+                "2" num
                 " " n
+                "pointers" qk
+                " to " n
+                "int" pt
+                "s) and returns\n    a " n
                 "void" pt
-                " and a " n
+            ],
+        ];
+        for (decl, expected) in decls.iter().zip(expected) {
+            assert_eq!(
+                explain_declaration_with_options(decl, &options)
+                    .coalesced()
+                    .0,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn explain_articles() {
+        run(
+            "union foo *u",
+            hltext![
+                "a " n
                 "pointer" qk
+                " named " n
+                "u" i
                 " to a " n
-                "const" q
+                "union foo" udt
+            ],
+        );
+        run(
+            "uint8_t bytes[8]",
+            hltext![
+                "an " n
+                "array" qk
+                " named " n
+                "bytes" i
+                " of " n
+                "8" num
                 " " n
-                "void" pt
-                ") and returns an " n
-                "int" pt
+                "uint8_t" udt
+                "s" n
+            ],
+        );
+
+        let mut phrasebook = Language::English.phrasebook().clone();
+        phrasebook.article_exceptions.push(("SQL", "a "));
+        let options = Options {
+            phrasebook: Some(Arc::new(phrasebook)),
+            ..Options::default()
+        };
+        let decls = crate::parser::parser()
+            .parse("typedef int SQL; SQL query")
+            .unwrap();
+        assert_eq!(
+            explain_declaration_with_options(&decls[1], &options)
+                .coalesced()
+                .0,
+            hltext![
+                "a " n
+                "SQL" udt
+                " named " n
+                "query" i
             ],
         );
     }
+
+    #[test]
+    fn phrase_ids() {
+        let mut phrasebook = Language::Spanish.phrasebook().clone();
+        for id in PHRASE_IDS {
+            assert!(phrasebook.phrase_mut(id).is_some(), "unknown phrase {id}");
+        }
+        assert!(phrasebook.phrase_mut("type.plural").is_none());
+    }
 }
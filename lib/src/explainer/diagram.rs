@@ -0,0 +1,272 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Diagrams of the structure of declarations, drawn under their source text
+
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+use crate::{
+    ast::{Declaration, Declarator, TypeQualifier},
+    c_syntax::{c_type, calling_convention_token, declarator_token},
+    color::{Highlight, HighlightedText, HighlightedTextSegment},
+};
+
+use super::{
+    Options,
+    walkthrough::{base_type_phrase, declarator_parts, part_phrase},
+};
+
+/// Characters used to draw diagrams
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum DiagramStyle {
+    /// Marks parts of declarations with `^` and leads from them to their phrases with `-`.
+    Ascii,
+    /// Marks parts of declarations with `━` and leads from them to their phrases with `─`.
+    #[default]
+    Unicode,
+}
+
+impl DiagramStyle {
+    /// Returns the characters which mark parts of declarations and lead from them to their
+    /// phrases.
+    fn characters(self) -> (char, char) {
+        match self {
+            Self::Ascii => ('^', '-'),
+            Self::Unicode => ('━', '─'),
+        }
+    }
+}
+
+/// Source text of a declaration, along with the columns of each part of its declarator
+#[derive(Debug, Default)]
+struct Source<'a, 'src> {
+    text: String,
+    parts: Vec<(&'a Declarator<'src>, Range<usize>)>,
+}
+
+impl<'a, 'src> Source<'a, 'src> {
+    /// Returns the column at the end of the text.
+    fn column(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Appends a part of the declarator as it's written in C, recording its columns without
+    /// trailing whitespace.
+    fn push_part(&mut self, part: &'a Declarator<'src>, token: &str) {
+        let start = self.column();
+        self.parts
+            .push((part, start..start + token.trim_end().chars().count()));
+        self.text.push_str(token);
+    }
+
+    /// Appends a declarator as it's written in C, like
+    /// [`format_declaration()`][crate::en2c::format_declaration] does.
+    fn push_declarator(&mut self, declarator: &'a Declarator<'src>) {
+        match declarator {
+            Declarator::Anonymous => {}
+            Declarator::Ident(name) => self.push_part(declarator, name),
+            Declarator::Ptr(..)
+            | Declarator::Reference(_)
+            | Declarator::Block(..)
+            | Declarator::MemberPtr(..) => {
+                let (inner, token) = declarator_token(declarator);
+                self.push_part(declarator, &token);
+                self.push_declarator(inner);
+            }
+            Declarator::Array(inner, ..) | Declarator::Function { func: inner, .. } => {
+                let parenthesize = matches!(
+                    **inner,
+                    Declarator::Ptr(..)
+                        | Declarator::Reference(_)
+                        | Declarator::Block(..)
+                        | Declarator::MemberPtr(..)
+                );
+                // The calling convention is written before the rest of the function.
+                let convention = calling_convention_token(declarator);
+                if !convention.is_empty() {
+                    self.push_part(declarator, &convention);
+                }
+                if parenthesize {
+                    self.text.push('(');
+                }
+                self.push_declarator(inner);
+                if parenthesize {
+                    self.trim_end();
+                    self.text.push(')');
+                }
+                self.push_part(declarator, &declarator_token(declarator).1);
+            }
+        }
+    }
+
+    /// Removes whitespace from the end of the text.
+    fn trim_end(&mut self) {
+        self.text.truncate(self.text.trim_end().len());
+    }
+
+    /// Returns the columns of the given part of the declarator, which may be split, like a
+    /// function's calling convention and its parameters.
+    fn columns_of(&self, part: &Declarator) -> impl Iterator<Item = Range<usize>> {
+        self.parts
+            .iter()
+            .filter(move |(other, _)| core::ptr::eq(*other, part))
+            .map(|(_, columns)| columns.clone())
+    }
+}
+
+/// Draws a diagram of a declaration's structure: the declaration as it's written in C, followed by
+/// a row for each of its parts in the order of the spiral rule, which marks the part and leads to
+/// the phrase explaining it. Reading the phrases from top to bottom gives the explanation, e.g.
+///
+/// ```text
+/// int *bar[5]
+///      ━━━─── bar is
+///         ━━━ an array of 5
+///     ━────── pointers to
+/// ━━━──────── ints
+/// ```
+///
+/// The connecting words are always in English, but the phrases use the language and verbosity
+/// given in the options.
+#[must_use]
+pub fn draw_diagram(decl: &Declaration, style: DiagramStyle, options: &Options) -> HighlightedText {
+    let options = &options.single_line();
+    // The name of a typedef is introduced separately, so the base type is explained without it.
+    let typedef = decl.base_type.0.contains(TypeQualifier::Typedef);
+    let mut decl = decl.clone();
+    decl.base_type.0.remove(TypeQualifier::Typedef);
+    let decl = &decl;
+    let mut source = Source::default();
+    if typedef {
+        source.text.push_str("typedef ");
+    }
+    let type_start = source.column();
+    source.text.push_str(&c_type(&decl.base_type));
+    let type_columns = type_start..source.column();
+    if !matches!(decl.declarator, Declarator::Anonymous) {
+        source.text.push(' ');
+        source.push_declarator(&decl.declarator);
+        source.trim_end();
+    }
+
+    let (parts, name) = declarator_parts(decl);
+    let mut rows: Vec<(Vec<Range<usize>>, HighlightedText)> = Vec::new();
+    if let Declarator::Ident(text) = name {
+        let mut label = HighlightedText::new();
+        label.push(HighlightedTextSegment::new(*text, Highlight::Ident));
+        label.push_str(if typedef { " is a name for" } else { " is" });
+        rows.push((source.columns_of(name).collect(), label));
+    }
+    for (part, inner) in parts.into_iter().rev() {
+        let mut columns: Vec<Range<usize>> = source.columns_of(part).collect();
+        // A block pointer to a function is one part along with the function.
+        if let Declarator::Function { func, .. } = part
+            && matches!(**func, Declarator::Block(..))
+        {
+            columns.extend(source.columns_of(func));
+        }
+        rows.push((columns, part_phrase(part, inner, options)));
+    }
+    rows.push((alloc::vec![type_columns], base_type_phrase(decl, options)));
+
+    let (mark, lead) = style.characters();
+    let width = source.column();
+    let mut diagram = HighlightedText::from(source.text);
+    for (columns, label) in rows {
+        let mut line: Vec<char> = alloc::vec![' '; width];
+        for range in &columns {
+            line[range.clone()].fill(mark);
+        }
+        let end = columns.iter().map(|range| range.end).max().unwrap_or(0);
+        line[end..].fill(lead);
+        let mut line: String = line.into_iter().collect();
+        line.push(' ');
+        diagram.push_str("\n");
+        diagram.push_str(&line);
+        diagram.extend(label.0);
+    }
+    diagram
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::color::fmt::PlainFormatter;
+
+    /// Draws a diagram of the single declaration in the source with the default options.
+    fn draw(src: &str, style: DiagramStyle) -> String {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        draw_diagram(&decls[0], style, &Options::default()).format_to_string(&PlainFormatter)
+    }
+
+    #[test]
+    fn diagram_array_of_pointers() {
+        assert_eq!(
+            draw("int *bar[5]", DiagramStyle::Unicode),
+            "int *bar[5]\n\
+             \x20    ━━━─── bar is\n\
+             \x20       ━━━ an array of 5\n\
+             \x20   ━────── pointers to\n\
+             ━━━──────── ints"
+        );
+    }
+
+    #[test]
+    fn diagram_function_pointer() {
+        assert_eq!(
+            draw("char *const (*fp)(int a)", DiagramStyle::Ascii),
+            "char *const (*fp)(int a)\n\
+             \x20             ^^-------- fp is\n\
+             \x20            ^---------- a pointer to\n\
+             \x20                ^^^^^^^ a function that takes (an int named a) and returns\n\
+             \x20    ^^^^^^------------- a const pointer to\n\
+             ^^^^-------------------- a char"
+        );
+    }
+
+    #[test]
+    fn diagram_block_and_typedef() {
+        assert_eq!(
+            draw("typedef void (^handler_t)(int)", DiagramStyle::Ascii),
+            "typedef void (^handler_t)(int)\n\
+             \x20              ^^^^^^^^^------ handler_t is a name for\n\
+             \x20             ^          ^^^^^ a block taking (an int) and returning\n\
+             \x20       ^^^^------------------ a void"
+        );
+        assert_eq!(
+            draw("const char *", DiagramStyle::Ascii),
+            "const char *\n\
+             \x20          ^ a pointer to\n\
+             ^^^^^^^^^^-- a const char"
+        );
+    }
+
+    #[test]
+    fn diagram_calling_convention() {
+        assert_eq!(
+            draw("void (__stdcall *f)(int)", DiagramStyle::Ascii),
+            "void __stdcall (*f)(int)\n\
+             \x20                ^------ f is\n\
+             \x20               ^------- a pointer to\n\
+             \x20    ^^^^^^^^^     ^^^^^ a stdcall function that takes (an int) and returns\n\
+             ^^^^-------------------- a void"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Explanations of how two declarations differ
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{
+    ast::{Declaration, Declarator},
+    color::{Highlight, HighlightedText, HighlightedTextSegment},
+};
+
+use super::{
+    Options, explain_declaration_with_options, format_qualified_type, pronunciation::article_for,
+};
+
+/// Kind of a part of a declarator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Pointer,
+    Reference,
+    Block,
+    MemberPointer,
+    Array,
+    Function,
+}
+
+impl Kind {
+    /// Returns the singular and plural nouns for the kind, and the phrase which connects it to
+    /// the type it derives from.
+    fn words(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Kind::Pointer => ("pointer", "pointers", " to "),
+            Kind::Reference => ("reference", "references", " to "),
+            Kind::Block => ("block", "blocks", " returning "),
+            Kind::MemberPointer => ("member pointer", "member pointers", " to "),
+            Kind::Array => ("array", "arrays", " of "),
+            Kind::Function => ("function", "functions", " returning "),
+        }
+    }
+}
+
+/// Returns the kinds of the parts of a declarator, from the name outwards. A block pointer to a
+/// function is a single block.
+fn kinds(declarator: &Declarator) -> Vec<Kind> {
+    let (inner, kind) = match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => return Vec::new(),
+        Declarator::Ptr(inner, _) => (inner, Kind::Pointer),
+        Declarator::Reference(inner) => (inner, Kind::Reference),
+        Declarator::Block(inner, _) => (inner, Kind::Block),
+        Declarator::MemberPtr(inner, ..) => (inner, Kind::MemberPointer),
+        Declarator::Array(inner, ..) => (inner, Kind::Array),
+        Declarator::Function { func, .. } => match &**func {
+            Declarator::Block(inner, _) => (inner, Kind::Block),
+            _ => (func, Kind::Function),
+        },
+    };
+    let mut kinds = kinds(inner);
+    kinds.push(kind);
+    kinds
+}
+
+/// Describes the shape of a declaration using only the nouns for the parts of its declarator,
+/// e.g. "an array of pointers", or using its type if the declarator has no parts.
+fn shape(decl: &Declaration, options: &Options) -> HighlightedText {
+    let kinds = kinds(&decl.declarator);
+    let mut msg = HighlightedText::new();
+    if kinds.is_empty() {
+        let ty = format_qualified_type(&decl.base_type, options);
+        msg.push_str(article_for(&ty[0]));
+        msg.extend(ty.0);
+        return msg;
+    }
+    let mut previous: Option<Kind> = None;
+    for kind in kinds {
+        let (singular, plural, _) = kind.words();
+        let noun = if let Some(previous) = previous {
+            msg.push_str(previous.words().2);
+            if previous == Kind::Array {
+                plural
+            } else {
+                singular
+            }
+        } else {
+            singular
+        };
+        let noun = HighlightedTextSegment::new(noun, Highlight::QuasiKeyword);
+        if noun.text == singular {
+            msg.push_str(article_for(&noun));
+        }
+        msg.push(noun);
+        previous = Some(kind);
+    }
+    msg
+}
+
+/// Returns a copy of the declarator with its identifier removed.
+fn without_name<'src>(declarator: &Declarator<'src>) -> Declarator<'src> {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => Declarator::Anonymous,
+        Declarator::Ptr(inner, qualifiers) => {
+            Declarator::Ptr(Box::new(without_name(inner)), *qualifiers)
+        }
+        Declarator::Reference(inner) => Declarator::Reference(Box::new(without_name(inner))),
+        Declarator::Block(inner, qualifiers) => {
+            Declarator::Block(Box::new(without_name(inner)), *qualifiers)
+        }
+        Declarator::MemberPtr(inner, class, qualifiers) => {
+            Declarator::MemberPtr(Box::new(without_name(inner)), class, *qualifiers)
+        }
+        Declarator::Array(inner, size, qualifiers) => {
+            Declarator::Array(Box::new(without_name(inner)), *size, *qualifiers)
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => Declarator::Function {
+            func: Box::new(without_name(func)),
+            params: params.clone(),
+            variadic: *variadic,
+            calling_convention: *calling_convention,
+            member_qualifiers: *member_qualifiers,
+        },
+    }
+}
+
+/// Explains a declaration without its name, e.g. "a pointer to an int".
+fn explain_without_name(decl: &Declaration, options: &Options) -> HighlightedText {
+    let decl = Declaration {
+        declarator: without_name(&decl.declarator),
+        ..decl.clone()
+    };
+    explain_declaration_with_options(&decl, options)
+}
+
+/// Pushes a sentence contrasting the two declarations, e.g. "the first is an int; the second is
+/// a char".
+fn push_contrast(msg: &mut HighlightedText, first: HighlightedText, second: HighlightedText) {
+    msg.push_str("the first is ");
+    msg.extend(first.0);
+    msg.push_str("; the second is ");
+    msg.extend(second.0);
+}
+
+/// Explains how two declarations differ, focusing on the most significant difference.
+///
+/// If the declarators have different shapes, only their shapes are described, e.g. "the first
+/// is an array of pointers; the second is a pointer to an array" for `int *a[10]` and
+/// `int (*a)[10]`. Otherwise, the declarations are explained without their names, which are
+/// only mentioned if they're the only difference. The connecting phrases are always in English.
+#[must_use]
+pub fn explain_difference(a: &Declaration, b: &Declaration, options: &Options) -> HighlightedText {
+    let mut msg = HighlightedText::new();
+    if a == b {
+        msg.push_str("the declarations are identical");
+        return msg;
+    }
+    if kinds(&a.declarator) != kinds(&b.declarator) {
+        push_contrast(&mut msg, shape(a, options), shape(b, options));
+        return msg;
+    }
+    let (first, second) = (
+        explain_without_name(a, options),
+        explain_without_name(b, options),
+    );
+    if first != second {
+        push_contrast(&mut msg, first, second);
+        return msg;
+    }
+    match (a.declarator.name(), b.declarator.name()) {
+        (Some(first_name), Some(second_name)) if first_name != second_name => {
+            msg.push_str("both are ");
+            msg.extend(first.0);
+            msg.push_str(", but the first is named ");
+            msg.push(HighlightedTextSegment::new(first_name, Highlight::Ident));
+            msg.push_str(" and the second is named ");
+            msg.push(HighlightedTextSegment::new(second_name, Highlight::Ident));
+        }
+        _ => msg.push_str("the declarations are equivalent"),
+    }
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Asserts that the difference between the two declarations in the source is explained as
+    /// expected.
+    fn run(src: &str, expected: &[HighlightedTextSegment]) {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 2, "Expected two declarations in {src}");
+        assert_eq!(
+            &explain_difference(&decls[0], &decls[1], &Options::default())
+                .coalesced()
+                .0,
+            expected,
+            "Wrong output for {src}"
+        );
+    }
+
+    #[test]
+    fn different_shapes() {
+        run(
+            "int *a[10]; int (*a)[10]",
+            hltext![
+                "the first is an " n
+                "array" qk
+                " of " n
+                "pointers" qk
+                "; the second is a " n
+                "pointer" qk
+                " to an " n
+                "array" qk
+            ],
+        );
+        run(
+            "int *f(void); int (*f)(void)",
+            hltext![
+                "the first is a " n
+                "function" qk
+                " returning a " n
+                "pointer" qk
+                "; the second is a " n
+                "pointer" qk
+                " to a " n
+                "function" qk
+            ],
+        );
+        run(
+            "char c; void (^c)(int)",
+            hltext![
+                "the first is a " n
+                "char" pt
+                "; the second is a " n
+                "block" qk
+            ],
+        );
+    }
+
+    #[test]
+    fn same_shapes() {
+        run(
+            "int a[10]; const int b[20]",
+            hltext![
+                "the first is an " n
+                "array" qk
+                " of " n
+                "10" num
+                " " n
+                "int" pt
+                "s; the second is an " n
+                "array" qk
+                " of " n
+                "20" num
+                " " n
+                "const" q
+                " " n
+                "int" pt
+                "s" n
+            ],
+        );
+        run(
+            "int *p; int *q",
+            hltext![
+                "both are a " n
+                "pointer" qk
+                " to an " n
+                "int" pt
+                ", but the first is named " n
+                "p" i
+                " and the second is named " n
+                "q" i
+            ],
+        );
+        run("int x; int x", hltext!["the declarations are identical" n]);
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Loading of [phrasebooks][Phrasebook] from [Fluent](https://projectfluent.org/) resources
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use fluent_bundle::{FluentBundle, FluentResource};
+
+use super::phrasebook::{Language, PHRASE_IDS, Phrasebook};
+
+/// Error loading a [`Phrasebook`] from a Fluent resource
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PhrasebookError {
+    #[error("invalid Fluent syntax: {0}")]
+    Syntax(String),
+    #[error("cannot format message '{id}': {message}")]
+    Format { id: String, message: String },
+    #[error("message 'adjectives-after-nouns' must be 'true' or 'false', but found '{0}'")]
+    InvalidBool(String),
+}
+
+/// Formats the value of the given message, or of the given attribute of it. Returns `None` if the
+/// resource doesn't define it.
+fn format(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    attribute: Option<&str>,
+) -> Result<Option<String>, PhrasebookError> {
+    let Some(message) = bundle.get_message(id) else {
+        return Ok(None);
+    };
+    let pattern = match attribute {
+        None => message.value(),
+        Some(attribute) => message
+            .get_attribute(attribute)
+            .map(|attribute| attribute.value()),
+    };
+    let Some(pattern) = pattern else {
+        return Ok(None);
+    };
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, None, &mut errors);
+    if let Some(error) = errors.first() {
+        return Err(PhrasebookError::Format {
+            id: id.to_string(),
+            message: error.to_string(),
+        });
+    }
+    Ok(Some(text.into_owned()))
+}
+
+impl Phrasebook {
+    /// Loads a phrasebook from a Fluent resource, such as the ones in the `locales` directory of
+    /// this crate. Messages are identified by the [phrase IDs][PHRASE_IDS], where the part after
+    /// a dot is the name of an attribute, e.g. the `plural` attribute of the `pointer` message.
+    ///
+    /// Messages which the resource doesn't define are taken from the `base` language, as are the
+    /// grammar rules for articles, plurals, numbers, and agreement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resource has a syntax error or a message can't be formatted, e.g.
+    /// because it references an undefined message.
+    pub fn from_fluent(source: &str, base: Language) -> Result<Self, PhrasebookError> {
+        let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| {
+            PhrasebookError::Syntax(errors.first().map(ToString::to_string).unwrap_or_default())
+        })?;
+        let mut bundle = FluentBundle::new(Vec::new());
+        bundle.set_use_isolating(false);
+        // Adding a single resource to an empty bundle can't cause conflicts.
+        bundle.add_resource(resource).unwrap();
+
+        let mut phrasebook = base.phrasebook().clone();
+        if let Some(value) = format(&bundle, "adjectives-after-nouns", None)? {
+            phrasebook.adjectives_after_nouns = match value.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => return Err(PhrasebookError::InvalidBool(value)),
+            };
+        }
+        for id in PHRASE_IDS {
+            let (message, attribute) = match id.split_once('.') {
+                Some((message, attribute)) => (message, Some(attribute)),
+                None => (*id, None),
+            };
+            if let Some(phrase) = format(&bundle, message, attribute)? {
+                // Every ID in `PHRASE_IDS` names a phrase.
+                *phrasebook.phrase_mut(id).unwrap() = phrase.into();
+            }
+        }
+        Ok(phrasebook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        color::HighlightedText,
+        explainer::{Options, explain_declaration_with_options},
+    };
+
+    const SAMPLES: &str = "int *p; static int f(int a, char *b); const char *const names[2]; \
+                           typedef unsigned long size; struct s { int x; } v = {0}";
+
+    /// Explains the sample declarations with the given options.
+    fn explain_samples(options: &Options) -> Vec<HighlightedText> {
+        crate::parser::parser()
+            .parse(SAMPLES)
+            .unwrap()
+            .iter()
+            .map(|decl| explain_declaration_with_options(decl, options).coalesced())
+            .collect()
+    }
+
+    #[test]
+    fn bundled_resources_match_builtin_phrasebooks() {
+        for (source, language) in [
+            (include_str!("../../locales/en.ftl"), Language::English),
+            (include_str!("../../locales/es.ftl"), Language::Spanish),
+        ] {
+            // Load on top of the other language to make sure every phrase is overridden.
+            let base = match language {
+                Language::English => Language::Spanish,
+                Language::Spanish => Language::English,
+            };
+            let mut phrasebook = Phrasebook::from_fluent(source, base).unwrap();
+            let builtin = language.phrasebook();
+            phrasebook.article_for = builtin.article_for;
+            phrasebook.plural_suffix_for = builtin.plural_suffix_for;
+            phrasebook.is_feminine = builtin.is_feminine;
+            phrasebook.storage_class_adjective = builtin.storage_class_adjective;
+            let loaded = Options {
+                phrasebook: Some(Arc::new(phrasebook)),
+                ..Options::default()
+            };
+            let expected = Options {
+                language,
+                ..Options::default()
+            };
+            assert_eq!(explain_samples(&loaded), explain_samples(&expected));
+        }
+    }
+
+    #[test]
+    fn partial_resource() {
+        let phrasebook = Phrasebook::from_fluent(
+            "pointer = ptr\n    .plural = ptrs\nto = {\" \"}->{\" \"}",
+            Language::English,
+        )
+        .unwrap();
+        assert_eq!(phrasebook.pointer.singular, "ptr");
+        assert_eq!(phrasebook.pointer.plural, "ptrs");
+        assert_eq!(phrasebook.to, " -> ");
+        assert_eq!(phrasebook.of, " of ");
+    }
+
+    #[test]
+    fn invalid_resources() {
+        assert!(matches!(
+            Phrasebook::from_fluent("to = {", Language::English),
+            Err(PhrasebookError::Syntax(_))
+        ));
+        assert_eq!(
+            Phrasebook::from_fluent("to = { missing }", Language::English).unwrap_err(),
+            PhrasebookError::Format {
+                id: "to".to_string(),
+                message: "Resolver error: Unknown message: missing".to_string(),
+            }
+        );
+        assert_eq!(
+            Phrasebook::from_fluent("adjectives-after-nouns = maybe", Language::English)
+                .unwrap_err(),
+            PhrasebookError::InvalidBool("maybe".to_string())
+        );
+    }
+}
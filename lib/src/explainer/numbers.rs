@@ -0,0 +1,270 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Spelling out of numbers as words, e.g. "one hundred twenty-three" for 123
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+const ENGLISH_ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const ENGLISH_TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Names of the powers of 1000, starting with 1000 itself
+const ENGLISH_SCALES: [&str; 6] = [
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+];
+
+/// Spells out a number below 1000 in English.
+fn english_below_thousand(n: usize) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let rest = match rest {
+        0 => String::new(),
+        1..20 => ENGLISH_ONES[rest].to_string(),
+        _ if rest % 10 == 0 => ENGLISH_TENS[rest / 10].to_string(),
+        _ => format!("{}-{}", ENGLISH_TENS[rest / 10], ENGLISH_ONES[rest % 10]),
+    };
+    match (hundreds, rest.is_empty()) {
+        (0, _) => rest,
+        (_, true) => format!("{} hundred", ENGLISH_ONES[hundreds]),
+        (_, false) => format!("{} hundred {rest}", ENGLISH_ONES[hundreds]),
+    }
+}
+
+/// Spells out a number in English, e.g. "one hundred twenty-three" for 123.
+pub(super) fn english(n: usize) -> String {
+    if n == 0 {
+        return ENGLISH_ONES[0].to_string();
+    }
+    let mut groups = Vec::new();
+    let mut rest = n;
+    let mut scale = None;
+    while rest > 0 {
+        let group = rest % 1000;
+        if group > 0 {
+            let words = english_below_thousand(group);
+            groups.push(match scale {
+                None => words,
+                Some(scale) => format!("{words} {}", ENGLISH_SCALES[scale]),
+            });
+        }
+        rest /= 1000;
+        scale = Some(scale.map_or(0, |scale| scale + 1));
+    }
+    groups.reverse();
+    groups.join(" ")
+}
+
+const SPANISH_ONES: [&str; 30] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+    "veintiuno",
+    "veintidós",
+    "veintitrés",
+    "veinticuatro",
+    "veinticinco",
+    "veintiséis",
+    "veintisiete",
+    "veintiocho",
+    "veintinueve",
+];
+
+const SPANISH_TENS: [&str; 10] = [
+    "",
+    "",
+    "",
+    "treinta",
+    "cuarenta",
+    "cincuenta",
+    "sesenta",
+    "setenta",
+    "ochenta",
+    "noventa",
+];
+
+const SPANISH_HUNDREDS: [&str; 10] = [
+    "",
+    "ciento",
+    "doscientos",
+    "trescientos",
+    "cuatrocientos",
+    "quinientos",
+    "seiscientos",
+    "setecientos",
+    "ochocientos",
+    "novecientos",
+];
+
+/// Spells out a number below 1000 in Spanish. If `apocopated` is true, the number precedes a
+/// noun, so a final "uno" is shortened to "un", e.g. "veintiún millones".
+fn spanish_below_thousand(n: usize, apocopated: bool) -> String {
+    let (hundreds, rest) = (n / 100, n % 100);
+    let mut rest = match rest {
+        0 => String::new(),
+        1..30 => SPANISH_ONES[rest].to_string(),
+        _ if rest % 10 == 0 => SPANISH_TENS[rest / 10].to_string(),
+        _ => format!("{} y {}", SPANISH_TENS[rest / 10], SPANISH_ONES[rest % 10]),
+    };
+    if apocopated && rest.ends_with("uno") {
+        rest.truncate(rest.len() - "o".len());
+        if rest == "veintiun" {
+            rest = "veintiún".to_string();
+        }
+    }
+    match (hundreds, rest.is_empty()) {
+        (0, _) => rest,
+        (1, true) => "cien".to_string(),
+        (_, true) => SPANISH_HUNDREDS[hundreds].to_string(),
+        (_, false) => format!("{} {rest}", SPANISH_HUNDREDS[hundreds]),
+    }
+}
+
+/// Spells out a number in Spanish, e.g. "ciento veintitrés" for 123. Numbers use the long scale,
+/// so "mil millones" is 10⁹ and "un billón" is 10¹².
+pub(super) fn spanish(n: usize) -> String {
+    if n == 0 {
+        return SPANISH_ONES[0].to_string();
+    }
+    // Groups of six digits, each of which is below a million, starting with the lowest.
+    let mut groups = Vec::new();
+    let mut rest = n;
+    let mut scale = 0;
+    while rest > 0 {
+        let group = rest % 1_000_000;
+        if group > 0 {
+            let (thousands, units) = (group / 1000, group % 1000);
+            let mut words = Vec::new();
+            match thousands {
+                0 => (),
+                1 => words.push("mil".to_string()),
+                _ => words.push(format!("{} mil", spanish_below_thousand(thousands, true))),
+            }
+            if units > 0 {
+                words.push(spanish_below_thousand(units, scale > 0));
+            }
+            let words = words.join(" ");
+            groups.push(match (scale, group) {
+                (0, _) => words,
+                (1, 1) => "un millón".to_string(),
+                (1, _) => format!("{words} millones"),
+                (2, 1) => "un billón".to_string(),
+                (2, _) => format!("{words} billones"),
+                (_, 1) => "un trillón".to_string(),
+                (_, _) => format!("{words} trillones"),
+            });
+        }
+        rest /= 1_000_000;
+        scale += 1;
+    }
+    groups.reverse();
+    groups.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn english_numbers() {
+        for (n, words) in [
+            (0, "zero"),
+            (7, "seven"),
+            (13, "thirteen"),
+            (40, "forty"),
+            (42, "forty-two"),
+            (100, "one hundred"),
+            (123, "one hundred twenty-three"),
+            (1000, "one thousand"),
+            (1024, "one thousand twenty-four"),
+            (65_536, "sixty-five thousand five hundred thirty-six"),
+            (2_000_001, "two million one"),
+        ] {
+            assert_eq!(english(n), words, "wrong words for {n}");
+        }
+    }
+
+    #[test]
+    fn spanish_numbers() {
+        for (n, words) in [
+            (0, "cero"),
+            (1, "uno"),
+            (16, "dieciséis"),
+            (21, "veintiuno"),
+            (42, "cuarenta y dos"),
+            (100, "cien"),
+            (101, "ciento uno"),
+            (500, "quinientos"),
+            (1000, "mil"),
+            (1024, "mil veinticuatro"),
+            (21_000, "veintiún mil"),
+            (65_536, "sesenta y cinco mil quinientos treinta y seis"),
+            (1_000_000, "un millón"),
+            (21_000_000, "veintiún millones"),
+            (1_000_000_000, "mil millones"),
+        ] {
+            assert_eq!(spanish(n), words, "wrong words for {n}");
+        }
+    }
+}
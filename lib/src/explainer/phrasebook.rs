@@ -0,0 +1,1027 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Words and grammar of the languages in which declarations can be explained
+
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{ast::StorageClass, color::HighlightedTextSegment};
+
+use super::{Plurality, numbers, plural_suffix_for, pronunciation::article_for};
+
+/// Language in which declarations are explained, given by its ISO 639-1 code, e.g. `es`
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+pub enum Language {
+    #[default]
+    #[display("en")]
+    English,
+    #[display("es")]
+    Spanish,
+}
+
+impl Language {
+    /// Returns the phrasebook for the language.
+    #[must_use]
+    pub fn phrasebook(self) -> &'static Phrasebook {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Spanish => &SPANISH,
+        }
+    }
+}
+
+/// Singular and plural forms of a word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub singular: Cow<'static, str>,
+    pub plural: Cow<'static, str>,
+}
+
+impl Word {
+    /// Returns the form of the word for the given plurality.
+    #[must_use]
+    pub fn get(&self, plurality: Plurality) -> &str {
+        match plurality {
+            Plurality::Singular => &self.singular,
+            Plurality::Plural => &self.plural,
+        }
+    }
+}
+
+/// Masculine and feminine forms of a word, for languages with grammatical gender
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gendered {
+    pub masculine: Cow<'static, str>,
+    pub feminine: Cow<'static, str>,
+}
+
+/// Text placed before and after a number, e.g. "-bit" after the width in "24-bit"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Affixes {
+    pub before: Cow<'static, str>,
+    pub after: Cow<'static, str>,
+}
+
+/// Phrase built around a highlighted keyword, e.g. "possibly " followed by "unused"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordPhrase {
+    pub before: Cow<'static, str>,
+    pub keyword: Cow<'static, str>,
+    pub after: Cow<'static, str>,
+}
+
+/// IDs of the phrases in a [`Phrasebook`], which can be [overridden][Phrasebook::with_overrides].
+/// Each ID is the name of a field in kebab-case, followed by `.plural` or `.feminine` for the
+/// other forms of [words][Word] and [gendered phrases][Gendered], or by `.before` or `.after`
+/// for the text around [numbers][Affixes] and [keywords][KeywordPhrase].
+pub const PHRASE_IDS: &[&str] = &[
+    "named",
+    "named.feminine",
+    "and",
+    "final-and",
+    "pointer",
+    "pointer.plural",
+    "reference",
+    "reference.plural",
+    "block",
+    "block.plural",
+    "array",
+    "array.plural",
+    "function",
+    "function.plural",
+    "value",
+    "value.plural",
+    "type",
+    "unnamed",
+    "thread-local",
+    "forward-declaration-of",
+    "to",
+    "to-member-of",
+    "to-member-of.plural",
+    "that-is",
+    "that-is.plural",
+    "of",
+    "at-least",
+    "that-takes",
+    "that-takes.plural",
+    "and-returns",
+    "and-returns.plural",
+    "taking",
+    "and-returning",
+    "that-takes-no-parameters",
+    "that-takes-no-parameters.plural",
+    "taking-no-parameters",
+    "variable-arguments",
+    "further-arguments",
+    "parameters-start",
+    "parameters-end",
+    "defined-as",
+    "variable-length",
+    "variable",
+    "signed-integer",
+    "unsigned-integer",
+    "bits.before",
+    "bits.after",
+    "bit-field",
+    "in",
+    "that-can-hold-values",
+    "with-the-same-type-as",
+    "initialized-to",
+    "initialized-with-an-empty-list",
+    "initialized-with-a-list-of",
+    "with-designators-for",
+    "implicitly",
+    "with-internal-linkage",
+    "defined-elsewhere",
+    "stored-in-register",
+    "with-assembler-name",
+    "commonly-a-string",
+    "commonly-an-array-of-strings",
+    "commonly-a-callback",
+    "call-it-as",
+    "or-simply",
+    "and-assign-it-with",
+    "may-be-modified",
+    "may-be-modified.plural",
+    "but",
+    "may-not",
+    "may-not.plural",
+    "may-not-be-modified",
+    "may-not-be-modified.plural",
+    "containing",
+    "containing-no-members",
+    "with-values",
+    "packed",
+    "packed.before",
+    "packed.after",
+    "aligned-to",
+    "aligned-to.before",
+    "aligned-to.after",
+    "bytes",
+    "maximally-aligned",
+    "maximally-aligned.before",
+    "maximally-aligned.after",
+    "deprecated",
+    "deprecated.before",
+    "deprecated.after",
+    "possibly-unused",
+    "possibly-unused.before",
+    "possibly-unused.after",
+    "non-returning",
+    "non-returning.before",
+    "non-returning.after",
+    "result-must-be-used",
+    "result-must-be-used.before",
+    "result-must-be-used.after",
+    "weakly-linked",
+    "weakly-linked.before",
+    "weakly-linked.after",
+    "imported-from-a-dll",
+    "imported-from-a-dll.before",
+    "imported-from-a-dll.after",
+    "exported-from-a-dll",
+    "exported-from-a-dll.before",
+    "exported-from-a-dll.after",
+    "target-cannot-be-modified",
+    "pointer-cannot-be-changed",
+    "value-cannot-be-modified",
+    "no-other-pointer-accesses-target",
+    "accesses-are-never-optimized-away",
+    "address-cannot-be-taken",
+];
+
+/// Error returned when overriding a phrase which doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unknown phrase '{0}'")]
+pub struct UnknownPhrase(pub String);
+
+/// Words, connecting phrases, and grammar rules used to explain declarations in a [`Language`]
+///
+/// Phrases which begin or end with spaces are inserted between other parts of an explanation.
+/// Keywords and the names of types, such as `const` and `unsigned int`, are left as they are in C.
+#[derive(Debug, Clone)]
+pub struct Phrasebook {
+    /// Returns the indefinite article, followed by a space, for the word which follows it.
+    pub article_for: fn(&HighlightedTextSegment) -> &'static str,
+    /// Words whose articles override the ones returned by [`article_for`][Self::article_for],
+    /// paired with their articles followed by a space, e.g. `("herb", "an ")`. Words are matched
+    /// case-insensitively against the first word of a noun.
+    pub article_exceptions: Vec<(&'static str, &'static str)>,
+    /// Clarifications of terms for [beginners][super::Audience::Beginner], e.g. "a variable that
+    /// stores a memory address" for "pointer". Each clarification follows the first use of its
+    /// term in an explanation. Add entries with `glossary.to_mut().push(...)`.
+    pub glossary: Cow<'static, [(Word, &'static str)]>,
+    /// Returns the suffix which makes the name of a type plural.
+    pub plural_suffix_for: fn(&HighlightedTextSegment) -> &'static str,
+    /// Returns whether the given noun is grammatically feminine.
+    pub is_feminine: fn(&HighlightedTextSegment) -> bool,
+    /// Returns the adjective describing a storage class, agreeing with the given noun.
+    pub storage_class_adjective: fn(StorageClass, &HighlightedTextSegment) -> &'static str,
+    /// Returns a number spelled out as words, e.g. "ten", for
+    /// [spelling out numbers][super::Options::spell_out_numbers].
+    pub number_in_words: fn(usize) -> String,
+    /// Whether adjectives and qualifiers follow the noun they describe, e.g. "un puntero const"
+    /// instead of "a const pointer".
+    pub adjectives_after_nouns: bool,
+    /// Phrase which introduces the name of a noun, e.g. " named "
+    pub named: Gendered,
+    /// Separator between the two items of a list
+    pub and: Cow<'static, str>,
+    /// Separator before the last of three or more items of a list, e.g. ", and "
+    pub final_and: Cow<'static, str>,
+    pub pointer: Word,
+    pub reference: Word,
+    pub block: Word,
+    pub array: Word,
+    pub function: Word,
+    pub value: Word,
+    pub type_: Cow<'static, str>,
+    pub unnamed: Cow<'static, str>,
+    pub thread_local: Cow<'static, str>,
+    pub forward_declaration_of: Cow<'static, str>,
+    pub to: Cow<'static, str>,
+    /// Phrase which introduces the class of a C++ member pointer, e.g. " to a member of "
+    pub to_member_of: Word,
+    /// Phrase which introduces the type of a member, e.g. " that is "
+    pub that_is: Word,
+    pub of: Cow<'static, str>,
+    pub at_least: Cow<'static, str>,
+    pub that_takes: Word,
+    pub and_returns: Word,
+    pub taking: Cow<'static, str>,
+    pub and_returning: Cow<'static, str>,
+    /// Phrase following a function which takes no parameters, e.g. " that takes no parameters"
+    pub that_takes_no_parameters: Word,
+    /// Phrase following a block which takes no parameters, e.g. " taking no parameters"
+    pub taking_no_parameters: Cow<'static, str>,
+    /// Parameters of a variadic function with no named parameters, e.g. "a variable number of
+    /// arguments"
+    pub variable_arguments: Cow<'static, str>,
+    /// Last item of the parameters of a variadic function, e.g. "a variable number of further
+    /// arguments"
+    pub further_arguments: Cow<'static, str>,
+    /// Phrase which begins a list of parameters, e.g. "("
+    pub parameters_start: Cow<'static, str>,
+    /// Phrase which ends a list of parameters, e.g. ")"
+    pub parameters_end: Cow<'static, str>,
+    pub defined_as: Cow<'static, str>,
+    pub variable_length: Cow<'static, str>,
+    /// Noun for a declaration whose type is given by `typeof`, e.g. "variable"
+    pub variable: Cow<'static, str>,
+    /// Noun for a signed bit-precise integer, e.g. "signed integer"
+    pub signed_integer: Cow<'static, str>,
+    /// Noun for an unsigned bit-precise integer, e.g. "unsigned integer"
+    pub unsigned_integer: Cow<'static, str>,
+    /// Text around the width of a bit-precise integer or bit-field, e.g. "-bit" after it
+    pub bits: Affixes,
+    pub bit_field: Cow<'static, str>,
+    /// Phrase which introduces the bit-field holding a member which isn't an integer, e.g. " in "
+    pub in_: Cow<'static, str>,
+    /// Phrase which introduces the range of values of an unsigned bit-field, e.g. " that can hold
+    /// values "
+    pub that_can_hold_values: Cow<'static, str>,
+    pub with_the_same_type_as: Cow<'static, str>,
+    pub initialized_to: Cow<'static, str>,
+    pub initialized_with_an_empty_list: Cow<'static, str>,
+    pub initialized_with_a_list_of: Cow<'static, str>,
+    pub with_designators_for: Cow<'static, str>,
+    pub implicitly: Cow<'static, str>,
+    pub with_internal_linkage: Cow<'static, str>,
+    pub defined_elsewhere: Cow<'static, str>,
+    pub stored_in_register: Cow<'static, str>,
+    pub with_assembler_name: Cow<'static, str>,
+    /// Hint for a pointer to `char`, e.g. "commonly a string"
+    pub commonly_a_string: Cow<'static, str>,
+    /// Hint for a pointer to a pointer to `char`, e.g. "commonly an array of strings"
+    pub commonly_an_array_of_strings: Cow<'static, str>,
+    /// Hint for a pointer to a function taking a `void *`, e.g. "commonly a callback with a
+    /// context pointer"
+    pub commonly_a_callback: Cow<'static, str>,
+    /// Phrase which introduces how to call a function through a pointer, e.g. "; call it as "
+    pub call_it_as: Cow<'static, str>,
+    /// Phrase which introduces a call without dereferencing the pointer, e.g. " or simply "
+    pub or_simply: Cow<'static, str>,
+    /// Phrase which introduces how to assign to a pointer, e.g. ", and assign it with "
+    pub and_assign_it_with: Cow<'static, str>,
+    /// Phrase following what may be modified through a name, e.g. " may be modified"
+    pub may_be_modified: Word,
+    /// Separator between what may and may not be modified, e.g. ", but "
+    pub but: Cow<'static, str>,
+    /// Phrase following what may not be modified, after what may, e.g. " may not"
+    pub may_not: Word,
+    /// Phrase following what may not be modified when nothing may, e.g. " may not be modified"
+    pub may_not_be_modified: Word,
+    pub containing: Cow<'static, str>,
+    pub containing_no_members: Cow<'static, str>,
+    pub with_values: Cow<'static, str>,
+    /// Description of the `packed` attribute
+    pub packed: KeywordPhrase,
+    /// Description of the `aligned` attribute, preceding the alignment, e.g. "aligned to "
+    pub aligned_to: KeywordPhrase,
+    /// Unit following an alignment, e.g. " bytes"
+    pub bytes: Cow<'static, str>,
+    /// Description of the `aligned` attribute without an alignment, e.g. "maximally aligned"
+    pub maximally_aligned: KeywordPhrase,
+    /// Description of the `deprecated` attribute, which may be followed by its message
+    pub deprecated: KeywordPhrase,
+    /// Description of the `unused` and `maybe_unused` attributes
+    pub possibly_unused: KeywordPhrase,
+    /// Description of the `noreturn` attribute
+    pub non_returning: KeywordPhrase,
+    /// Description of the `warn_unused_result` and `nodiscard` attributes
+    pub result_must_be_used: KeywordPhrase,
+    /// Description of the `weak` attribute
+    pub weakly_linked: KeywordPhrase,
+    /// Description of the `dllimport` attribute
+    pub imported_from_a_dll: KeywordPhrase,
+    /// Description of the `dllexport` attribute
+    pub exported_from_a_dll: KeywordPhrase,
+    /// Verbose aside for a pointer to a const object
+    pub target_cannot_be_modified: Cow<'static, str>,
+    /// Verbose aside for a const pointer
+    pub pointer_cannot_be_changed: Cow<'static, str>,
+    /// Verbose aside for a const object
+    pub value_cannot_be_modified: Cow<'static, str>,
+    /// Verbose aside for a restrict pointer
+    pub no_other_pointer_accesses_target: Cow<'static, str>,
+    /// Verbose aside for a volatile object or pointer
+    pub accesses_are_never_optimized_away: Cow<'static, str>,
+    /// Verbose aside for a register variable
+    pub address_cannot_be_taken: Cow<'static, str>,
+}
+
+impl Phrasebook {
+    /// Returns the indefinite article, followed by a space, for the given noun, taking the
+    /// [exceptions][Self::article_exceptions] into account.
+    #[must_use]
+    pub fn article(&self, noun: &HighlightedTextSegment) -> &'static str {
+        let word = noun.text.split_whitespace().next().unwrap_or_default();
+        self.article_exceptions
+            .iter()
+            .find(|(exception, _)| exception.eq_ignore_ascii_case(word))
+            .map_or_else(|| (self.article_for)(noun), |(_, article)| article)
+    }
+
+    /// Returns the phrase which introduces the name of the given noun, e.g. " named ".
+    #[must_use]
+    pub fn named_for(&self, noun: &HighlightedTextSegment) -> &str {
+        if (self.is_feminine)(noun) {
+            &self.named.feminine
+        } else {
+            &self.named.masculine
+        }
+    }
+
+    /// Returns a mutable reference to the phrase with the given [ID][PHRASE_IDS].
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn phrase_mut(&mut self, id: &str) -> Option<&mut Cow<'static, str>> {
+        Some(match id {
+            "named" => &mut self.named.masculine,
+            "named.feminine" => &mut self.named.feminine,
+            "and" => &mut self.and,
+            "final-and" => &mut self.final_and,
+            "pointer" => &mut self.pointer.singular,
+            "pointer.plural" => &mut self.pointer.plural,
+            "reference" => &mut self.reference.singular,
+            "reference.plural" => &mut self.reference.plural,
+            "block" => &mut self.block.singular,
+            "block.plural" => &mut self.block.plural,
+            "array" => &mut self.array.singular,
+            "array.plural" => &mut self.array.plural,
+            "function" => &mut self.function.singular,
+            "function.plural" => &mut self.function.plural,
+            "value" => &mut self.value.singular,
+            "value.plural" => &mut self.value.plural,
+            "type" => &mut self.type_,
+            "unnamed" => &mut self.unnamed,
+            "thread-local" => &mut self.thread_local,
+            "forward-declaration-of" => &mut self.forward_declaration_of,
+            "to" => &mut self.to,
+            "to-member-of" => &mut self.to_member_of.singular,
+            "to-member-of.plural" => &mut self.to_member_of.plural,
+            "that-is" => &mut self.that_is.singular,
+            "that-is.plural" => &mut self.that_is.plural,
+            "of" => &mut self.of,
+            "at-least" => &mut self.at_least,
+            "that-takes" => &mut self.that_takes.singular,
+            "that-takes.plural" => &mut self.that_takes.plural,
+            "and-returns" => &mut self.and_returns.singular,
+            "and-returns.plural" => &mut self.and_returns.plural,
+            "taking" => &mut self.taking,
+            "and-returning" => &mut self.and_returning,
+            "that-takes-no-parameters" => &mut self.that_takes_no_parameters.singular,
+            "that-takes-no-parameters.plural" => &mut self.that_takes_no_parameters.plural,
+            "taking-no-parameters" => &mut self.taking_no_parameters,
+            "variable-arguments" => &mut self.variable_arguments,
+            "further-arguments" => &mut self.further_arguments,
+            "parameters-start" => &mut self.parameters_start,
+            "parameters-end" => &mut self.parameters_end,
+            "defined-as" => &mut self.defined_as,
+            "variable-length" => &mut self.variable_length,
+            "variable" => &mut self.variable,
+            "signed-integer" => &mut self.signed_integer,
+            "unsigned-integer" => &mut self.unsigned_integer,
+            "bits.before" => &mut self.bits.before,
+            "bits.after" => &mut self.bits.after,
+            "bit-field" => &mut self.bit_field,
+            "in" => &mut self.in_,
+            "that-can-hold-values" => &mut self.that_can_hold_values,
+            "with-the-same-type-as" => &mut self.with_the_same_type_as,
+            "initialized-to" => &mut self.initialized_to,
+            "initialized-with-an-empty-list" => &mut self.initialized_with_an_empty_list,
+            "initialized-with-a-list-of" => &mut self.initialized_with_a_list_of,
+            "with-designators-for" => &mut self.with_designators_for,
+            "implicitly" => &mut self.implicitly,
+            "with-internal-linkage" => &mut self.with_internal_linkage,
+            "defined-elsewhere" => &mut self.defined_elsewhere,
+            "stored-in-register" => &mut self.stored_in_register,
+            "with-assembler-name" => &mut self.with_assembler_name,
+            "commonly-a-string" => &mut self.commonly_a_string,
+            "commonly-an-array-of-strings" => &mut self.commonly_an_array_of_strings,
+            "commonly-a-callback" => &mut self.commonly_a_callback,
+            "call-it-as" => &mut self.call_it_as,
+            "or-simply" => &mut self.or_simply,
+            "and-assign-it-with" => &mut self.and_assign_it_with,
+            "may-be-modified" => &mut self.may_be_modified.singular,
+            "may-be-modified.plural" => &mut self.may_be_modified.plural,
+            "but" => &mut self.but,
+            "may-not" => &mut self.may_not.singular,
+            "may-not.plural" => &mut self.may_not.plural,
+            "may-not-be-modified" => &mut self.may_not_be_modified.singular,
+            "may-not-be-modified.plural" => &mut self.may_not_be_modified.plural,
+            "containing" => &mut self.containing,
+            "containing-no-members" => &mut self.containing_no_members,
+            "with-values" => &mut self.with_values,
+            "packed" => &mut self.packed.keyword,
+            "packed.before" => &mut self.packed.before,
+            "packed.after" => &mut self.packed.after,
+            "aligned-to" => &mut self.aligned_to.keyword,
+            "aligned-to.before" => &mut self.aligned_to.before,
+            "aligned-to.after" => &mut self.aligned_to.after,
+            "bytes" => &mut self.bytes,
+            "maximally-aligned" => &mut self.maximally_aligned.keyword,
+            "maximally-aligned.before" => &mut self.maximally_aligned.before,
+            "maximally-aligned.after" => &mut self.maximally_aligned.after,
+            "deprecated" => &mut self.deprecated.keyword,
+            "deprecated.before" => &mut self.deprecated.before,
+            "deprecated.after" => &mut self.deprecated.after,
+            "possibly-unused" => &mut self.possibly_unused.keyword,
+            "possibly-unused.before" => &mut self.possibly_unused.before,
+            "possibly-unused.after" => &mut self.possibly_unused.after,
+            "non-returning" => &mut self.non_returning.keyword,
+            "non-returning.before" => &mut self.non_returning.before,
+            "non-returning.after" => &mut self.non_returning.after,
+            "result-must-be-used" => &mut self.result_must_be_used.keyword,
+            "result-must-be-used.before" => &mut self.result_must_be_used.before,
+            "result-must-be-used.after" => &mut self.result_must_be_used.after,
+            "weakly-linked" => &mut self.weakly_linked.keyword,
+            "weakly-linked.before" => &mut self.weakly_linked.before,
+            "weakly-linked.after" => &mut self.weakly_linked.after,
+            "imported-from-a-dll" => &mut self.imported_from_a_dll.keyword,
+            "imported-from-a-dll.before" => &mut self.imported_from_a_dll.before,
+            "imported-from-a-dll.after" => &mut self.imported_from_a_dll.after,
+            "exported-from-a-dll" => &mut self.exported_from_a_dll.keyword,
+            "exported-from-a-dll.before" => &mut self.exported_from_a_dll.before,
+            "exported-from-a-dll.after" => &mut self.exported_from_a_dll.after,
+            "target-cannot-be-modified" => &mut self.target_cannot_be_modified,
+            "pointer-cannot-be-changed" => &mut self.pointer_cannot_be_changed,
+            "value-cannot-be-modified" => &mut self.value_cannot_be_modified,
+            "no-other-pointer-accesses-target" => &mut self.no_other_pointer_accesses_target,
+            "accesses-are-never-optimized-away" => &mut self.accesses_are_never_optimized_away,
+            "address-cannot-be-taken" => &mut self.address_cannot_be_taken,
+            _ => return None,
+        })
+    }
+
+    /// Returns a copy of the phrasebook with the phrases with the given [IDs][PHRASE_IDS]
+    /// replaced, e.g. `[("pointer", "address"), ("pointer.plural", "addresses")]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ID isn't the ID of a phrase.
+    pub fn with_overrides<'a, P: Into<Cow<'static, str>>>(
+        &self,
+        overrides: impl IntoIterator<Item = (&'a str, P)>,
+    ) -> Result<Self, UnknownPhrase> {
+        let mut phrasebook = self.clone();
+        for (id, phrase) in overrides {
+            *phrasebook
+                .phrase_mut(id)
+                .ok_or_else(|| UnknownPhrase(id.to_string()))? = phrase.into();
+        }
+        Ok(phrasebook)
+    }
+}
+
+/// Phrasebooks are compared by identity, since their grammar rules are functions.
+impl PartialEq for Phrasebook {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl Eq for Phrasebook {}
+
+pub static ENGLISH: Phrasebook = Phrasebook {
+    article_for,
+    article_exceptions: Vec::new(),
+    glossary: Cow::Borrowed(ENGLISH_GLOSSARY),
+    plural_suffix_for,
+    is_feminine: |_| false,
+    storage_class_adjective: |class, _| super::storage_class_adjective(class),
+    number_in_words: numbers::english,
+    adjectives_after_nouns: false,
+    named: Gendered {
+        masculine: Cow::Borrowed(" named "),
+        feminine: Cow::Borrowed(" named "),
+    },
+    and: Cow::Borrowed(" and "),
+    final_and: Cow::Borrowed(", and "),
+    pointer: Word {
+        singular: Cow::Borrowed("pointer"),
+        plural: Cow::Borrowed("pointers"),
+    },
+    reference: Word {
+        singular: Cow::Borrowed("reference"),
+        plural: Cow::Borrowed("references"),
+    },
+    block: Word {
+        singular: Cow::Borrowed("block"),
+        plural: Cow::Borrowed("blocks"),
+    },
+    array: Word {
+        singular: Cow::Borrowed("array"),
+        plural: Cow::Borrowed("arrays"),
+    },
+    function: Word {
+        singular: Cow::Borrowed("function"),
+        plural: Cow::Borrowed("functions"),
+    },
+    value: Word {
+        singular: Cow::Borrowed(" value"),
+        plural: Cow::Borrowed(" values"),
+    },
+    type_: Cow::Borrowed("type"),
+    unnamed: Cow::Borrowed("unnamed"),
+    thread_local: Cow::Borrowed("thread-local"),
+    forward_declaration_of: Cow::Borrowed("forward declaration of "),
+    to: Cow::Borrowed(" to "),
+    to_member_of: Word {
+        singular: Cow::Borrowed(" to a member of "),
+        plural: Cow::Borrowed(" to members of "),
+    },
+    that_is: Word {
+        singular: Cow::Borrowed(" that is "),
+        plural: Cow::Borrowed(" that are "),
+    },
+    of: Cow::Borrowed(" of "),
+    at_least: Cow::Borrowed("at least "),
+    that_takes: Word {
+        singular: Cow::Borrowed(" that takes "),
+        plural: Cow::Borrowed(" that take "),
+    },
+    and_returns: Word {
+        singular: Cow::Borrowed(" and returns "),
+        plural: Cow::Borrowed(" and return "),
+    },
+    taking: Cow::Borrowed(" taking "),
+    and_returning: Cow::Borrowed(" and returning "),
+    that_takes_no_parameters: Word {
+        singular: Cow::Borrowed(" that takes no parameters"),
+        plural: Cow::Borrowed(" that take no parameters"),
+    },
+    taking_no_parameters: Cow::Borrowed(" taking no parameters"),
+    variable_arguments: Cow::Borrowed("a variable number of arguments"),
+    further_arguments: Cow::Borrowed("a variable number of further arguments"),
+    parameters_start: Cow::Borrowed("("),
+    parameters_end: Cow::Borrowed(")"),
+    defined_as: Cow::Borrowed(" defined as "),
+    variable_length: Cow::Borrowed(" (variable length)"),
+    variable: Cow::Borrowed("variable"),
+    signed_integer: Cow::Borrowed("signed integer"),
+    unsigned_integer: Cow::Borrowed("unsigned integer"),
+    bits: Affixes {
+        before: Cow::Borrowed(""),
+        after: Cow::Borrowed("-bit"),
+    },
+    bit_field: Cow::Borrowed("bit-field"),
+    in_: Cow::Borrowed(" in "),
+    that_can_hold_values: Cow::Borrowed(" that can hold values "),
+    with_the_same_type_as: Cow::Borrowed(" with the same type as "),
+    initialized_to: Cow::Borrowed(" initialized to "),
+    initialized_with_an_empty_list: Cow::Borrowed(" initialized with an empty list"),
+    initialized_with_a_list_of: Cow::Borrowed(" initialized with a list of "),
+    with_designators_for: Cow::Borrowed(", with designators for "),
+    implicitly: Cow::Borrowed("implicitly "),
+    with_internal_linkage: Cow::Borrowed("with internal linkage"),
+    defined_elsewhere: Cow::Borrowed("defined elsewhere"),
+    stored_in_register: Cow::Borrowed("stored in register "),
+    with_assembler_name: Cow::Borrowed("with assembler name "),
+    commonly_a_string: Cow::Borrowed("commonly a string"),
+    commonly_an_array_of_strings: Cow::Borrowed("commonly an array of strings"),
+    commonly_a_callback: Cow::Borrowed("commonly a callback with a context pointer"),
+    call_it_as: Cow::Borrowed("; call it as "),
+    or_simply: Cow::Borrowed(" or simply "),
+    and_assign_it_with: Cow::Borrowed(", and assign it with "),
+    may_be_modified: Word {
+        singular: Cow::Borrowed(" may be modified"),
+        plural: Cow::Borrowed(" may be modified"),
+    },
+    but: Cow::Borrowed(", but "),
+    may_not: Word {
+        singular: Cow::Borrowed(" may not"),
+        plural: Cow::Borrowed(" may not"),
+    },
+    may_not_be_modified: Word {
+        singular: Cow::Borrowed(" may not be modified"),
+        plural: Cow::Borrowed(" may not be modified"),
+    },
+    containing: Cow::Borrowed(" containing "),
+    containing_no_members: Cow::Borrowed(" containing no members"),
+    with_values: Cow::Borrowed(" with values "),
+    packed: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("packed"),
+        after: Cow::Borrowed(""),
+    },
+    aligned_to: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("aligned"),
+        after: Cow::Borrowed(" to "),
+    },
+    bytes: Cow::Borrowed(" bytes"),
+    maximally_aligned: KeywordPhrase {
+        before: Cow::Borrowed("maximally "),
+        keyword: Cow::Borrowed("aligned"),
+        after: Cow::Borrowed(""),
+    },
+    deprecated: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("deprecated"),
+        after: Cow::Borrowed(""),
+    },
+    possibly_unused: KeywordPhrase {
+        before: Cow::Borrowed("possibly "),
+        keyword: Cow::Borrowed("unused"),
+        after: Cow::Borrowed(""),
+    },
+    non_returning: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("non-returning"),
+        after: Cow::Borrowed(""),
+    },
+    result_must_be_used: KeywordPhrase {
+        before: Cow::Borrowed("result must be "),
+        keyword: Cow::Borrowed("used"),
+        after: Cow::Borrowed(""),
+    },
+    weakly_linked: KeywordPhrase {
+        before: Cow::Borrowed("weakly "),
+        keyword: Cow::Borrowed("linked"),
+        after: Cow::Borrowed(""),
+    },
+    imported_from_a_dll: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("imported"),
+        after: Cow::Borrowed(" from a DLL"),
+    },
+    exported_from_a_dll: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("exported"),
+        after: Cow::Borrowed(" from a DLL"),
+    },
+    target_cannot_be_modified: Cow::Borrowed(
+        "the value it points to cannot be modified through it",
+    ),
+    pointer_cannot_be_changed: Cow::Borrowed(
+        "the pointer itself cannot be changed to point elsewhere",
+    ),
+    value_cannot_be_modified: Cow::Borrowed("its value cannot be modified after initialization"),
+    no_other_pointer_accesses_target: Cow::Borrowed(
+        "no other pointer is used to access the object it points to",
+    ),
+    accesses_are_never_optimized_away: Cow::Borrowed(
+        "accesses are never optimized away, since the value may change unexpectedly",
+    ),
+    address_cannot_be_taken: Cow::Borrowed("its address cannot be taken"),
+};
+
+/// Creates a glossary entry for a word with the given singular and plural forms.
+const fn term(
+    singular: &'static str,
+    plural: &'static str,
+    clarification: &'static str,
+) -> (Word, &'static str) {
+    (
+        Word {
+            singular: Cow::Borrowed(singular),
+            plural: Cow::Borrowed(plural),
+        },
+        clarification,
+    )
+}
+
+/// Clarifications of English terms for beginners
+const ENGLISH_GLOSSARY: &[(Word, &str)] = &[
+    term(
+        "pointer",
+        "pointers",
+        "a variable that stores a memory address",
+    ),
+    term(
+        "reference",
+        "references",
+        "another name for an existing object",
+    ),
+    term(
+        "block",
+        "blocks",
+        "a function which can capture variables from where it's defined",
+    ),
+    term(
+        "array",
+        "arrays",
+        "a sequence of elements stored next to each other in memory",
+    ),
+    term(
+        "function",
+        "functions",
+        "a piece of code which can be called",
+    ),
+    term("const", "const", "cannot be modified"),
+    term(
+        "volatile",
+        "volatile",
+        "may change at any time, even if the program doesn't change it",
+    ),
+    term(
+        "restrict",
+        "restrict",
+        "the only way to access the object it points to",
+    ),
+];
+
+/// Returns whether a Spanish noun phrase is grammatically feminine, based on its first word.
+fn spanish_is_feminine(noun: &HighlightedTextSegment) -> bool {
+    matches!(
+        noun.text.split(' ').next(),
+        Some("función" | "funciones" | "referencia" | "referencias" | "declaración" | "variable")
+    )
+}
+
+pub static SPANISH: Phrasebook = Phrasebook {
+    article_exceptions: Vec::new(),
+    glossary: Cow::Borrowed(SPANISH_GLOSSARY),
+    article_for: |noun| match noun.text.as_str() {
+        "" => "",
+        _ if spanish_is_feminine(noun) => "una ",
+        _ => "un ",
+    },
+    // Names of types are left as-is, e.g. "un arreglo de 10 int".
+    plural_suffix_for: |_| "",
+    is_feminine: spanish_is_feminine,
+    storage_class_adjective: |class, noun| match (class, spanish_is_feminine(noun)) {
+        (StorageClass::Auto, false) => "automático",
+        (StorageClass::Auto, true) => "automática",
+        (StorageClass::Register, _) => "de registro",
+        (StorageClass::Static, false) => "estático",
+        (StorageClass::Static, true) => "estática",
+        (StorageClass::Extern, false) => "externo",
+        (StorageClass::Extern, true) => "externa",
+    },
+    number_in_words: numbers::spanish,
+    adjectives_after_nouns: true,
+    named: Gendered {
+        masculine: Cow::Borrowed(" llamado "),
+        feminine: Cow::Borrowed(" llamada "),
+    },
+    and: Cow::Borrowed(" y "),
+    final_and: Cow::Borrowed(" y "),
+    pointer: Word {
+        singular: Cow::Borrowed("puntero"),
+        plural: Cow::Borrowed("punteros"),
+    },
+    reference: Word {
+        singular: Cow::Borrowed("referencia"),
+        plural: Cow::Borrowed("referencias"),
+    },
+    block: Word {
+        singular: Cow::Borrowed("bloque"),
+        plural: Cow::Borrowed("bloques"),
+    },
+    array: Word {
+        singular: Cow::Borrowed("arreglo"),
+        plural: Cow::Borrowed("arreglos"),
+    },
+    function: Word {
+        singular: Cow::Borrowed("función"),
+        plural: Cow::Borrowed("funciones"),
+    },
+    value: Word {
+        singular: Cow::Borrowed(" valor"),
+        plural: Cow::Borrowed(" valores"),
+    },
+    type_: Cow::Borrowed("tipo"),
+    unnamed: Cow::Borrowed("anónimo"),
+    thread_local: Cow::Borrowed("local al hilo"),
+    forward_declaration_of: Cow::Borrowed("declaración adelantada de "),
+    to: Cow::Borrowed(" a "),
+    to_member_of: Word {
+        singular: Cow::Borrowed(" a un miembro de "),
+        plural: Cow::Borrowed(" a miembros de "),
+    },
+    that_is: Word {
+        singular: Cow::Borrowed(" que es "),
+        plural: Cow::Borrowed(" que son "),
+    },
+    of: Cow::Borrowed(" de "),
+    at_least: Cow::Borrowed("al menos "),
+    that_takes: Word {
+        singular: Cow::Borrowed(" que recibe "),
+        plural: Cow::Borrowed(" que reciben "),
+    },
+    and_returns: Word {
+        singular: Cow::Borrowed(" y devuelve "),
+        plural: Cow::Borrowed(" y devuelven "),
+    },
+    taking: Cow::Borrowed(" que recibe "),
+    and_returning: Cow::Borrowed(" y devuelve "),
+    that_takes_no_parameters: Word {
+        singular: Cow::Borrowed(" que no recibe parámetros"),
+        plural: Cow::Borrowed(" que no reciben parámetros"),
+    },
+    taking_no_parameters: Cow::Borrowed(" que no recibe parámetros"),
+    variable_arguments: Cow::Borrowed("un número variable de argumentos"),
+    further_arguments: Cow::Borrowed("un número variable de argumentos adicionales"),
+    parameters_start: Cow::Borrowed("("),
+    parameters_end: Cow::Borrowed(")"),
+    defined_as: Cow::Borrowed(" definido como "),
+    variable_length: Cow::Borrowed(" (de longitud variable)"),
+    variable: Cow::Borrowed("variable"),
+    signed_integer: Cow::Borrowed("entero con signo"),
+    unsigned_integer: Cow::Borrowed("entero sin signo"),
+    bits: Affixes {
+        before: Cow::Borrowed("de "),
+        after: Cow::Borrowed(" bits"),
+    },
+    bit_field: Cow::Borrowed("campo de bits"),
+    in_: Cow::Borrowed(" en "),
+    that_can_hold_values: Cow::Borrowed(" que puede contener valores "),
+    with_the_same_type_as: Cow::Borrowed(" con el mismo tipo que "),
+    initialized_to: Cow::Borrowed(" con valor inicial "),
+    initialized_with_an_empty_list: Cow::Borrowed(" con una lista vacía como valor inicial"),
+    initialized_with_a_list_of: Cow::Borrowed(" con valor inicial una lista de "),
+    with_designators_for: Cow::Borrowed(", con designadores para "),
+    implicitly: Cow::Borrowed("implícitamente "),
+    with_internal_linkage: Cow::Borrowed("con enlace interno"),
+    defined_elsewhere: Cow::Borrowed("definido en otro lugar"),
+    stored_in_register: Cow::Borrowed("almacenado en el registro "),
+    with_assembler_name: Cow::Borrowed("con nombre de ensamblador "),
+    commonly_a_string: Cow::Borrowed("normalmente una cadena"),
+    commonly_an_array_of_strings: Cow::Borrowed("normalmente un arreglo de cadenas"),
+    commonly_a_callback: Cow::Borrowed(
+        "normalmente una función de retrollamada con un puntero de contexto",
+    ),
+    call_it_as: Cow::Borrowed("; se llama como "),
+    or_simply: Cow::Borrowed(" o simplemente "),
+    and_assign_it_with: Cow::Borrowed(", y se asigna con "),
+    may_be_modified: Word {
+        singular: Cow::Borrowed(" puede modificarse"),
+        plural: Cow::Borrowed(" pueden modificarse"),
+    },
+    but: Cow::Borrowed(", pero "),
+    may_not: Word {
+        singular: Cow::Borrowed(" no"),
+        plural: Cow::Borrowed(" no"),
+    },
+    may_not_be_modified: Word {
+        singular: Cow::Borrowed(" no puede modificarse"),
+        plural: Cow::Borrowed(" no pueden modificarse"),
+    },
+    containing: Cow::Borrowed(" que contiene "),
+    containing_no_members: Cow::Borrowed(" sin miembros"),
+    with_values: Cow::Borrowed(" con valores "),
+    packed: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("sin relleno"),
+        after: Cow::Borrowed(""),
+    },
+    aligned_to: KeywordPhrase {
+        before: Cow::Borrowed("con "),
+        keyword: Cow::Borrowed("alineación"),
+        after: Cow::Borrowed(" de "),
+    },
+    bytes: Cow::Borrowed(" bytes"),
+    maximally_aligned: KeywordPhrase {
+        before: Cow::Borrowed("con "),
+        keyword: Cow::Borrowed("alineación"),
+        after: Cow::Borrowed(" máxima"),
+    },
+    deprecated: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("en desuso"),
+        after: Cow::Borrowed(""),
+    },
+    possibly_unused: KeywordPhrase {
+        before: Cow::Borrowed("posiblemente "),
+        keyword: Cow::Borrowed("sin usar"),
+        after: Cow::Borrowed(""),
+    },
+    non_returning: KeywordPhrase {
+        before: Cow::Borrowed(""),
+        keyword: Cow::Borrowed("sin retorno"),
+        after: Cow::Borrowed(""),
+    },
+    result_must_be_used: KeywordPhrase {
+        before: Cow::Borrowed("el resultado debe "),
+        keyword: Cow::Borrowed("usarse"),
+        after: Cow::Borrowed(""),
+    },
+    weakly_linked: KeywordPhrase {
+        before: Cow::Borrowed("con "),
+        keyword: Cow::Borrowed("enlace débil"),
+        after: Cow::Borrowed(""),
+    },
+    imported_from_a_dll: KeywordPhrase {
+        before: Cow::Borrowed("con "),
+        keyword: Cow::Borrowed("importación"),
+        after: Cow::Borrowed(" desde una DLL"),
+    },
+    exported_from_a_dll: KeywordPhrase {
+        before: Cow::Borrowed("con "),
+        keyword: Cow::Borrowed("exportación"),
+        after: Cow::Borrowed(" desde una DLL"),
+    },
+    target_cannot_be_modified: Cow::Borrowed(
+        "el valor al que apunta no puede modificarse a través de él",
+    ),
+    pointer_cannot_be_changed: Cow::Borrowed(
+        "el propio puntero no puede cambiarse para apuntar a otro lugar",
+    ),
+    value_cannot_be_modified: Cow::Borrowed(
+        "su valor no puede modificarse después de la inicialización",
+    ),
+    no_other_pointer_accesses_target: Cow::Borrowed(
+        "ningún otro puntero se usa para acceder al objeto al que apunta",
+    ),
+    accesses_are_never_optimized_away: Cow::Borrowed(
+        "los accesos nunca se eliminan al optimizar, ya que el valor puede cambiar inesperadamente",
+    ),
+    address_cannot_be_taken: Cow::Borrowed("no se puede obtener su dirección"),
+};
+
+/// Clarifications of Spanish terms for beginners
+const SPANISH_GLOSSARY: &[(Word, &str)] = &[
+    term(
+        "puntero",
+        "punteros",
+        "una variable que almacena una dirección de memoria",
+    ),
+    term(
+        "referencia",
+        "referencias",
+        "otro nombre para un objeto existente",
+    ),
+    term(
+        "bloque",
+        "bloques",
+        "una función que puede capturar variables de donde se define",
+    ),
+    term(
+        "arreglo",
+        "arreglos",
+        "una secuencia de elementos almacenados uno junto a otro en memoria",
+    ),
+    term(
+        "función",
+        "funciones",
+        "un fragmento de código que se puede llamar",
+    ),
+    term("const", "const", "no se puede modificar"),
+    term(
+        "volatile",
+        "volatile",
+        "puede cambiar en cualquier momento, aunque el programa no lo cambie",
+    ),
+    term(
+        "restrict",
+        "restrict",
+        "la única forma de acceder al objeto al que apunta",
+    ),
+];
@@ -0,0 +1,171 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Choice of English indefinite articles based on how words are pronounced
+
+use alloc::string::String;
+
+use crate::color::HighlightedTextSegment;
+
+/// Prefixes of words which begin with a consonant sound despite starting with a vowel, e.g. the
+/// "you" sound of "union" and the "w" sound of "one"
+const CONSONANT_SOUND_PREFIXES: &[&str] = &[
+    "uni", "use", "usa", "usi", "usu", "uti", "ure", "uri", "ubiq", "eu", "ewe", "one", "once",
+];
+
+/// Names of types which are abbreviated after a "u" for "unsigned", as in `ulong` and `uint8_t`,
+/// where the "u" is pronounced "you"
+const UNSIGNED_TYPE_WORDS: &[&str] = &[
+    "char", "short", "int", "long", "llong", "byte", "word", "quad", "ptr", "size",
+];
+
+/// Prefixes of words which begin with a vowel sound despite matching one of
+/// [`CONSONANT_SOUND_PREFIXES`], e.g. "uninitialized"
+const VOWEL_SOUND_EXCEPTIONS: &[&str] = &["unin", "unim", "unid"];
+
+/// Prefixes of words which begin with a silent "h", e.g. "hour"
+const SILENT_H_PREFIXES: &[&str] = &["hour", "honest", "honor", "honour", "heir"];
+
+/// Letters whose names begin with a vowel sound when spelled out, e.g. "an" for "eff"
+const VOWEL_SOUND_LETTERS: &str = "aefhilmnorsx";
+
+/// Returns whether a number written with the given digits begins with a vowel sound, i.e. if it
+/// begins with "eight", "eleven", or "eighteen".
+fn number_starts_with_vowel_sound(digits: &str) -> bool {
+    digits.starts_with('8')
+        || (digits.len() % 3 == 2 && (digits.starts_with("11") || digits.starts_with("18")))
+}
+
+/// Returns whether a word which is spelled out letter by letter, like "HWND", begins with a
+/// vowel sound.
+fn spelled_out_starts_with_vowel_sound(word: &str) -> bool {
+    word.chars()
+        .next()
+        .is_some_and(|c| VOWEL_SOUND_LETTERS.contains(c.to_ascii_lowercase()))
+}
+
+/// Returns whether a word begins with a vowel sound.
+fn word_starts_with_vowel_sound(word: &str) -> bool {
+    // Acronyms without vowels, like "HWND", and single letters are spelled out.
+    let has_vowel = word.chars().any(|c| "aeiouyAEIOUY".contains(c));
+    if word.chars().count() == 1 || (!has_vowel && word.chars().all(|c| c.is_ascii_uppercase())) {
+        return spelled_out_starts_with_vowel_sound(word);
+    }
+    let word = word.to_ascii_lowercase();
+    if SILENT_H_PREFIXES
+        .iter()
+        .any(|prefix| word.starts_with(prefix))
+    {
+        return true;
+    }
+    if word.strip_prefix('u').is_some_and(|rest| {
+        UNSIGNED_TYPE_WORDS
+            .iter()
+            .any(|type_word| rest.starts_with(type_word))
+    }) {
+        return false;
+    }
+    if CONSONANT_SOUND_PREFIXES
+        .iter()
+        .any(|prefix| word.starts_with(prefix))
+        && !VOWEL_SOUND_EXCEPTIONS
+            .iter()
+            .any(|prefix| word.starts_with(prefix))
+    {
+        return false;
+    }
+    word.starts_with(['a', 'e', 'i', 'o', 'u'])
+}
+
+/// Returns the appropriate article ("a" or "an") for the given noun, followed by a space.
+///
+/// The article depends on how the noun's first word is pronounced: only its leading letters or
+/// digits are considered, so `uint8_t` is pronounced like "uint" and takes "a".
+pub(super) fn article_for(noun: &HighlightedTextSegment) -> &'static str {
+    let Some(first) = noun.text.chars().next() else {
+        return "";
+    };
+    let is_vowel_sound = if first.is_ascii_digit() {
+        let digits: String = noun
+            .text
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',' || *c == '_')
+            .filter(char::is_ascii_digit)
+            .collect();
+        number_starts_with_vowel_sound(&digits)
+    } else {
+        let end = noun
+            .text
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(noun.text.len());
+        if end == 0 {
+            false
+        } else {
+            word_starts_with_vowel_sound(&noun.text[..end])
+        }
+    };
+    if is_vowel_sound { "an " } else { "a " }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn articles() {
+        for (noun, article) in [
+            ("int", "an "),
+            ("char", "a "),
+            ("union foo", "a "),
+            ("unsigned int", "an "),
+            ("uninitialized", "an "),
+            ("user_t", "a "),
+            ("uint8_t", "a "),
+            ("ulong", "a "),
+            ("ushort", "a "),
+            ("uchar", "a "),
+            ("uintptr_t", "a "),
+            ("u_int32_t", "a "),
+            ("ullong", "a "),
+            ("upper_t", "an "),
+            ("unicode_t", "a "),
+            ("hour", "an "),
+            ("honest_t", "an "),
+            ("handle", "a "),
+            ("one_t", "a "),
+            ("enum color", "an "),
+            ("HWND", "an "),
+            ("LPSTR", "an "),
+            ("DWORD", "a "),
+            ("FILE", "a "),
+            ("T", "a "),
+            ("x", "an "),
+            ("8", "an "),
+            ("3", "a "),
+            ("11", "an "),
+            ("18", "an "),
+            ("110", "a "),
+            ("11000", "an "),
+            ("_Bool", "a "),
+            ("", ""),
+        ] {
+            assert_eq!(
+                article_for(&noun.into()),
+                article,
+                "wrong article for {noun:?}"
+            );
+        }
+    }
+}
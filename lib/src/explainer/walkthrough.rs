@@ -0,0 +1,300 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Step-by-step derivations of explanations, following the spiral rule
+
+use alloc::{format, string::ToString, vec::Vec};
+
+use crate::{
+    ast::{Declaration, Declarator},
+    c_syntax::{c_type, calling_convention_token, declarator_token},
+    color::{Highlight, HighlightedText, HighlightedTextSegment},
+};
+
+use super::{Options, explain_declarator, format_qualified_type, push_type};
+
+/// Returns the part of `outer` which follows `inner`, with surrounding whitespace trimmed.
+/// `inner` must be a prefix of `outer`, except that its last segment may have been extended.
+fn suffix_after(outer: HighlightedText, inner: &HighlightedText) -> HighlightedText {
+    let mut suffix = HighlightedText(
+        outer
+            .0
+            .into_iter()
+            .skip(inner.len().saturating_sub(1))
+            .collect(),
+    );
+    if let (Some(last), Some(first)) = (inner.last(), suffix.first_mut())
+        && let Some(rest) = first.text.strip_prefix(&last.text)
+    {
+        first.text = rest.to_string();
+    }
+    trimmed(suffix)
+}
+
+/// Trims whitespace from the start and end of the text, removing segments which become empty.
+fn trimmed(mut text: HighlightedText) -> HighlightedText {
+    if let Some(first) = text.first_mut() {
+        first.text = first.text.trim_start().to_string();
+    }
+    if let Some(last) = text.last_mut() {
+        last.text = last.text.trim_end().to_string();
+    }
+    text.retain(|segment| !segment.text.is_empty());
+    text
+}
+
+/// Returns whether the declarator is written before the name, like `*`, rather than after it,
+/// like `[10]`.
+fn is_prefix(declarator: &Declarator) -> bool {
+    matches!(
+        declarator,
+        Declarator::Ptr(..)
+            | Declarator::Reference(_)
+            | Declarator::Block(..)
+            | Declarator::MemberPtr(..)
+    )
+}
+
+/// Returns the parts of a declaration's declarator from the outside in, each along with the
+/// declarator inside it, followed by the innermost declarator, which is the name or where it would
+/// be. Block pointers to functions are explained along with the function, so they're one part.
+pub(super) fn declarator_parts<'a, 'src>(
+    decl: &'a Declaration<'src>,
+) -> (
+    Vec<(&'a Declarator<'src>, &'a Declarator<'src>)>,
+    &'a Declarator<'src>,
+) {
+    let mut parts = Vec::new();
+    let mut declarator = &decl.declarator;
+    while !matches!(declarator, Declarator::Anonymous | Declarator::Ident(_)) {
+        let inner = match declarator {
+            Declarator::Function { func, .. } if matches!(**func, Declarator::Block(..)) => {
+                declarator_token(func).0
+            }
+            _ => declarator_token(declarator).0,
+        };
+        parts.push((declarator, inner));
+        declarator = inner;
+    }
+    (parts, declarator)
+}
+
+/// Returns the phrase explaining a part of a declarator, given the declarator inside it, e.g.
+/// "pointers to".
+pub(super) fn part_phrase(
+    part: &Declarator,
+    inner: &Declarator,
+    options: &Options,
+) -> HighlightedText {
+    suffix_after(
+        explain_declarator(part, true, options).msg,
+        &explain_declarator(inner, true, options).msg,
+    )
+}
+
+/// Returns the phrase explaining the base type of a declaration, e.g. "ints" for `int *bar[5]`.
+pub(super) fn base_type_phrase(decl: &Declaration, options: &Options) -> HighlightedText {
+    let mut phrase = HighlightedText::new();
+    let plurality = explain_declarator(&decl.declarator, true, options).plurality;
+    push_type(
+        &mut phrase,
+        format_qualified_type(&decl.base_type, options),
+        plurality,
+        options,
+    );
+    phrase
+}
+
+/// Walks through a declaration using the spiral rule, returning the steps used to decode it,
+/// e.g. "start at `bar`", "go right: `[5]`, an array of 5", "go left: `*`, pointers to", and
+/// "go left to the type: `int`, ints". The steps aren't numbered.
+///
+/// The directions are always in English, but the phrases describing each part of the
+/// declaration use the language and verbosity given in the options.
+#[must_use]
+pub fn walk_through_declaration(decl: &Declaration, options: &Options) -> Vec<HighlightedText> {
+    let options = &options.single_line();
+    let (parts, declarator) = declarator_parts(decl);
+
+    let mut steps = Vec::new();
+    let mut step = HighlightedText::new();
+    match declarator {
+        Declarator::Ident(name) => {
+            step.push_str("start at `");
+            step.push(HighlightedTextSegment::new(*name, Highlight::Ident));
+            step.push_str("`");
+        }
+        _ => step.push_str("start where the name would be"),
+    }
+    steps.push(step);
+
+    let mut previous_is_prefix = false;
+    for (part, inner) in parts.into_iter().rev() {
+        let mut step = HighlightedText::new();
+        match part {
+            Declarator::Function { func, .. } if matches!(**func, Declarator::Block(..)) => {
+                step.push_str(&format!(
+                    "go left: `{}`, then leave the parentheses and go right: `{}`, ",
+                    declarator_token(func).1.trim_end(),
+                    declarator_token(part).1,
+                ));
+            }
+            _ => {
+                let direction = if is_prefix(part) {
+                    "go left"
+                } else if previous_is_prefix {
+                    "leave the parentheses and go right"
+                } else {
+                    "go right"
+                };
+                step.push_str(&format!(
+                    "{direction}: `{}`, ",
+                    declarator_token(part).1.trim_end()
+                ));
+                // The calling convention is written before the function's name, so it's
+                // mentioned separately.
+                let convention = calling_convention_token(part);
+                if !convention.is_empty() {
+                    step.push_str(&format!("marked `{}` on the left, ", convention.trim_end()));
+                }
+            }
+        }
+        step.extend(part_phrase(part, inner, options).0);
+        steps.push(step);
+        previous_is_prefix = is_prefix(part);
+    }
+
+    let mut step = HighlightedText::new();
+    step.push_str(&format!(
+        "go left to the type: `{}`, ",
+        c_type(&decl.base_type)
+    ));
+    step.extend(base_type_phrase(decl, options).0);
+    steps.push(step);
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Walks through the single declaration in the source with the default options and asserts
+    /// that the steps match the expected ones.
+    fn run(src: &str, expected: &[&[HighlightedTextSegment]]) {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        let steps = walk_through_declaration(&decls[0], &Options::default());
+        assert_eq!(
+            steps.len(),
+            expected.len(),
+            "Wrong number of steps for {src}"
+        );
+        for (step, expected) in steps.into_iter().zip(expected) {
+            assert_eq!(&step.coalesced().0, expected, "Wrong step for {src}");
+        }
+    }
+
+    #[test]
+    fn walk_through_array_of_pointers() {
+        run(
+            "int *bar[5]",
+            &[
+                hltext!["start at `" n "bar" i "`" n],
+                hltext!["go right: `[5]`, an " n "array" qk " of " n "5" num],
+                hltext!["go left: `*`, " n "pointers" qk " to" n],
+                hltext!["go left to the type: `int`, " n "int" pt "s" n],
+            ],
+        );
+    }
+
+    #[test]
+    fn walk_through_function_pointer() {
+        run(
+            "char *const (*fp)(int a, char **b)",
+            &[
+                hltext!["start at `" n "fp" i "`" n],
+                hltext!["go left: `*`, a " n "pointer" qk " to" n],
+                hltext![
+                    "leave the parentheses and go right: `(int a, char **b)`, a " n
+                    "function" qk
+                    " that takes (an " n
+                    "int" pt
+                    " named " n
+                    "a" i
+                    " and a " n
+                    "pointer" qk
+                    " named " n
+                    "b" i
+                    " to a " n
+                    "pointer" qk
+                    " to a " n
+                    "char" pt
+                    ") and returns" n
+                ],
+                hltext!["go left: `*const`, a " n "const" q " " n "pointer" qk " to" n],
+                hltext!["go left to the type: `char`, a " n "char" pt],
+            ],
+        );
+    }
+
+    #[test]
+    fn walk_through_other_declarations() {
+        run(
+            "const volatile unsigned long x",
+            &[
+                hltext!["start at `" n "x" i "`" n],
+                hltext![
+                    "go left to the type: `const volatile unsigned long`, a " n
+                    "const volatile" q
+                    " " n
+                    "unsigned long" pt
+                ],
+            ],
+        );
+        run(
+            "void (^handler)(int [static 3])",
+            &[
+                hltext!["start at `" n "handler" i "`" n],
+                hltext![
+                    "go left: `^`, then leave the parentheses and go right: \
+                     `(int [static 3])`, a " n
+                    "block" qk
+                    " taking (an " n
+                    "array" qk
+                    " of at least " n
+                    "3" num
+                    " " n
+                    "int" pt
+                    "s) and returning" n
+                ],
+                hltext!["go left to the type: `void`, a " n "void" pt],
+            ],
+        );
+        run(
+            "int __fastcall f(void)",
+            &[
+                hltext!["start at `" n "f" i "`" n],
+                hltext![
+                    "go right: `(void)`, marked `__fastcall` on the left, a " n
+                    "fastcall" q
+                    " " n
+                    "function" qk
+                    " that takes no parameters and returns" n
+                ],
+                hltext!["go left to the type: `int`, an " n "int" pt],
+            ],
+        );
+    }
+}
@@ -0,0 +1,190 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Export of flashcard decks for studying declarations with spaced-repetition software like Anki
+//!
+//! Each card has a C declaration on the front and its plain-text explanation on the back. Decks
+//! are exported as CSV or TSV files, which Anki and most similar programs can import.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    ast::Declaration,
+    color::fmt::PlainFormatter,
+    en2c::format_declaration,
+    explainer::{Options, explain_declaration_with_options},
+    generator::{Difficulty, Generator},
+};
+
+/// File format of an exported deck
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "lowercase")]
+pub enum DeckFormat {
+    /// Comma-separated values, with fields quoted as described in RFC 4180 when needed
+    Csv,
+    /// Tab-separated values, with tabs and line breaks in fields replaced by spaces
+    #[default]
+    Tsv,
+}
+
+/// Flashcard with a C declaration on the front and its explanation on the back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flashcard {
+    pub front: String,
+    pub back: String,
+}
+
+impl Flashcard {
+    /// Creates a flashcard for the given declaration, explaining it using the given options.
+    #[must_use]
+    pub fn new(decl: &Declaration, options: &Options) -> Self {
+        Self {
+            front: format_declaration(decl),
+            back: explain_declaration_with_options(decl, options).format_to_string(&PlainFormatter),
+        }
+    }
+}
+
+/// Returns a field of a CSV file, quoted if it contains a separator, quote, or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        let mut field = String::from("\"");
+        field.push_str(&text.replace('"', "\"\""));
+        field.push('"');
+        field
+    } else {
+        text.into()
+    }
+}
+
+/// Returns a field of a TSV file, which can't contain tabs or line breaks.
+fn tsv_field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Deck of flashcards
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, explainer::Options, parser::parser};
+/// # use c2e::flashcards::{Deck, DeckFormat};
+/// let decls = parser().parse("int *p; char buf[4]").unwrap();
+/// let deck = Deck::from_declarations(&decls, &Options::default());
+/// assert_eq!(
+///     deck.export(DeckFormat::Tsv),
+///     "int *p\ta pointer named p to an int\nchar buf[4]\tan array named buf of 4 chars\n",
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deck {
+    pub cards: Vec<Flashcard>,
+}
+
+impl Deck {
+    /// Creates a deck with a card for each of the given declarations.
+    #[must_use]
+    pub fn from_declarations(decls: &[Declaration], options: &Options) -> Self {
+        Self {
+            cards: decls
+                .iter()
+                .map(|decl| Flashcard::new(decl, options))
+                .collect(),
+        }
+    }
+
+    /// Creates a deck of `count` cards for random declarations of the given difficulty. The same
+    /// seed always produces the same deck.
+    #[must_use]
+    pub fn random(seed: u64, difficulty: Difficulty, count: usize, options: &Options) -> Self {
+        let mut generator = Generator::new(seed, difficulty);
+        Self {
+            cards: (0..count)
+                .map(|_| Flashcard::new(&generator.generate(), options))
+                .collect(),
+        }
+    }
+
+    /// Returns the contents of a file containing the deck in the given format, with one line per
+    /// card and no header.
+    #[must_use]
+    pub fn export(&self, format: DeckFormat) -> String {
+        let (separator, field): (char, fn(&str) -> String) = match format {
+            DeckFormat::Csv => (',', csv_field),
+            DeckFormat::Tsv => ('\t', tsv_field),
+        };
+        let mut file = String::new();
+        for card in &self.cards {
+            file.push_str(&field(&card.front));
+            file.push(separator);
+            file.push_str(&field(&card.back));
+            file.push('\n');
+        }
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn csv() {
+        let decls = crate::parser::parser()
+            .parse("int x; int (*fp)(char *s, int n)")
+            .unwrap();
+        let deck = Deck::from_declarations(&decls, &Options::default());
+        assert_eq!(
+            deck.export(DeckFormat::Csv),
+            "int x,an int named x\n\"int (*fp)(char *s, int n)\",a pointer named fp to a \
+             function that takes (a pointer named s to a char and an int named n) and returns an \
+             int\n"
+        );
+    }
+
+    #[test]
+    fn quoting() {
+        let card = Flashcard {
+            front: "a\tb".into(),
+            back: "say \"hi\"\nthen leave".into(),
+        };
+        let deck = Deck { cards: vec![card] };
+        assert_eq!(
+            deck.export(DeckFormat::Csv),
+            "a\tb,\"say \"\"hi\"\"\nthen leave\"\n"
+        );
+        assert_eq!(deck.export(DeckFormat::Tsv), "a b\tsay \"hi\" then leave\n");
+    }
+
+    #[test]
+    fn random() {
+        let options = Options::default();
+        let deck = Deck::random(42, Difficulty::FunctionPointers, 5, &options);
+        assert_eq!(deck.cards.len(), 5);
+        assert_eq!(
+            deck,
+            Deck::random(42, Difficulty::FunctionPointers, 5, &options)
+        );
+        for card in &deck.cards {
+            let decls = crate::parser::parser().parse(&card.front).unwrap();
+            assert_eq!(*card, Flashcard::new(&decls[0], &options));
+        }
+    }
+}
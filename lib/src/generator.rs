@@ -0,0 +1,341 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Generation of random but valid declarations, for quizzes, worksheets, and fuzzing
+//!
+//! Generation is seeded, so the same seed and [difficulty](Difficulty) always produce the same
+//! sequence of declarations.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::ast::{
+    ArrayQualifiers, ArraySize, Declaration, Declarator, PrimitiveType, QualifiedType, Type,
+    TypeQualifier, TypeQualifiers,
+};
+
+/// Complexity of generated declarations. Each level uses the features of the levels before it.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    parse_display::Display,
+    parse_display::FromStr,
+)]
+#[display(style = "kebab-case")]
+pub enum Difficulty {
+    /// Pointers to primitive types, like `char **p`
+    #[default]
+    Pointers,
+    /// Arrays, like `int *arr[10]`
+    Arrays,
+    /// Functions and pointers to them, like `int (*fp)(char *)`
+    FunctionPointers,
+    /// `const`, `volatile`, and `restrict`, like `const char *const p`
+    Qualifiers,
+    /// Typedef declarations and typedef names, like `typedef uint8_t (*handler_t)(void)`
+    Typedefs,
+}
+
+/// Part of a type which derives from another, listed from the name outwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Pointer,
+    Array,
+    Function,
+}
+
+const PRIMITIVE_TYPES: &[&str] = &["char", "int", "long", "double", "unsigned int", "float"];
+/// Typedef names which the parser knows by default, from `<stdint.h>`
+const TYPEDEF_NAMES: &[&str] = &["uint8_t", "int32_t", "uint64_t"];
+const NAMES: &[&str] = &["x", "p", "arr", "fp", "table", "buf", "cb", "data", "grid"];
+const NEW_TYPEDEF_NAMES: &[&str] = &["handler_t", "table_t", "buf_t", "node_t"];
+const PARAM_NAMES: &[&str] = &["a", "b", "c"];
+
+/// Seeded generator of random declarations, which are always valid C
+#[derive(Debug, Clone)]
+pub struct Generator {
+    state: u64,
+    difficulty: Difficulty,
+}
+
+impl Generator {
+    /// Creates a generator of declarations at the given difficulty.
+    #[must_use]
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            state: seed,
+            difficulty,
+        }
+    }
+
+    /// Returns the next pseudo-random number, using `SplitMix64`.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number less than `n`.
+    fn below(&mut self, n: usize) -> usize {
+        let n = u64::try_from(n).unwrap();
+        usize::try_from(self.next_u64() % n).unwrap()
+    }
+
+    /// Returns true with a probability of one in `n`.
+    fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    /// Returns the parts of a derived type, from the name outwards. Arrays never contain
+    /// functions, and functions never return arrays or functions.
+    fn kinds(&mut self, nested: bool) -> Vec<Kind> {
+        let (allowed, min, max): (&[Kind], usize, usize) = match self.difficulty {
+            _ if nested => (&[Kind::Pointer, Kind::Array], 0, 2),
+            Difficulty::Pointers => (&[Kind::Pointer], 1, 3),
+            Difficulty::Arrays => (&[Kind::Pointer, Kind::Array], 1, 4),
+            _ => (&[Kind::Pointer, Kind::Array, Kind::Function], 2, 5),
+        };
+        let len = min + self.below(max - min + 1);
+        let mut kinds: Vec<Kind> = Vec::with_capacity(len);
+        for _ in 0..len {
+            let options: Vec<Kind> = allowed
+                .iter()
+                .copied()
+                .filter(|kind| {
+                    !matches!(
+                        (kinds.last(), kind),
+                        (Some(Kind::Array), Kind::Function)
+                            | (Some(Kind::Function), Kind::Array | Kind::Function)
+                    )
+                })
+                .collect();
+            kinds.push(self.choose(&options));
+        }
+        kinds
+    }
+
+    /// Returns random qualifiers if the difficulty allows them. `restrict` is only chosen if
+    /// `restrict` is true.
+    fn qualifiers(&mut self, restrict: bool) -> TypeQualifiers {
+        let mut qualifiers = TypeQualifiers::default();
+        if self.difficulty < Difficulty::Qualifiers {
+            return qualifiers;
+        }
+        if self.one_in(3) {
+            qualifiers.insert(TypeQualifier::Const);
+        }
+        if self.one_in(8) {
+            qualifiers.insert(TypeQualifier::Volatile);
+        }
+        if restrict && self.one_in(8) {
+            qualifiers.insert(TypeQualifier::Restrict);
+        }
+        qualifiers
+    }
+
+    /// Returns the base type for a declaration whose outermost part is `last`. `void` is only
+    /// chosen if it can be pointed to or returned, and return types are never qualified, since
+    /// the qualifiers would be meaningless.
+    fn base_type(&mut self, last: Option<Kind>) -> QualifiedType<'static> {
+        let mut types: Vec<Type<'static>> = PRIMITIVE_TYPES
+            .iter()
+            .map(|primitive| Type::Primitive(PrimitiveType(primitive)))
+            .collect();
+        if matches!(last, Some(Kind::Pointer | Kind::Function)) {
+            types.push(Type::Primitive(PrimitiveType("void")));
+        }
+        if self.difficulty >= Difficulty::Typedefs {
+            types.extend(TYPEDEF_NAMES.iter().map(|name| Type::Custom(name)));
+        }
+        let ty = types.swap_remove(self.below(types.len()));
+        let qualifiers = if last == Some(Kind::Function) {
+            TypeQualifiers::default()
+        } else {
+            self.qualifiers(false)
+        };
+        QualifiedType(qualifiers, ty)
+    }
+
+    /// Generates a declaration. Nested declarations, i.e. function parameters, are simpler and
+    /// never declare functions or typedefs.
+    fn declaration(&mut self, name: Option<&'static str>, nested: bool) -> Declaration<'static> {
+        let kinds = self.kinds(nested);
+        let mut declarator = name.map_or(Declarator::Anonymous, Declarator::Ident);
+        for (i, kind) in kinds.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|i| kinds[i]);
+            let next = kinds.get(i + 1).copied();
+            declarator = match kind {
+                Kind::Pointer => {
+                    let restrict = next != Some(Kind::Function);
+                    Declarator::Ptr(Box::new(declarator), self.qualifiers(restrict))
+                }
+                Kind::Array => {
+                    // Only the outermost dimension of an array of arrays may be unspecified.
+                    let size = if previous != Some(Kind::Array) && self.one_in(4) {
+                        ArraySize::Unspecified
+                    } else {
+                        ArraySize::Fixed(1 + self.below(16))
+                    };
+                    Declarator::Array(Box::new(declarator), size, ArrayQualifiers::default())
+                }
+                Kind::Function => {
+                    let count = self.below(PARAM_NAMES.len());
+                    let params = PARAM_NAMES[..count]
+                        .iter()
+                        .map(|name| {
+                            let name = self.one_in(2).then_some(*name);
+                            self.declaration(name, true)
+                        })
+                        .collect();
+                    Declarator::Function {
+                        func: Box::new(declarator),
+                        params,
+                        variadic: false,
+                        calling_convention: None,
+                        member_qualifiers: TypeQualifiers::default(),
+                    }
+                }
+            };
+        }
+        Declaration::new(self.base_type(kinds.last().copied()), declarator)
+    }
+
+    /// Generates the next declaration.
+    #[must_use]
+    pub fn generate(&mut self) -> Declaration<'static> {
+        if self.difficulty >= Difficulty::Typedefs && self.one_in(3) {
+            let name = self.choose(NEW_TYPEDEF_NAMES);
+            let mut decl = self.declaration(Some(name), false);
+            decl.base_type.0.insert(TypeQualifier::Typedef);
+            decl
+        } else {
+            let name = self.choose(NAMES);
+            self.declaration(Some(name), false)
+        }
+    }
+}
+
+impl Iterator for Generator {
+    type Item = Declaration<'static>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.generate())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{en2c::format_declaration, semantics::check_declaration};
+
+    const DIFFICULTIES: [Difficulty; 5] = [
+        Difficulty::Pointers,
+        Difficulty::Arrays,
+        Difficulty::FunctionPointers,
+        Difficulty::Qualifiers,
+        Difficulty::Typedefs,
+    ];
+
+    #[test]
+    fn reproducible() {
+        for difficulty in DIFFICULTIES {
+            let a: Vec<_> = Generator::new(42, difficulty).take(20).collect();
+            let b: Vec<_> = Generator::new(42, difficulty).take(20).collect();
+            let c: Vec<_> = Generator::new(43, difficulty).take(20).collect();
+            assert_eq!(a, b, "Different output for the same seed at {difficulty}");
+            assert_ne!(a, c, "Same output for different seeds at {difficulty}");
+        }
+    }
+
+    #[test]
+    fn valid() {
+        for difficulty in DIFFICULTIES {
+            for decl in Generator::new(7, difficulty).take(500) {
+                let src = format_declaration(&decl);
+                assert_eq!(check_declaration(&decl), Ok(()), "Invalid: {src}");
+                let parsed = crate::parser::parser().parse(&src).into_result().ok();
+                assert_eq!(parsed, Some(alloc::vec![decl]), "Wrong round trip of {src}");
+            }
+        }
+    }
+
+    #[test]
+    fn difficulty() {
+        fn has(declarator: &Declarator, f: fn(&Declarator) -> bool) -> bool {
+            f(declarator)
+                || match declarator {
+                    Declarator::Anonymous | Declarator::Ident(_) => false,
+                    Declarator::Ptr(inner, _)
+                    | Declarator::Reference(inner)
+                    | Declarator::Block(inner, _)
+                    | Declarator::MemberPtr(inner, ..)
+                    | Declarator::Array(inner, ..)
+                    | Declarator::Function { func: inner, .. } => has(inner, f),
+                }
+        }
+
+        for decl in Generator::new(1, Difficulty::Pointers).take(100) {
+            assert!(!has(&decl.declarator, |d| !matches!(
+                d,
+                Declarator::Ptr(..) | Declarator::Ident(_)
+            )));
+        }
+        for decl in Generator::new(1, Difficulty::Arrays).take(100) {
+            assert!(!has(&decl.declarator, |d| matches!(
+                d,
+                Declarator::Function { .. }
+            )));
+        }
+        for decl in Generator::new(1, Difficulty::FunctionPointers).take(100) {
+            assert!(decl.base_type.0.is_empty());
+            assert!(!has(&decl.declarator, |d| matches!(
+                d,
+                Declarator::Ptr(_, qualifiers) if !qualifiers.is_empty()
+            )));
+        }
+        for decl in Generator::new(1, Difficulty::Qualifiers).take(100) {
+            assert!(!matches!(decl.base_type.1, Type::Custom(_)));
+            assert!(!decl.base_type.0.contains(TypeQualifier::Typedef));
+        }
+        assert!(
+            Generator::new(1, Difficulty::Typedefs)
+                .take(100)
+                .any(|decl| decl.base_type.0.contains(TypeQualifier::Typedef))
+        );
+    }
+
+    #[test]
+    fn difficulty_names() {
+        assert_eq!(
+            Difficulty::FunctionPointers.to_string(),
+            "function-pointers"
+        );
+        assert_eq!("typedefs".parse(), Ok(Difficulty::Typedefs));
+    }
+}
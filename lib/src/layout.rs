@@ -0,0 +1,538 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Computation of the sizes and alignments of declared types under common data models
+//!
+//! Scalars are aligned to their sizes, except for `long double` on [`DataModel::Ilp32`], and
+//! records are laid out like the System V ABI does, with bit-fields packed into storage units of
+//! their declared types. Attributes such as `packed` are ignored.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::ast::{
+    ArraySize, BitInt, Declaration, Declarator, Member, Record, RecordBody, RecordKind, Type,
+};
+
+/// Data model, which determines the sizes of `long` and of pointers
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display, parse_display::FromStr,
+)]
+#[display(style = "UPPERCASE")]
+pub enum DataModel {
+    /// 32-bit `int`, `long`, and pointers, as on 32-bit Linux and Windows
+    Ilp32,
+    /// 32-bit `int` and 64-bit `long` and pointers, as on 64-bit Linux and macOS
+    #[default]
+    Lp64,
+    /// 32-bit `int` and `long` and 64-bit pointers, as on 64-bit Windows
+    Llp64,
+}
+
+impl DataModel {
+    /// Returns the size of a pointer in bytes.
+    #[must_use]
+    pub fn pointer_size(self) -> u64 {
+        match self {
+            DataModel::Ilp32 => 4,
+            DataModel::Lp64 | DataModel::Llp64 => 8,
+        }
+    }
+
+    /// Returns the size of `long` in bytes.
+    #[must_use]
+    pub fn long_size(self) -> u64 {
+        match self {
+            DataModel::Ilp32 | DataModel::Llp64 => 4,
+            DataModel::Lp64 => 8,
+        }
+    }
+}
+
+/// Size and alignment of a type, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
+
+impl Layout {
+    /// Returns the layout of a scalar of the given size, which is aligned to its size.
+    fn scalar(size: u64) -> Self {
+        Self { size, align: size }
+    }
+}
+
+/// Error computing the layout of a type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LayoutError {
+    #[error("functions have no size")]
+    Function,
+    #[error("{0} is an incomplete type")]
+    Incomplete(String),
+    #[error("the size of {0} is unknown")]
+    Unknown(String),
+    #[error("the size of a variable-length array is only known at runtime")]
+    VariableLength,
+    #[error("the type is too large")]
+    TooLarge,
+}
+
+/// Rounds the offset up to the next multiple of the alignment.
+fn align_to(offset: u64, align: u64) -> Result<u64, LayoutError> {
+    offset
+        .checked_next_multiple_of(align.max(1))
+        .ok_or(LayoutError::TooLarge)
+}
+
+/// Converts a size in bytes to bits.
+fn bits(bytes: u64) -> Result<u64, LayoutError> {
+    bytes.checked_mul(8).ok_or(LayoutError::TooLarge)
+}
+
+/// Adds a size to an offset.
+fn add(offset: u64, size: u64) -> Result<u64, LayoutError> {
+    offset.checked_add(size).ok_or(LayoutError::TooLarge)
+}
+
+/// Returns the layout of a primitive type.
+fn primitive_layout(name: &str, model: DataModel) -> Result<Layout, LayoutError> {
+    if let Some(real) = name.strip_suffix(" _Complex") {
+        let real = primitive_layout(real, model)?;
+        return Ok(Layout {
+            size: real.size * 2,
+            align: real.align,
+        });
+    }
+    // Signedness doesn't affect the size, and `int` is implied by the other integer types.
+    let unsigned = name
+        .strip_prefix("unsigned")
+        .or_else(|| name.strip_prefix("signed"))
+        .map_or(name, str::trim_start);
+    let base = unsigned.strip_suffix(" int").unwrap_or(unsigned);
+    let size = match base {
+        "char" | "_Bool" | "bool" | "char8_t" => 1,
+        "wchar_t" if model == DataModel::Llp64 => 2,
+        "short" | "char16_t" => 2,
+        "" | "int" | "char32_t" | "wchar_t" | "float" | "_Decimal32" => 4,
+        "long" => model.long_size(),
+        "long long" | "double" | "_Decimal64" => 8,
+        "_Decimal128" => 16,
+        "nullptr_t" => model.pointer_size(),
+        "long double" => {
+            return Ok(match model {
+                DataModel::Ilp32 => Layout { size: 12, align: 4 },
+                DataModel::Lp64 => Layout::scalar(16),
+                DataModel::Llp64 => Layout::scalar(8),
+            });
+        }
+        "void" => return Err(LayoutError::Incomplete("void".to_string())),
+        _ => return Err(LayoutError::Unknown(format!("`{name}`"))),
+    };
+    Ok(Layout::scalar(size))
+}
+
+/// Returns the layout of a [builtin typedef](crate::builtins) whose size doesn't depend on the
+/// platform beyond its data model, if it has one.
+fn builtin_layout(name: &str, model: DataModel) -> Option<Layout> {
+    let size = match name {
+        "int8_t" | "uint8_t" | "int_least8_t" | "uint_least8_t" | "int_fast8_t"
+        | "uint_fast8_t" | "BYTE" | "CHAR" => 1,
+        "int16_t" | "uint16_t" | "int_least16_t" | "uint_least16_t" | "WORD" | "WCHAR" => 2,
+        "int32_t" | "uint32_t" | "int_least32_t" | "uint_least32_t" | "DWORD" | "BOOL" | "INT"
+        | "UINT" | "LONG" | "ULONG" | "HRESULT" => 4,
+        "int64_t" | "uint64_t" | "int_least64_t" | "uint_least64_t" | "int_fast64_t"
+        | "uint_fast64_t" | "intmax_t" | "uintmax_t" | "QWORD" | "LONGLONG" | "ULONGLONG" => 8,
+        "intptr_t" | "uintptr_t" | "size_t" | "ptrdiff_t" | "ssize_t" | "SIZE_T" | "HANDLE"
+        | "HWND" | "HINSTANCE" | "HMODULE" | "LPVOID" | "LPSTR" | "LPCSTR" | "LPWSTR"
+        | "LPCWSTR" => model.pointer_size(),
+        _ => return None,
+    };
+    Some(Layout::scalar(size))
+}
+
+/// Returns the layout of a bit-precise integer, which is stored like the smallest standard
+/// integer type that can hold it, or in 8-byte chunks if it's wider than 64 bits.
+fn bit_int_layout(bit_int: BitInt) -> Layout {
+    let bytes = (bit_int.width as u64).div_ceil(8).max(1);
+    if bytes <= 8 {
+        Layout::scalar(bytes.next_power_of_two())
+    } else {
+        Layout {
+            size: bytes.next_multiple_of(8),
+            align: 8,
+        }
+    }
+}
+
+/// Returns the layout of a `struct` or `union` with the given members.
+fn record_layout(
+    kind: RecordKind,
+    members: &[Member],
+    model: DataModel,
+) -> Result<Layout, LayoutError> {
+    // Offsets are in bits so that bit-fields can be packed.
+    let mut end = 0;
+    let mut align = 1;
+    for (i, member) in members.iter().enumerate() {
+        let is_last = i + 1 == members.len();
+        let layout = declarator_layout(
+            &member.declaration.declarator,
+            base_layout(&member.declaration.base_type.1, model),
+            kind == RecordKind::Struct && is_last,
+            model,
+        )?;
+        align = align.max(layout.align);
+        let offset = if kind == RecordKind::Union { 0 } else { end };
+        end = end.max(match member.bit_width {
+            None => add(align_to(offset, bits(layout.align)?)?, bits(layout.size)?)?,
+            // A zero-width bit-field aligns the next bit-field to its type's storage unit.
+            Some(0) => align_to(offset, bits(layout.size)?)?,
+            Some(width) => {
+                let width = width as u64;
+                let unit = bits(layout.size)?;
+                let field_end = add(offset, width)?;
+                // Bit-fields can't straddle the boundary between two storage units.
+                if unit > 0 && offset / unit != (field_end - 1) / unit {
+                    add(align_to(offset, unit)?, width)?
+                } else {
+                    field_end
+                }
+            }
+        });
+    }
+    Ok(Layout {
+        size: align_to(end.div_ceil(8), align)?,
+        align,
+    })
+}
+
+/// Returns the layout of a base type, which may be an error that a pointer declarator discards,
+/// e.g. for `void *`.
+fn base_layout(ty: &Type, model: DataModel) -> Result<Layout, LayoutError> {
+    match ty {
+        Type::Primitive(primitive) => primitive_layout(primitive.as_ref(), model),
+        Type::Record(Record {
+            kind: RecordKind::Enum,
+            ..
+        }) => Ok(Layout::scalar(4)),
+        Type::Record(Record {
+            kind,
+            body: Some(RecordBody::Members(members)),
+            ..
+        }) => record_layout(*kind, members, model),
+        Type::Record(record) => Err(LayoutError::Incomplete(format!("`{record}`"))),
+        Type::Custom(name) => {
+            builtin_layout(name, model).ok_or_else(|| LayoutError::Unknown(format!("`{name}`")))
+        }
+        Type::Typeof(operand) => Err(LayoutError::Unknown(format!("`typeof({operand})`"))),
+        Type::BitInt(bit_int) => Ok(bit_int_layout(*bit_int)),
+    }
+}
+
+/// Returns the layout of the type declared by a declarator given the layout of the type it
+/// derives from. Errors are only returned if they aren't discarded by a pointer.
+///
+/// If `flexible` is true, an array of unknown size directly containing the identifier is a
+/// flexible array member, which takes no space.
+fn declarator_layout(
+    declarator: &Declarator,
+    ty: Result<Layout, LayoutError>,
+    flexible: bool,
+    model: DataModel,
+) -> Result<Layout, LayoutError> {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => ty,
+        Declarator::Ptr(inner, _) | Declarator::Block(inner, _) => declarator_layout(
+            inner,
+            Ok(Layout::scalar(model.pointer_size())),
+            flexible,
+            model,
+        ),
+        // As in the Itanium C++ ABI, a pointer to a member function holds a function pointer and
+        // an adjustment to the object pointer, so it's twice the size of a pointer.
+        Declarator::MemberPtr(inner, ..) => {
+            let pointer = model.pointer_size();
+            let layout = if matches!(ty, Err(LayoutError::Function)) {
+                Layout {
+                    size: pointer * 2,
+                    align: pointer,
+                }
+            } else {
+                Layout::scalar(pointer)
+            };
+            declarator_layout(inner, Ok(layout), flexible, model)
+        }
+        // The size of a reference is the size of the object it refers to.
+        Declarator::Reference(inner) => declarator_layout(inner, ty, flexible, model),
+        Declarator::Array(inner, size, _) => {
+            let element = ty?;
+            let layout = match size {
+                ArraySize::Fixed(len) => Layout {
+                    size: element
+                        .size
+                        .checked_mul(*len as u64)
+                        .ok_or(LayoutError::TooLarge)?,
+                    align: element.align,
+                },
+                ArraySize::Unspecified if flexible && matches!(**inner, Declarator::Ident(_)) => {
+                    Layout {
+                        size: 0,
+                        align: element.align,
+                    }
+                }
+                ArraySize::Unspecified => {
+                    return Err(LayoutError::Incomplete(
+                        "an array of unknown size".to_string(),
+                    ));
+                }
+                ArraySize::Expr(expr) => {
+                    return Err(LayoutError::Unknown(format!("an array of size `{expr}`")));
+                }
+                ArraySize::Variable(_) | ArraySize::VariableUnspecified => {
+                    return Err(LayoutError::VariableLength);
+                }
+            };
+            declarator_layout(inner, Ok(layout), flexible, model)
+        }
+        Declarator::Function { func, .. } => {
+            declarator_layout(func, Err(LayoutError::Function), flexible, model)
+        }
+    }
+}
+
+/// Computes the size and alignment of the type declared by a declaration.
+///
+/// # Errors
+///
+/// Returns an error if the type has no size, like a function or `void`, or if its size isn't
+/// known, like that of a typedef defined elsewhere or of a variable-length array.
+pub fn layout_of(decl: &Declaration, model: DataModel) -> Result<Layout, LayoutError> {
+    if decl.forward_declaration
+        && let Type::Record(record) = &decl.base_type.1
+    {
+        return Err(LayoutError::Incomplete(format!("`{record}`")));
+    }
+    declarator_layout(
+        &decl.declarator,
+        base_layout(&decl.base_type.1, model),
+        false,
+        model,
+    )
+}
+
+/// Describes the size and alignment of the type declared by a declaration, e.g. "arr occupies
+/// 80 bytes and is aligned to 8 bytes on LP64".
+///
+/// # Errors
+///
+/// Returns an error if the layout can't be computed; see [`layout_of`].
+pub fn describe_layout(decl: &Declaration, model: DataModel) -> Result<String, LayoutError> {
+    let layout = layout_of(decl, model)?;
+    let subject = decl.declarator.name().unwrap_or("the type");
+    let bytes = |n: u64| if n == 1 { "byte" } else { "bytes" };
+    Ok(format!(
+        "{subject} occupies {} {} and is aligned to {} {} on {model}",
+        layout.size,
+        bytes(layout.size),
+        layout.align,
+        bytes(layout.align),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Computes the layout of the last declaration in the source.
+    fn layout(src: &str, model: DataModel) -> Result<Layout, LayoutError> {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        layout_of(decls.last().unwrap(), model)
+    }
+
+    /// Shorthand for a layout with the given size and alignment.
+    fn sized(size: u64, align: u64) -> Layout {
+        Layout { size, align }
+    }
+
+    #[test]
+    fn scalars() {
+        for (src, ilp32, lp64, llp64) in [
+            ("char c", sized(1, 1), sized(1, 1), sized(1, 1)),
+            (
+                "unsigned short int s",
+                sized(2, 2),
+                sized(2, 2),
+                sized(2, 2),
+            ),
+            ("unsigned x", sized(4, 4), sized(4, 4), sized(4, 4)),
+            ("signed long l", sized(4, 4), sized(8, 8), sized(4, 4)),
+            ("long long int ll", sized(8, 8), sized(8, 8), sized(8, 8)),
+            ("long double ld", sized(12, 4), sized(16, 16), sized(8, 8)),
+            (
+                "double _Complex z",
+                sized(16, 8),
+                sized(16, 8),
+                sized(16, 8),
+            ),
+            ("wchar_t w", sized(4, 4), sized(4, 4), sized(2, 2)),
+            ("void *p", sized(4, 4), sized(8, 8), sized(8, 8)),
+            ("intptr_t n", sized(4, 4), sized(8, 8), sized(8, 8)),
+            ("uint16_t u", sized(2, 2), sized(2, 2), sized(2, 2)),
+            ("_BitInt(24) b", sized(4, 4), sized(4, 4), sized(4, 4)),
+            (
+                "unsigned _BitInt(100) b",
+                sized(16, 8),
+                sized(16, 8),
+                sized(16, 8),
+            ),
+            ("enum color c", sized(4, 4), sized(4, 4), sized(4, 4)),
+        ] {
+            assert_eq!(
+                layout(src, DataModel::Ilp32),
+                Ok(ilp32),
+                "wrong ILP32 layout of {src}"
+            );
+            assert_eq!(
+                layout(src, DataModel::Lp64),
+                Ok(lp64),
+                "wrong LP64 layout of {src}"
+            );
+            assert_eq!(
+                layout(src, DataModel::Llp64),
+                Ok(llp64),
+                "wrong LLP64 layout of {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn derived_types() {
+        assert_eq!(layout("int *arr[10]", DataModel::Lp64), Ok(sized(80, 8)));
+        assert_eq!(layout("int *arr[10]", DataModel::Ilp32), Ok(sized(40, 4)));
+        assert_eq!(layout("int (*arr)[10]", DataModel::Lp64), Ok(sized(8, 8)));
+        assert_eq!(layout("char grid[3][5]", DataModel::Lp64), Ok(sized(15, 1)));
+        assert_eq!(layout("int (*fp)(int)", DataModel::Lp64), Ok(sized(8, 8)));
+        assert_eq!(layout("void (^b)(void)", DataModel::Llp64), Ok(sized(8, 8)));
+
+        // Pointers to member functions are twice the size of other pointers.
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let decls = crate::parser::parser()
+            .parse_with_state("int C::*m; int (C::*p)(int) const", &mut state)
+            .unwrap();
+        assert_eq!(layout_of(&decls[0], DataModel::Lp64), Ok(sized(8, 8)));
+        assert_eq!(layout_of(&decls[1], DataModel::Lp64), Ok(sized(16, 8)));
+        assert_eq!(layout_of(&decls[1], DataModel::Ilp32), Ok(sized(8, 4)));
+    }
+
+    #[test]
+    fn records() {
+        for (src, expected) in [
+            ("struct { char c; int i; char d; } s", sized(12, 4)),
+            ("struct { char c; double d; } s", sized(16, 8)),
+            ("union { char c[5]; int i; } u", sized(8, 4)),
+            ("struct { int len; char data[]; } s", sized(4, 4)),
+            (
+                "struct { struct { char a, b; } inner; short s; } s",
+                sized(4, 2),
+            ),
+            (
+                "struct { unsigned a : 3; unsigned b : 30; unsigned char c : 2; } s",
+                sized(8, 4),
+            ),
+            (
+                "struct { char a : 4; char : 0; char b : 4; } s",
+                sized(2, 1),
+            ),
+            ("struct { long l; int i; } s", sized(16, 8)),
+        ] {
+            assert_eq!(
+                layout(src, DataModel::Lp64),
+                Ok(expected),
+                "wrong layout of {src}"
+            );
+        }
+        assert_eq!(
+            layout("struct { long l; int i; } s", DataModel::Llp64),
+            Ok(sized(8, 4))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for (src, error) in [
+            ("int f(void)", LayoutError::Function),
+            ("void v", LayoutError::Incomplete("void".to_string())),
+            (
+                "int arr[]",
+                LayoutError::Incomplete("an array of unknown size".to_string()),
+            ),
+            (
+                "struct node n",
+                LayoutError::Incomplete("`struct node`".to_string()),
+            ),
+            (
+                "struct node;",
+                LayoutError::Incomplete("`struct node`".to_string()),
+            ),
+            ("int n; int a[n]", LayoutError::VariableLength),
+            (
+                "typedef int T; T t",
+                LayoutError::Unknown("`T`".to_string()),
+            ),
+            (
+                "typeof(1) x",
+                LayoutError::Unknown("`typeof(1)`".to_string()),
+            ),
+            ("char huge[99999999999][99999999999]", LayoutError::TooLarge),
+            (
+                "struct { char a[4000000000000000000]; } s",
+                LayoutError::TooLarge,
+            ),
+            (
+                "struct { char c; long a[2305843009213693951]; } s",
+                LayoutError::TooLarge,
+            ),
+        ] {
+            assert_eq!(
+                layout(src, DataModel::Lp64),
+                Err(error),
+                "wrong error for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn descriptions() {
+        let decls = crate::parser::parser()
+            .parse("int *arr[10]; char c; int (*)(void)")
+            .unwrap();
+        assert_eq!(
+            describe_layout(&decls[0], DataModel::Lp64).unwrap(),
+            "arr occupies 80 bytes and is aligned to 8 bytes on LP64"
+        );
+        assert_eq!(
+            describe_layout(&decls[1], DataModel::Ilp32).unwrap(),
+            "c occupies 1 byte and is aligned to 1 byte on ILP32"
+        );
+        assert_eq!(
+            describe_layout(&decls[2], DataModel::Llp64).unwrap(),
+            "the type occupies 8 bytes and is aligned to 8 bytes on LLP64"
+        );
+    }
+}
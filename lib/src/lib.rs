@@ -17,9 +17,26 @@
 extern crate alloc;
 
 pub mod ast;
+pub mod builtins;
+mod c_syntax;
+pub mod cdecl;
 pub mod color;
+pub mod compatibility;
+pub mod en2c;
 pub mod explainer;
+pub mod flashcards;
+pub mod generator;
+pub mod layout;
+pub mod lint;
+pub mod notation;
 pub mod parser;
+pub mod provenance;
+pub mod quiz;
+pub mod semantics;
+pub mod standard;
+#[cfg(feature = "transpile")]
+pub mod transpile;
+pub mod usage;
 
 /// Re-export the [`chumsky`] crate's prelude for convenience.
 pub mod chumsky {
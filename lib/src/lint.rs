@@ -0,0 +1,418 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Lints which flag declarations that are legal but probably not what was intended, such as
+//! `char* a, b`, where `b` is not a pointer
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use chumsky::span::SimpleSpan;
+
+use crate::{
+    ast::{ArraySize, Declaration, Declarator, RecordBody, Type, TypeQualifier, TypeQualifiers},
+    parser::SpannedDeclaration,
+};
+
+/// Kind of suspicious declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    /// `restrict` qualifying a type which isn't a pointer, like `restrict int x`
+    RestrictNonPointer,
+    /// Qualifier repeated in the same list, like `const const int`
+    DuplicateQualifier(TypeQualifier),
+    /// `const` qualifying the return type of a function, which has no effect
+    ConstReturnType,
+    /// Array of size zero, which is a GNU extension
+    ZeroLengthArray,
+    /// Declarator which isn't a pointer following one which is, like `b` in `char* a, b`. Holds
+    /// the names of the pointer and of the declarator following it.
+    PointerBinding { pointer: String, name: String },
+}
+
+/// Formats the problem described by the lint, e.g. "duplicate `const` qualifier".
+impl Display for LintKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LintKind::RestrictNonPointer => {
+                write!(f, "`restrict` only has an effect on pointers")
+            }
+            LintKind::DuplicateQualifier(qualifier) => {
+                write!(f, "duplicate `{}` qualifier", qualifier_keyword(*qualifier))
+            }
+            LintKind::ConstReturnType => {
+                write!(f, "`const` on a return type has no effect")
+            }
+            LintKind::ZeroLengthArray => {
+                write!(f, "zero-length arrays are a non-standard extension")
+            }
+            LintKind::PointerBinding { pointer, name } => {
+                write!(f, "`{name}` is not a pointer, unlike `{pointer}`")
+            }
+        }
+    }
+}
+
+/// Suspicious declaration, along with a suggestion for how to fix it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    /// Span of the offending source text
+    pub span: SimpleSpan,
+    /// Human-readable suggestion, e.g. "remove the repeated `const`"
+    pub suggestion: String,
+}
+
+impl Lint {
+    fn new(kind: LintKind, span: SimpleSpan) -> Self {
+        let suggestion = match &kind {
+            LintKind::RestrictNonPointer => {
+                "remove `restrict`, or place it after the `*` of a pointer".into()
+            }
+            LintKind::DuplicateQualifier(qualifier) => {
+                alloc::format!("remove the repeated `{}`", qualifier_keyword(*qualifier))
+            }
+            LintKind::ConstReturnType => "remove `const` from the return type".into(),
+            LintKind::ZeroLengthArray => {
+                "use a flexible array member like `[]` instead, or give the array a size".into()
+            }
+            LintKind::PointerBinding { name, .. } => alloc::format!(
+                "write `*{name}` if it should also be a pointer, or declare it separately"
+            ),
+        };
+        Self {
+            kind,
+            span,
+            suggestion,
+        }
+    }
+}
+
+/// Formats the lint like a parse error, e.g. "at 0..15: duplicate `const` qualifier; remove the
+/// repeated `const`".
+impl Display for Lint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "at {}: {}; {}", self.span, self.kind, self.suggestion)
+    }
+}
+
+/// Returns the standard keyword for a qualifier.
+fn qualifier_keyword(qualifier: TypeQualifier) -> &'static str {
+    match qualifier {
+        TypeQualifier::Const => "const",
+        TypeQualifier::Volatile => "volatile",
+        TypeQualifier::Restrict => "restrict",
+        TypeQualifier::Atomic => "_Atomic",
+        TypeQualifier::Typedef => "typedef",
+    }
+}
+
+/// Returns the qualifier spelled by a keyword, including the GNU and MSVC spellings.
+fn keyword_qualifier(keyword: &str) -> Option<TypeQualifier> {
+    match keyword {
+        "const" | "__const" | "__const__" => Some(TypeQualifier::Const),
+        "volatile" | "__volatile" | "__volatile__" => Some(TypeQualifier::Volatile),
+        "restrict" | "__restrict" | "__restrict__" => Some(TypeQualifier::Restrict),
+        "_Atomic" => Some(TypeQualifier::Atomic),
+        _ => None,
+    }
+}
+
+/// Splits source text into words and punctuation characters, skipping whitespace, comments,
+/// and string literals. Returns each token with its span.
+fn tokens(src: &str, offset: usize) -> Vec<(&str, SimpleSpan)> {
+    let mut tokens = Vec::new();
+    let mut rest = src;
+    while let Some(c) = rest.chars().next() {
+        let start = src.len() - rest.len();
+        let len = if c.is_alphanumeric() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push((
+                &rest[..len],
+                SimpleSpan::from(offset + start..offset + start + len),
+            ));
+            len
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            comment.find("*/").map_or(rest.len(), |end| end + 4)
+        } else if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if let Some(literal) = rest.strip_prefix('"') {
+            literal.find('"').map_or(rest.len(), |end| end + 2)
+        } else {
+            if !c.is_whitespace() {
+                tokens.push((
+                    &rest[..c.len_utf8()],
+                    SimpleSpan::from(offset + start..offset + start + c.len_utf8()),
+                ));
+            }
+            c.len_utf8()
+        };
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+/// Finds qualifiers repeated within a run of words which isn't interrupted by punctuation,
+/// which is where the qualifiers of a single type appear.
+fn lint_duplicate_qualifiers(tokens: &[(&str, SimpleSpan)], lints: &mut Vec<Lint>) {
+    let mut seen = TypeQualifiers::default();
+    for (i, &(token, span)) in tokens.iter().enumerate() {
+        if !token.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            seen = TypeQualifiers::default();
+            continue;
+        }
+        // `_Atomic(` is the atomic type specifier, not a qualifier.
+        let Some(qualifier) = keyword_qualifier(token)
+            .filter(|_| token != "_Atomic" || tokens.get(i + 1).is_none_or(|&(t, _)| t != "("))
+        else {
+            continue;
+        };
+        if seen.contains(qualifier) {
+            lints.push(Lint::new(LintKind::DuplicateQualifier(qualifier), span));
+        }
+        seen.insert(qualifier);
+    }
+}
+
+/// Lints a declarator, given the qualifiers of the type it derives from.
+fn lint_declarator(
+    declarator: &Declarator,
+    qualifiers: TypeQualifiers,
+    span: SimpleSpan,
+    lints: &mut Vec<Lint>,
+) {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => {}
+        Declarator::Ptr(inner, qualifiers)
+        | Declarator::Block(inner, qualifiers)
+        | Declarator::MemberPtr(inner, _, qualifiers) => {
+            lint_declarator(inner, *qualifiers, span, lints);
+        }
+        Declarator::Reference(inner) => {
+            lint_declarator(inner, TypeQualifiers::default(), span, lints);
+        }
+        Declarator::Array(inner, size, _) => {
+            if *size == ArraySize::Fixed(0) {
+                lints.push(Lint::new(LintKind::ZeroLengthArray, span));
+            }
+            lint_declarator(inner, TypeQualifiers::default(), span, lints);
+        }
+        Declarator::Function { func, params, .. } => {
+            if qualifiers.contains(TypeQualifier::Const) {
+                lints.push(Lint::new(LintKind::ConstReturnType, span));
+            }
+            for param in params {
+                lint_declaration(param, span, lints);
+            }
+            lint_declarator(func, TypeQualifiers::default(), span, lints);
+        }
+    }
+}
+
+/// Lints a declaration and the declarations nested in it, reporting each lint at the given span.
+fn lint_declaration(decl: &Declaration, span: SimpleSpan, lints: &mut Vec<Lint>) {
+    let qualifiers = decl.base_type.0;
+    if let Type::Record(record) = &decl.base_type.1
+        && let Some(RecordBody::Members(members)) = &record.body
+    {
+        for member in members {
+            lint_declaration(&member.declaration, span, lints);
+        }
+    }
+    // A typedef name or `typeof` may denote a pointer type.
+    if qualifiers.contains(TypeQualifier::Restrict)
+        && !matches!(decl.base_type.1, Type::Custom(_) | Type::Typeof(_))
+    {
+        lints.push(Lint::new(LintKind::RestrictNonPointer, span));
+    }
+    lint_declarator(&decl.declarator, qualifiers, span, lints);
+}
+
+/// Returns whether the declarator contains a pointer anywhere.
+fn has_pointer(declarator: &Declarator) -> bool {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => false,
+        Declarator::Ptr(..) | Declarator::MemberPtr(..) => true,
+        Declarator::Reference(inner)
+        | Declarator::Block(inner, _)
+        | Declarator::Array(inner, ..) => has_pointer(inner),
+        Declarator::Function { func, .. } => has_pointer(func),
+    }
+}
+
+/// Finds declarators which aren't pointers following one which is in the same declaration, like
+/// `b` in `char* a, b`. The declarations sharing a span are those declared together.
+fn lint_pointer_binding(
+    group: &[&Declaration],
+    tokens: &[(&str, SimpleSpan)],
+    span: SimpleSpan,
+    lints: &mut Vec<Lint>,
+) {
+    let Some((first, rest)) = group.split_first() else {
+        return;
+    };
+    let (Declarator::Ptr(..), Some(pointer)) = (&first.declarator, first.declarator.name()) else {
+        return;
+    };
+    for decl in rest {
+        let Some(name) = decl.declarator.name() else {
+            continue;
+        };
+        if has_pointer(&decl.declarator) {
+            continue;
+        }
+        // Report the lint at the name, which follows the first comma.
+        let comma = tokens.iter().position(|&(t, _)| t == ",").unwrap_or(0);
+        let name_span = tokens[comma..]
+            .iter()
+            .find(|&&(t, _)| t == name)
+            .map_or(span, |&(_, span)| span);
+        lints.push(Lint::new(
+            LintKind::PointerBinding {
+                pointer: pointer.into(),
+                name: name.into(),
+            },
+            name_span,
+        ));
+    }
+}
+
+/// Flags suspicious but legal declarations, such as those with duplicate qualifiers or `const`
+/// return types. The declarations must have been parsed from the given source, e.g. by
+/// [`parse_translation_unit`](crate::parser::parse_translation_unit), and the lints are returned
+/// in the order of their declarations.
+#[must_use]
+pub fn lint(src: &str, decls: &[SpannedDeclaration]) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let mut i = 0;
+    while i < decls.len() {
+        // Declarations sharing a type, like `int a, b;`, share a span.
+        let span = decls[i].1;
+        let group: Vec<&Declaration> = decls[i..]
+            .iter()
+            .take_while(|(_, s)| *s == span)
+            .map(|(decl, _)| decl)
+            .collect();
+        i += group.len();
+
+        let tokens = tokens(&src[span.into_range()], span.start);
+        lint_duplicate_qualifiers(&tokens, &mut lints);
+        for decl in &group {
+            lint_declaration(decl, span, &mut lints);
+        }
+        lint_pointer_binding(&group, &tokens, span, &mut lints);
+    }
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::parser::{State, parse_translation_unit};
+
+    /// Lints the source, returning the kind and source text of each lint.
+    fn run(src: &str) -> Vec<(LintKind, &str)> {
+        let decls = parse_translation_unit(src, &mut State::default()).unwrap();
+        lint(src, &decls)
+            .into_iter()
+            .map(|lint| (lint.kind, &src[lint.span.into_range()]))
+            .collect()
+    }
+
+    #[test]
+    fn clean() {
+        for src in [
+            "int *restrict p;",
+            "void f(int a[restrict]);",
+            "const char *f(void);",
+            "char *a, *b;",
+            "char *a, (*b)[2];",
+            "int a, *b;",
+            "const volatile int x;",
+            "const int *const p;",
+            "const _Atomic(int) x;",
+            "struct s { int len; char data[]; };",
+            "typedef int *IntPtr; restrict IntPtr p;",
+            "/* const */ const int x;",
+        ] {
+            assert_eq!(run(src), vec![], "Wrong lints for {src}");
+        }
+    }
+
+    #[test]
+    fn lints() {
+        assert_eq!(
+            run("restrict int x;"),
+            vec![(LintKind::RestrictNonPointer, "restrict int x;")]
+        );
+        assert_eq!(
+            run("const const int x; int *const __const p;"),
+            vec![
+                (LintKind::DuplicateQualifier(TypeQualifier::Const), "const"),
+                (
+                    LintKind::DuplicateQualifier(TypeQualifier::Const),
+                    "__const"
+                ),
+            ]
+        );
+        assert_eq!(
+            run("const int f(void);"),
+            vec![(LintKind::ConstReturnType, "const int f(void);")]
+        );
+        assert_eq!(
+            run("int *const g(void);"),
+            vec![(LintKind::ConstReturnType, "int *const g(void);")]
+        );
+        assert_eq!(
+            run("struct s { int len; char data[0]; };"),
+            vec![(
+                LintKind::ZeroLengthArray,
+                "struct s { int len; char data[0]; };"
+            )]
+        );
+        assert_eq!(
+            run("char* a, b, *c, d;"),
+            vec![
+                (
+                    LintKind::PointerBinding {
+                        pointer: "a".to_string(),
+                        name: "b".to_string()
+                    },
+                    "b"
+                ),
+                (
+                    LintKind::PointerBinding {
+                        pointer: "a".to_string(),
+                        name: "d".to_string()
+                    },
+                    "d"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn display() {
+        let src = "const const int x;";
+        let decls = parse_translation_unit(src, &mut State::default()).unwrap();
+        assert_eq!(
+            lint(src, &decls)[0].to_string(),
+            "at 6..11: duplicate `const` qualifier; remove the repeated `const`"
+        );
+    }
+}
@@ -0,0 +1,288 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Compact notations for the types of declarations, for places like tooltips and logs where full
+//! sentences are too long
+//!
+//! Two notations are available: [`arrow_notation`] spells out the chain of derived types, e.g.
+//! `p: ptr -> array[10] -> int` for `int (*p)[10]`, and [`type_name`] writes the type the way
+//! compilers print it in diagnostics, e.g. `int (*)[10]`.
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    ast::{ArrayQualifiers, Declaration, Declarator, TypeQualifier, TypeQualifiers},
+    c_syntax::{c_array_contents, c_declarator, c_member_qualifiers, c_qualifiers, c_type},
+};
+
+/// Returns the declarator of a parameter with the same type as the given one, adjusted the way C
+/// adjusts parameter types: arrays become pointers to their elements and functions become
+/// pointers to functions.
+fn adjusted_parameter<'src>(declarator: &Declarator<'src>) -> Declarator<'src> {
+    let is_leaf =
+        |inner: &Declarator| matches!(inner, Declarator::Anonymous | Declarator::Ident(_));
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => declarator.clone(),
+        Declarator::Array(inner, _, ArrayQualifiers { qualifiers, .. }) if is_leaf(inner) => {
+            Declarator::Ptr(inner.clone(), *qualifiers)
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => {
+            let func = if is_leaf(func) {
+                Declarator::Ptr(func.clone(), TypeQualifiers::default())
+            } else {
+                adjusted_parameter(func)
+            };
+            Declarator::Function {
+                func: Box::new(func),
+                params: params.clone(),
+                variadic: *variadic,
+                calling_convention: *calling_convention,
+                member_qualifiers: *member_qualifiers,
+            }
+        }
+        Declarator::Ptr(inner, qualifiers) => {
+            Declarator::Ptr(Box::new(adjusted_parameter(inner)), *qualifiers)
+        }
+        Declarator::Reference(inner) => Declarator::Reference(Box::new(adjusted_parameter(inner))),
+        Declarator::Block(inner, qualifiers) => {
+            Declarator::Block(Box::new(adjusted_parameter(inner)), *qualifiers)
+        }
+        Declarator::MemberPtr(inner, class, qualifiers) => {
+            Declarator::MemberPtr(Box::new(adjusted_parameter(inner)), class, *qualifiers)
+        }
+        Declarator::Array(inner, size, qualifiers) => {
+            Declarator::Array(Box::new(adjusted_parameter(inner)), *size, *qualifiers)
+        }
+    }
+}
+
+/// Returns the declarator with its identifier removed, along with the identifiers of any
+/// parameters, whose types are [adjusted][adjusted_parameter].
+fn abstract_declarator<'src>(declarator: &Declarator<'src>) -> Declarator<'src> {
+    let inner = |inner: &Declarator<'src>| Box::new(abstract_declarator(inner));
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => Declarator::Anonymous,
+        Declarator::Ptr(decl, qualifiers) => Declarator::Ptr(inner(decl), *qualifiers),
+        Declarator::Reference(decl) => Declarator::Reference(inner(decl)),
+        Declarator::Block(decl, qualifiers) => Declarator::Block(inner(decl), *qualifiers),
+        Declarator::MemberPtr(decl, class, qualifiers) => {
+            Declarator::MemberPtr(inner(decl), class, *qualifiers)
+        }
+        Declarator::Array(decl, size, qualifiers) => {
+            Declarator::Array(inner(decl), *size, *qualifiers)
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => Declarator::Function {
+            func: inner(func),
+            params: params
+                .iter()
+                .map(|param| {
+                    let declarator = abstract_declarator(&adjusted_parameter(&param.declarator));
+                    Declaration::new(param.base_type.clone(), declarator)
+                })
+                .collect(),
+            variadic: *variadic,
+            calling_convention: *calling_convention,
+            member_qualifiers: *member_qualifiers,
+        },
+    }
+}
+
+/// Returns the type declared by a declaration, without its name or the names of any parameters,
+/// as compilers print types in diagnostics, e.g. `int (*)(char *)` for `int (*fp)(char *s)`.
+/// Parameters of array and function types are adjusted to pointers, as they are in C.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, notation::type_name, parser::parser};
+/// let decls = parser().parse("int (*fp)(char *s, int a[10]); const char *names[4]").unwrap();
+/// assert_eq!(type_name(&decls[0]), "int (*)(char *, int *)");
+/// assert_eq!(type_name(&decls[1]), "const char *[4]");
+/// ```
+#[must_use]
+pub fn type_name(decl: &Declaration) -> String {
+    let ty = c_type(&decl.base_type);
+    match c_declarator(&abstract_declarator(&decl.declarator)).0 {
+        declarator if declarator.is_empty() => ty,
+        declarator => format!("{ty} {declarator}"),
+    }
+}
+
+/// Returns the links of the chain of types derived by a declarator, starting with the one closest
+/// to the identifier, e.g. `["ptr", "array[10]"]` for `(*p)[10]`.
+fn chain(declarator: &Declarator) -> Vec<String> {
+    let (inner, link) = match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => return Vec::new(),
+        Declarator::Ptr(inner, qualifiers) => (inner, format!("{}ptr", c_qualifiers(*qualifiers))),
+        Declarator::Reference(inner) => (inner, "ref".to_string()),
+        Declarator::Block(inner, qualifiers) => {
+            (inner, format!("{}block", c_qualifiers(*qualifiers)))
+        }
+        Declarator::MemberPtr(inner, class, qualifiers) => (
+            inner,
+            format!("{}member_ptr({class})", c_qualifiers(*qualifiers)),
+        ),
+        Declarator::Array(inner, size, qualifiers) => (
+            inner,
+            format!("array[{}]", c_array_contents(size, *qualifiers)),
+        ),
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => {
+            let convention = calling_convention
+                .map(|convention| format!("__{convention} "))
+                .unwrap_or_default();
+            let mut params: Vec<String> = params
+                .iter()
+                .map(|param| {
+                    let mut links = chain(&adjusted_parameter(&param.declarator));
+                    links.push(c_type(&param.base_type));
+                    links.join(" -> ")
+                })
+                .collect();
+            if *variadic {
+                params.push("...".to_string());
+            }
+            (
+                func,
+                format!(
+                    "{convention}fn({}){}",
+                    params.join(", "),
+                    c_member_qualifiers(*member_qualifiers)
+                ),
+            )
+        }
+    };
+    let mut links = chain(inner);
+    links.push(link);
+    links
+}
+
+/// Returns the type declared by a declaration as a chain of derived types ending with the base
+/// type, preceded by the declared name, e.g. `p: ptr -> array[10] -> int` for `int (*p)[10]`.
+/// Typedefs are written as `type name = ...`, and parameters are written without their names.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, notation::arrow_notation, parser::parser};
+/// let decls = parser().parse("int (*p)[10]; char *(*fp)(int)").unwrap();
+/// assert_eq!(arrow_notation(&decls[0]), "p: ptr -> array[10] -> int");
+/// assert_eq!(arrow_notation(&decls[1]), "fp: ptr -> fn(int) -> ptr -> char");
+/// ```
+#[must_use]
+pub fn arrow_notation(decl: &Declaration) -> String {
+    let mut links = chain(&decl.declarator);
+    links.push(c_type(&decl.base_type));
+    let chain = links.join(" -> ");
+    match decl.declarator.name() {
+        Some(name) if decl.base_type.0.contains(TypeQualifier::Typedef) => {
+            format!("type {name} = {chain}")
+        }
+        Some(name) => format!("{name}: {chain}"),
+        None => chain,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(src: &str) -> Declaration<'_> {
+        let mut decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 1, "Expected one declaration for {src}");
+        decls.remove(0)
+    }
+
+    #[test]
+    fn arrows() {
+        for (src, expected) in [
+            ("int x", "x: int"),
+            ("int *p[10]", "p: array[10] -> ptr -> int"),
+            ("const char *const s", "s: const ptr -> const char"),
+            (
+                "void (*signal(int sig, void (*handler)(int)))(int)",
+                "signal: fn(int, ptr -> fn(int) -> void) -> ptr -> fn(int) -> void",
+            ),
+            (
+                "int main(int argc, char *argv[])",
+                "main: fn(int, ptr -> ptr -> char) -> int",
+            ),
+            ("typedef unsigned long size", "type size = unsigned long"),
+            ("struct point { int x, y; } origin", "origin: struct point"),
+            ("int __stdcall f(void)", "f: __stdcall fn() -> int"),
+            ("int (*)[]", "ptr -> array[] -> int"),
+            (
+                "int printf(const char *fmt, ...)",
+                "printf: fn(ptr -> const char, ...) -> int",
+            ),
+        ] {
+            assert_eq!(
+                arrow_notation(&parse(src)),
+                expected,
+                "Wrong notation for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn type_names() {
+        for (src, expected) in [
+            ("int x", "int"),
+            ("int *p[10]", "int *[10]"),
+            ("int (*p)[10]", "int (*)[10]"),
+            ("static const char *const s", "const char *const"),
+            (
+                "void (*signal(int sig, void (*handler)(int)))(int)",
+                "void (*(int, void (*)(int)))(int)",
+            ),
+            ("int main(int argc, char *argv[])", "int (int, char **)"),
+            ("void qsort(int cmp(int))", "void (int (*)(int))"),
+            ("typedef unsigned long size", "unsigned long"),
+            (
+                "int printf(const char *fmt, ...)",
+                "int (const char *, ...)",
+            ),
+        ] {
+            assert_eq!(
+                type_name(&parse(src)),
+                expected,
+                "Wrong type name for {src}"
+            );
+        }
+    }
+}
@@ -15,28 +15,346 @@
 
 use core::str::FromStr;
 
-use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, format, string::String, vec, vec::Vec};
 use chumsky::{
+    error::RichPattern,
     extra::Full,
+    input::InputRef,
     inspector::Inspector,
+    label::LabelError,
     prelude::*,
-    text::{ident, int, keyword},
+    text::{Char, TextExpected, int, keyword},
+    util::MaybeRef,
 };
 use error::RichWrapper;
+use expr::{ConstExpr, EvalError, const_expr_parser};
 
 use crate::ast::{
-    Declaration, Declarator, PrimitiveType, QualifiedType, RecordKind, Type, TypeQualifier,
-    TypeQualifiers,
+    ArrayQualifiers, ArraySize, Attribute, BitInt, CallingConvention, Declaration, Declarator,
+    Designator, Enumerator, Initializer, ListInitializer, Member, PrimitiveType, QualifiedType,
+    Record, RecordBody, RecordKind, StorageClass, Type, TypeQualifier, TypeQualifiers,
 };
+use crate::builtins::{BuiltinTypedef, Profile};
+use crate::semantics::check_declaration;
+use crate::standard::{Standard, is_keyword, primitive_type_since};
 
 mod error;
+mod expr;
 
 pub type Extra<'src> = Full<RichWrapper<'src>, State, ()>;
 
+/// Declaration paired with the span of the source text declaring it
+pub type SpannedDeclaration<'src> = (Declaration<'src>, SimpleSpan);
+
+/// Type name, i.e. a type and an abstract declarator, as in a cast
+pub type TypeName<'src> = (QualifiedType<'src>, Declarator<'src>);
+
 /// Parser state
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+///
+/// The default state knows the [builtin typedefs][crate::builtins] of the
+/// [`Stdint`][Profile::Stdint] profile.
+// The flags are independent options, so they wouldn't be clearer as an enum.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct State {
-    custom_types: Vec<String>,
+    /// Names of the types declared by typedefs so far, with the source of the declarations
+    /// declaring them
+    custom_types: Vec<(String, String)>,
+    /// Lists of typedefs which are known without being declared
+    builtin_typedefs: Vec<&'static [BuiltinTypedef]>,
+    /// Names and values of enumeration constants declared so far
+    enum_constants: Vec<(Option<String>, String, i64)>,
+    /// Names and values of object-like macros defined as integer constants so far
+    macros: Vec<(String, i64)>,
+    /// Kinds and tags of the records named by top-level declarations so far
+    record_tags: Vec<(RecordKind, String)>,
+    /// Whether K&R-style function declarators are accepted
+    kr_parameters: bool,
+    /// Whether a missing type specifier defaults to `int`
+    implicit_int: bool,
+    /// Whether C++ references and namespace-qualified type names are accepted
+    cpp: bool,
+    /// Version of the C standard whose features are accepted
+    standard: Standard,
+    /// Whether the most recently parsed top-level declarator declares a function, meaning
+    /// a function body may follow it
+    declares_function: bool,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            custom_types: Vec::new(),
+            builtin_typedefs: Profile::Stdint.typedefs().to_vec(),
+            enum_constants: Vec::new(),
+            macros: Vec::new(),
+            record_tags: Vec::new(),
+            kr_parameters: false,
+            implicit_int: false,
+            cpp: false,
+            standard: Standard::default(),
+            declares_function: false,
+        }
+    }
+}
+
+impl State {
+    /// Enables or disables parsing of K&R-style (old-style) function declarators, i.e. those
+    /// with an identifier list followed by parameter declarations such as
+    /// `int foo(a, b) int a; char *b;`. All declarations following such a declarator are
+    /// treated as parameter declarations.
+    #[must_use]
+    pub fn with_kr_parameters(mut self, enabled: bool) -> Self {
+        self.kr_parameters = enabled;
+        self
+    }
+
+    /// Enables or disables lenient parsing of declarations without a type specifier, like
+    /// `register x;` or `foo();`, whose type defaults to `int` as in pre-C99 code. A declaration
+    /// with no specifiers at all must declare a function.
+    #[must_use]
+    pub fn with_implicit_int(mut self, enabled: bool) -> Self {
+        self.implicit_int = enabled;
+        self
+    }
+
+    /// Enables or disables C++ mode, in which references like `int &r`, namespace-qualified
+    /// type names like `std::string`, and pointers to members like `int (C::*f)(int) const` are
+    /// accepted. Qualified type names and classes are assumed to be declared elsewhere, since
+    /// namespaces and classes can't be declared.
+    #[must_use]
+    pub fn with_cpp(mut self, enabled: bool) -> Self {
+        self.cpp = enabled;
+        self
+    }
+
+    /// Selects the version of the C standard to accept. Features introduced by later versions
+    /// are reported as errors. Defaults to the latest version.
+    #[must_use]
+    pub fn with_standard(mut self, standard: Standard) -> Self {
+        self.standard = standard;
+        self
+    }
+
+    /// Adds the builtin typedefs of the given profile to the known types.
+    #[must_use]
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        for &typedefs in profile.typedefs() {
+            if !self.builtin_typedefs.contains(&typedefs) {
+                self.builtin_typedefs.push(typedefs);
+            }
+        }
+        self
+    }
+
+    /// Removes all builtin typedefs, so that only types declared by parsed `typedef`
+    /// declarations are known.
+    #[must_use]
+    pub fn without_builtin_typedefs(mut self) -> Self {
+        self.builtin_typedefs.clear();
+        self
+    }
+
+    /// Returns whether declarations without a type specifier are accepted. See
+    /// [`with_implicit_int`](Self::with_implicit_int).
+    #[must_use]
+    pub fn implicit_int(&self) -> bool {
+        self.implicit_int
+    }
+
+    /// Returns whether C++ mode is enabled. See [`with_cpp`](Self::with_cpp).
+    #[must_use]
+    pub fn cpp(&self) -> bool {
+        self.cpp
+    }
+
+    /// Returns the version of the C standard whose features are accepted.
+    #[must_use]
+    pub fn standard(&self) -> Standard {
+        self.standard
+    }
+
+    /// Returns the profiles whose builtin typedefs are all known.
+    pub fn profiles(&self) -> impl Iterator<Item = Profile> {
+        Profile::ALL.into_iter().filter(|profile| {
+            profile
+                .typedefs()
+                .iter()
+                .all(|typedefs| self.builtin_typedefs.contains(typedefs))
+        })
+    }
+
+    /// Forgets everything declared by the declarations parsed so far, i.e. typedefs, enumeration
+    /// constants, macros, and record tags. Options and builtin typedefs are kept.
+    pub fn clear_declarations(&mut self) {
+        self.custom_types.clear();
+        self.enum_constants.clear();
+        self.macros.clear();
+        self.record_tags.clear();
+        self.declares_function = false;
+    }
+
+    /// Returns `true` if the given name is a typedef name, either declared or builtin.
+    fn is_type_name(&self, name: &str) -> bool {
+        self.custom_types.iter().any(|(ty, _)| ty == name)
+            || self
+                .builtin_typedefs
+                .iter()
+                .flat_map(|typedefs| typedefs.iter())
+                .any(|typedef| typedef.name == name)
+    }
+
+    /// Returns the value of the enumeration constant or macro with the given name, if one has
+    /// been declared.
+    fn constant(&self, name: &str) -> Option<i64> {
+        let enum_constants = self
+            .enum_constants
+            .iter()
+            .rev()
+            .map(|(_, constant, value)| (constant, value));
+        self.macros
+            .iter()
+            .map(|(constant, value)| (constant, value))
+            .chain(enum_constants)
+            .find(|(constant, _)| *constant == name)
+            .map(|(_, &value)| value)
+    }
+
+    /// Handles a preprocessor directive, given as the text following the `#`. Object-like
+    /// macros whose replacement is an integer constant expression are recorded by `#define` and
+    /// forgotten by `#undef`. Other directives are ignored.
+    fn apply_directive(&mut self, directive: &str) {
+        let directive = directive.replace("\\\r\n", " ").replace("\\\n", " ");
+        let mut words = directive.trim_start().splitn(2, char::is_whitespace);
+        let (Some(command @ ("define" | "undef")), Some(rest)) = (words.next(), words.next())
+        else {
+            return;
+        };
+        let rest = rest.trim_start();
+        let name_len = rest
+            .find(|c: char| c != '_' && !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        let (name, replacement) = rest.split_at(name_len);
+        self.macros.retain(|(defined, _)| defined != name);
+        // Function-like macros have a parameter list directly after the name.
+        if command == "undef" || name.is_empty() || replacement.starts_with('(') {
+            return;
+        }
+        let lookup = |name: &str| self.constant(name).map(i128::from);
+        let value = const_expr_parser()
+            .parse(replacement.trim())
+            .into_output()
+            .and_then(|expr| expr.evaluate(&lookup).ok().flatten())
+            .and_then(|value| i64::try_from(value).ok());
+        if let Some(value) = value {
+            self.macros.push((name.to_owned(), value));
+        }
+    }
+
+    /// Records the enumeration constants declared by the base type of the given declaration,
+    /// if it is an enum with a body.
+    fn register_enum_constants(&mut self, decl: &Declaration) {
+        let Type::Record(Record {
+            name: tag,
+            body: Some(RecordBody::Enumerators(enumerators)),
+            ..
+        }) = &decl.base_type.1
+        else {
+            return;
+        };
+        for (enumerator, value) in Enumerator::values(enumerators) {
+            self.enum_constants
+                .push((tag.map(str::to_owned), enumerator.name.to_owned(), value));
+        }
+    }
+
+    /// Records the tag of the base type of the given declaration, if it is a named record which
+    /// hasn't been recorded yet.
+    fn register_record_tag(&mut self, decl: &Declaration) {
+        if let Type::Record(Record {
+            kind,
+            name: Some(name),
+            ..
+        }) = decl.base_type.1
+            && !self
+                .record_tags
+                .iter()
+                .any(|(k, n)| *k == kind && n == name)
+        {
+            self.record_tags.push((kind, name.to_owned()));
+        }
+    }
+
+    /// Returns the names of the types declared by the typedefs parsed so far, each with the
+    /// source of the declaration which declares it, like `("byte", "typedef unsigned char byte")`,
+    /// in the order they were declared. A declaration which declares several types is given for
+    /// each of them, and a type which was declared again is given with its most recent
+    /// declaration.
+    pub fn custom_types(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.custom_types
+            .iter()
+            .map(|(name, decl)| (name.as_str(), decl.as_str()))
+    }
+
+    /// Returns C source which declares everything declared by the declarations parsed so far,
+    /// i.e. macros, enumeration constants, record tags, and typedefs, one declaration per line.
+    /// Parsing it with a state which has the same options and nothing declared declares the same
+    /// things again, e.g. to restore a saved session.
+    #[must_use]
+    pub fn to_source(&self) -> String {
+        let mut lines = Vec::new();
+        for (name, value) in &self.macros {
+            lines.push(format!("#define {name} {value}\n"));
+        }
+        // Only the most recent value of each enumeration constant is in effect. Enum tags are
+        // declared along with their enumerators, since an enum can't be declared without them.
+        let in_effect = self
+            .enum_constants
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, name, _))| {
+                !self.enum_constants[i + 1..]
+                    .iter()
+                    .any(|(_, later, _)| later == name)
+            })
+            .map(|(_, constant)| constant);
+        let mut enums: Vec<(Option<&str>, Vec<String>)> = Vec::new();
+        for (tag, name, value) in in_effect {
+            let enumerator = format!("{name} = {value}");
+            match enums.last_mut() {
+                Some((last, enumerators)) if *last == tag.as_deref() => {
+                    enumerators.push(enumerator);
+                }
+                _ => enums.push((tag.as_deref(), vec![enumerator])),
+            }
+        }
+        for (tag, enumerators) in enums {
+            let tag = tag.map(|tag| format!(" {tag}")).unwrap_or_default();
+            lines.push(format!("enum{tag} {{ {} }};\n", enumerators.join(", ")));
+        }
+        for (kind, tag) in self.record_tags() {
+            if kind != RecordKind::Enum {
+                lines.push(format!("{};\n", Record::named(kind, tag)));
+            }
+        }
+        // A declaration of several types is listed once for each of them.
+        let mut previous = None;
+        for (_, decl) in self.custom_types() {
+            if previous != Some(decl) {
+                lines.push(format!("{decl};\n"));
+            }
+            previous = Some(decl);
+        }
+        lines.concat()
+    }
+
+    /// Returns the kinds and tags of the records named by the top-level declarations parsed so
+    /// far, like `struct node`, in the order they first appeared.
+    pub fn record_tags(&self) -> impl Iterator<Item = (RecordKind, &str)> {
+        self.record_tags
+            .iter()
+            .map(|(kind, name)| (*kind, name.as_str()))
+    }
 }
 
 impl<'src, I: Input<'src>> Inspector<'src, I> for State {
@@ -57,18 +375,47 @@ impl<'src, I: Input<'src>> Inspector<'src, I> for State {
     }
 }
 
+/// Returns a parser which behaves like `parser`, but reports an error if `feature` was
+/// introduced after the standard selected in the [`State`], i.e. if the standard is older than
+/// `since`.
+fn since<'src, O>(
+    since: Standard,
+    feature: &'static str,
+    parser: impl Parser<'src, &'src str, O, Extra<'src>> + Clone,
+) -> impl Parser<'src, &'src str, O, Extra<'src>> + Clone {
+    parser.validate(move |out, info, emitter| {
+        let state: &mut State = info.state();
+        if state.standard < since {
+            emitter.emit(
+                Rich::custom(
+                    info.span(),
+                    format!("{feature} is not available before {since}"),
+                )
+                .into(),
+            );
+        }
+        out
+    })
+}
+
 /// From <https://www.open-std.org/jtc1/sc22/WG14/www/docs/n1256.pdf> section 6.7.2.
 #[must_use]
 fn primitive_type_parser<'src>() -> impl Parser<'src, &'src str, PrimitiveType, Extra<'src>> + Clone
 {
     /// Macro to generate choices from a nicer syntax.
     /// Turns something like `unsigned long int` into
-    /// `keyword("unsigned").padded().then(keyword("long").padded()).then(keyword("int").padded)`.
+    ///
+    /// ```text
+    /// keyword("unsigned").padded_by(padding_parser())
+    ///     .then(keyword("long").padded_by(padding_parser()))
+    ///     .then(keyword("int").padded_by(padding_parser()))
+    ///     .to(PrimitiveType("unsigned long int"))
+    /// ```
     macro_rules! gen_choices {
         ( $( $first:ident $($more:ident)* , )* ) => {
             choice(( $(
-                keyword(stringify!($first)).padded()
-                $(.then(keyword(stringify!($more)).padded()))*
+                keyword(stringify!($first)).padded_by(padding_parser())
+                $(.then(keyword(stringify!($more)).padded_by(padding_parser())))*
                 .to(PrimitiveType(stringify!($first $($more)*))),
             )* ))
         };
@@ -113,286 +460,1773 @@ fn primitive_type_parser<'src>() -> impl Parser<'src, &'src str, PrimitiveType,
             char,
             int,
             _Bool,
+            // C23 types
+            bool,
+            nullptr_t,
+            char8_t,
+            // Wide character types
+            wchar_t,
+            char16_t,
+            char32_t,
+            // Decimal floating types
+            _Decimal32,
+            _Decimal64,
+            _Decimal128,
         ],
     ))
-    .padded()
+    .validate(|ty: PrimitiveType, info, emitter| {
+        let state: &mut State = info.state();
+        if let Some(since) = primitive_type_since(ty.0)
+            && state.standard < since
+        {
+            emitter.emit(
+                Rich::custom(info.span(), format!("{ty} is not available before {since}")).into(),
+            );
+        }
+        ty
+    })
+    .padded_by(padding_parser())
     .labelled("primitive type")
 }
 
-/// Helper enum to represent the possible suffixes of a declarator. This is needed so we have one
-/// concrete type which can be used for all suffixes, allowing us to mix suffixes inside
-/// a `choice().repeated()`, which requires the same type for all branches.
-#[derive(Debug, Clone)]
-enum SuffixInfo<'src> {
-    Array(Option<usize>),
-    Function(Vec<Declaration<'src>>),
+/// Returns a parser which parses a record type given parsers for its keyword and its body. The
+/// record must have a tag, a body, or both.
+#[must_use]
+fn record_parser<'src>(
+    kind: impl Parser<'src, &'src str, RecordKind, Extra<'src>> + Clone,
+    body: impl Parser<'src, &'src str, RecordBody<'src>, Extra<'src>> + Clone,
+) -> impl Parser<'src, &'src str, Type<'src>, Extra<'src>> + Clone {
+    kind.then(attributes_parser())
+        .then(choice((
+            identifier_parser()
+                .padded_by(padding_parser())
+                .map(Some)
+                // A body following the tag must be valid, so that errors within it are reported
+                // instead of the body being left for the declarator to reject.
+                .then(choice((body.clone().map(Some), just('{').not().to(None)))),
+            body.map(|body| (None, Some(body))),
+        )))
+        .map(|((kind, attributes), (name, body))| {
+            Type::Record(Record {
+                kind,
+                name,
+                body,
+                attributes,
+            })
+        })
 }
 
-/// Returns a parser which parses a C declaration.
-#[allow(clippy::too_many_lines)]
+/// Returns a parser which parses a GNU attribute specifier, like
+/// `__attribute__((packed, aligned(8)))`, a C23 attribute specifier, like
+/// `[[gnu::packed, deprecated("reason")]]`, or an MSVC `__declspec(dllimport)` specifier.
 #[must_use]
-pub fn parser<'src>() -> impl Parser<'src, &'src str, Vec<Declaration<'src>>, Extra<'src>> {
-    // Parses a declaration. Returns `Declaration`.
-    let declaration = recursive(|declaration| {
-        // Parses zero or more type qualifiers. Returns `TypeQualifiers`.
-        let qualifiers = choice((
-            keyword("const").to(TypeQualifier::Const),
-            keyword("volatile").to(TypeQualifier::Volatile),
-            keyword("restrict").to(TypeQualifier::Restrict),
-        ))
-        .labelled("type qualifier")
-        .padded()
-        .repeated()
-        .collect::<TypeQualifiers>();
-
-        let primitive_type = primitive_type_parser();
-        let r#type = choice((
-            // Primitive type
-            primitive_type.map(Type::Primitive),
-            // Record (struct/union/enum) type
-            choice([keyword("struct"), keyword("union"), keyword("enum")])
-                .map(|k| RecordKind::from_str(k).unwrap())
-                .then(ident().padded())
-                .map(|(kind, id)| Type::Record(kind, id)),
-            // Custom (typedef) type
-            ident()
-                .padded()
-                .try_map_with(|ident: &str, info| {
-                    let state: &mut State = info.state();
-                    if state.custom_types.iter().any(|ty| ty == ident) {
-                        Ok(Type::Custom(ident))
-                    } else {
-                        Err(Rich::custom(
-                            info.span(),
-                            format!("\"{ident}\" is used as a type but has not been defined"),
-                        )
-                        .into())
-                    }
-                })
-                .labelled("custom type"),
-        ))
-        .labelled("type");
-        let qualified_type = qualifiers.clone().then(r#type).map(QualifiedType::from);
-
-        let declarator = recursive(|declarator| {
-            // Parses a declarator atom: either an identifier or parenthesized declarator.
-            // Returns `Declarator`.
-            let atom = choice((
-                ident().map(Declarator::Ident),
-                declarator
-                    .clone()
-                    .delimited_by(just('(').padded(), just(')').padded()),
-            ));
+fn attribute_specifier_parser<'src>()
+-> impl Parser<'src, &'src str, Vec<Attribute<'src>>, Extra<'src>> + Clone {
+    // Parses a single attribute with optional arguments. Returns `Attribute`. C23 attribute
+    // names may have a prefix, e.g. `gnu::packed`, which is kept as part of the name.
+    let attribute = identifier_parser()
+        .then(
+            just("::")
+                .padded_by(padding_parser())
+                .then(identifier_parser())
+                .or_not(),
+        )
+        .to_slice()
+        .padded_by(padding_parser())
+        .then(
+            balanced_parser()
+                .to_slice()
+                .map(str::trim)
+                .delimited_by(just('('), just(')'))
+                .padded_by(padding_parser())
+                .or_not(),
+        )
+        .map(|(name, args)| Attribute { name, args });
 
-            // Parses array declarator suffix. Returns `SuffixInfo`.
-            let array_suffix = int(10)
-                .try_map(|s, span| usize::from_str(s).map_err(|err| Rich::custom(span, err).into()))
-                .or_not()
-                .delimited_by(just('[').padded(), just(']').padded())
-                .labelled("array brackets");
+    let attribute_list = attribute
+        .clone()
+        .separated_by(just(',').padded_by(padding_parser()))
+        .allow_trailing()
+        .collect::<Vec<Attribute>>();
 
-            // Parses function parameter list. Returns `Vec<Declaration>`.
-            let func_param_list = declaration
-                .labelled("function parameter")
-                .separated_by(just(',').padded())
-                .allow_trailing()
-                .collect::<Vec<Declaration>>();
-
-            // Parses function declarator suffix. Returns `SuffixInfo`.
-            let func_suffix = choice((
-                // Special case: func(void) means no parameters
-                keyword("void")
-                    .delimited_by(just('(').padded(), just(')').padded())
-                    .to(Vec::new()),
-                func_param_list.delimited_by(just('(').padded(), just(')').padded()),
-            ))
-            .labelled("function parentheses");
+    let gnu = choice((keyword("__attribute__"), keyword("__attribute")))
+        .padded_by(padding_parser())
+        .ignore_then(attribute_list.clone().delimited_by(
+            just('(').padded_by(padding_parser()).then(just('(')),
+            just(')').padded_by(padding_parser()).then(just(')')),
+        ));
 
-            // Parses atom with zero or more suffixes.
-            // Returns `Declarator`.
-            let with_suffixes = atom
-                .or_not()
-                .map(|atom| atom.unwrap_or(Declarator::Anonymous))
-                .foldl(
-                    choice((
-                        array_suffix.map(SuffixInfo::Array),
-                        func_suffix.map(SuffixInfo::Function),
-                    ))
-                    .repeated(),
-                    |inner, suffix| match suffix {
-                        SuffixInfo::Array(size) => Declarator::Array(Box::new(inner), size),
-                        SuffixInfo::Function(params) => Declarator::Function {
-                            func: Box::new(inner),
-                            params,
-                        },
-                    },
-                );
+    let c23 = since(
+        Standard::C23,
+        "[[...]] attribute syntax",
+        attribute_list.delimited_by(
+            just('[').then(just('[')),
+            just(']').padded_by(padding_parser()).then(just(']')),
+        ),
+    );
 
-            // Parses a suffixed atom with zero or more pointer prefixes.
-            // Returns `Declarator`.
-            just('*')
-                .padded()
-                .ignore_then(qualifiers)
+    // MSVC attributes are separated by whitespace rather than commas.
+    let declspec = keyword("__declspec")
+        .padded_by(padding_parser())
+        .ignore_then(
+            attribute
                 .repeated()
-                .foldr(with_suffixes, |qualifiers, inner| {
-                    Declarator::Ptr(Box::new(inner), qualifiers)
+                .collect::<Vec<Attribute>>()
+                .delimited_by(just('(').padded_by(padding_parser()), just(')')),
+        );
+
+    choice((gnu, c23, declspec))
+        .padded_by(padding_parser())
+        .labelled("attribute")
+}
+
+/// Returns a parser which parses a GNU asm label, like `asm("r11")` or `__asm__("stat")`.
+/// Returns the contents of the string literal.
+#[must_use]
+fn asm_label_parser<'src>() -> impl Parser<'src, &'src str, &'src str, Extra<'src>> + Clone {
+    choice((keyword("asm"), keyword("__asm__"), keyword("__asm")))
+        .padded_by(padding_parser())
+        .ignore_then(
+            quoted_parser('"')
+                .to_slice()
+                .map(|literal: &str| &literal[1..literal.len() - 1])
+                .padded_by(padding_parser())
+                .delimited_by(just('('), just(')')),
+        )
+        .padded_by(padding_parser())
+        .labelled("asm label")
+}
+
+/// Returns a parser which parses zero or more attribute specifiers. Returns the attributes from
+/// all of the specifiers.
+#[must_use]
+fn attributes_parser<'src>()
+-> impl Parser<'src, &'src str, Vec<Attribute<'src>>, Extra<'src>> + Clone {
+    attribute_specifier_parser()
+        .repeated()
+        .collect::<Vec<Vec<Attribute>>>()
+        .map(|lists| lists.into_iter().flatten().collect())
+}
+
+/// Returns a parser which parses the body of an enum definition. Enumerator values may be
+/// constant expressions referring to earlier enumerators, enumeration constants, and macros.
+#[must_use]
+fn enumerators_parser<'src>() -> impl Parser<'src, &'src str, RecordBody<'src>, Extra<'src>> + Clone
+{
+    // Parses a constant expression. Returns `(ConstExpr, SimpleSpan)`.
+    let value = const_expr_parser()
+        .map_with(|expr, info| (expr, info.span()))
+        .labelled("enumerator value");
+
+    identifier_parser()
+        .padded_by(padding_parser())
+        .then(
+            just('=')
+                .padded_by(padding_parser())
+                .ignore_then(value)
+                .or_not(),
+        )
+        .labelled("enumerator")
+        .separated_by(just(',').padded_by(padding_parser()))
+        .at_least(1)
+        .allow_trailing()
+        .collect::<Vec<_>>()
+        .delimited_by(
+            just('{').padded_by(padding_parser()),
+            just('}').padded_by(padding_parser()),
+        )
+        .validate(|enumerators, info, emitter| {
+            let state: &mut State = info.state();
+            // Names and values of the enumerators parsed so far
+            let mut values: Vec<(&str, i64)> = Vec::new();
+            let mut next = 0;
+            enumerators
+                .into_iter()
+                .map(|(name, value): (&str, Option<(ConstExpr, SimpleSpan)>)| {
+                    let value = value.and_then(|(expr, span)| {
+                        let lookup = |name: &str| {
+                            values
+                                .iter()
+                                .rev()
+                                .find(|(enumerator, _)| *enumerator == name)
+                                .map(|&(_, value)| value)
+                                .or_else(|| state.constant(name))
+                                .map(i128::from)
+                        };
+                        let error = match expr.evaluate(&lookup) {
+                            Ok(Some(value)) => match i64::try_from(value) {
+                                Ok(value) => return Some(value),
+                                Err(_) => Rich::custom(span, EvalError::Overflow),
+                            },
+                            Ok(None) => Rich::custom(
+                                span,
+                                format!("value of \"{name}\" is not an integer constant"),
+                            ),
+                            Err(err) => Rich::custom(span, err),
+                        };
+                        emitter.emit(error.into());
+                        None
+                    });
+                    // Implicit values are computed as in `Enumerator::values()`.
+                    let known = value.unwrap_or(next);
+                    next = known.wrapping_add(1);
+                    values.push((name, known));
+                    Enumerator { name, value }
                 })
-        });
+                .collect::<Vec<Enumerator>>()
+        })
+        .map(RecordBody::Enumerators)
+}
 
-        qualified_type
-            .then(declarator)
-            .map(Declaration::from)
-            .padded()
-    });
+/// Returns a parser which parses an identifier. Besides the characters accepted by
+/// [`text::ident()`], identifiers may contain universal character names like `\u00e9` and
+/// `\U0001D400`. Returns the source text of the identifier.
+#[must_use]
+fn identifier_parser<'src>() -> impl Parser<'src, &'src str, &'src str, Extra<'src>> + Clone {
+    // Like `filter()`, but reports errors at the start of the character, as `text::ident()` does
+    let char_where = |predicate: fn(&char) -> bool| {
+        any().try_map(move |c, span| {
+            if predicate(&c) {
+                Ok(c)
+            } else {
+                Err(LabelError::expected_found(
+                    [TextExpected::<&str>::IdentifierPart],
+                    Some(MaybeRef::Val(c)),
+                    span,
+                ))
+            }
+        })
+    };
+    let hex_digits = move |count| {
+        char_where(char::is_ascii_hexdigit)
+            .repeated()
+            .exactly(count)
+            .to_slice()
+    };
+    // Parses a universal character name. Returns the character it names.
+    let universal_character_name = just('\\')
+        .ignore_then(choice((
+            just('u').ignore_then(hex_digits(4)),
+            just('U').ignore_then(hex_digits(8)),
+        )))
+        .validate(|digits: &str, info, emitter| {
+            let c = u32::from_str_radix(digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .filter(Char::is_ident_continue);
+            c.unwrap_or_else(|| {
+                emitter.emit(
+                    Rich::custom(
+                        info.span(),
+                        format!("invalid universal character name \"{}\"", info.slice()),
+                    )
+                    .into(),
+                );
+                '_'
+            })
+        });
 
     choice((
-        // Parses a typedef declaration. Returns `Declaration`.
-        keyword("typedef")
-            .padded()
-            .ignore_then(declaration.clone())
-            .map_with(|mut decl, info| {
-                // If the typedef has a name, add it to the custom types in the state.
-                if let Some(name) = decl.declarator.name() {
-                    let state: &mut State = info.state();
-                    state.custom_types.push(name.to_owned());
-                }
-                // Add the typedef qualifier and return the declaration.
-                decl.base_type.0.insert(TypeQualifier::Typedef);
-                decl
-            }),
-        // Parses a regular declaration. Returns `Declaration`.
-        declaration,
+        char_where(Char::is_ident_start).ignored(),
+        universal_character_name.try_map(|c, span| {
+            if c.is_ident_start() {
+                Ok(())
+            } else {
+                Err(Rich::custom(span, "identifier cannot start with this character").into())
+            }
+        }),
     ))
-    .separated_by(just(';').padded().repeated().at_least(1))
-    .allow_trailing()
-    .collect()
+    .then(
+        choice((
+            char_where(Char::is_ident_continue).ignored(),
+            universal_character_name.ignored(),
+        ))
+        .repeated(),
+    )
+    .to_slice()
+    .labelled("identifier")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Returns the error message for a keyword used as the name of a declaration.
+pub(crate) fn keyword_as_name(name: &str) -> String {
+    format!("\"{name}\" is a keyword, so it cannot be used as a name")
+}
 
-    use alloc::{format, string::ToString, vec, vec::Vec};
-    use pretty_assertions::assert_eq;
+/// Checks that the text can name a declaration in the given standard, i.e. that it is a single
+/// identifier which isn't a keyword.
+///
+/// # Errors
+///
+/// Returns a message describing why the text can't be used as a name.
+pub(crate) fn check_name(name: &str, standard: Standard) -> Result<(), String> {
+    if is_keyword(name, standard) {
+        Err(keyword_as_name(name))
+    } else if identifier_parser().parse(name).has_errors() {
+        Err(format!("\"{name}\" is not an identifier"))
+    } else {
+        Ok(())
+    }
+}
 
-    /// Qualified version of [`primitive()`].
-    fn qprimitive<'src, I>(
-        qualifiers: I,
-        r#type: &'static str,
-        declarator: Declarator<'src>,
-    ) -> Declaration<'src>
-    where
-        I: IntoIterator<Item = TypeQualifier>,
-    {
-        Declaration {
-            base_type: QualifiedType(
-                TypeQualifiers(qualifiers.into_iter().collect()),
-                Type::Primitive(PrimitiveType(r#type)),
-            ),
-            declarator,
+/// Skips a `//` or `/* */` comment at the current position. Returns `false` without consuming
+/// anything if there is no comment there. An unterminated `/*` does not start a comment.
+fn skip_comment<'src>(input: &mut InputRef<'src, '_, &'src str, Extra<'src>>) -> bool {
+    let before = input.save();
+    match (input.next(), input.next()) {
+        (Some('/'), Some('/')) => {
+            while input.peek().is_some_and(|c| c != '\n') {
+                input.skip();
+            }
+            return true;
+        }
+        (Some('/'), Some('*')) => {
+            let mut previous = None;
+            while let Some(c) = input.next() {
+                if previous == Some('*') && c == '/' {
+                    return true;
+                }
+                previous = Some(c);
+            }
         }
+        _ => (),
     }
+    input.rewind(before);
+    false
+}
 
-    fn primitive<'src>(r#type: &'static str, declarator: Declarator<'src>) -> Declaration<'src> {
-        qprimitive([], r#type, declarator)
-    }
+/// Returns a parser which skips a `//` or `/* */` comment. Failure produces an error with no
+/// expected patterns, so it doesn't change the error reported at that position.
+fn comment_parser<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    custom(|input| {
+        let before = input.cursor();
+        if skip_comment(input) {
+            Ok(())
+        } else {
+            Err(RichWrapper::expected_found(
+                Vec::<RichPattern<char>>::new(),
+                None,
+                input.span_since(&before),
+            ))
+        }
+    })
+}
 
-    fn qrecord<'src, I>(
-        qualifiers: I,
-        kind: &'static str,
-        name: &'static str,
-        declarator: Declarator<'src>,
-    ) -> Declaration<'src>
-    where
-        I: IntoIterator<Item = TypeQualifier>,
-    {
-        Declaration {
-            base_type: QualifiedType(
-                TypeQualifiers(qualifiers.into_iter().collect()),
-                Type::Record(kind.parse().unwrap(), name),
-            ),
-            declarator,
+/// Returns a parser which skips any amount of whitespace and comments. It is used in place of
+/// [`Parser::padded()`] so that comments may appear anywhere whitespace may. Like `padded()`, it
+/// never fails, so it doesn't add to the expected patterns of errors.
+fn padding_parser<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    custom(|input| {
+        loop {
+            while input.peek().is_some_and(char::is_whitespace) {
+                input.skip();
+            }
+            if !skip_comment(input) {
+                return Ok(());
+            }
         }
-    }
+    })
+}
 
-    fn anon() -> Declarator<'static> {
-        Declarator::Anonymous
-    }
+/// Returns a parser which parses a string or character literal delimited by `quote`.
+fn quoted_parser<'src>(quote: char) -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    just(quote)
+        .then(
+            choice((
+                just('\\').then(any()).ignored(),
+                none_of([quote, '\\']).ignored(),
+            ))
+            .repeated(),
+        )
+        .then(just(quote))
+        .ignored()
+}
 
-    fn qptr<I>(qualifiers: I, val: Declarator) -> Declarator
-    where
-        I: IntoIterator<Item = TypeQualifier>,
-    {
-        Declarator::Ptr(
-            Box::new(val),
-            TypeQualifiers(qualifiers.into_iter().collect()),
+/// Returns a parser which skips anything with balanced delimiters, including commas and
+/// semicolons. It stops at the first unmatched closing delimiter.
+fn balanced_parser<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    recursive(|nested| {
+        choice((
+            quoted_parser('"'),
+            quoted_parser('\''),
+            comment_parser(),
+            nested.clone().delimited_by(just('('), just(')')),
+            nested.clone().delimited_by(just('['), just(']')),
+            nested.clone().delimited_by(just('{'), just('}')),
+            none_of("()[]{}\"'").ignored(),
+        ))
+        .repeated()
+    })
+}
+
+/// Returns a parser which skips the remainder of a malformed declaration so that parsing can
+/// resume after it. It skips up to and including the next top-level `;`, or up to the next
+/// preprocessor directive or the end of input. If `in_block` is true, it also stops before an
+/// unmatched `}`, which closes the enclosing block. It fails if there is nothing but whitespace
+/// and comments to skip.
+fn skip_declaration_parser<'src>(
+    in_block: bool,
+) -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    let boundaries = if in_block { "#}" } else { "#" };
+    let rest = choice((
+        quoted_parser('"'),
+        quoted_parser('\''),
+        comment_parser(),
+        balanced_parser().delimited_by(just('('), just(')')),
+        balanced_parser().delimited_by(just('['), just(']')),
+        balanced_parser().delimited_by(just('{'), just('}')),
+        none_of(';').and_is(one_of(boundaries).not()).ignored(),
+    ))
+    .repeated()
+    .then(choice((
+        just(';').ignored(),
+        one_of(boundaries).rewind().ignored(),
+        end(),
+    )))
+    .to_slice()
+    .try_map(|skipped: &str, span| {
+        if skipped.is_empty() {
+            Err(Rich::custom(span, "nothing to skip").into())
+        } else {
+            Ok(())
+        }
+    });
+    padding_parser().ignore_then(rest)
+}
+
+/// Returns a parser which consumes no input and succeeds only if `predicate` holds for the
+/// [`State`], e.g. if the most recently parsed top-level declarator declares a function or if
+/// an optional feature is enabled. Failure produces an error with no expected patterns, so it
+/// doesn't change the error reported at that position.
+fn state_check<'src>(
+    predicate: fn(&State) -> bool,
+) -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    empty().try_map_with(move |(), info| {
+        let state: &mut State = info.state();
+        if predicate(state) {
+            Ok(())
+        } else {
+            Err(RichWrapper::expected_found(
+                Vec::<RichPattern<char>>::new(),
+                None,
+                info.span(),
+            ))
+        }
+    })
+}
+
+/// Returns a parser which parses a preprocessor directive, i.e. a `#` and the rest of its line,
+/// including lines continued with a backslash. Directives are handled by
+/// [`State::apply_directive()`].
+#[must_use]
+fn directive_parser<'src>() -> impl Parser<'src, &'src str, (), Extra<'src>> + Clone {
+    let line = choice((
+        just('\\').then(text::newline()).ignored(),
+        any().and_is(text::newline().not()).ignored(),
+    ))
+    .repeated()
+    .to_slice();
+
+    just('#')
+        .ignore_then(line)
+        .map_with(|directive: &str, info| {
+            let state: &mut State = info.state();
+            state.apply_directive(directive);
+        })
+        .padded_by(padding_parser())
+        .labelled("preprocessor directive")
+}
+
+/// Returns a parser which parses a function body, skipping its contents. Returns the source text
+/// of the body including the braces.
+fn function_body_parser<'src>() -> impl Parser<'src, &'src str, &'src str, Extra<'src>> + Clone {
+    padding_parser()
+        .ignore_then(
+            balanced_parser()
+                .delimited_by(just('{'), just('}'))
+                .to_slice(),
         )
-    }
+        .labelled("function body")
+}
 
-    fn ptr(val: Declarator) -> Declarator {
-        qptr([], val)
-    }
+/// Returns a parser which parses the source text of an expression without interpreting it. The
+/// expression ends at the first top-level `,`, `;`, or unmatched closing delimiter. Parentheses,
+/// brackets, braces, and quotes must be balanced.
+#[must_use]
+fn expression_text_parser<'src>() -> impl Parser<'src, &'src str, &'src str, Extra<'src>> + Clone {
+    choice((
+        quoted_parser('"'),
+        quoted_parser('\''),
+        balanced_parser().delimited_by(just('('), just(')')),
+        balanced_parser().delimited_by(just('['), just(']')),
+        none_of("()[]{}\"',;").ignored(),
+    ))
+    .repeated()
+    .at_least(1)
+    .to_slice()
+    .map(str::trim)
+    .labelled("expression")
+}
 
-    fn ident(val: &str) -> Declarator<'_> {
-        Declarator::Ident(val)
-    }
+/// Returns a parser which parses an initializer, i.e. an expression or a brace-enclosed list of
+/// possibly-designated initializers.
+#[must_use]
+fn initializer_parser<'src>() -> impl Parser<'src, &'src str, Initializer<'src>, Extra<'src>> + Clone
+{
+    recursive(|initializer| {
+        // Parses a designator. Returns `Designator`.
+        let designator = choice((
+            just('.')
+                .padded_by(padding_parser())
+                .ignore_then(identifier_parser().padded_by(padding_parser()))
+                .map(Designator::Member),
+            expression_text_parser()
+                .delimited_by(
+                    just('[').padded_by(padding_parser()),
+                    just(']').padded_by(padding_parser()),
+                )
+                .map(Designator::Index),
+        ))
+        .labelled("designator");
 
-    fn array(d: Declarator, size: impl Into<Option<usize>>) -> Declarator {
-        Declarator::Array(Box::new(d), size.into())
-    }
+        // Parses an element of an initializer list. Returns `ListInitializer`.
+        let element = designator
+            .repeated()
+            .at_least(1)
+            .collect::<Vec<Designator>>()
+            .then_ignore(just('=').padded_by(padding_parser()))
+            .or_not()
+            .then(initializer)
+            .map(|(designators, initializer)| ListInitializer {
+                designators: designators.unwrap_or_default(),
+                initializer,
+            });
 
-    fn func<'src>(
-        func: Declarator<'src>,
-        args: impl Into<Vec<Declaration<'src>>>,
-    ) -> Declarator<'src> {
-        Declarator::Function {
-            func: Box::new(func),
-            params: args.into(),
+        choice((
+            element
+                .separated_by(just(',').padded_by(padding_parser()))
+                .allow_trailing()
+                .collect::<Vec<ListInitializer>>()
+                .delimited_by(
+                    just('{').padded_by(padding_parser()),
+                    just('}').padded_by(padding_parser()),
+                )
+                .map(Initializer::List),
+            expression_text_parser().map(Initializer::Expr),
+        ))
+        .labelled("initializer")
+    })
+}
+
+/// Error message for K&R-style function declarators when they are not enabled.
+const KR_PARAMETERS_DISABLED: &str = "K&R-style function declarators are not enabled";
+
+/// Replaces the implicit `int` parameters of a K&R-style function declarator with the
+/// declarations from the parameter declaration list following it.
+///
+/// Returns an error message if the declarator is not a K&R-style function declarator or if one
+/// of the declarations doesn't name a parameter.
+fn declare_kr_parameters<'src>(
+    declarator: &mut Declarator<'src>,
+    declarations: Vec<Declaration<'src>>,
+) -> Result<(), String> {
+    /// Returns the parameters of the function declarator closest to the identifier.
+    fn innermost_params<'a, 'src>(
+        declarator: &'a mut Declarator<'src>,
+    ) -> Option<&'a mut Vec<Declaration<'src>>> {
+        match declarator {
+            Declarator::Anonymous | Declarator::Ident(_) => None,
+            Declarator::Ptr(inner, _)
+            | Declarator::Reference(inner)
+            | Declarator::Block(inner, _)
+            | Declarator::MemberPtr(inner, ..)
+            | Declarator::Array(inner, ..) => innermost_params(inner),
+            Declarator::Function { func, params, .. } => match innermost_params(func) {
+                Some(inner_params) => Some(inner_params),
+                None => Some(params),
+            },
         }
     }
 
-    #[test]
-    fn test_basic_int_var() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: ident("myvar123"),
+    let params = innermost_params(declarator)
+        .filter(|params| !params.is_empty() && params.iter().all(|param| param.implicit_int))
+        .ok_or("parameter declarations require a function declarator with an identifier list")?;
+    for declaration in declarations {
+        let name = declaration.declarator.name();
+        let Some(param) = params
+            .iter_mut()
+            .find(|param| param.implicit_int && param.declarator.name() == name)
+        else {
+            return Err(match name {
+                Some(name) => format!("\"{name}\" is declared but is not a parameter"),
+                None => "parameter declaration has no name".to_owned(),
+            });
         };
-        assert_eq!(vec![expected], parser().parse("int myvar123").unwrap());
+        *param = declaration;
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_basic_int_ptr_vars() {
-        let expected = vec![Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: ptr(ident("p")),
-        }];
-        let cases = ["int *p", "int*p", "int* p", "int *\np"];
-        for case in cases {
-            assert_eq!(expected, parser().parse(case).unwrap());
+/// Returns a declaration with the specifiers and type of `spec`, which is parsed by the
+/// specified type parser, and the given declarator and attributes. This is used to share one
+/// base type among several declarators, like in `int a, *b;`.
+fn with_declarator<'src>(
+    spec: &Declaration<'src>,
+    declarator: Declarator<'src>,
+    attributes: Vec<Attribute<'src>>,
+) -> Declaration<'src> {
+    let mut decl = Declaration {
+        declarator,
+        ..spec.clone()
+    };
+    decl.attributes.extend(attributes);
+    decl
+}
+
+/// Returns `true` if the declaration consists of only a named record type without a body, like
+/// `struct node`. Qualifiers, storage classes, and attributes make it more than a tag.
+fn is_tag_only(decl: &Declaration) -> bool {
+    matches!(
+        decl,
+        Declaration {
+            base_type: QualifiedType(qualifiers, Type::Record(Record {
+                name: Some(_),
+                body: None,
+                attributes: record_attributes,
+                ..
+            })),
+            storage_class: None,
+            thread_local: false,
+            inline: false,
+            declarator: Declarator::Anonymous,
+            attributes,
+            ..
+        } if qualifiers.is_empty() && record_attributes.is_empty() && attributes.is_empty()
+    )
+}
+
+/// Returns the width in bits of an integer type, or `None` if it isn't known. `long` is taken to
+/// be as wide as it is in any data model.
+fn integer_width(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Primitive(primitive) => {
+            // Signedness doesn't affect the width, and `int` is implied by the other types.
+            let name = primitive.as_ref();
+            let name = name
+                .strip_prefix("unsigned")
+                .or_else(|| name.strip_prefix("signed"))
+                .map_or(name, str::trim_start);
+            Some(match name.strip_suffix(" int").unwrap_or(name) {
+                "_Bool" | "bool" => 1,
+                "char" => 8,
+                "short" => 16,
+                "" | "int" => 32,
+                "long" | "long long" => 64,
+                _ => return None,
+            })
         }
+        Type::BitInt(BitInt { width, .. }) => Some(*width),
+        _ => None,
     }
+}
 
-    #[test]
-    fn test_nested_ptrs() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("char")).into(),
-            declarator: ptr(ptr(ptr(ident("p")))),
-        };
-        assert_eq!(vec![expected], parser().parse("char ***p").unwrap());
+/// Checks the width of a bit-field with the given type and declarator. A bit-field can't be
+/// wider than its type, and only an unnamed bit-field may have a width of zero.
+fn check_bit_width(ty: &Type, declarator: &Declarator, width: usize) -> Result<(), String> {
+    if width == 0 && declarator.name().is_some() {
+        return Err("a named bit-field cannot have a width of zero".to_owned());
     }
+    // Other declarators don't declare integers, so their widths can't be checked.
+    if let Declarator::Ident(_) | Declarator::Anonymous = declarator
+        && let Some(max) = integer_width(ty)
+        && width > max
+    {
+        return Err(format!(
+            "bit-field width {width} exceeds the {max}-bit width of its type"
+        ));
+    }
+    Ok(())
+}
 
-    #[test]
-    fn test_record_vars() {
-        let cases = [
-            ("struct foo bar", RecordKind::Struct),
-            ("enum foo bar", RecordKind::Enum),
+/// Helper enum to represent the possible suffixes of a declarator. This is needed so we have one
+/// concrete type which can be used for all suffixes, allowing us to mix suffixes inside
+/// a `choice().repeated()`, which requires the same type for all branches.
+#[derive(Debug, Clone)]
+enum SuffixInfo<'src> {
+    Array(ArraySize<'src>, ArrayQualifiers),
+    /// Parameters, whether the function is variadic, and its member function qualifiers
+    Function(Vec<Declaration<'src>>, bool, TypeQualifiers),
+}
+
+/// Helper enum to represent a pointer, C++ reference, block pointer, or C++ member pointer
+/// declarator prefix, so all of them can be parsed by the same `choice().repeated()`.
+#[derive(Debug, Clone)]
+enum PrefixInfo<'src> {
+    Pointer(TypeQualifiers),
+    Reference,
+    Block(TypeQualifiers),
+    /// Class name and qualifiers
+    MemberPointer(&'src str, TypeQualifiers),
+}
+
+/// Helper enum to represent a single declaration specifier. Storage classes and type qualifiers
+/// may appear in any order before the type, so we parse them together and sort them out after.
+#[derive(Debug, Clone)]
+enum Specifier<'src> {
+    StorageClass(StorageClass),
+    ThreadLocal,
+    Inline,
+    Qualifier(TypeQualifier),
+    Attributes(Vec<Attribute<'src>>),
+}
+
+/// Error message for variable length arrays when the selected standard is C89.
+const VLA_BEFORE_C99: &str = "variable length arrays are not available before C99";
+
+/// Error message for declarations with more than one storage class.
+const MULTIPLE_STORAGE_CLASSES: &str = "multiple storage classes in declaration specifiers";
+
+/// Returns a parser which parses a C declaration.
+#[must_use]
+pub fn parser<'src>() -> impl Parser<'src, &'src str, Vec<Declaration<'src>>, Extra<'src>> {
+    grammar()
+        .0
+        .map(|decls| decls.into_iter().map(|(decl, _)| decl).collect())
+}
+
+/// Parses an entire translation unit, such as a header file, so that all of its declarations
+/// can be explained. Preprocessor directives and comments are skipped, function bodies are
+/// kept as text, and typedef names and record tags are recorded in the state as they are
+/// declared. Malformed declarations are reported as errors and skipped.
+///
+/// Each declaration is returned with the span of the source text declaring it. Declarations
+/// sharing a type, like `int a, b;`, share a span.
+pub fn parse_translation_unit<'src>(
+    src: &'src str,
+    state: &mut State,
+) -> ParseResult<Vec<SpannedDeclaration<'src>>, RichWrapper<'src>> {
+    grammar().0.parse_with_state(src, state)
+}
+
+/// Returns a parser which parses a type name, like the operand of a cast or `sizeof`, e.g.
+/// `const char *(*)(void)`. A type name is a type followed by an abstract declarator, which is
+/// one that doesn't declare an identifier.
+#[must_use]
+pub fn type_name_parser<'src>() -> impl Parser<'src, &'src str, TypeName<'src>, Extra<'src>> + Clone
+{
+    grammar().1
+}
+
+/// Returns the parsers for declarations and for type names, which share most of the grammar.
+#[allow(clippy::too_many_lines)]
+fn grammar<'src>() -> (
+    impl Parser<'src, &'src str, Vec<SpannedDeclaration<'src>>, Extra<'src>>,
+    impl Parser<'src, &'src str, TypeName<'src>, Extra<'src>> + Clone,
+) {
+    // Parses a declaration. Returns `Declaration`.
+    let mut declaration = Recursive::declare();
+    // Parses storage classes, qualifiers, and attributes followed by a type.
+    // Returns `Declaration` with an anonymous declarator.
+    let mut specified_type = Recursive::declare();
+
+    // Parses a type qualifier. Returns `TypeQualifier`.
+    // The double-underscore spellings are GNU and MSVC extensions available in all versions.
+    let qualifier = choice((
+        choice((keyword("const"), keyword("__const"), keyword("__const__")))
+            .to(TypeQualifier::Const),
+        choice((
+            keyword("volatile"),
+            keyword("__volatile"),
+            keyword("__volatile__"),
+        ))
+        .to(TypeQualifier::Volatile),
+        choice((
+            since(Standard::C99, "restrict", keyword("restrict")),
+            keyword("__restrict"),
+            keyword("__restrict__"),
+        ))
+        .to(TypeQualifier::Restrict),
+        // `_Atomic(` is the atomic type specifier, not a qualifier
+        since(
+            Standard::C11,
+            "_Atomic",
+            keyword("_Atomic")
+                .then_ignore(just('(').padded_by(padding_parser()).not())
+                .to(TypeQualifier::Atomic),
+        ),
+    ))
+    .labelled("type qualifier")
+    .padded_by(padding_parser());
+
+    // Parses zero or more type qualifiers. Returns `TypeQualifiers`.
+    let qualifiers = qualifier.clone().repeated().collect::<TypeQualifiers>();
+
+    // Parses a storage class specifier. Returns `StorageClass`.
+    let storage_class = choice((
+        keyword("auto").to(StorageClass::Auto),
+        keyword("register").to(StorageClass::Register),
+        keyword("static").to(StorageClass::Static),
+        keyword("extern").to(StorageClass::Extern),
+    ))
+    .labelled("storage class")
+    .padded_by(padding_parser());
+
+    // Parses a bit-field width. Returns `(usize, SimpleSpan)`, where the span is that of the
+    // width.
+    let bit_width = just(':')
+        .padded_by(padding_parser())
+        .ignore_then(
+            int(10)
+                .try_map(|s, span| {
+                    usize::from_str(s)
+                        .map(|width| (width, span))
+                        .map_err(|err| Rich::custom(span, err).into())
+                })
+                .padded_by(padding_parser()),
+        )
+        .labelled("bit-field width");
+
+    // Parses a declarator. Returns `(Declarator, Option<CallingConvention>)`, where the
+    // calling convention is one which did not apply to any function declarator inside the
+    // declarator and so applies to the next function suffix following it.
+    let declarator = recursive(|declarator| {
+        // Parses a declarator atom: either an identifier or parenthesized declarator.
+        // Returns `(Declarator, Option<CallingConvention>)`.
+        let atom = choice((
+            identifier_parser().validate(|name, info, emitter| {
+                let state: &mut State = info.state();
+                if is_keyword(name, state.standard) {
+                    emitter.emit(Rich::custom(info.span(), keyword_as_name(name)).into());
+                }
+                (Declarator::Ident(name), None)
+            }),
+            declarator.clone().delimited_by(
+                just('(').padded_by(padding_parser()),
+                just(')').padded_by(padding_parser()),
+            ),
+        ));
+
+        // Parses array declarator suffix. Returns `SuffixInfo`.
+        // Parses an array size. Returns `ArraySize`.
+        let array_size = const_expr_parser()
+            .map_with(|expr: ConstExpr, info| {
+                // Enumeration constants and macros declared earlier have known values.
+                let state: &mut State = info.state();
+                let lookup = |name: &str| state.constant(name).map(i128::from);
+                let variable = match expr {
+                    ConstExpr::Ident(name) => Some(name),
+                    _ => None,
+                };
+                (expr.evaluate(&lookup), variable, info.slice())
+            })
+            .validate(|(size, variable, text): (_, _, &str), info, emitter| {
+                let error = match size {
+                    Ok(Some(size)) => match usize::try_from(size) {
+                        Ok(size) => return ArraySize::Fixed(size),
+                        Err(_) if size < 0 => Rich::custom(info.span(), "array size is negative"),
+                        Err(_) => Rich::custom(info.span(), "array size is too large"),
+                    },
+                    // A lone identifier with no known value is a runtime size.
+                    Ok(None) => {
+                        let state: &mut State = info.state();
+                        if variable.is_some() && state.standard < Standard::C99 {
+                            emitter.emit(Rich::custom(info.span(), VLA_BEFORE_C99).into());
+                        }
+                        return variable.map_or(ArraySize::Expr(text.trim()), ArraySize::Variable);
+                    }
+                    Err(err) => Rich::custom(info.span(), err),
+                };
+                emitter.emit(error.into());
+                ArraySize::Unspecified
+            });
+
+        // Parses the type qualifiers and `static` keyword which may precede an array size.
+        // Returns `ArrayQualifiers`.
+        let array_qualifiers = choice((
+            qualifier.clone().map(Some),
+            keyword("static").padded_by(padding_parser()).to(None),
+        ))
+        .repeated()
+        .collect::<Vec<_>>()
+        .map(|specifiers| {
+            let mut qualifiers = ArrayQualifiers::default();
+            for specifier in specifiers {
+                match specifier {
+                    Some(qualifier) => qualifiers.qualifiers.insert(qualifier),
+                    None => qualifiers.is_static = true,
+                }
+            }
+            qualifiers
+        });
+
+        // Parses array declarator suffix. Returns `(ArraySize, ArrayQualifiers)`.
+        let array_suffix = array_qualifiers
+            .then(
+                choice((
+                    just('*')
+                        .padded_by(padding_parser())
+                        .then_ignore(just(']').rewind())
+                        .to(ArraySize::VariableUnspecified)
+                        .validate(|size, info, emitter| {
+                            let state: &mut State = info.state();
+                            if state.standard < Standard::C99 {
+                                emitter.emit(Rich::custom(info.span(), VLA_BEFORE_C99).into());
+                            }
+                            size
+                        }),
+                    array_size,
+                ))
+                .or_not()
+                .map(Option::unwrap_or_default),
+            )
+            .try_map(|(qualifiers, size), span| {
+                if qualifiers.is_static
+                    && matches!(
+                        size,
+                        ArraySize::Unspecified | ArraySize::VariableUnspecified
+                    )
+                {
+                    return Err(Rich::custom(span, "static array parameter requires a size").into());
+                }
+                Ok((size, qualifiers))
+            })
+            .delimited_by(
+                just('[').padded_by(padding_parser()),
+                just(']').padded_by(padding_parser()),
+            )
+            .labelled("array brackets");
+
+        // Parses the `...` ending the parameter list of a variadic function.
+        let ellipsis = just("...").padded_by(padding_parser()).labelled("'...'");
+
+        // Parses function parameter list, which may end with `...`. A list with only `...` is
+        // allowed since C23. Returns `(Vec<Declaration>, bool)`.
+        let func_param_list = choice((
+            declaration
+                .clone()
+                .labelled("function parameter")
+                .separated_by(just(',').padded_by(padding_parser()))
+                .at_least(1)
+                .collect::<Vec<Declaration>>()
+                .then(
+                    just(',')
+                        .padded_by(padding_parser())
+                        .ignore_then(ellipsis.clone().or_not())
+                        .or_not()
+                        .map(|ellipsis| matches!(ellipsis, Some(Some(_)))),
+                ),
+            since(
+                Standard::C23,
+                "variadic function without named parameters",
+                ellipsis,
+            )
+            .to((Vec::new(), true)),
+            empty().to((Vec::new(), false)),
+        ));
+
+        // Parses a K&R-style identifier list, which is an error unless enabled in the `State`.
+        // Each parameter is an implicit `int` until declared by the declaration list
+        // following the declarator.
+        // Returns `Vec<Declaration>`.
+        let identifier_list = identifier_parser()
+            .padded_by(padding_parser())
+            .labelled("function parameter")
+            .separated_by(just(',').padded_by(padding_parser()))
+            .at_least(1)
+            .collect::<Vec<&str>>()
+            .validate(|names, info, emitter| {
+                let state: &mut State = info.state();
+                if !state.kr_parameters {
+                    emitter.emit(Rich::custom(info.span(), KR_PARAMETERS_DISABLED).into());
+                }
+                names
+            })
+            .map(|names| {
+                names
+                    .into_iter()
+                    .map(|name| Declaration {
+                        implicit_int: true,
+                        ..Declaration::new(
+                            Type::Primitive(PrimitiveType("int")).into(),
+                            Declarator::Ident(name),
+                        )
+                    })
+                    .collect()
+            })
+            .map(|params| (params, false));
+
+        // Parses function declarator suffix. Returns `(Vec<Declaration>, bool)`.
+        let func_suffix = choice((
+            // Special case: func(void) means no parameters
+            keyword("void")
+                .delimited_by(
+                    just('(').padded_by(padding_parser()),
+                    just(')').padded_by(padding_parser()),
+                )
+                .to((Vec::new(), false)),
+            func_param_list.delimited_by(
+                just('(').padded_by(padding_parser()),
+                just(')').padded_by(padding_parser()),
+            ),
+            identifier_list.delimited_by(
+                just('(').padded_by(padding_parser()),
+                just(')').padded_by(padding_parser()),
+            ),
+        ))
+        .labelled("function parentheses");
+
+        // Parses a calling convention keyword. Returns `CallingConvention`.
+        let calling_convention = choice((
+            keyword("__cdecl").to(CallingConvention::Cdecl),
+            keyword("__stdcall").to(CallingConvention::Stdcall),
+            keyword("__fastcall").to(CallingConvention::Fastcall),
+        ))
+        .padded_by(padding_parser())
+        .labelled("calling convention");
+
+        // Parses the qualifiers of a member function (C++), e.g. the `const` in
+        // `int (C::*f)(int) const`. Returns `TypeQualifiers`.
+        let member_qualifiers = state_check(|state| state.cpp)
+            .ignore_then(qualifiers.clone())
+            .or_not()
+            .map(Option::unwrap_or_default);
+
+        // Parses pointer, block pointer, and (in C++ mode) reference and member pointer
+        // prefixes. Returns `Vec<PrefixInfo>`.
+        let pointers = choice((
+            just('*')
+                .padded_by(padding_parser())
+                .ignore_then(qualifiers.clone())
+                .map(PrefixInfo::Pointer),
+            just('^')
+                .padded_by(padding_parser())
+                .ignore_then(qualifiers.clone())
+                .map(PrefixInfo::Block),
+            state_check(|state| state.cpp)
+                .ignore_then(just('&').padded_by(padding_parser()))
+                .to(PrefixInfo::Reference),
+            // Member pointer (C++), e.g. `C::*`
+            state_check(|state| state.cpp)
+                .ignore_then(
+                    just("::")
+                        .or_not()
+                        .then(identifier_parser().separated_by(just("::")).at_least(1))
+                        .to_slice()
+                        .padded_by(padding_parser()),
+                )
+                .then_ignore(just("::").then(just('*').padded_by(padding_parser())))
+                .then(qualifiers)
+                .map(|(class, qualifiers)| PrefixInfo::MemberPointer(class, qualifiers)),
+        ))
+        .repeated()
+        .collect::<Vec<PrefixInfo>>();
+
+        // Parses an atom with zero or more pointer prefixes, an optional calling convention
+        // before or after the pointers, and zero or more suffixes. A calling convention
+        // applies to the first function suffix to its right, which may be outside of the
+        // parentheses enclosing it.
+        // Returns `(Declarator, Option<CallingConvention>)`.
+        choice((
+            calling_convention.clone().map(Some).then(pointers.clone()),
+            pointers
+                .then(calling_convention.or_not())
+                .map(|(p, c)| (c, p)),
+        ))
+        .then(atom.or_not())
+        .then(
+            choice((
+                array_suffix.map(|(size, qualifiers)| SuffixInfo::Array(size, qualifiers)),
+                func_suffix
+                    .then(member_qualifiers)
+                    .map(|((params, variadic), qualifiers)| {
+                        SuffixInfo::Function(params, variadic, qualifiers)
+                    }),
+            ))
+            .repeated()
+            .collect::<Vec<SuffixInfo>>(),
+        )
+        .map(|(((convention, pointers), atom), suffixes)| {
+            let (mut declarator, inner_convention) = atom.unwrap_or((Declarator::Anonymous, None));
+            let mut pending = inner_convention.or(convention);
+            for suffix in suffixes {
+                declarator = match suffix {
+                    SuffixInfo::Array(size, qualifiers) => {
+                        Declarator::Array(Box::new(declarator), size, qualifiers)
+                    }
+                    SuffixInfo::Function(params, variadic, member_qualifiers) => {
+                        Declarator::Function {
+                            func: Box::new(declarator),
+                            params,
+                            variadic,
+                            calling_convention: pending.take(),
+                            member_qualifiers,
+                        }
+                    }
+                };
+            }
+            for prefix in pointers.into_iter().rev() {
+                declarator = match prefix {
+                    PrefixInfo::Pointer(qualifiers) => {
+                        Declarator::Ptr(Box::new(declarator), qualifiers)
+                    }
+                    PrefixInfo::Reference => Declarator::Reference(Box::new(declarator)),
+                    PrefixInfo::Block(qualifiers) => {
+                        Declarator::Block(Box::new(declarator), qualifiers)
+                    }
+                    PrefixInfo::MemberPointer(class, qualifiers) => {
+                        Declarator::MemberPtr(Box::new(declarator), class, qualifiers)
+                    }
+                };
+            }
+            (declarator, pending)
+        })
+    })
+    .validate(|(declarator, pending), info, emitter| {
+        if pending.is_some() {
+            emitter.emit(
+                Rich::custom(
+                    info.span(),
+                    "calling convention requires a function declarator",
+                )
+                .into(),
+            );
+        }
+        declarator
+    });
+
+    // Parses a member declarator with an optional bit-field width.
+    // Returns `((Declarator, Vec<Attribute>), Option<(usize, SimpleSpan)>)`.
+    let member_declarator = declarator
+        .clone()
+        .then(attributes_parser())
+        .then(bit_width.or_not());
+
+    // Parses the body of a struct or union definition. Each member declaration may declare
+    // several members with the same type. Returns `RecordBody`.
+    let members = specified_type
+        .clone()
+        .then(
+            member_declarator
+                .separated_by(just(',').padded_by(padding_parser()))
+                .at_least(1)
+                .collect::<Vec<_>>(),
+        )
+        .validate(|(spec, declarators): (Declaration, Vec<_>), _, emitter| {
+            declarators
+                .into_iter()
+                .map(|((declarator, attributes), bit_width)| {
+                    if let Some((width, span)) = bit_width
+                        && let Err(msg) = check_bit_width(&spec.base_type.1, &declarator, width)
+                    {
+                        emitter.emit(Rich::custom(span, msg).into());
+                    }
+                    Member {
+                        declaration: with_declarator(&spec, declarator, attributes),
+                        bit_width: bit_width.map(|(width, _)| width),
+                    }
+                })
+                .collect::<Vec<Member>>()
+        })
+        .then_ignore(just(';').padded_by(padding_parser()))
+        .repeated()
+        .collect::<Vec<Vec<Member>>>()
+        .map(|members| RecordBody::Members(members.into_iter().flatten().collect()))
+        .delimited_by(
+            just('{').padded_by(padding_parser()),
+            just('}').padded_by(padding_parser()),
+        )
+        .labelled("record body");
+
+    // Parses a bit-precise integer type. Returns `BitInt`.
+    let bit_int = choice((
+        keyword("unsigned").padded_by(padding_parser()).to(true),
+        keyword("signed").padded_by(padding_parser()).to(false),
+    ))
+    .or_not()
+    .then_ignore(since(Standard::C23, "_BitInt", keyword("_BitInt")).padded_by(padding_parser()))
+    .then(
+        int(10)
+            .map_with(|width: &str, info| (width.parse::<usize>(), info.span()))
+            .padded_by(padding_parser())
+            .delimited_by(just('('), just(')')),
+    )
+    .validate(|(unsigned, (width, span)), _, emitter| {
+        let unsigned = unsigned.unwrap_or(false);
+        // A signed `_BitInt` needs a bit for the sign in addition to its value bits.
+        let minimum = if unsigned { 1 } else { 2 };
+        let width = match width {
+            Ok(width) if width >= minimum => width,
+            Ok(_) => {
+                emitter.emit(
+                    Rich::custom(span, format!("_BitInt width must be at least {minimum}")).into(),
+                );
+                minimum
+            }
+            Err(err) => {
+                emitter.emit(Rich::custom(span, err).into());
+                minimum
+            }
+        };
+        BitInt { width, unsigned }
+    })
+    .padded_by(padding_parser())
+    .labelled("bit-precise integer type");
+
+    let primitive_type = primitive_type_parser();
+    let r#type = choice((
+        bit_int.map(Type::BitInt),
+        // Primitive type
+        primitive_type.map(Type::Primitive),
+        // Record (struct/union) type
+        record_parser(
+            choice([keyword("struct"), keyword("union")]).map(|k| RecordKind::from_str(k).unwrap()),
+            members,
+        ),
+        // Enum type
+        record_parser(keyword("enum").to(RecordKind::Enum), enumerators_parser()),
+        // `typeof` specifier (C23 and GNU C)
+        choice((
+            since(Standard::C23, "typeof", keyword("typeof")),
+            keyword("__typeof__"),
+            keyword("__typeof"),
+        ))
+        .padded_by(padding_parser())
+        .ignore_then(
+            expression_text_parser().delimited_by(just('(').padded_by(padding_parser()), just(')')),
+        )
+        .map(Type::Typeof)
+        .padded_by(padding_parser()),
+        // Namespace-qualified type name (C++), e.g. `std::string`
+        state_check(|state| state.cpp)
+            .ignore_then(
+                just("::")
+                    .or_not()
+                    .then(identifier_parser().separated_by(just("::")).at_least(1))
+                    .to_slice()
+                    .try_map(|name: &str, span| {
+                        if name.contains("::") {
+                            Ok(Type::Custom(name))
+                        } else {
+                            Err(RichWrapper::expected_found(
+                                Vec::<RichPattern<char>>::new(),
+                                None,
+                                span,
+                            ))
+                        }
+                    }),
+            )
+            .padded_by(padding_parser()),
+        // Custom (typedef) type
+        identifier_parser()
+            .padded_by(padding_parser())
+            .try_map_with(|ident: &str, info| {
+                let state: &mut State = info.state();
+                if state.is_type_name(ident) {
+                    Ok(Type::Custom(ident))
+                } else {
+                    Err(Rich::custom(
+                        info.span(),
+                        format!("\"{ident}\" is used as a type but has not been defined"),
+                    )
+                    .into())
+                }
+            })
+            .labelled("custom type"),
+    ))
+    .labelled("type");
+
+    // Parses a type, including the `_Atomic(type)` specifier form. Returns `QualifiedType`.
+    let atomic_or_plain_type = choice((
+        since(Standard::C11, "_Atomic", keyword("_Atomic"))
+            .padded_by(padding_parser())
+            .ignore_then(r#type.clone().delimited_by(
+                just('(').padded_by(padding_parser()),
+                just(')').padded_by(padding_parser()),
+            ))
+            .map(|ty| QualifiedType(TypeQualifiers(TypeQualifier::Atomic.into()), ty)),
+        r#type.map(QualifiedType::from),
+    ))
+    .labelled("type");
+
+    // Parses storage classes, qualifiers, and attributes. Returns `Specifier`.
+    let specifier = choice((
+        storage_class.map(Specifier::StorageClass),
+        since(Standard::C11, "_Thread_local", keyword("_Thread_local"))
+            .labelled("storage class")
+            .padded_by(padding_parser())
+            .to(Specifier::ThreadLocal),
+        // The double-underscore spellings are GNU and MSVC extensions available in all versions.
+        choice((
+            since(Standard::C99, "inline", keyword("inline")),
+            keyword("__inline"),
+            keyword("__inline__"),
+        ))
+        .labelled("function specifier")
+        .padded_by(padding_parser())
+        .to(Specifier::Inline),
+        qualifier.clone().map(Specifier::Qualifier),
+        attribute_specifier_parser().map(Specifier::Attributes),
+    ));
+
+    // Parses storage classes and qualifiers followed by a type.
+    // Returns `Declaration` with an anonymous declarator.
+    specified_type.define(
+        choice((
+            specifier
+                .clone()
+                .repeated()
+                .collect::<Vec<Specifier>>()
+                .then(atomic_or_plain_type.map(Some)),
+            // In lenient mode, the type may be omitted after at least one specifier, in which
+            // case it defaults to `int`.
+            state_check(|state| state.implicit_int)
+                .ignore_then(specifier.repeated().at_least(1).collect::<Vec<Specifier>>())
+                .map(|specifiers| (specifiers, None)),
+        ))
+        .then(attributes_parser())
+        .try_map_with(|((specifiers, ty), attributes), info| {
+            let mut decl = Declaration {
+                attributes,
+                implicit_int: ty.is_none(),
+                ..Declaration::new(
+                    ty.unwrap_or_else(|| Type::Primitive(PrimitiveType("int")).into()),
+                    Declarator::Anonymous,
+                )
+            };
+            for specifier in specifiers {
+                match specifier {
+                    Specifier::StorageClass(_) if decl.storage_class.is_some() => {
+                        return Err(Rich::custom(info.span(), MULTIPLE_STORAGE_CLASSES).into());
+                    }
+                    Specifier::StorageClass(class) => decl.storage_class = Some(class),
+                    Specifier::ThreadLocal => decl.thread_local = true,
+                    Specifier::Inline => decl.inline = true,
+                    Specifier::Qualifier(qualifier) => decl.base_type.0.insert(qualifier),
+                    Specifier::Attributes(attributes) => decl.attributes.extend(attributes),
+                }
+            }
+            // `_Thread_local` may only be combined with `static` or `extern`.
+            if decl.thread_local
+                && let Some(class @ (StorageClass::Auto | StorageClass::Register)) =
+                    decl.storage_class
+            {
+                return Err(Rich::custom(
+                    info.span(),
+                    format!("_Thread_local cannot be used with {class}"),
+                )
+                .into());
+            }
+            Ok(decl)
+        }),
+    );
+
+    declaration.define(
+        specified_type
+            .clone()
+            .then(declarator.clone())
+            .then(attributes_parser())
+            .map(|((spec, declarator), attributes)| with_declarator(&spec, declarator, attributes))
+            .padded_by(padding_parser()),
+    );
+
+    // Parses a type name, which is a type followed by an abstract declarator.
+    // Returns `(QualifiedType, Declarator)`.
+    let type_name = specified_type
+        .clone()
+        .then(declarator.clone())
+        .padded_by(padding_parser())
+        .validate(|(spec, declarator), info, emitter| {
+            if spec.storage_class.is_some() || spec.thread_local {
+                emitter.emit(
+                    Rich::custom(info.span(), "a type name cannot have a storage class").into(),
+                );
+            }
+            if let Some(name) = declarator.name() {
+                emitter.emit(
+                    Rich::custom(
+                        info.span(),
+                        format!("a type name cannot declare an identifier, but found \"{name}\""),
+                    )
+                    .into(),
+                );
+            }
+            (spec.base_type, declarator)
+        });
+
+    // Parses the semicolons ending a declaration, which may be omitted at the end of the input.
+    // A declaration may also end where a preprocessor directive begins.
+    // Whitespace and comments after the last semicolon are skipped separately, so that they
+    // aren't part of the declaration's span.
+    let terminator = choice((
+        padding_parser()
+            .ignore_then(just(';'))
+            .repeated()
+            .at_least(1),
+        end(),
+        just('#').rewind().ignored(),
+    ));
+
+    // Parses an optional initializer. Returns `Option<Initializer>`.
+    let initializer = just('=')
+        .padded_by(padding_parser())
+        .ignore_then(initializer_parser())
+        .or_not();
+
+    // Parses a top-level item, which is a directive or one or more declarations. Each
+    // declaration is paired with the span of the item, excluding surrounding whitespace and
+    // comments. Returns `Vec<(Declaration, SimpleSpan)>`.
+    let item = padding_parser().ignore_then(
+        choice((
+            // Directives produce no declarations. This can't use `to()`, since that would skip
+            // handling the directive.
+            directive_parser().map(|()| Vec::new()),
+            // Parses a typedef declaration. Returns `Vec<Declaration>`.
+            keyword("typedef")
+                .padded_by(padding_parser())
+                .ignore_then(specified_type.clone().padded_by(padding_parser()))
+                .then(
+                    declarator
+                        .clone()
+                        .then(attributes_parser())
+                        .padded_by(padding_parser())
+                        .separated_by(just(',').padded_by(padding_parser()))
+                        .at_least(1)
+                        .collect::<Vec<_>>(),
+                )
+                .try_map_with(|(mut spec, declarators), info| {
+                    // `typedef` is itself a storage class, so it can't be combined with another.
+                    if spec.storage_class.is_some() || spec.thread_local {
+                        return Err(Rich::custom(info.span(), MULTIPLE_STORAGE_CLASSES).into());
+                    }
+                    // Add the typedef qualifier to each declaration.
+                    spec.base_type.0.insert(TypeQualifier::Typedef);
+                    let decls: Vec<Declaration> = declarators
+                        .into_iter()
+                        .map(|(declarator, attributes)| {
+                            with_declarator(&spec, declarator, attributes)
+                        })
+                        .collect();
+                    let src: &str = info.slice();
+                    Ok((decls, src))
+                })
+                .validate(|(decls, src), info, emitter| {
+                    for decl in &decls {
+                        if let Err(err) = check_declaration(decl) {
+                            emitter.emit(Rich::custom(info.span(), err).into());
+                        }
+                    }
+                    (decls, src)
+                })
+                .then_ignore(terminator.clone())
+                // The state is only updated once the whole typedef has parsed, since it isn't
+                // restored if the typedef turns out to be malformed.
+                .map_with(|(decls, src), info| {
+                    let state: &mut State = info.state();
+                    state.register_enum_constants(&decls[0]);
+                    state.register_record_tag(&decls[0]);
+                    // Add the new types to the custom types in the state.
+                    for name in decls.iter().filter_map(|decl| decl.declarator.name()) {
+                        state.custom_types.retain(|(ty, _)| ty != name);
+                        state.custom_types.push((name.to_owned(), src.to_owned()));
+                    }
+                    decls
+                }),
+            // Parses a regular declaration with an optional K&R-style parameter declaration list,
+            // an optional initializer, and either an optional function body or more declarators
+            // sharing the same type. Returns `Vec<Declaration>`.
+            choice((
+                specified_type.clone().padded_by(padding_parser()),
+                // In lenient mode, a function may be declared without any specifiers at all, in
+                // which case it returns `int`.
+                state_check(|state| state.implicit_int)
+                    .then(
+                        identifier_parser()
+                            .padded_by(padding_parser())
+                            .then(just('('))
+                            .rewind(),
+                    )
+                    .map(|_| Declaration {
+                        implicit_int: true,
+                        ..Declaration::new(
+                            Type::Primitive(PrimitiveType("int")).into(),
+                            Declarator::Anonymous,
+                        )
+                    }),
+            ))
+            .then(
+                declarator
+                    .clone()
+                    .then(asm_label_parser().or_not())
+                    .then(attributes_parser())
+                    .padded_by(padding_parser()),
+            )
+            .map(|(spec, ((declarator, asm_label), attributes))| {
+                let decl = Declaration {
+                    asm_label,
+                    ..with_declarator(&spec, declarator, attributes)
+                };
+                (spec, decl)
+            })
+            .then(
+                state_check(|state| state.kr_parameters)
+                    .ignore_then(
+                        declaration
+                            .separated_by(just(';').padded_by(padding_parser()))
+                            .at_least(1)
+                            .allow_trailing()
+                            .collect::<Vec<Declaration>>(),
+                    )
+                    .or_not(),
+            )
+            .validate(|((spec, mut decl), kr_declarations), info, emitter| {
+                // The identifier list has already been rejected if K&R mode is disabled.
+                if let Some(kr_declarations) = kr_declarations
+                    && let Err(msg) = declare_kr_parameters(&mut decl.declarator, kr_declarations)
+                {
+                    emitter.emit(Rich::custom(info.span(), msg).into());
+                }
+                let state: &mut State = info.state();
+                state.declares_function = decl.declarator.is_function();
+                (spec, decl)
+            })
+            .then(initializer.clone())
+            .then(choice((
+                terminator.clone().to((Vec::new(), None)),
+                // Parses more declarators, each with an optional initializer.
+                just(',')
+                    .padded_by(padding_parser())
+                    .ignore_then(
+                        declarator
+                            .then(asm_label_parser().or_not())
+                            .then(attributes_parser())
+                            .padded_by(padding_parser())
+                            .then(initializer)
+                            .separated_by(just(',').padded_by(padding_parser()))
+                            .at_least(1)
+                            .collect::<Vec<_>>(),
+                    )
+                    .then_ignore(terminator)
+                    .map(|declarators| (declarators, None)),
+                // A function definition doesn't need a semicolon after its body. Only a declarator
+                // which declares a function may have a body.
+                state_check(|state| state.declares_function)
+                    .ignore_then(function_body_parser())
+                    .then_ignore(padding_parser().ignore_then(just(';')).repeated())
+                    .map(|body| (Vec::new(), Some(body))),
+            )))
+            .validate(
+                |(((spec, decl), initializer), (declarators, body)), info, emitter| {
+                    if body.is_some() && initializer.is_some() {
+                        emitter.emit(
+                            Rich::custom(
+                                info.span(),
+                                "a function definition cannot have an initializer",
+                            )
+                            .into(),
+                        );
+                    }
+                    let state: &mut State = info.state();
+                    state.register_enum_constants(&spec);
+                    state.register_record_tag(&spec);
+                    let mut decls = vec![Declaration {
+                        forward_declaration: declarators.is_empty()
+                            && initializer.is_none()
+                            && body.is_none()
+                            && is_tag_only(&decl),
+                        initializer,
+                        body,
+                        ..decl
+                    }];
+                    decls.extend(declarators.into_iter().map(
+                        |(((declarator, asm_label), attributes), initializer)| Declaration {
+                            initializer,
+                            asm_label,
+                            ..with_declarator(&spec, declarator, attributes)
+                        },
+                    ));
+                    for decl in &decls {
+                        if let Err(err) = check_declaration(decl) {
+                            emitter.emit(Rich::custom(info.span(), err).into());
+                        }
+                    }
+                    decls
+                },
+            ),
+        ))
+        .map_with(|decls: Vec<Declaration<'src>>, info| {
+            let span: SimpleSpan = info.span();
+            let trailing = info.slice().len() - info.slice().trim_end().len();
+            let span = SimpleSpan::from(span.start..span.end - trailing);
+            decls
+                .into_iter()
+                .map(|decl| (decl, span))
+                .collect::<Vec<_>>()
+        })
+        .then_ignore(padding_parser()),
+    );
+
+    // Parses a C++ linkage specification, like `extern "C" { ... }` or `extern "C" int f();`,
+    // which often wraps the declarations in a header. It is unwrapped so that the enclosed
+    // declarations are parsed normally. Returns `Vec<Declaration>`.
+    let linkage_specification = keyword("extern")
+        .padded_by(padding_parser())
+        .then(choice((just("\"C\""), just("\"C++\""))))
+        .padded_by(padding_parser())
+        .ignore_then(choice((
+            item.clone()
+                .recover_with(via_parser(
+                    skip_declaration_parser(true).map(|()| Vec::new()),
+                ))
+                .repeated()
+                .collect::<Vec<Vec<_>>>()
+                .map(|decls| decls.into_iter().flatten().collect())
+                .delimited_by(
+                    just('{').padded_by(padding_parser()),
+                    just('}').padded_by(padding_parser()),
+                ),
+            item.clone(),
+        )));
+
+    let declarations = choice((linkage_specification, item))
+        // If a declaration is malformed, report the error and continue with the next one, so
+        // that the declarations which did parse can still be explained.
+        .recover_with(via_parser(
+            skip_declaration_parser(false).map(|()| Vec::new()),
+        ))
+        .repeated()
+        .collect::<Vec<Vec<_>>>()
+        .map(|decls| decls.into_iter().flatten().collect());
+
+    (declarations, type_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::{format, string::ToString, vec, vec::Vec};
+    use pretty_assertions::assert_eq;
+
+    /// Qualified version of [`primitive()`].
+    fn qprimitive<'src, I>(
+        qualifiers: I,
+        r#type: &'static str,
+        declarator: Declarator<'src>,
+    ) -> Declaration<'src>
+    where
+        I: IntoIterator<Item = TypeQualifier>,
+    {
+        Declaration::new(
+            QualifiedType(
+                TypeQualifiers(qualifiers.into_iter().collect()),
+                Type::Primitive(PrimitiveType(r#type)),
+            ),
+            declarator,
+        )
+    }
+
+    fn primitive<'src>(r#type: &'static str, declarator: Declarator<'src>) -> Declaration<'src> {
+        qprimitive([], r#type, declarator)
+    }
+
+    fn qrecord<'src, I>(
+        qualifiers: I,
+        kind: &'static str,
+        name: &'static str,
+        declarator: Declarator<'src>,
+    ) -> Declaration<'src>
+    where
+        I: IntoIterator<Item = TypeQualifier>,
+    {
+        Declaration::new(
+            QualifiedType(
+                TypeQualifiers(qualifiers.into_iter().collect()),
+                Type::Record(Record::named(kind.parse().unwrap(), name)),
+            ),
+            declarator,
+        )
+    }
+
+    fn anon() -> Declarator<'static> {
+        Declarator::Anonymous
+    }
+
+    fn qptr<I>(qualifiers: I, val: Declarator) -> Declarator
+    where
+        I: IntoIterator<Item = TypeQualifier>,
+    {
+        Declarator::Ptr(
+            Box::new(val),
+            TypeQualifiers(qualifiers.into_iter().collect()),
+        )
+    }
+
+    fn ptr(val: Declarator) -> Declarator {
+        qptr([], val)
+    }
+
+    fn ident(val: &str) -> Declarator<'_> {
+        Declarator::Ident(val)
+    }
+
+    fn array(d: Declarator, size: impl Into<Option<usize>>) -> Declarator {
+        Declarator::Array(Box::new(d), size.into().into(), ArrayQualifiers::default())
+    }
+
+    fn func<'src>(
+        func: Declarator<'src>,
+        args: impl Into<Vec<Declaration<'src>>>,
+    ) -> Declarator<'src> {
+        Declarator::Function {
+            func: Box::new(func),
+            params: args.into(),
+            variadic: false,
+            calling_convention: None,
+            member_qualifiers: TypeQualifiers::default(),
+        }
+    }
+
+    #[test]
+    fn test_basic_int_var() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            ident("myvar123"),
+        );
+        assert_eq!(vec![expected], parser().parse("int myvar123").unwrap());
+    }
+
+    #[test]
+    fn test_basic_int_ptr_vars() {
+        let expected = vec![Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            ptr(ident("p")),
+        )];
+        let cases = ["int *p", "int*p", "int* p", "int *\np"];
+        for case in cases {
+            assert_eq!(expected, parser().parse(case).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_nested_ptrs() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("char")).into(),
+            ptr(ptr(ptr(ident("p")))),
+        );
+        assert_eq!(vec![expected], parser().parse("char ***p").unwrap());
+    }
+
+    #[test]
+    fn test_record_vars() {
+        let cases = [
+            ("struct foo bar", RecordKind::Struct),
+            ("enum foo bar", RecordKind::Enum),
             ("union foo bar", RecordKind::Union),
         ];
         for (input, record_kind) in cases {
-            let expected = Declaration {
-                base_type: Type::Record(record_kind, "foo").into(),
-                declarator: ident("bar"),
-            };
+            let expected = Declaration::new(
+                Type::Record(Record::named(record_kind, "foo")).into(),
+                ident("bar"),
+            );
             assert_eq!(vec![expected], parser().parse(input).unwrap());
         }
     }
@@ -434,257 +2268,2043 @@ mod tests {
             "char",
             "int",
             "_Bool",
+            "bool",
+            "nullptr_t",
+            "char8_t",
+            "wchar_t",
+            "char16_t",
+            "char32_t",
+            "_Decimal32",
+            "_Decimal64",
+            "_Decimal128",
         ];
         for r#type in cases {
-            let expected = Declaration {
-                base_type: Type::Primitive(PrimitiveType(r#type)).into(),
-                declarator: ident("foo"),
-            };
+            let expected =
+                Declaration::new(Type::Primitive(PrimitiveType(r#type)).into(), ident("foo"));
             let src = format!("{type} foo");
             assert_eq!(vec![expected], parser().parse(&src).unwrap());
         }
     }
 
     #[test]
-    fn test_array_declarator_no_size() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: array(ptr(ident("foo")), None),
-        };
-        assert_eq!(vec![expected], parser().parse("int (*foo)[]").unwrap());
+    fn test_array_declarator_no_size() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            array(ptr(ident("foo")), None),
+        );
+        assert_eq!(vec![expected], parser().parse("int (*foo)[]").unwrap());
+    }
+
+    #[test]
+    fn test_array_declarator_with_size() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            array(ptr(ident("foo")), Some(10)),
+        );
+        assert_eq!(vec![expected], parser().parse("int (*foo)[10]").unwrap());
+    }
+
+    #[test]
+    fn test_multi_dimen_array_and_ptr() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("char")).into(),
+            ptr(array(array(ident("foo"), 3), 2)),
+        );
+        assert_eq!(vec![expected], parser().parse("char *foo[3][2]").unwrap());
+    }
+
+    #[test]
+    fn test_function_no_args() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            func(ident("foo"), []),
+        );
+        assert_eq!(vec![expected], parser().parse("int foo()").unwrap());
+    }
+
+    #[test]
+    fn test_function_single_unnamed_arg() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            func(ident("foo"), [primitive("int", anon())]),
+        );
+        assert_eq!(vec![expected], parser().parse("int foo(int)").unwrap());
+    }
+
+    #[test]
+    fn test_function_single_named_arg() {
+        let expected = Declaration::new(
+            Type::Primitive(PrimitiveType("int")).into(),
+            func(ident("foo"), [primitive("int", ident("bar"))]),
+        );
+        assert_eq!(vec![expected], parser().parse("int foo(int bar)").unwrap());
+    }
+
+    #[test]
+    fn test_function_multiple_named_args() {
+        let expected = primitive(
+            "int",
+            ptr(func(
+                ident("foo"),
+                [
+                    primitive("int", ident("bar")),
+                    primitive("char", ident("baz")),
+                ],
+            )),
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("int *foo(int bar, char baz)").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_function_variadic() {
+        let variadic = |name, params: Vec<Declaration<'static>>| Declarator::Function {
+            func: Box::new(ident(name)),
+            params,
+            variadic: true,
+            calling_convention: None,
+            member_qualifiers: TypeQualifiers::default(),
+        };
+        let fmt = qprimitive([TypeQualifier::Const], "char", ptr(ident("fmt")));
+        assert_eq!(
+            vec![
+                primitive("int", variadic("printf", vec![fmt])),
+                primitive("int", variadic("f", vec![])),
+            ],
+            parser()
+                .parse("int printf(const char *fmt, ...); int f(...)")
+                .unwrap()
+        );
+
+        for src in [
+            "int f(..., int a)",
+            "int f(int a ...)",
+            "int f(int a, ..., ...)",
+        ] {
+            assert!(parser().parse(src).has_errors(), "{src} should not parse");
+        }
+        let mut state = State::default().with_standard(Standard::C17);
+        let errors = parser()
+            .parse_with_state("int f(...)", &mut state)
+            .into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 6..9: variadic function without named parameters is not available before C23"
+        );
+    }
+
+    #[test]
+    fn parse_qualified_primitive() {
+        assert_eq!(
+            vec![qprimitive([TypeQualifier::Const], "int", ident("x"))],
+            parser().parse("const int x").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_const_char_ptr() {
+        assert_eq!(
+            vec![qprimitive(
+                [TypeQualifier::Const],
+                "char",
+                ptr(ident("str"))
+            )],
+            parser().parse("const char *str").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_qualified_ptr() {
+        assert_eq!(
+            vec![qprimitive(
+                [TypeQualifier::Const],
+                "int",
+                qptr([TypeQualifier::Volatile], ident("x"))
+            )],
+            parser().parse("const int *volatile x").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_storage_classes() {
+        let cases = [
+            ("auto int x", StorageClass::Auto),
+            ("register int x", StorageClass::Register),
+            ("static int x", StorageClass::Static),
+            ("extern int x", StorageClass::Extern),
+        ];
+        for (src, class) in cases {
+            let mut expected = primitive("int", ident("x"));
+            expected.storage_class = Some(class);
+            assert_eq!(vec![expected], parser().parse(src).unwrap());
+        }
+    }
+
+    #[test]
+    fn parse_storage_class_mixed_with_qualifiers() {
+        let mut expected = qprimitive([TypeQualifier::Const], "char", ptr(ident("name")));
+        expected.storage_class = Some(StorageClass::Static);
+        assert_eq!(
+            vec![expected.clone()],
+            parser().parse("static const char *name").unwrap()
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("const static char *name").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_multiple_storage_classes() {
+        for src in ["static extern int x", "typedef static int x"] {
+            let errors = parser().parse(src).into_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            assert!(
+                errors[0]
+                    .to_string()
+                    .ends_with("multiple storage classes in declaration specifiers"),
+                "wrong error for {src}: {}",
+                errors[0]
+            );
+        }
+    }
+
+    #[test]
+    fn parse_thread_local() {
+        let mut expected = primitive("int", ident("counter"));
+        expected.thread_local = true;
+        assert_eq!(
+            vec![expected.clone()],
+            parser().parse("_Thread_local int counter").unwrap()
+        );
+
+        expected.storage_class = Some(StorageClass::Static);
+        assert_eq!(
+            vec![expected.clone()],
+            parser().parse("static _Thread_local int counter").unwrap()
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("_Thread_local static int counter").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_thread_local_with_auto() {
+        let errors = parser()
+            .parse("auto _Thread_local int counter")
+            .into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert!(
+            errors[0]
+                .to_string()
+                .ends_with("_Thread_local cannot be used with auto")
+        );
+    }
+
+    #[test]
+    fn parse_atomic() {
+        let expected = qprimitive([TypeQualifier::Atomic], "int", ident("x"));
+        assert_eq!(vec![expected], parser().parse("_Atomic int x").unwrap());
+
+        let expected = qprimitive([TypeQualifier::Atomic], "int", ptr(ident("p")));
+        assert_eq!(
+            vec![expected.clone()],
+            parser().parse("_Atomic(int) *p").unwrap()
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("_Atomic ( int ) *p").unwrap()
+        );
+
+        let expected = qprimitive(
+            [TypeQualifier::Const, TypeQualifier::Atomic],
+            "long",
+            ident("x"),
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("const _Atomic(long) x").unwrap()
+        );
+
+        let expected = primitive("int", qptr([TypeQualifier::Atomic], ident("p")));
+        assert_eq!(vec![expected], parser().parse("int *_Atomic p").unwrap());
+    }
+
+    #[test]
+    fn parse_struct_body() {
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Struct,
+                name: Some("point"),
+                body: Some(RecordBody::Members(vec![
+                    primitive("int", ident("x")).into(),
+                    primitive("int", ident("y")).into(),
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            ident("p"),
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("struct point { int x; int y; } p;").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_anonymous_union_body() {
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Union,
+                name: None,
+                body: Some(RecordBody::Members(vec![
+                    primitive("int", ident("i")).into(),
+                    primitive("float", ptr(ident("f"))).into(),
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            ptr(ident("u")),
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("union{int i;float *f;}*u").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_bit_fields() {
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Struct,
+                name: Some("flags"),
+                body: Some(RecordBody::Members(vec![
+                    Member {
+                        declaration: primitive("unsigned", ident("flags")),
+                        bit_width: Some(3),
+                    },
+                    Member {
+                        declaration: primitive("int", anon()),
+                        bit_width: Some(0),
+                    },
+                    primitive("int", ident("x")).into(),
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            anon(),
+        );
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("struct flags { unsigned flags : 3; int : 0; int x; }")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_bit_field_errors() {
+        for (src, expected) in [
+            (
+                "struct s { int a : 99; }",
+                "at 19..21: bit-field width 99 exceeds the 32-bit width of its type",
+            ),
+            (
+                "struct s { unsigned char c : 9, d : 8; }",
+                "at 29..30: bit-field width 9 exceeds the 8-bit width of its type",
+            ),
+            (
+                "struct s { bool b : 2; }",
+                "at 20..21: bit-field width 2 exceeds the 1-bit width of its type",
+            ),
+            (
+                "struct s { unsigned _BitInt(100) x : 101; }",
+                "at 37..40: bit-field width 101 exceeds the 100-bit width of its type",
+            ),
+            (
+                "struct s { int a : 0; }",
+                "at 19..20: a named bit-field cannot have a width of zero",
+            ),
+        ] {
+            let errors = parser().parse(src).into_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            assert_eq!(errors[0].to_string(), expected, "wrong error for {src}");
+        }
+        assert!(
+            !parser()
+                .parse("struct s { unsigned long long a : 64; signed char : 0; }")
+                .has_errors()
+        );
+    }
+
+    #[test]
+    fn parse_anonymous_member() {
+        let inner = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Union,
+                name: None,
+                body: Some(RecordBody::Members(vec![
+                    primitive("int", ident("i")).into(),
+                    primitive("float", ident("f")).into(),
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            anon(),
+        );
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Struct,
+                name: Some("s"),
+                body: Some(RecordBody::Members(vec![inner.into()])),
+                attributes: vec![],
+            })
+            .into(),
+            anon(),
+        );
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("struct s { union { int i; float f; }; };")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_empty_struct_body() {
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Struct,
+                name: Some("empty"),
+                body: Some(RecordBody::Members(vec![])),
+                attributes: vec![],
+            })
+            .into(),
+            ident("e"),
+        );
+        assert_eq!(vec![expected], parser().parse("struct empty {} e").unwrap());
+    }
+
+    #[test]
+    fn parse_struct_body_missing_semicolon() {
+        let errors = parser().parse("struct point { int x } p").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+    }
+
+    #[test]
+    fn parse_struct_body_errors() {
+        let mut state = State::default();
+        parser()
+            .parse_with_state("typedef unsigned long size_t;", &mut state)
+            .unwrap();
+        let errors = parser()
+            .parse_with_state("struct s { foo x; } v", &mut state)
+            .into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 11..15: \"foo\" is used as a type but has not been defined"
+        );
+        assert!(
+            !parser()
+                .parse_with_state("struct buf { size_t len; char data[]; };", &mut state)
+                .has_errors()
+        );
+    }
+
+    #[test]
+    fn parse_enum_body() {
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Enum,
+                name: Some("color"),
+                body: Some(RecordBody::Enumerators(vec![
+                    Enumerator {
+                        name: "RED",
+                        value: None,
+                    },
+                    Enumerator {
+                        name: "GREEN",
+                        value: Some(5),
+                    },
+                    Enumerator {
+                        name: "BLUE",
+                        value: Some(-1),
+                    },
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            ident("c"),
+        );
+        assert_eq!(
+            vec![expected.clone()],
+            parser()
+                .parse("enum color { RED, GREEN = 5, BLUE = -1 } c;")
+                .unwrap()
+        );
+        // Trailing comma
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("enum color { RED, GREEN = 5, BLUE = - 1, } c;")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_enum_constant_expressions() {
+        let mut state = State::default();
+        parser()
+            .parse_with_state("enum { BASE = 0x10 };", &mut state)
+            .unwrap();
+        let decls = parser()
+            .parse_with_state(
+                "enum flags { A = 1 << 2, B, C = B * 2, D = BASE + 1, E = -D } f",
+                &mut state,
+            )
+            .unwrap();
+        let Type::Record(Record {
+            body: Some(RecordBody::Enumerators(enumerators)),
+            ..
+        }) = &decls[0].base_type.1
+        else {
+            panic!("expected an enum body");
+        };
+        let values: Vec<_> = enumerators.iter().map(|e| (e.name, e.value)).collect();
+        assert_eq!(
+            vec![
+                ("A", Some(4)),
+                ("B", None),
+                ("C", Some(10)),
+                ("D", Some(17)),
+                ("E", Some(-17)),
+            ],
+            values
+        );
+
+        let errors = parser().parse("enum { A = x } e").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert!(
+            errors[0]
+                .to_string()
+                .ends_with("value of \"A\" is not an integer constant")
+        );
+    }
+
+    #[test]
+    fn parse_empty_enum_body() {
+        let errors = parser().parse("enum color {} c").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+    }
+
+    #[test]
+    fn parse_struct_var() {
+        assert_eq!(
+            vec![qrecord(
+                [TypeQualifier::Const],
+                "struct",
+                "foo",
+                ident("bar")
+            )],
+            parser().parse("const struct foo bar").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_complex_1() {
+        assert_eq!(
+            vec![primitive(
+                "void",
+                func(
+                    ptr(ident("cb")),
+                    vec![qrecord([], "struct", "foo", ptr(anon()))]
+                )
+            )],
+            parser().parse("void (*cb)(struct foo *)").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_complex_2() {
+        let expected = qprimitive(
+            [TypeQualifier::Const],
+            "char",
+            ptr(func(
+                ptr(ident("func")),
+                vec![
+                    qprimitive(
+                        [],
+                        "void",
+                        func(
+                            ptr(ident("cb")),
+                            vec![qrecord([], "struct", "foo", ptr(anon()))],
+                        ),
+                    ),
+                    primitive("int", anon()),
+                    qprimitive(
+                        [TypeQualifier::Const],
+                        "char",
+                        qptr([TypeQualifier::Restrict], ident("my_str")),
+                    ),
+                ],
+            )),
+        );
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse(
+                    "const char *(*func)(void (*cb)(struct foo *), int, const char *restrict my_str)"
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_array_length() {
+        let result = parser().parse("int arr[1 +]");
+        let errors = result.into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].span().into_range(),
+            10..11,
+            "error position mismatch"
+        );
+    }
+
+    #[test]
+    fn parse_out_of_bounds_array_length() {
+        let src = format!("int arr[{}0]", usize::MAX);
+        let result = parser().parse(&src);
+        let errors = result.into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].span().into_range(),
+            8..29,
+            "error position mismatch"
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "at 8..29: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn parse_constant_expression_array_length() {
+        let cases = [
+            ("int buf[4 * 1024]", ArraySize::Fixed(4096)),
+            ("int buf[(1 << 4) + 1]", ArraySize::Fixed(17)),
+            ("char s[N + 1]", ArraySize::Expr("N + 1")),
+            ("char pad[ sizeof(int) ]", ArraySize::Expr("sizeof(int)")),
+            ("char buf[0x100]", ArraySize::Fixed(256)),
+            ("int flags[0b1000]", ArraySize::Fixed(8)),
+            ("int perms[0777]", ArraySize::Fixed(511)),
+            ("long big[10U]", ArraySize::Fixed(10)),
+            ("int big[1'000'000]", ArraySize::Fixed(1_000_000)),
+        ];
+        for (src, size) in cases {
+            let decl = parser().parse(src).unwrap().remove(0);
+            let Declarator::Array(_, actual, _) = decl.declarator else {
+                panic!("expected array declarator for {src}");
+            };
+            assert_eq!(actual, size, "size mismatch for {src}");
+        }
+    }
+
+    #[test]
+    fn parse_invalid_constant_expression_array_length() {
+        let result = parser().parse("int arr[2 - 3]");
+        let errors = result.into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(errors[0].to_string(), "at 8..13: array size is negative");
+
+        let result = parser().parse("int arr[1 / 0]");
+        let errors = result.into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 8..13: division by zero in constant expression"
+        );
+
+        for src in ["int arr[1 << 64]", "int arr[18446744073709551615 + 1]"] {
+            let errors = parser().parse(src).into_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            let message = errors[0].to_string();
+            assert!(message.ends_with(": array size is too large"), "{message}");
+        }
+
+        // An invalid literal leaves the size as written, rather than giving it a made-up value.
+        let (decls, errors) = parser().parse("int a[0x]").into_output_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            Some(vec![primitive(
+                "int",
+                Declarator::Array(
+                    Box::new(ident("a")),
+                    ArraySize::Expr("0x"),
+                    ArrayQualifiers::default()
+                )
+            )]),
+            decls
+        );
+    }
+
+    #[test]
+    fn parse_variable_length_array() {
+        let expected = primitive(
+            "int",
+            Declarator::Array(
+                Box::new(Declarator::Array(
+                    Box::new(ident("matrix")),
+                    ArraySize::Variable("rows"),
+                    ArrayQualifiers::default(),
+                )),
+                ArraySize::Variable("cols"),
+                ArrayQualifiers::default(),
+            ),
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("int matrix[rows][cols]").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_array_parameter_qualifiers() {
+        let expected = primitive(
+            "void",
+            func(
+                ident("g"),
+                [
+                    primitive(
+                        "int",
+                        Declarator::Array(
+                            Box::new(ident("arr")),
+                            ArraySize::Fixed(10),
+                            ArrayQualifiers {
+                                qualifiers: TypeQualifiers::default(),
+                                is_static: true,
+                            },
+                        ),
+                    ),
+                    primitive(
+                        "double",
+                        Declarator::Array(
+                            Box::new(Declarator::Array(
+                                Box::new(ident("m")),
+                                ArraySize::Unspecified,
+                                ArrayQualifiers {
+                                    qualifiers: TypeQualifiers(TypeQualifier::Const.into()),
+                                    is_static: false,
+                                },
+                            )),
+                            ArraySize::VariableUnspecified,
+                            ArrayQualifiers::default(),
+                        ),
+                    ),
+                ],
+            ),
+        );
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("void g(int arr[static 10], double m[const][*])")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_static_array_parameter_without_size() {
+        let errors = parser().parse("void f(int a[static])").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 13..19: static array parameter requires a size"
+        );
+    }
+
+    #[test]
+    fn parse_enum_constant_array_length() {
+        let decls = parser()
+            .parse("enum { MIN = -1, MAX = 32, END }; int table[MAX]; int more[END - MIN]")
+            .unwrap();
+        assert_eq!(decls[1], primitive("int", array(ident("table"), 32)));
+        assert_eq!(decls[2], primitive("int", array(ident("more"), 34)));
+    }
+
+    #[test]
+    fn parse_enum_constant_array_length_with_state() {
+        let mut state = State::default();
+        parser()
+            .parse_with_state("typedef enum { SIZE = 8 } size_t", &mut state)
+            .unwrap();
+        assert_eq!(
+            vec![primitive("char", array(ident("buf"), 8))],
+            parser()
+                .parse_with_state("char buf[SIZE]", &mut state)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_multiple_declarations() {
+        let expected = vec![
+            primitive("int", ident("a")),
+            qprimitive([TypeQualifier::Const], "char", ident("b")),
+            qrecord([], "struct", "foo", ident("c")),
+        ];
+
+        let src = "int a; const char b; struct foo c;";
+        assert_eq!(expected, parser().parse(src).unwrap());
+
+        // Same test with extra semicolons
+        let src = "int a; const char b;;; struct foo c;";
+        assert_eq!(expected, parser().parse(src).unwrap());
+    }
+
+    #[test]
+    fn parse_multiple_declarators() {
+        let expected = vec![
+            primitive("int", ident("a")),
+            primitive("int", ptr(ident("b"))),
+            primitive("int", array(ident("c"), 10)),
+        ];
+        assert_eq!(expected, parser().parse("int a, *b, c[10];").unwrap());
+
+        let mut first = qprimitive([TypeQualifier::Const], "char", ident("x"));
+        first.storage_class = Some(StorageClass::Static);
+        first.attributes = vec![Attribute::new("unused")];
+        first.initializer = Some(Initializer::Expr("'a'"));
+        let mut second = first.clone();
+        second.declarator = ptr(ident("y"));
+        second.attributes.push(Attribute::new("weak"));
+        second.initializer = None;
+        let expected = vec![first, second, primitive("int", ident("z"))];
+        assert_eq!(
+            expected,
+            parser()
+                .parse(
+                    "__attribute__((unused)) static const char x = 'a',
+                     *y __attribute__((weak)); int z "
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_multiple_member_declarators() {
+        let bit_field = |name, width| Member {
+            declaration: primitive("unsigned", ident(name)),
+            bit_width: Some(width),
+        };
+        let expected = Declaration::new(
+            Type::Record(Record {
+                kind: RecordKind::Struct,
+                name: Some("s"),
+                body: Some(RecordBody::Members(vec![
+                    primitive("int", ident("x")).into(),
+                    primitive("int", ptr(ident("y"))).into(),
+                    bit_field("a", 3),
+                    bit_field("b", 5),
+                ])),
+                attributes: vec![],
+            })
+            .into(),
+            ident("v"),
+        );
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("struct s { int x, *y; unsigned a : 3, b : 5; } v")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(parser().parse("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn parse_initializers() {
+        let cases = [
+            ("int x = 5", "5"),
+            ("int x=5;", "5"),
+            ("int x = (1, 2) ;", "(1, 2)"),
+            ("int x = f(a, b[1])", "f(a, b[1])"),
+            (r#"int x = strlen("a;b\"c")"#, r#"strlen("a;b\"c")"#),
+            ("int x = ';'", "';'"),
+        ];
+        for (src, init) in cases {
+            let mut expected = primitive("int", ident("x"));
+            expected.initializer = Some(Initializer::Expr(init));
+            assert_eq!(vec![expected], parser().parse(src).unwrap(), "for {src}");
+        }
+    }
+
+    #[test]
+    fn parse_initializer_then_declaration() {
+        let mut first = primitive("int", ident("x"));
+        first.initializer = Some(Initializer::Expr("1"));
+        let second = primitive("char", ident("c"));
+        assert_eq!(
+            vec![first, second],
+            parser().parse("int x = 1; char c").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_initializer_list() {
+        let mut expected = primitive("int", array(ident("a"), 3));
+        expected.initializer = Some(Initializer::List(
+            ["1", "2", "3"]
+                .into_iter()
+                .map(|n| ListInitializer {
+                    designators: vec![],
+                    initializer: Initializer::Expr(n),
+                })
+                .collect(),
+        ));
+        assert_eq!(
+            vec![expected.clone()],
+            parser().parse("int a[3] = {1, 2, 3};").unwrap()
+        );
+        assert_eq!(
+            vec![expected],
+            parser().parse("int a[3] = { 1,2,3, };").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_designated_initializer_list() {
+        let mut expected = qrecord([], "struct", "p", ident("q"));
+        expected.initializer = Some(Initializer::List(vec![
+            ListInitializer {
+                designators: vec![Designator::Member("x")],
+                initializer: Initializer::Expr("1"),
+            },
+            ListInitializer {
+                designators: vec![Designator::Member("y"), Designator::Index("i + 1")],
+                initializer: Initializer::List(vec![]),
+            },
+            ListInitializer {
+                designators: vec![],
+                initializer: Initializer::Expr("f(a, b)"),
+            },
+        ]));
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse("struct p q = {.x = 1, .y[i + 1] = {}, f(a, b)};")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_empty_initializer() {
+        let errors = parser().parse("int x = ;").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+    }
+
+    #[test]
+    fn parse_unicode_identifiers() {
+        let parser = parser();
+        for src in [
+            "int szép",
+            r"int \u00e9t\u00e9",
+            r"int x\U0001D400",
+            r"int _\u0301",
+        ] {
+            let name = src.strip_prefix("int ").unwrap();
+            assert_eq!(
+                vec![primitive("int", ident(name))],
+                parser.parse(src).unwrap(),
+                "{src}"
+            );
+        }
+        for src in [
+            r"int \u0301",
+            r"int x\uD800",
+            r"int x\U0001F600",
+            r"int x\u12",
+            r"int x\U00110000",
+        ] {
+            assert!(parser.parse(src).has_errors(), "{src} should not parse");
+        }
+
+        // The backslash of a universal character name isn't listed as expected.
+        let errors = parser.parse("int &r").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        let message = errors[0].to_string();
+        assert!(message.contains(" identifier, "), "{message}");
+        assert!(!message.contains(r"'\'"), "{message}");
+    }
+
+    #[test]
+    fn parse_standard_versions() {
+        let parse = |standard: Standard, src| {
+            let mut state = State::default().with_standard(standard);
+            parser()
+                .parse_with_state(src, &mut state)
+                .into_errors()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        let cases = [
+            (
+                "_Bool b",
+                Standard::C99,
+                "_Bool is not available before C99",
+            ),
+            ("bool b", Standard::C99, "bool is not available before C99"),
+            (
+                "unsigned long long x",
+                Standard::C99,
+                "unsigned long long is not available before C99",
+            ),
+            (
+                "char *restrict s",
+                Standard::C99,
+                "restrict is not available before C99",
+            ),
+            (
+                "inline int f(void)",
+                Standard::C99,
+                "inline is not available before C99",
+            ),
+            (
+                "void f(int n, int a[n])",
+                Standard::C99,
+                "variable length arrays are not available before C99",
+            ),
+            (
+                "void f(int a[*])",
+                Standard::C99,
+                "variable length arrays are not available before C99",
+            ),
+            (
+                "_Atomic int x",
+                Standard::C11,
+                "_Atomic is not available before C11",
+            ),
+            (
+                "_Atomic(int) x",
+                Standard::C11,
+                "_Atomic is not available before C11",
+            ),
+            (
+                "static _Thread_local int x",
+                Standard::C11,
+                "_Thread_local is not available before C11",
+            ),
+            (
+                "char16_t c",
+                Standard::C11,
+                "char16_t is not available before C11",
+            ),
+            (
+                "_BitInt(8) x",
+                Standard::C23,
+                "_BitInt is not available before C23",
+            ),
+            (
+                "typeof(x) y",
+                Standard::C23,
+                "typeof is not available before C23",
+            ),
+            (
+                "nullptr_t p",
+                Standard::C23,
+                "nullptr_t is not available before C23",
+            ),
+            (
+                "[[nodiscard]] int f(void)",
+                Standard::C23,
+                "[[...]] attribute syntax is not available before C23",
+            ),
+        ];
+        let standards = [
+            Standard::C89,
+            Standard::C99,
+            Standard::C11,
+            Standard::C17,
+            Standard::C23,
+        ];
+        for (src, since, message) in cases {
+            assert_eq!(Vec::<String>::new(), parse(since, src), "{src} in {since}");
+            let before = standards[standards.iter().position(|&std| std == since).unwrap() - 1];
+            let errors = parse(before, src);
+            assert_eq!(errors.len(), 1, "{src} in {before}: {errors:?}");
+            assert!(errors[0].ends_with(message), "{src}: {}", errors[0]);
+        }
+        // GNU extensions aren't tied to a standard.
+        assert!(parse(Standard::C89, "__typeof__(x) y").is_empty());
+        assert!(parse(Standard::C89, "__attribute__((packed)) struct s x").is_empty());
+    }
+
+    #[test]
+    fn parse_standard_names() {
+        assert_eq!(Ok(Standard::C89), "c90".parse());
+        assert_eq!(Ok(Standard::C99), "C99".parse());
+        assert_eq!(Ok(Standard::C17), "c18".parse());
+        assert!("c2y".parse::<Standard>().is_err());
+        assert_eq!("C11", Standard::C11.to_string());
+    }
+
+    #[test]
+    fn parse_with_recovery() {
+        let (decls, errors) = parser()
+            .parse("int x = ; int y; char *z(@); void f(int @) { return; }")
+            .into_output_errors();
+        assert_eq!(Some(vec![primitive("int", ident("y"))]), decls);
+        assert_eq!(errors.len(), 3, "expected three errors: {errors:?}");
+
+        let (decls, errors) = parser()
+            .parse("int a[2 = 1;\n#define N 4\nint b[N];")
+            .into_output_errors();
+        assert_eq!(Some(vec![primitive("int", array(ident("b"), 4))]), decls);
+        assert_eq!(errors.len(), 1, "expected one error: {errors:?}");
+    }
+
+    #[test]
+    fn parse_typedef_declaration() {
+        let expected = qprimitive([TypeQualifier::Typedef], "int", ident("foo"));
+        let parser = parser();
+        assert_eq!(vec![expected], parser.parse("typedef int foo").unwrap());
+    }
+
+    #[test]
+    fn parse_typedef_multiple_names() {
+        let typedef =
+            |declarator| qprimitive([TypeQualifier::Typedef], "unsigned long", declarator);
+        let expected = vec![
+            typedef(ident("ulong")),
+            typedef(ptr(ident("ulong_ptr"))),
+            Declaration::new(Type::Custom("ulong_ptr").into(), ident("p")),
+            Declaration::new(Type::Custom("ulong").into(), array(ident("a"), 2)),
+        ];
+        let mut state = State::default();
+        assert_eq!(
+            expected,
+            parser()
+                .parse_with_state(
+                    "typedef unsigned long ulong, *ulong_ptr; ulong_ptr p; ulong a[2]",
+                    &mut state
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            state.custom_types().collect::<Vec<_>>(),
+            [
+                ("ulong", "typedef unsigned long ulong, *ulong_ptr"),
+                ("ulong_ptr", "typedef unsigned long ulong, *ulong_ptr")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_malformed_typedef() {
+        // A typedef which fails to parse declares nothing, even after recovering from it.
+        let mut state = State::default();
+        let (decls, errors) = parser()
+            .parse_with_state("typedef int T x; T y; int z", &mut state)
+            .into_output_errors();
+        assert_eq!(Some(vec![primitive("int", ident("z"))]), decls);
+        assert_eq!(errors.len(), 2, "expected two errors: {errors:?}");
+        assert_eq!(state.custom_types().count(), 0);
+    }
+
+    #[test]
+    fn parse_typedef_reference() {
+        let expected = Declaration::new(
+            QualifiedType(
+                TypeQualifiers([TypeQualifier::Const].into_iter().collect()),
+                Type::Custom("foo"),
+            ),
+            ptr(ident("bar")),
+        );
+        let mut state = State {
+            custom_types: vec![("foo".to_owned(), "typedef int foo".to_owned())],
+            ..State::default()
+        };
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse_with_state("const foo *bar", &mut state)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_builtin_typedefs() {
+        let expected = Declaration::new(Type::Custom("uint8_t").into(), array(ident("buf"), 16));
+        assert_eq!(vec![expected], parser().parse("uint8_t buf[16]").unwrap());
+
+        let mut state = State::default().without_builtin_typedefs();
+        let errors = parser()
+            .parse_with_state("uint8_t buf[16]", &mut state)
+            .into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 0..8: \"uint8_t\" is used as a type but has not been defined"
+        );
+    }
+
+    #[test]
+    fn parse_profile_typedefs() {
+        let src = "size_t n; DWORD flags";
+        assert!(parser().parse(src).has_errors());
+
+        let mut state = State::default().with_profile(Profile::Windows);
+        let expected = vec![
+            Declaration::new(Type::Custom("size_t").into(), ident("n")),
+            Declaration::new(Type::Custom("DWORD").into(), ident("flags")),
+        ];
+        assert_eq!(
+            expected,
+            parser().parse_with_state(src, &mut state).unwrap()
+        );
+
+        let mut state = State::default().with_profile(Profile::Posix);
+        assert!(parser().parse_with_state(src, &mut state).has_errors());
+    }
+
+    #[test]
+    fn parse_preprocessor_directives() {
+        let variable = |name, size| {
+            Declarator::Array(
+                Box::new(ident(name)),
+                ArraySize::Variable(size),
+                ArrayQualifiers::default(),
+            )
+        };
+        let expected = vec![
+            primitive("int", array(ident("arr"), 64)),
+            primitive("char", array(ident("buf"), 130)),
+            primitive("int", variable("x", "MACRO")),
+            primitive("long", variable("y", "MAX")),
+        ];
+        assert_eq!(
+            expected,
+            parser()
+                .parse(
+                    "#include <stdio.h>
+                     #define MAX 64
+                     int arr[MAX];
+                     #define SIZE (MAX * 2 + \\
+                                   2)
+                     # define MACRO(x) ((x) + 1)
+                     #ifdef SIZE
+                     char buf[SIZE]
+                     #endif
+                     int x[MACRO]
+                     #undef MAX
+                     long y[MAX];"
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_kr_parameters() {
+        let mut implicit = primitive("int", ident("c"));
+        implicit.implicit_int = true;
+        let expected = primitive(
+            "int",
+            func(
+                ident("foo"),
+                [
+                    primitive("int", ident("a")),
+                    primitive("char", ptr(ident("b"))),
+                    implicit,
+                ],
+            ),
+        );
+        let mut state = State::default().with_kr_parameters(true);
+        assert_eq!(
+            vec![expected],
+            parser()
+                .parse_with_state("int foo(a, b, c) char *b; int a;", &mut state)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_cpp_mode() {
+        let reference = |inner| Declarator::Reference(Box::new(inner));
+        let expected = vec![
+            primitive("int", reference(ident("r"))),
+            Declaration::new(
+                QualifiedType(
+                    TypeQualifiers(TypeQualifier::Const.into()),
+                    Type::Custom("std::string"),
+                ),
+                reference(ident("name")),
+            ),
+            Declaration::new(Type::Custom("::size_t").into(), ident("n")),
+            primitive("char", array(reference(ident("buf")), 16)),
+            primitive(
+                "void",
+                func(
+                    ident("f"),
+                    [Declaration::new(
+                        Type::Custom("ns::inner::T").into(),
+                        ptr(reference(ident("p"))),
+                    )],
+                ),
+            ),
+        ];
+        let src = "int &r; const std::string &name; ::size_t n; char (&buf)[16];
+                   void f(ns::inner::T *&p);";
+        let mut state = State::default().with_cpp(true);
+        assert_eq!(
+            expected,
+            parser().parse_with_state(src, &mut state).unwrap()
+        );
+
+        // Member pointers, and the qualifiers of the member functions they point to
+        let member_ptr =
+            |inner, class, qualifiers| Declarator::MemberPtr(Box::new(inner), class, qualifiers);
+        let expected = vec![
+            primitive(
+                "int",
+                member_ptr(ident("m"), "C", TypeQualifiers::default()),
+            ),
+            primitive(
+                "int",
+                Declarator::Function {
+                    func: Box::new(member_ptr(ident("p"), "C", TypeQualifiers::default())),
+                    params: vec![primitive("int", Declarator::Anonymous)],
+                    variadic: false,
+                    calling_convention: None,
+                    member_qualifiers: TypeQualifiers(TypeQualifier::Const.into()),
+                },
+            ),
+            primitive(
+                "char",
+                member_ptr(
+                    ident("q"),
+                    "ns::C",
+                    TypeQualifiers(TypeQualifier::Const.into()),
+                ),
+            ),
+        ];
+        let src = "int C::*m; int (C::*p)(int) const; char ns::C::* const q;";
+        let mut state = State::default().with_cpp(true);
+        assert_eq!(
+            expected,
+            parser().parse_with_state(src, &mut state).unwrap()
+        );
+
+        // Disabled by default
+        assert!(parser().parse("int &r").has_errors());
+        assert!(parser().parse("std::string s").has_errors());
+        assert!(parser().parse("int C::*m").has_errors());
+        assert!(parser().parse("int (*f)(void) const").has_errors());
+        // Unqualified type names must still be declared.
+        let mut state = State::default().with_cpp(true);
+        assert!(
+            parser()
+                .parse_with_state("string s", &mut state)
+                .has_errors()
+        );
+    }
+
+    #[test]
+    fn parse_alternative_keyword_spellings() {
+        let expected = vec![
+            qprimitive(
+                [TypeQualifier::Const, TypeQualifier::Volatile],
+                "int",
+                qptr([TypeQualifier::Restrict], ident("p")),
+            ),
+            qprimitive(
+                [TypeQualifier::Const, TypeQualifier::Volatile],
+                "char",
+                qptr([TypeQualifier::Restrict], ident("q")),
+            ),
+            Declaration {
+                storage_class: Some(StorageClass::Static),
+                inline: true,
+                ..primitive("int", func(ident("f"), []))
+            },
+            Declaration {
+                inline: true,
+                ..primitive("void", func(ident("g"), []))
+            },
+        ];
+        let src = "__const __volatile int *__restrict p;
+                   __const__ __volatile__ char *__restrict__ q;
+                   static __inline int f(void);
+                   __inline__ void g(void);";
+        // The alternative spellings are available before C99.
+        let mut state = State::default().with_standard(Standard::C89);
+        assert_eq!(
+            expected,
+            parser().parse_with_state(src, &mut state).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_asm_labels() {
+        let expected = vec![
+            Declaration {
+                storage_class: Some(StorageClass::Register),
+                asm_label: Some("r11"),
+                ..primitive("int", ident("counter"))
+            },
+            Declaration {
+                storage_class: Some(StorageClass::Extern),
+                asm_label: Some("stat"),
+                ..primitive(
+                    "int",
+                    func(
+                        ident("stat64"),
+                        [qprimitive(
+                            [TypeQualifier::Const],
+                            "char",
+                            ptr(Declarator::Anonymous),
+                        )],
+                    ),
+                )
+            },
+            Declaration {
+                asm_label: Some("x"),
+                attributes: vec![Attribute {
+                    name: "unused",
+                    args: None,
+                }],
+                ..primitive("int", ident("a"))
+            },
+            Declaration {
+                asm_label: Some("y"),
+                initializer: Some(Initializer::Expr("2")),
+                ..primitive("int", ident("b"))
+            },
+        ];
+        let src = r#"register int counter asm("r11");
+                     extern int stat64(const char *) __asm__ ("stat");
+                     int a __asm("x") __attribute__((unused)), b asm("y") = 2;"#;
+        assert_eq!(expected, parser().parse(src).unwrap());
+        // `asm` is only a keyword after a declarator.
+        assert_eq!(
+            vec![primitive("int", ident("asm"))],
+            parser().parse("int asm;").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_comments() {
+        let expected = vec![
+            primitive("int", ident("x")),
+            primitive("char", func(ident("f"), [primitive("int", ident("n"))])),
+        ];
+        let src = "/* leading */ int /* inner */ x; // trailing
+                   char f(int n /* count */); /* after */";
+        assert_eq!(expected, parser().parse(src).unwrap());
+
+        // A `/` which doesn't start a comment is still an error.
+        assert!(parser().parse("int / x").has_errors());
+        assert!(parser().parse("int x /* unterminated").has_errors());
+    }
+
+    #[test]
+    fn parse_translation_units() {
+        let src = "#ifndef LIST_H
+#define LIST_H
+
+/* A node in a singly-linked list */
+struct node;
+typedef struct node node_t;
+
+// Returns the length of the list. Don't pass NULL.
+static size_t length(const node_t *head) {
+    size_t n = 0; /* } */
+    for (; head; head = head->next) n++;
+    return n;
+}
+
+int a, b;
+#endif
+";
+        let mut state = State::default().with_profile(Profile::Posix);
+        let decls = parse_translation_unit(src, &mut state).unwrap();
+        let declared: Vec<(Option<&str>, &str)> = decls
+            .iter()
+            .map(|(decl, span)| (decl.declarator.name(), &src[span.into_range()]))
+            .collect();
+        assert_eq!(
+            vec![
+                (None, "struct node;"),
+                (Some("node_t"), "typedef struct node node_t;"),
+                (
+                    Some("length"),
+                    &src[src.find("static").unwrap()..=src.rfind('}').unwrap()]
+                ),
+                (Some("a"), "int a, b;"),
+                (Some("b"), "int a, b;"),
+            ],
+            declared
+        );
+        assert!(decls[0].0.forward_declaration);
+        assert!(decls[2].0.body.unwrap().contains("/* } */"));
+        assert!(state.is_type_name("node_t"));
+        assert_eq!(
+            vec![(RecordKind::Struct, "node")],
+            state.record_tags().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn state_to_source() {
+        let mut state = State::default();
+        let src = "#define N 4\n\
+                   typedef unsigned long ulong, *ulong_ptr;\n\
+                   enum color { RED = 4, GREEN };\n\
+                   typedef int vec[N];\n\
+                   typedef struct node { struct node *next; } node_t;\n\
+                   enum { GREEN = 7 };\n\
+                   union u;";
+        let (_, errs) = parse_translation_unit(src, &mut state).into_output_errors();
+        assert!(errs.is_empty());
+        let saved = state.to_source();
+        assert_eq!(
+            saved,
+            "#define N 4\n\
+             enum color { RED = 4 };\n\
+             enum { GREEN = 7 };\n\
+             struct node;\n\
+             union u;\n\
+             typedef unsigned long ulong, *ulong_ptr;\n\
+             typedef int vec[N];\n\
+             typedef struct node { struct node *next; } node_t;\n"
+        );
+
+        let mut restored = State::default();
+        let (_, errs) = parse_translation_unit(&saved, &mut restored).into_output_errors();
+        assert!(errs.is_empty());
+        assert_eq!(restored.to_source(), saved);
+        assert_eq!(restored.constant("GREEN"), Some(7));
     }
 
     #[test]
-    fn test_array_declarator_with_size() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: array(ptr(ident("foo")), Some(10)),
-        };
-        assert_eq!(vec![expected], parser().parse("int (*foo)[10]").unwrap());
+    fn clear_declarations() {
+        let mut state = State::default().with_cpp(true);
+        let src = "#define N 4\ntypedef int T; enum { A = 3 }; struct s; T x[A + N];";
+        let (_, errs) = parse_translation_unit(src, &mut state).into_output_errors();
+        assert!(errs.is_empty());
+        assert!(state.is_type_name("T"));
+        assert_eq!(state.constant("A"), Some(3));
+        assert_eq!(state.constant("N"), Some(4));
+
+        state.clear_declarations();
+        assert!(!state.is_type_name("T"));
+        assert_eq!(state.constant("A"), None);
+        assert_eq!(state.constant("N"), None);
+        assert_eq!(state.record_tags().count(), 0);
+        assert_eq!(state.custom_types().count(), 0);
+        // Options and builtin typedefs are kept.
+        assert!(state.cpp);
+        assert!(state.is_type_name("uint8_t"));
+        assert!(parse_translation_unit("T x", &mut state).has_errors());
     }
 
     #[test]
-    fn test_multi_dimen_array_and_ptr() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("char")).into(),
-            declarator: ptr(array(array(ident("foo"), 3), 2)),
-        };
-        assert_eq!(vec![expected], parser().parse("char *foo[3][2]").unwrap());
+    fn parse_type_names() {
+        let tests = [
+            (
+                "int (*)[10]",
+                Type::Primitive(PrimitiveType("int")).into(),
+                array(ptr(Declarator::Anonymous), 10),
+            ),
+            (
+                "const char *(*)(void)",
+                QualifiedType(
+                    TypeQualifiers(TypeQualifier::Const.into()),
+                    Type::Primitive(PrimitiveType("char")),
+                ),
+                ptr(func(ptr(Declarator::Anonymous), [])),
+            ),
+            (
+                " unsigned long ",
+                Type::Primitive(PrimitiveType("unsigned long")).into(),
+                Declarator::Anonymous,
+            ),
+        ];
+        for (src, base_type, declarator) in tests {
+            assert_eq!(
+                (base_type, declarator),
+                type_name_parser().parse(src).unwrap(),
+                "Wrong output for input {src}"
+            );
+        }
+
+        for src in ["int x", "static int", "int *p[4]", "int;"] {
+            assert!(
+                type_name_parser().parse(src).has_errors(),
+                "Expected error for input {src}"
+            );
+        }
     }
 
     #[test]
-    fn test_function_no_args() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: func(ident("foo"), []),
-        };
-        assert_eq!(vec![expected], parser().parse("int foo()").unwrap());
+    fn parse_linkage_specifications() {
+        let expected = vec![
+            primitive("int", func(ident("foo"), [])),
+            primitive("char", ptr(ident("bar"))),
+            primitive("int", ident("x")),
+            primitive("long", ident("y")),
+        ];
+        let src = r#"
+#ifdef __cplusplus
+extern "C" {
+#endif
+int foo(void);
+char *bar;
+#ifdef __cplusplus
+}
+#endif
+extern "C++" int x;
+extern "C" { } long y;
+"#;
+        assert_eq!(expected, parser().parse(src).unwrap());
+
+        // A malformed declaration inside the block doesn't consume the closing brace.
+        let (decls, errs) = parser()
+            .parse(r#"extern "C" { int a; 1 + 1; } int b;"#)
+            .into_output_errors();
+        assert_eq!(
+            Some(vec![
+                primitive("int", ident("a")),
+                primitive("int", ident("b"))
+            ]),
+            decls
+        );
+        assert_eq!(1, errs.len());
     }
 
     #[test]
-    fn test_function_single_unnamed_arg() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: func(ident("foo"), [primitive("int", anon())]),
-        };
-        assert_eq!(vec![expected], parser().parse("int foo(int)").unwrap());
+    fn parse_forward_declarations() {
+        let node = || Type::Record(Record::named(RecordKind::Struct, "node")).into();
+        let expected = vec![
+            Declaration {
+                forward_declaration: true,
+                ..Declaration::new(node(), Declarator::Anonymous)
+            },
+            Declaration::new(node(), ptr(ident("head"))),
+            Declaration::new(
+                Type::Primitive(PrimitiveType("void")).into(),
+                func(
+                    ident("f"),
+                    [Declaration::new(node(), Declarator::Anonymous)],
+                ),
+            ),
+            Declaration {
+                storage_class: Some(StorageClass::Static),
+                ..Declaration::new(node(), Declarator::Anonymous)
+            },
+        ];
+        let src = "struct node; struct node *head; void f(struct node); static struct node;";
+        assert_eq!(expected, parser().parse(src).unwrap());
     }
 
     #[test]
-    fn test_function_single_named_arg() {
-        let expected = Declaration {
-            base_type: Type::Primitive(PrimitiveType("int")).into(),
-            declarator: func(ident("foo"), [primitive("int", ident("bar"))]),
+    fn parse_block_pointers() {
+        let block = |inner, qualifiers: &[TypeQualifier]| {
+            Declarator::Block(
+                Box::new(inner),
+                TypeQualifiers(qualifiers.iter().copied().collect()),
+            )
         };
-        assert_eq!(vec![expected], parser().parse("int foo(int bar)").unwrap());
+        let expected = vec![
+            primitive(
+                "void",
+                func(
+                    block(ident("handler"), &[]),
+                    [primitive("int", Declarator::Anonymous)],
+                ),
+            ),
+            primitive(
+                "int",
+                func(
+                    block(ident("cmp"), &[TypeQualifier::Const]),
+                    [
+                        primitive("int", Declarator::Anonymous),
+                        primitive("int", Declarator::Anonymous),
+                    ],
+                ),
+            ),
+        ];
+        let src = "void (^handler)(int); int (^const cmp)(int, int)";
+        assert_eq!(expected, parser().parse(src).unwrap());
     }
 
     #[test]
-    fn test_function_multiple_named_args() {
-        let expected = primitive(
-            "int",
-            ptr(func(
-                ident("foo"),
-                [
-                    primitive("int", ident("bar")),
-                    primitive("char", ident("baz")),
-                ],
-            )),
-        );
+    fn parse_implicit_int() {
+        let implicit = |declarator| Declaration {
+            implicit_int: true,
+            ..primitive("int", declarator)
+        };
+        let expected = vec![
+            Declaration {
+                storage_class: Some(StorageClass::Register),
+                ..implicit(ident("x"))
+            },
+            implicit(func(ident("foo"), [])),
+            Declaration {
+                base_type: QualifiedType(
+                    TypeQualifiers(TypeQualifier::Const.into()),
+                    Type::Primitive(PrimitiveType("int")),
+                ),
+                ..implicit(ptr(ident("p")))
+            },
+            primitive("unsigned", ident("u")),
+        ];
+        let src = "register x; foo(); const *p; unsigned u;";
+        let mut state = State::default().with_implicit_int(true);
         assert_eq!(
-            vec![expected],
-            parser().parse("int *foo(int bar, char baz)").unwrap()
+            expected,
+            parser().parse_with_state(src, &mut state).unwrap()
         );
+        // Disabled by default
+        assert!(parser().parse(src).has_errors());
+
+        // Without any specifiers, only functions may be declared.
+        let mut state = State::default().with_implicit_int(true);
+        let errors = parser().parse_with_state("x;", &mut state).into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert!(errors[0].to_string().ends_with("has not been defined"));
     }
 
     #[test]
-    fn parse_qualified_primitive() {
+    fn parse_kr_parameters_errors() {
+        let cases = [
+            (
+                "int foo(a) int b",
+                "at 0..16: \"b\" is declared but is not a parameter",
+            ),
+            (
+                "int foo(int a) int a",
+                "at 0..20: parameter declarations require a function declarator with an identifier list",
+            ),
+        ];
+        for (src, expected) in cases {
+            let mut state = State::default().with_kr_parameters(true);
+            let errors = parser().parse_with_state(src, &mut state).into_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            assert_eq!(errors[0].to_string(), expected);
+        }
+
+        let errors = parser().parse("int foo(a, b)").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
         assert_eq!(
-            vec![qprimitive([TypeQualifier::Const], "int", ident("x"))],
-            parser().parse("const int x").unwrap()
+            errors[0].to_string(),
+            "at 8..12: K&R-style function declarators are not enabled"
         );
+
+        // Without K&R mode, a declaration following a declarator isn't parsed as a parameter
+        // declaration.
+        let errors = parser().parse("int x y").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        let message = errors[0].to_string();
+        assert!(message.starts_with("at 6..7: expected "), "{message}");
+        assert!(message.ends_with(", but found 'y'"), "{message}");
     }
 
     #[test]
-    fn parse_const_char_ptr() {
+    fn parse_function_definition() {
+        let mut main = primitive("int", func(ident("main"), []));
+        main.body = Some("{ if (x) { return \"}\"; } }");
+        let expected = vec![
+            main,
+            primitive("int", ptr(func(ident("f"), [primitive("int", ident("a"))]))),
+            primitive("int", ident("x")),
+        ];
         assert_eq!(
-            vec![qprimitive(
-                [TypeQualifier::Const],
-                "char",
-                ptr(ident("str"))
-            )],
-            parser().parse("const char *str").unwrap()
+            expected,
+            parser()
+                .parse(
+                    "int main(void) { if (x) { return \"}\"; } }
+                     int *f(int a);
+                     int x"
+                )
+                .unwrap()
         );
     }
 
     #[test]
-    fn parse_qualified_ptr() {
+    fn parse_function_definition_errors() {
+        let errors = parser().parse("int x {}").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(errors[0].span().into_range(), 6..7);
+
+        let errors = parser().parse("int f(void) = 0 {}").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
         assert_eq!(
-            vec![qprimitive(
-                [TypeQualifier::Const],
-                "int",
-                qptr([TypeQualifier::Volatile], ident("x"))
-            )],
-            parser().parse("const int *volatile x").unwrap()
+            errors[0].to_string(),
+            "at 0..18: a function definition cannot have an initializer"
         );
     }
 
     #[test]
-    fn parse_struct_var() {
+    fn parse_keyword_names() {
+        let errors = parser().parse("int int; char *f(int while)").into_errors();
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
         assert_eq!(
-            vec![qrecord(
-                [TypeQualifier::Const],
-                "struct",
-                "foo",
-                ident("bar")
-            )],
-            parser().parse("const struct foo bar").unwrap()
+            errors,
+            [
+                "at 4..7: \"int\" is a keyword, so it cannot be used as a name",
+                "at 21..26: \"while\" is a keyword, so it cannot be used as a name",
+            ]
         );
-    }
 
-    #[test]
-    fn parse_complex_1() {
+        // Keywords introduced by later standards are names in earlier ones.
+        let mut state = State::default().with_standard(Standard::C99);
         assert_eq!(
-            vec![primitive(
-                "void",
-                func(
-                    ptr(ident("cb")),
-                    vec![qrecord([], "struct", "foo", ptr(anon()))]
-                )
-            )],
-            parser().parse("void (*cb)(struct foo *)").unwrap()
+            vec![primitive("int", ident("bool"))],
+            parser().parse_with_state("int bool", &mut state).unwrap()
         );
     }
 
     #[test]
-    fn parse_complex_2() {
-        let expected = qprimitive(
-            [TypeQualifier::Const],
-            "char",
-            ptr(func(
-                ptr(ident("func")),
-                vec![
-                    qprimitive(
-                        [],
-                        "void",
-                        func(
-                            ptr(ident("cb")),
-                            vec![qrecord([], "struct", "foo", ptr(anon()))],
-                        ),
-                    ),
-                    primitive("int", anon()),
-                    qprimitive(
-                        [TypeQualifier::Const],
-                        "char",
-                        qptr([TypeQualifier::Restrict], ident("my_str")),
-                    ),
-                ],
-            )),
-        );
+    fn parse_impossible_declarations() {
+        for (src, expected) in [
+            (
+                "void a[10]",
+                "at 0..10: an array cannot contain void, which has no size; use an array of \
+                 pointers to void instead",
+            ),
+            (
+                "typedef void A[10]",
+                "at 0..18: an array cannot contain void, which has no size; use an array of \
+                 pointers to void instead",
+            ),
+            (
+                "typedef int F(void)[3]",
+                "at 0..22: a function cannot return an array; return a pointer to an array \
+                 instead",
+            ),
+            (
+                "typedef int T, G[2](void)",
+                "at 0..25: an array cannot contain functions; use an array of pointers to \
+                 functions instead",
+            ),
+        ] {
+            let errors = parser().parse(src).into_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            assert_eq!(errors[0].to_string(), expected, "wrong error for {src}");
+        }
+    }
+
+    #[test]
+    fn parse_typeof() {
+        let expected = vec![
+            Declaration::new(Type::Typeof("x").into(), ident("y")),
+            Declaration::new(Type::Typeof("int *").into(), ptr(ident("p"))),
+            Declaration::new(Type::Typeof("f(a, b)").into(), ident("r")),
+        ];
         assert_eq!(
-            vec![expected],
+            expected,
             parser()
-                .parse(
-                    "const char *(*func)(void (*cb)(struct foo *), int, const char *restrict my_str)"
-                )
+                .parse("typeof(x) y; __typeof__( int * ) *p; __typeof(f(a, b)) r")
                 .unwrap()
         );
     }
 
     #[test]
-    fn parse_invalid_array_length() {
-        let result = parser().parse("int arr[x]");
-        let errors = result.into_errors();
-        assert_eq!(errors.len(), 1, "expected one error");
+    fn parse_bit_int() {
+        let bit_int = |width, unsigned| Type::BitInt(BitInt { width, unsigned }).into();
+        let expected = vec![
+            Declaration::new(bit_int(24, true), ident("x")),
+            Declaration::new(bit_int(2, false), ptr(ident("p"))),
+            Declaration::new(bit_int(128, false), array(ident("a"), 4)),
+        ];
         assert_eq!(
-            errors[0].span().into_range(),
-            8..9,
-            "error position mismatch"
+            expected,
+            parser()
+                .parse("unsigned _BitInt(24) x; _BitInt( 2 ) *p; signed _BitInt(128) a[4]")
+                .unwrap()
         );
-    }
 
-    #[test]
-    fn parse_out_of_bounds_array_length() {
-        let src = format!("int arr[{}0]", usize::MAX);
-        let result = parser().parse(&src);
-        let errors = result.into_errors();
+        let errors = parser().parse("_BitInt(1) x").into_errors();
         assert_eq!(errors.len(), 1, "expected one error");
         assert_eq!(
-            errors[0].span().into_range(),
-            8..29,
-            "error position mismatch"
+            errors[0].to_string(),
+            "at 8..9: _BitInt width must be at least 2"
         );
+        let errors = parser().parse("unsigned _BitInt(0) x").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
         assert_eq!(
             errors[0].to_string(),
-            "at 8..29: number too large to fit in target type"
+            "at 17..18: _BitInt width must be at least 1"
         );
     }
 
     #[test]
-    fn parse_multiple_declarations() {
+    fn parse_attributes() {
+        let aligned = Attribute {
+            name: "aligned",
+            args: Some("8"),
+        };
         let expected = vec![
-            primitive("int", ident("a")),
-            qprimitive([TypeQualifier::Const], "char", ident("b")),
-            qrecord([], "struct", "foo", ident("c")),
+            Declaration {
+                attributes: vec![aligned],
+                ..Declaration::new(
+                    Type::Record(Record {
+                        kind: RecordKind::Struct,
+                        name: Some("point"),
+                        body: Some(RecordBody::Members(vec![
+                            primitive("int", ident("x")).into(),
+                        ])),
+                        attributes: vec![Attribute::new("packed")],
+                    })
+                    .into(),
+                    ident("p"),
+                )
+            },
+            Declaration {
+                attributes: vec![
+                    Attribute::new("__noreturn__"),
+                    Attribute::new("cold"),
+                    Attribute {
+                        name: "format",
+                        args: Some("printf, 1, (2)"),
+                    },
+                ],
+                ..primitive("void", func(ident("die"), []))
+            },
+            Declaration {
+                attributes: vec![Attribute::new("unused")],
+                ..primitive("int", ident("x"))
+            },
         ];
-
-        let src = "int a; const char b; struct foo c;";
-        assert_eq!(expected, parser().parse(src).unwrap());
-
-        // Same test with extra semicolons
-        let src = "int a; const char b;;; struct foo c;";
-        assert_eq!(expected, parser().parse(src).unwrap());
-    }
-
-    #[test]
-    fn parse_empty() {
-        assert_eq!(parser().parse("").unwrap(), vec![]);
+        assert_eq!(
+            expected,
+            parser()
+                .parse(
+                    "struct __attribute__((packed)) point { int x; } __attribute__((aligned(8))) p;
+                     __attribute__((__noreturn__)) void die(void) __attribute((cold, format( printf, 1, (2) )));
+                     int x __attribute__ (( unused ))"
+                )
+                .unwrap()
+        );
     }
 
     #[test]
-    fn parse_typedef_declaration() {
-        let expected = qprimitive([TypeQualifier::Typedef], "int", ident("foo"));
-        let parser = parser();
-        assert_eq!(vec![expected], parser.parse("typedef int foo").unwrap());
+    fn parse_c23_attributes() {
+        let expected = vec![
+            Declaration {
+                attributes: vec![Attribute::new("nodiscard")],
+                ..primitive("int", func(ident("compute"), []))
+            },
+            Declaration {
+                storage_class: Some(StorageClass::Extern),
+                attributes: vec![Attribute {
+                    name: "deprecated",
+                    args: Some("\"use new_api\""),
+                }],
+                ..primitive("int", ident("old"))
+            },
+            Declaration {
+                attributes: vec![
+                    Attribute::new("gnu::packed"),
+                    Attribute::new("maybe_unused"),
+                ],
+                ..Declaration::new(
+                    Type::Record(Record::named(RecordKind::Struct, "s")).into(),
+                    ident("v"),
+                )
+            },
+            Declaration {
+                attributes: vec![Attribute::new("maybe_unused")],
+                ..primitive("int", array(ident("a"), 2))
+            },
+        ];
+        assert_eq!(
+            expected,
+            parser()
+                .parse(
+                    "[[nodiscard]] int compute(void);
+                     [[deprecated(\"use new_api\")]] extern int old;
+                     [[gnu::packed, ]] struct s v [[ maybe_unused ]];
+                     int a[2] [[maybe_unused]]"
+                )
+                .unwrap()
+        );
     }
 
     #[test]
-    fn parse_typedef_reference() {
-        let expected = Declaration {
-            base_type: QualifiedType(
-                TypeQualifiers([TypeQualifier::Const].into_iter().collect()),
-                Type::Custom("foo"),
-            ),
-            declarator: ptr(ident("bar")),
-        };
-        let mut state = State {
-            custom_types: vec!["foo".to_owned()],
+    fn parse_msvc_extensions() {
+        let with_convention = |declarator, convention| match declarator {
+            Declarator::Function {
+                func,
+                params,
+                variadic,
+                member_qualifiers,
+                ..
+            } => Declarator::Function {
+                func,
+                params,
+                variadic,
+                calling_convention: Some(convention),
+                member_qualifiers,
+            },
+            _ => unreachable!(),
         };
+        let expected = vec![
+            Declaration {
+                attributes: vec![Attribute::new("dllimport")],
+                ..primitive(
+                    "int",
+                    with_convention(
+                        func(ident("Foo"), [primitive("int", Declarator::Anonymous)]),
+                        CallingConvention::Stdcall,
+                    ),
+                )
+            },
+            primitive(
+                "void",
+                with_convention(func(ptr(ident("callback")), []), CallingConvention::Cdecl),
+            ),
+            Declaration {
+                attributes: vec![
+                    Attribute::new("noreturn"),
+                    Attribute {
+                        name: "align",
+                        args: Some("16"),
+                    },
+                ],
+                ..primitive(
+                    "int",
+                    ptr(with_convention(
+                        func(ident("g"), []),
+                        CallingConvention::Fastcall,
+                    )),
+                )
+            },
+        ];
         assert_eq!(
-            vec![expected],
+            expected,
             parser()
-                .parse_with_state("const foo *bar", &mut state)
+                .parse(
+                    "__declspec(dllimport) int __stdcall Foo(int);
+                     void (__cdecl *callback)(void);
+                     __declspec( noreturn align(16) ) int *__fastcall g(void)"
+                )
                 .unwrap()
         );
+
+        let errors = parser().parse("int __stdcall x").into_errors();
+        assert_eq!(errors.len(), 1, "expected one error");
+        assert_eq!(
+            errors[0].to_string(),
+            "at 4..15: calling convention requires a function declarator"
+        );
     }
 }
@@ -174,7 +174,7 @@ mod tests {
         let err = errs.first().unwrap();
         assert_eq!(
             err.to_string(),
-            "at 1..1: expected anything, type qualifier, or type, but found end of input"
+            "at 1..1: expected anything, storage class, function specifier, type qualifier, attribute, or type, but found end of input"
         );
     }
 
@@ -207,7 +207,7 @@ mod tests {
         let err = errs.first().unwrap();
         assert_eq!(
             err.to_string(),
-            "at 6..6: expected anything, function parameter, or ')', but found end of input"
+            "at 6..6: expected anything, function parameter, '...', or ')', but found end of input"
         );
     }
 }
@@ -0,0 +1,447 @@
+//! Integer constant expressions
+
+use alloc::{boxed::Box, format, string::ToString};
+use chumsky::{prelude::*, text::keyword};
+
+use super::{Extra, expression_text_parser, identifier_parser, padding_parser};
+
+/// Integer constant expression, such as an array size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstExpr<'src> {
+    Int(i128),
+    /// Integer literal which is malformed or too large, and has already been reported. Its value
+    /// is unknown, so that it isn't explained as some other number.
+    InvalidInt,
+    Ident(&'src str),
+    /// `sizeof` expression, stored as its source text. These can't be evaluated without knowing
+    /// the sizes of types.
+    Sizeof(&'src str),
+    Unary(UnaryOp, Box<ConstExpr<'src>>),
+    Binary(BinaryOp, Box<ConstExpr<'src>>, Box<ConstExpr<'src>>),
+    /// `cond ? then : else`
+    Conditional(
+        Box<ConstExpr<'src>>,
+        Box<ConstExpr<'src>>,
+        Box<ConstExpr<'src>>,
+    ),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Neg,
+    BitNot,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitXor,
+    BitOr,
+    And,
+    Or,
+}
+
+/// Error produced when evaluating a [`ConstExpr`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EvalError {
+    #[error("division by zero in constant expression")]
+    DivisionByZero,
+    #[error("integer overflow in constant expression")]
+    Overflow,
+}
+
+impl ConstExpr<'_> {
+    /// Evaluates the expression, using `lookup` to find the values of identifiers.
+    ///
+    /// Returns `Ok(None)` if the expression depends on an identifier with no known value or on
+    /// a `sizeof` expression.
+    ///
+    /// Arithmetic is done on 128-bit integers rather than on C's integer types, so only results
+    /// which don't fit in an `i128` are reported as overflowing. An expression which overflows
+    /// its type in C, such as `9223372036854775807 * 2`, is evaluated as if the type were wide
+    /// enough to hold the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression divides by zero or overflows.
+    pub fn evaluate(
+        &self,
+        lookup: &impl Fn(&str) -> Option<i128>,
+    ) -> Result<Option<i128>, EvalError> {
+        let value = match self {
+            ConstExpr::Int(n) => *n,
+            ConstExpr::Ident(name) => match lookup(name) {
+                Some(n) => n,
+                None => return Ok(None),
+            },
+            ConstExpr::Sizeof(_) | ConstExpr::InvalidInt => return Ok(None),
+            ConstExpr::Unary(op, operand) => {
+                let Some(n) = operand.evaluate(lookup)? else {
+                    return Ok(None);
+                };
+                match op {
+                    UnaryOp::Plus => n,
+                    UnaryOp::Neg => n.checked_neg().ok_or(EvalError::Overflow)?,
+                    UnaryOp::BitNot => !n,
+                    UnaryOp::Not => i128::from(n == 0),
+                }
+            }
+            ConstExpr::Binary(op, lhs, rhs) => {
+                let (Some(a), Some(b)) = (lhs.evaluate(lookup)?, rhs.evaluate(lookup)?) else {
+                    return Ok(None);
+                };
+                match op {
+                    BinaryOp::Mul => a.checked_mul(b).ok_or(EvalError::Overflow)?,
+                    BinaryOp::Div | BinaryOp::Rem if b == 0 => {
+                        return Err(EvalError::DivisionByZero);
+                    }
+                    BinaryOp::Div => a.checked_div(b).ok_or(EvalError::Overflow)?,
+                    BinaryOp::Rem => a.checked_rem(b).ok_or(EvalError::Overflow)?,
+                    BinaryOp::Add => a.checked_add(b).ok_or(EvalError::Overflow)?,
+                    BinaryOp::Sub => a.checked_sub(b).ok_or(EvalError::Overflow)?,
+                    BinaryOp::Shl | BinaryOp::Shr => {
+                        let shift = u32::try_from(b).map_err(|_| EvalError::Overflow)?;
+                        if *op == BinaryOp::Shl {
+                            a.checked_shl(shift).ok_or(EvalError::Overflow)?
+                        } else {
+                            a.checked_shr(shift).ok_or(EvalError::Overflow)?
+                        }
+                    }
+                    BinaryOp::Lt => i128::from(a < b),
+                    BinaryOp::Le => i128::from(a <= b),
+                    BinaryOp::Gt => i128::from(a > b),
+                    BinaryOp::Ge => i128::from(a >= b),
+                    BinaryOp::Eq => i128::from(a == b),
+                    BinaryOp::Ne => i128::from(a != b),
+                    BinaryOp::BitAnd => a & b,
+                    BinaryOp::BitXor => a ^ b,
+                    BinaryOp::BitOr => a | b,
+                    BinaryOp::And => i128::from(a != 0 && b != 0),
+                    BinaryOp::Or => i128::from(a != 0 || b != 0),
+                }
+            }
+            ConstExpr::Conditional(cond, then, otherwise) => {
+                let Some(cond) = cond.evaluate(lookup)? else {
+                    return Ok(None);
+                };
+                if cond == 0 {
+                    return otherwise.evaluate(lookup);
+                }
+                return then.evaluate(lookup);
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+/// Returns a parser which folds a left-associative chain of binary operators.
+fn binary_level<'src>(
+    operand: impl Parser<'src, &'src str, ConstExpr<'src>, Extra<'src>> + Clone + 'src,
+    op: impl Parser<'src, &'src str, BinaryOp, Extra<'src>> + Clone + 'src,
+) -> Boxed<'src, 'src, &'src str, ConstExpr<'src>, Extra<'src>> {
+    operand
+        .clone()
+        .foldl(
+            op.padded_by(padding_parser()).then(operand).repeated(),
+            |lhs, (op, rhs)| ConstExpr::Binary(op, Box::new(lhs), Box::new(rhs)),
+        )
+        .boxed()
+}
+
+/// Integer suffixes accepted after a literal, in lowercase
+const INTEGER_SUFFIXES: &[&str] = &[
+    "", "u", "l", "ul", "lu", "ll", "ull", "llu", "wb", "uwb", "wbu",
+];
+
+/// Returns a parser which parses an integer literal in decimal, hexadecimal (`0x`), octal
+/// (leading `0`), or binary (`0b`) form, with an optional suffix such as `U` or `ULL`. Returns
+/// `None` if the digits are invalid or the value is too large, after reporting the error.
+fn integer_literal_parser<'src>() -> impl Parser<'src, &'src str, Option<i128>, Extra<'src>> + Clone
+{
+    // Digits may be separated by single quotes, e.g. `1'000'000`.
+    let digits = |radix| {
+        let digit = any().filter(move |c: &char| c.is_digit(radix));
+        digit
+            .then(just('\'').or_not().then(digit).repeated())
+            .to_slice()
+    };
+    let number = choice((
+        choice((just("0x"), just("0X")))
+            .ignore_then(digits(16))
+            .map(|digits| (digits, 16)),
+        choice((just("0b"), just("0B")))
+            .ignore_then(digits(2))
+            .map(|digits| (digits, 2)),
+        // Octal digits are checked below so that `08` is reported as invalid rather than
+        // parsed as `0` followed by garbage.
+        just('0')
+            .then(just('\'').or_not())
+            .ignore_then(digits(10))
+            .map(|digits| (digits, 8)),
+        digits(10).map(|digits| (digits, 10)),
+    ));
+    let suffix = any()
+        .filter(|c: &char| c.is_ascii_alphanumeric() || *c == '_')
+        .repeated()
+        .to_slice();
+
+    number
+        .then(suffix)
+        .validate(
+            |((digits, radix), suffix): ((&str, u32), &str), info, emitter| {
+                // `ll` must not be mixed-case, but other letters may be either case.
+                let lowercase = suffix.to_ascii_lowercase();
+                if !INTEGER_SUFFIXES.contains(&lowercase.as_str())
+                    || suffix.contains("lL")
+                    || suffix.contains("Ll")
+                {
+                    emitter.emit(
+                        Rich::custom(
+                            info.span(),
+                            format!("invalid suffix \"{suffix}\" on integer constant"),
+                        )
+                        .into(),
+                    );
+                    return None;
+                }
+                let digits = digits.replace('\'', "");
+                match u64::from_str_radix(&digits, radix) {
+                    Ok(n) => Some(n.into()),
+                    Err(err) => {
+                        let msg = if radix == 8 && digits.contains(['8', '9']) {
+                            format!("invalid digit in octal constant \"0{digits}\"")
+                        } else {
+                            err.to_string()
+                        };
+                        emitter.emit(Rich::custom(info.span(), msg).into());
+                        None
+                    }
+                }
+            },
+        )
+        .labelled("number")
+}
+
+/// Returns a parser which parses an integer constant expression.
+#[must_use]
+pub fn const_expr_parser<'src>()
+-> impl Parser<'src, &'src str, ConstExpr<'src>, Extra<'src>> + Clone {
+    recursive(|expr| {
+        let literal =
+            integer_literal_parser().map(|n| n.map_or(ConstExpr::InvalidInt, ConstExpr::Int));
+
+        let sizeof = keyword("sizeof")
+            .then(choice((
+                expression_text_parser()
+                    .delimited_by(just('(').padded_by(padding_parser()), just(')'))
+                    .ignored(),
+                identifier_parser().padded_by(padding_parser()).ignored(),
+            )))
+            .to_slice()
+            .map(ConstExpr::Sizeof);
+
+        let atom = choice((
+            literal,
+            sizeof,
+            identifier_parser().map(ConstExpr::Ident),
+            expr.clone()
+                .delimited_by(just('(').padded_by(padding_parser()), just(')')),
+        ))
+        .padded_by(padding_parser());
+
+        let unary = recursive(|unary| {
+            choice((
+                just('+').to(UnaryOp::Plus),
+                just('-').to(UnaryOp::Neg),
+                just('~').to(UnaryOp::BitNot),
+                just('!').to(UnaryOp::Not),
+            ))
+            .padded_by(padding_parser())
+            .then(unary)
+            .map(|(op, operand)| ConstExpr::Unary(op, Box::new(operand)))
+            .or(atom)
+        });
+
+        let multiplicative = binary_level(
+            unary,
+            choice((
+                just('*').to(BinaryOp::Mul),
+                just('/').to(BinaryOp::Div),
+                just('%').to(BinaryOp::Rem),
+            )),
+        );
+        let additive = binary_level(
+            multiplicative,
+            choice((just('+').to(BinaryOp::Add), just('-').to(BinaryOp::Sub))),
+        );
+        let shift = binary_level(
+            additive,
+            choice((just("<<").to(BinaryOp::Shl), just(">>").to(BinaryOp::Shr))),
+        );
+        let relational = binary_level(
+            shift,
+            choice((
+                just("<=").to(BinaryOp::Le),
+                just(">=").to(BinaryOp::Ge),
+                just('<').then_ignore(just('<').not()).to(BinaryOp::Lt),
+                just('>').then_ignore(just('>').not()).to(BinaryOp::Gt),
+            )),
+        );
+        let equality = binary_level(
+            relational,
+            choice((just("==").to(BinaryOp::Eq), just("!=").to(BinaryOp::Ne))),
+        );
+        let and_expr = binary_level(
+            equality,
+            just('&').then_ignore(just('&').not()).to(BinaryOp::BitAnd),
+        );
+        let exclusive_or = binary_level(and_expr, just('^').to(BinaryOp::BitXor));
+        let inclusive_or = binary_level(
+            exclusive_or,
+            just('|').then_ignore(just('|').not()).to(BinaryOp::BitOr),
+        );
+        let logical_and = binary_level(inclusive_or, just("&&").to(BinaryOp::And));
+        let logical_or = binary_level(logical_and, just("||").to(BinaryOp::Or));
+
+        logical_or
+            .clone()
+            .then(
+                just('?')
+                    .padded_by(padding_parser())
+                    .ignore_then(expr.clone())
+                    .then_ignore(just(':').padded_by(padding_parser()))
+                    .then(expr)
+                    .or_not(),
+            )
+            .map(|(cond, branches)| match branches {
+                Some((then, otherwise)) => {
+                    ConstExpr::Conditional(Box::new(cond), Box::new(then), Box::new(otherwise))
+                }
+                None => cond,
+            })
+    })
+    .labelled("constant expression")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Parses and evaluates the given expression, with `N` defined as 10.
+    fn eval(src: &str) -> Result<Option<i128>, EvalError> {
+        let expr = const_expr_parser().parse(src).unwrap();
+        expr.evaluate(&|name| (name == "N").then_some(10))
+    }
+
+    #[test]
+    fn evaluate_arithmetic() {
+        assert_eq!(eval("4 * 1024"), Ok(Some(4096)));
+        assert_eq!(eval("1 + 2 * 3"), Ok(Some(7)));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(Some(9)));
+        assert_eq!(eval("10 - 4 - 3"), Ok(Some(3)));
+        assert_eq!(eval("17 / 5 % 2"), Ok(Some(1)));
+        assert_eq!(eval("-3 + +5"), Ok(Some(2)));
+    }
+
+    #[test]
+    fn evaluate_bitwise_and_logical() {
+        assert_eq!(eval("1 << 4 | 1"), Ok(Some(17)));
+        assert_eq!(eval("256 >> 2 & 0"), Ok(Some(0)));
+        assert_eq!(eval("6 ^ 3"), Ok(Some(5)));
+        assert_eq!(eval("~0"), Ok(Some(-1)));
+        assert_eq!(eval("!0 && 2 || 0"), Ok(Some(1)));
+        assert_eq!(eval("1 < 2 == 2 >= 1"), Ok(Some(1)));
+        assert_eq!(eval("1 > 2 ? 8 : 16"), Ok(Some(16)));
+    }
+
+    #[test]
+    fn evaluate_identifiers() {
+        assert_eq!(eval("N + 1"), Ok(Some(11)));
+        assert_eq!(eval("M + 1"), Ok(None));
+        assert_eq!(eval("sizeof(int)"), Ok(None));
+        assert_eq!(eval("2 * sizeof N"), Ok(None));
+    }
+
+    #[test]
+    fn evaluate_literals() {
+        assert_eq!(eval("0x100"), Ok(Some(256)));
+        assert_eq!(eval("0XfF"), Ok(Some(255)));
+        assert_eq!(eval("0b1000"), Ok(Some(8)));
+        assert_eq!(eval("017"), Ok(Some(15)));
+        assert_eq!(eval("0"), Ok(Some(0)));
+        assert_eq!(eval("10U + 1ull + 2LL + 3lu + 4wb"), Ok(Some(20)));
+        assert_eq!(eval("1'000'000"), Ok(Some(1_000_000)));
+        assert_eq!(eval("0xFF'FF"), Ok(Some(0xFFFF)));
+        assert_eq!(eval("0'17 + 0b1'0"), Ok(Some(17)));
+    }
+
+    #[test]
+    fn invalid_literals() {
+        let error = |src| {
+            const_expr_parser()
+                .parse(src)
+                .into_errors()
+                .into_iter()
+                .map(|err| err.to_string())
+                .collect::<alloc::vec::Vec<_>>()
+        };
+        assert_eq!(
+            error("10lL"),
+            ["at 0..4: invalid suffix \"lL\" on integer constant"]
+        );
+        assert_eq!(
+            error("12abc"),
+            ["at 0..5: invalid suffix \"abc\" on integer constant"]
+        );
+        assert_eq!(
+            error("0189"),
+            ["at 0..4: invalid digit in octal constant \"0189\""]
+        );
+
+        // Invalid literals have no value, rather than being replaced by zero.
+        for src in ["0x", "08", "1000000000000000000000000000000"] {
+            let (expr, errors) = const_expr_parser().parse(src).into_output_errors();
+            assert_eq!(errors.len(), 1, "expected one error for {src}");
+            assert_eq!(expr, Some(ConstExpr::InvalidInt), "wrong output for {src}");
+        }
+    }
+
+    #[test]
+    fn evaluate_errors() {
+        assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("1 % (N - 10)"), Err(EvalError::DivisionByZero));
+        assert_eq!(eval("1 << 200"), Err(EvalError::Overflow));
+        assert_eq!(eval("1 << -1"), Err(EvalError::Overflow));
+    }
+
+    #[test]
+    fn evaluate_beyond_c_integer_types() {
+        // Results are only limited by the range of `i128`, not by the types of the operands.
+        assert_eq!(
+            eval("9223372036854775807 * 2"),
+            Ok(Some(18_446_744_073_709_551_614))
+        );
+        assert_eq!(
+            eval("(-9223372036854775807 - 1) / -1"),
+            Ok(Some(9_223_372_036_854_775_808))
+        );
+        assert_eq!(eval("18446744073709551615 + 1"), Ok(Some(1 << 64)));
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Paths linking the segments of an explanation to the parts of the declaration they explain
+//!
+//! When [`Options::provenance`][crate::explainer::Options::provenance] is set, each highlighted
+//! segment of an explanation records its [origin][crate::color::HighlightedTextSegment::origin]
+//! as a [`NodePath`] from the explained declaration, which can be [resolved][NodePath::resolve]
+//! to the AST node. The parser only records the spans of whole declarations, so a segment can be
+//! traced back to the input text through the span of its declaration, as returned by
+//! [`parse_translation_unit()`][crate::parser::parse_translation_unit].
+
+use alloc::vec::Vec;
+
+use crate::ast::{
+    Declaration, Declarator, Enumerator, Initializer, QualifiedType, RecordBody, Type,
+};
+
+/// Step from a node of a declaration's AST to one of its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Step {
+    /// From a declaration to its declarator
+    Declarator,
+    /// From a pointer, reference, block, array, or function declarator to the declarator it
+    /// derives from
+    Inner,
+    /// From a declaration to its base type
+    BaseType,
+    /// From a function declarator to the declaration of the parameter with the given index
+    Parameter(usize),
+    /// From a declaration to the declaration of the member with the given index of the struct or
+    /// union its base type defines
+    Member(usize),
+    /// From a declaration to the enumerator with the given index of the enum its base type
+    /// defines
+    Enumerator(usize),
+    /// From a declaration to its initializer
+    Initializer,
+}
+
+/// Path from a declaration to one of the nodes of its AST. The empty path refers to the
+/// declaration itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct NodePath(pub Vec<Step>);
+
+/// Node of a declaration's AST
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node<'a, 'src> {
+    Declaration(&'a Declaration<'src>),
+    Declarator(&'a Declarator<'src>),
+    BaseType(&'a QualifiedType<'src>),
+    Enumerator(&'a Enumerator<'src>),
+    Initializer(&'a Initializer<'src>),
+}
+
+impl NodePath {
+    /// Returns the path with the given step prepended, i.e. the path to the same node from the
+    /// parent of the node this path starts at.
+    #[must_use]
+    pub fn within(mut self, step: Step) -> Self {
+        self.0.insert(0, step);
+        self
+    }
+
+    /// Returns the node of the declaration's AST which the path leads to, or [`None`] if the
+    /// path doesn't lead to a node of this declaration.
+    #[must_use]
+    pub fn resolve<'a, 'src>(&self, decl: &'a Declaration<'src>) -> Option<Node<'a, 'src>> {
+        let mut node = Node::Declaration(decl);
+        for step in &self.0 {
+            node = match (node, step) {
+                (Node::Declaration(decl), Step::Declarator) => Node::Declarator(&decl.declarator),
+                (Node::Declaration(decl), Step::BaseType) => Node::BaseType(&decl.base_type),
+                (Node::Declaration(decl), Step::Initializer) => {
+                    Node::Initializer(decl.initializer.as_ref()?)
+                }
+                (Node::Declaration(decl), Step::Member(i)) => match &decl.base_type.1 {
+                    Type::Record(record) => match &record.body {
+                        Some(RecordBody::Members(members)) => {
+                            Node::Declaration(&members.get(*i)?.declaration)
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                (Node::Declaration(decl), Step::Enumerator(i)) => match &decl.base_type.1 {
+                    Type::Record(record) => match &record.body {
+                        Some(RecordBody::Enumerators(enumerators)) => {
+                            Node::Enumerator(enumerators.get(*i)?)
+                        }
+                        _ => return None,
+                    },
+                    _ => return None,
+                },
+                (
+                    Node::Declarator(
+                        Declarator::Ptr(inner, _)
+                        | Declarator::Reference(inner)
+                        | Declarator::Block(inner, _)
+                        | Declarator::MemberPtr(inner, ..)
+                        | Declarator::Array(inner, ..)
+                        | Declarator::Function { func: inner, .. },
+                    ),
+                    Step::Inner,
+                ) => Node::Declarator(inner),
+                (Node::Declarator(Declarator::Function { params, .. }), Step::Parameter(i)) => {
+                    Node::Declaration(params.get(*i)?)
+                }
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        let decls = crate::parser::parser()
+            .parse("int (*fp)(char c) = 0; enum e { A, B } x")
+            .unwrap();
+        let fp = &decls[0];
+        let Declarator::Function { func, params, .. } = &fp.declarator else {
+            panic!("Expected a function declarator");
+        };
+        let Declarator::Ptr(ident, _) = &**func else {
+            panic!("Expected a pointer declarator");
+        };
+        for (path, expected) in [
+            (vec![], Some(Node::Declaration(fp))),
+            (
+                vec![Step::Declarator],
+                Some(Node::Declarator(&fp.declarator)),
+            ),
+            (
+                vec![Step::Declarator, Step::Inner],
+                Some(Node::Declarator(func)),
+            ),
+            (
+                vec![Step::Declarator, Step::Inner, Step::Inner],
+                Some(Node::Declarator(ident)),
+            ),
+            (
+                vec![Step::Declarator, Step::Parameter(0), Step::BaseType],
+                Some(Node::BaseType(&params[0].base_type)),
+            ),
+            (
+                vec![Step::Initializer],
+                Some(Node::Initializer(fp.initializer.as_ref().unwrap())),
+            ),
+            (vec![Step::Declarator, Step::Parameter(1)], None),
+            (vec![Step::BaseType, Step::Inner], None),
+            (vec![Step::Member(0)], None),
+        ] {
+            assert_eq!(NodePath(path.clone()).resolve(fp), expected, "{path:?}");
+        }
+
+        let Type::Record(record) = &decls[1].base_type.1 else {
+            panic!("Expected a record type");
+        };
+        let Some(RecordBody::Enumerators(enumerators)) = &record.body else {
+            panic!("Expected enumerators");
+        };
+        assert_eq!(
+            NodePath(vec![Step::Enumerator(1)]).resolve(&decls[1]),
+            Some(Node::Enumerator(&enumerators[1]))
+        );
+    }
+}
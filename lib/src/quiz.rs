@@ -0,0 +1,325 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Quizzes which ask for random declarations to be explained in English or written in C
+//!
+//! Answers are checked by parsing them and comparing the declared types with
+//! [`check_same_type`], so array sizes must match exactly, but equivalent spellings like `int *a`
+//! and `int a[]` for a parameter are both accepted.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use chumsky::Parser;
+
+use crate::{
+    ast::{Declaration, TypeQualifier},
+    color::fmt::PlainFormatter,
+    compatibility::check_same_type,
+    en2c::{format_declaration, parse_english},
+    explainer::explain_declaration,
+    generator::{Difficulty, Generator},
+};
+
+/// What a question asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestionKind {
+    /// Explain a C declaration in English
+    Explain,
+    /// Write the C declaration described in English
+    Write,
+}
+
+/// Question asked by a quiz
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+    pub kind: QuestionKind,
+    /// Text to show: a C declaration for [`QuestionKind::Explain`], or an English description
+    /// for [`QuestionKind::Write`]
+    pub prompt: String,
+    /// A correct answer, to show after the question has been answered
+    pub solution: String,
+    declaration: Declaration<'static>,
+}
+
+/// Result of checking an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    /// The answer declares something else, for the given reason
+    Incorrect(String),
+    /// The answer couldn't be parsed, for the given reason
+    Invalid(String),
+}
+
+impl Verdict {
+    #[must_use]
+    pub fn is_correct(&self) -> bool {
+        *self == Verdict::Correct
+    }
+}
+
+impl Question {
+    /// Creates a question asking about the given declaration.
+    #[must_use]
+    pub fn new(kind: QuestionKind, declaration: Declaration<'static>) -> Self {
+        let c = format_declaration(&declaration);
+        let english = explain_declaration(&declaration).format_to_string(&PlainFormatter);
+        let (prompt, solution) = match kind {
+            QuestionKind::Explain => (c, english),
+            QuestionKind::Write => (english, c),
+        };
+        Self {
+            kind,
+            prompt,
+            solution,
+            declaration,
+        }
+    }
+
+    /// Checks an answer to the question. Answers to [`QuestionKind::Explain`] questions may
+    /// leave out the declared name.
+    #[must_use]
+    pub fn check(&self, answer: &str) -> Verdict {
+        let answer = answer.trim().trim_end_matches(['.', ';']);
+        let given = match self.kind {
+            QuestionKind::Explain => match parse_english(answer) {
+                Ok(decl) => decl,
+                Err(err) => return Verdict::Invalid(err.to_string()),
+            },
+            QuestionKind::Write => match crate::parser::parser().parse(answer).into_result() {
+                Ok(mut decls) if decls.len() == 1 => decls.remove(0),
+                Ok(_) => return Verdict::Invalid("expected a single declaration".to_string()),
+                Err(errs) => {
+                    let errs: Vec<String> = errs.iter().map(ToString::to_string).collect();
+                    return Verdict::Invalid(errs.join("; "));
+                }
+            },
+        };
+
+        let expected = &self.declaration;
+        if let Err(incompatibility) = check_same_type(&given, expected) {
+            return Verdict::Incorrect(incompatibility.to_string());
+        }
+        let is_typedef = |decl: &Declaration| decl.base_type.0.contains(TypeQualifier::Typedef);
+        if is_typedef(&given) != is_typedef(expected) {
+            return Verdict::Incorrect(if is_typedef(expected) {
+                "the declaration should be a typedef".to_string()
+            } else {
+                "the declaration should not be a typedef".to_string()
+            });
+        }
+        let name = expected.declarator.name();
+        match given.declarator.name() {
+            None if self.kind == QuestionKind::Explain => Verdict::Correct,
+            given_name if given_name == name => Verdict::Correct,
+            _ => Verdict::Incorrect(format!(
+                "the declared name should be `{}`",
+                name.unwrap_or_default()
+            )),
+        }
+    }
+}
+
+/// Number of questions answered correctly out of those answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, parse_display::Display)]
+#[display("{correct}/{answered}")]
+pub struct Score {
+    pub correct: usize,
+    pub answered: usize,
+}
+
+/// Quiz asking questions about random declarations, which alternate between
+/// [explaining](QuestionKind::Explain) and [writing](QuestionKind::Write) declarations
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{generator::Difficulty, quiz::Quiz};
+/// let mut quiz = Quiz::new(42, Difficulty::Arrays);
+/// let question = quiz.next_question();
+/// let solution = question.solution.clone();
+/// assert!(quiz.answer(&question, &solution).is_correct());
+/// assert_eq!(quiz.score().to_string(), "1/1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Quiz {
+    generator: Generator,
+    asked: usize,
+    score: Score,
+}
+
+impl Quiz {
+    /// Creates a quiz about declarations of the given difficulty. The same seed always produces
+    /// the same questions.
+    #[must_use]
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            generator: Generator::new(seed, difficulty),
+            asked: 0,
+            score: Score::default(),
+        }
+    }
+
+    /// Returns the next question.
+    pub fn next_question(&mut self) -> Question {
+        let kind = if self.asked.is_multiple_of(2) {
+            QuestionKind::Explain
+        } else {
+            QuestionKind::Write
+        };
+        self.asked += 1;
+        Question::new(kind, self.generator.generate())
+    }
+
+    /// Checks an answer to a question, counting it in the score.
+    pub fn answer(&mut self, question: &Question, answer: &str) -> Verdict {
+        let verdict = question.check(answer);
+        self.score.answered += 1;
+        if verdict.is_correct() {
+            self.score.correct += 1;
+        }
+        verdict
+    }
+
+    #[must_use]
+    pub fn score(&self) -> Score {
+        self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn question(kind: QuestionKind, src: &'static str) -> Question {
+        let decl = crate::parser::parser().parse(src).unwrap().remove(0);
+        Question::new(kind, decl)
+    }
+
+    #[test]
+    fn solutions_are_correct() {
+        for difficulty in [
+            Difficulty::Pointers,
+            Difficulty::Arrays,
+            Difficulty::FunctionPointers,
+            Difficulty::Qualifiers,
+            Difficulty::Typedefs,
+        ] {
+            let mut quiz = Quiz::new(5, difficulty);
+            for _ in 0..100 {
+                let question = quiz.next_question();
+                assert_eq!(
+                    question.check(&question.solution),
+                    Verdict::Correct,
+                    "Wrong verdict for '{}' answering '{}'",
+                    question.solution,
+                    question.prompt,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn explain() {
+        let question = question(QuestionKind::Explain, "char *(*fp)(int)");
+        assert_eq!(question.prompt, "char *(*fp)(int)");
+        for (answer, expected) in [
+            (
+                "pointer to function taking int returning pointer to char",
+                Verdict::Correct,
+            ),
+            (
+                "a pointer named fp to a function that takes an int and returns a char pointer.",
+                Verdict::Correct,
+            ),
+            (
+                "pointer to function taking int returning char",
+                Verdict::Incorrect(
+                    "in the return type of the pointed-to type, a non-derived type is not \
+                     compatible with a pointer"
+                        .to_string(),
+                ),
+            ),
+            (
+                "pointer named f to function taking int returning pointer to char",
+                Verdict::Incorrect("the declared name should be `fp`".to_string()),
+            ),
+            (
+                "pointer to function taking array of 3 int returning pointer to char",
+                Verdict::Incorrect(
+                    "in parameter 1 of the pointed-to type, a pointer is not compatible with a \
+                     non-derived type"
+                        .to_string(),
+                ),
+            ),
+            (
+                "pointer to",
+                Verdict::Invalid("expected a type, but the phrase ended".to_string()),
+            ),
+        ] {
+            assert_eq!(
+                question.check(answer),
+                expected,
+                "Wrong verdict for {answer}"
+            );
+        }
+    }
+
+    #[test]
+    fn write() {
+        let question = question(QuestionKind::Write, "int *arr[10]");
+        assert_eq!(question.prompt, "an array named arr of 10 pointers to ints");
+        assert_eq!(question.solution, "int *arr[10]");
+        for (answer, expected) in [
+            ("int *arr[10];", Verdict::Correct),
+            ("int (*arr)[10]", Verdict::Incorrect(String::new())),
+            ("int *arr[]", Verdict::Incorrect(String::new())),
+            ("int *a[10]", Verdict::Incorrect(String::new())),
+            ("typedef int *arr[10]", Verdict::Incorrect(String::new())),
+            ("int *arr[10]; int x", Verdict::Invalid(String::new())),
+            ("int *arr[10", Verdict::Invalid(String::new())),
+        ] {
+            let verdict = question.check(answer);
+            assert_eq!(
+                core::mem::discriminant(&verdict),
+                core::mem::discriminant(&expected),
+                "Wrong verdict for {answer}: {verdict:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn score() {
+        let mut quiz = Quiz::new(1, Difficulty::Pointers);
+        let first = quiz.next_question();
+        assert_eq!(first.kind, QuestionKind::Explain);
+        let second = quiz.next_question();
+        assert_eq!(second.kind, QuestionKind::Write);
+        assert!(quiz.answer(&first, &first.solution).is_correct());
+        assert!(!quiz.answer(&second, "").is_correct());
+        assert_eq!(
+            quiz.score(),
+            Score {
+                correct: 1,
+                answered: 2
+            }
+        );
+        assert_eq!(quiz.score().to_string(), "1/2");
+    }
+}
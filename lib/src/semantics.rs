@@ -0,0 +1,201 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Semantic checks for declarations which parse but can't denote a type, such as arrays of
+//! functions
+
+use crate::ast::{ArraySize, Declaration, Declarator, RecordBody, Type};
+
+/// Error describing why a declaration is impossible, along with how to fix it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SemanticError {
+    #[error(
+        "an array cannot contain void, which has no size; use an array of pointers to void instead"
+    )]
+    ArrayOfVoid,
+    #[error("an array cannot contain functions; use an array of pointers to functions instead")]
+    ArrayOfFunctions,
+    #[error(
+        "an array cannot contain arrays of unspecified size; only the first dimension may be left \
+         unspecified"
+    )]
+    ArrayOfIncompleteArrays,
+    #[error("a function cannot return an array; return a pointer to an array instead")]
+    FunctionReturningArray,
+    #[error("a function cannot return a function; return a pointer to a function instead")]
+    FunctionReturningFunction,
+    #[error(
+        "only member functions can have qualifiers after their parameters; use a pointer to a \
+         member function instead"
+    )]
+    QualifiedNonMemberFunction,
+}
+
+/// Type which a declarator derives from, as far as the checks are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Derived {
+    Void,
+    Array,
+    /// Array of unspecified size, i.e. `[]`
+    IncompleteArray,
+    Function,
+    Other,
+}
+
+/// Checks a declarator, given the kind of type it derives from.
+fn check_declarator(declarator: &Declarator, derived: Derived) -> Result<(), SemanticError> {
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => Ok(()),
+        Declarator::Ptr(inner, _)
+        | Declarator::Reference(inner)
+        | Declarator::Block(inner, _)
+        | Declarator::MemberPtr(inner, ..) => check_declarator(inner, Derived::Other),
+        Declarator::Array(inner, size, _) => match derived {
+            Derived::Void => Err(SemanticError::ArrayOfVoid),
+            Derived::Function => Err(SemanticError::ArrayOfFunctions),
+            Derived::IncompleteArray => Err(SemanticError::ArrayOfIncompleteArrays),
+            Derived::Array | Derived::Other => {
+                let array = if *size == ArraySize::Unspecified {
+                    Derived::IncompleteArray
+                } else {
+                    Derived::Array
+                };
+                check_declarator(inner, array)
+            }
+        },
+        Declarator::Function {
+            func,
+            params,
+            member_qualifiers,
+            ..
+        } => {
+            if !member_qualifiers.is_empty() && !matches!(**func, Declarator::MemberPtr(..)) {
+                return Err(SemanticError::QualifiedNonMemberFunction);
+            }
+            match derived {
+                Derived::Array | Derived::IncompleteArray => {
+                    return Err(SemanticError::FunctionReturningArray);
+                }
+                Derived::Function => return Err(SemanticError::FunctionReturningFunction),
+                Derived::Void | Derived::Other => {}
+            }
+            params.iter().try_for_each(check_declaration)?;
+            check_declarator(func, Derived::Function)
+        }
+    }
+}
+
+/// Checks that a declaration denotes a possible type, including the declarations of its
+/// parameters and of the members of any record it defines.
+///
+/// # Errors
+///
+/// Returns the first problem found, e.g. [`SemanticError::FunctionReturningArray`] for
+/// `int f(void)[3]`.
+pub fn check_declaration(decl: &Declaration) -> Result<(), SemanticError> {
+    let derived = match &decl.base_type.1 {
+        Type::Primitive(primitive) if primitive.as_ref() == "void" => Derived::Void,
+        Type::Record(record) => {
+            if let Some(RecordBody::Members(members)) = &record.body {
+                members
+                    .iter()
+                    .try_for_each(|member| check_declaration(&member.declaration))?;
+            }
+            Derived::Other
+        }
+        _ => Derived::Other,
+    };
+    check_declarator(&decl.declarator, derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn possible() {
+        for src in [
+            "void f(void)",
+            "void *arr[10]",
+            "int (*fs[3])(void)",
+            "int (*f(void))[3]",
+            "int (*(*f)(void))(int)",
+            "int m[2][3]",
+            "int m[][3]",
+            "int (*p[])[]",
+            "void (^b)(int)",
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            assert_eq!(
+                check_declaration(&decls[0]),
+                Ok(()),
+                "Wrong result for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn impossible() {
+        for (src, expected) in [
+            ("void arr[10]", SemanticError::ArrayOfVoid),
+            ("const void m[2][3]", SemanticError::ArrayOfVoid),
+            ("int fs[3](void)", SemanticError::ArrayOfFunctions),
+            ("int f(void)[3]", SemanticError::FunctionReturningArray),
+            ("int f(void)(int)", SemanticError::FunctionReturningFunction),
+            (
+                "int (*f(void))(int)[2]",
+                SemanticError::FunctionReturningArray,
+            ),
+            ("int (^b)(void)[2]", SemanticError::FunctionReturningArray),
+            (
+                "void f(int g(void)[2])",
+                SemanticError::FunctionReturningArray,
+            ),
+            ("struct s { void v[2]; } x", SemanticError::ArrayOfVoid),
+            ("int m[][]", SemanticError::ArrayOfIncompleteArrays),
+            ("int m[2][]", SemanticError::ArrayOfIncompleteArrays),
+        ] {
+            // The parser reports the error but still produces the declaration.
+            let (decls, errs) = crate::parser::parser().parse(src).into_output_errors();
+            assert_eq!(errs.len(), 1, "Wrong number of errors for {src}");
+            assert_eq!(
+                check_declaration(&decls.unwrap()[0]),
+                Err(expected),
+                "Wrong result for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn member_function_qualifiers() {
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let (decls, errs) = crate::parser::parser()
+            .parse_with_state(
+                "int (C::*p)(int) const; int f(void) const; int (*fp)(void) volatile",
+                &mut state,
+            )
+            .into_output_errors();
+        assert_eq!(errs.len(), 2);
+        let decls = decls.unwrap();
+        assert_eq!(check_declaration(&decls[0]), Ok(()));
+        for decl in &decls[1..] {
+            assert_eq!(
+                check_declaration(decl),
+                Err(SemanticError::QualifiedNonMemberFunction)
+            );
+        }
+    }
+}
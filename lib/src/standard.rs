@@ -0,0 +1,113 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Versions of the C standard, used to reject features which the selected version lacks.
+
+use core::str::FromStr;
+
+use parse_display::ParseError;
+
+/// Version of the ISO C standard
+///
+/// Versions are ordered chronologically, so a feature introduced in C99 is available whenever
+/// the standard is at least [`Standard::C99`]. The default is the latest version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, parse_display::Display)]
+pub enum Standard {
+    /// ANSI C, also known as C90
+    C89,
+    C99,
+    C11,
+    /// Also known as C18
+    C17,
+    #[default]
+    C23,
+}
+
+/// Parses a standard name like `c99`, case-insensitively. The aliases `c90` and `c18` are
+/// accepted too.
+impl FromStr for Standard {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "c89" | "c90" => Ok(Standard::C89),
+            "c99" => Ok(Standard::C99),
+            "c11" => Ok(Standard::C11),
+            "c17" | "c18" => Ok(Standard::C17),
+            "c23" => Ok(Standard::C23),
+            _ => Err(ParseError::new()),
+        }
+    }
+}
+
+/// Returns the standard which introduced the primitive type with the given name, or `None` if
+/// it has been available since C89.
+#[must_use]
+pub(crate) fn primitive_type_since(name: &str) -> Option<Standard> {
+    match name {
+        "_Bool" | "bool" => Some(Standard::C99),
+        _ if name.contains("long long") || name.ends_with("_Complex") => Some(Standard::C99),
+        "char16_t" | "char32_t" => Some(Standard::C11),
+        "nullptr_t" | "char8_t" | "_Decimal32" | "_Decimal64" | "_Decimal128" => {
+            Some(Standard::C23)
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if the name is a keyword in the given standard, so it can't be used as an
+/// identifier.
+#[must_use]
+pub(crate) fn is_keyword(name: &str, standard: Standard) -> bool {
+    const C89: &[&str] = &[
+        "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+        "enum", "extern", "float", "for", "goto", "if", "int", "long", "register", "return",
+        "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned",
+        "void", "volatile", "while",
+    ];
+    const C99: &[&str] = &["inline", "restrict", "_Bool", "_Complex", "_Imaginary"];
+    const C11: &[&str] = &[
+        "_Alignas",
+        "_Alignof",
+        "_Atomic",
+        "_Generic",
+        "_Noreturn",
+        "_Static_assert",
+        "_Thread_local",
+    ];
+    const C23: &[&str] = &[
+        "alignas",
+        "alignof",
+        "bool",
+        "constexpr",
+        "false",
+        "nullptr",
+        "static_assert",
+        "thread_local",
+        "true",
+        "typeof",
+        "typeof_unqual",
+        "_BitInt",
+        "_Decimal32",
+        "_Decimal64",
+        "_Decimal128",
+    ];
+    [
+        (Standard::C89, C89),
+        (Standard::C99, C99),
+        (Standard::C11, C11),
+        (Standard::C23, C23),
+    ]
+    .iter()
+    .any(|&(since, keywords)| standard >= since && keywords.contains(&name))
+}
@@ -0,0 +1,250 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Translation of C declarations to equivalent declarations in other languages
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::ast::{
+    ArraySize, CallingConvention, Declaration, Declarator, QualifiedType, TypeQualifiers,
+};
+
+pub mod to_go;
+pub mod to_rust;
+pub mod to_zig;
+
+/// Language which declarations can be translated to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, parse_display::Display, parse_display::FromStr)]
+#[display(style = "lowercase")]
+pub enum Target {
+    /// Rust's foreign function interface
+    Rust,
+    /// Go, as seen through cgo
+    Go,
+    /// Zig
+    Zig,
+}
+
+impl Target {
+    /// Translates a declaration to this language, formatted as code followed by comments noting
+    /// the differences between the declaration and its translation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use c2e::{chumsky::Parser, parser::parser, transpile::Target};
+    /// let decls = parser().parse("int *p").unwrap();
+    /// assert_eq!(Target::Go.translate(&decls[0]), "var p *C.int");
+    /// assert_eq!(Target::Zig.translate(&decls[0]), "pub extern var p: [*c]c_int;");
+    /// ```
+    #[must_use]
+    pub fn translate(self, decl: &Declaration) -> String {
+        match self {
+            Target::Rust => to_rust::translate(decl).to_string(),
+            Target::Go => to_go::translate(decl).to_string(),
+            Target::Zig => to_zig::translate(decl).to_string(),
+        }
+    }
+}
+
+/// Returns the Rust or Zig integer type for a [builtin typedef](crate::builtins) whose size is
+/// fixed, e.g. `u8` for `uint8_t`.
+fn builtin(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "int8_t" => "i8",
+        "int16_t" => "i16",
+        "int32_t" => "i32",
+        "int64_t" => "i64",
+        "uint8_t" => "u8",
+        "uint16_t" => "u16",
+        "uint32_t" => "u32",
+        "uint64_t" => "u64",
+        "intptr_t" | "ptrdiff_t" | "ssize_t" => "isize",
+        "uintptr_t" | "size_t" => "usize",
+        _ => return None,
+    })
+}
+
+/// Splits a primitive type's name into its base type and whether it's unsigned, e.g. `long` and
+/// true for `unsigned long int`. A trailing `int` is left out, since it's implied by the other
+/// integer types, so `unsigned` on its own has the base type `""`.
+fn split_primitive(name: &str) -> (&str, bool) {
+    let unsigned = name.starts_with("unsigned");
+    let base = name
+        .strip_prefix("unsigned")
+        .or_else(|| name.strip_prefix("signed"))
+        .map_or(name, str::trim_start);
+    (base.strip_suffix(" int").unwrap_or(base), unsigned)
+}
+
+/// Translation of a C declaration to another language, along with notes on the differences
+/// between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation<N> {
+    /// Code declaring the same identifier as the C declaration, preceded by the definitions of
+    /// any records which the declaration defines, one per line
+    pub code: String,
+    /// Differences between the declaration and its translation
+    pub notes: Vec<N>,
+}
+
+/// Formats the translation as code, with the notes as comments following it.
+impl<N: Display> Display for Translation<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.code)?;
+        for note in &self.notes {
+            write!(f, "\n// Note: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// State of the translation of a declaration to some language. Each language translates base
+/// types and each kind of derived type, and the walk over the declarators is shared.
+trait Translator {
+    /// Type being translated, which is built up from a declaration's base type one declarator at
+    /// a time
+    type Ty;
+    /// Difference between a declaration and its translation
+    type Note: PartialEq;
+
+    /// Note for an array of unknown size, which is translated to an array of length 0
+    const UNKNOWN_SIZE: Self::Note;
+    /// Note for a variable-length array, whose length is translated as if it were a constant
+    const VARIABLE_LENGTH: Self::Note;
+    /// Note for a Clang block pointer, which is translated to an [untyped
+    /// pointer](Self::untyped_pointer)
+    const BLOCK: Self::Note;
+    /// Note for a C++ member pointer, which is translated to an [untyped
+    /// pointer](Self::untyped_pointer)
+    const MEMBER_POINTER: Self::Note;
+
+    /// Returns the notes made so far.
+    fn notes(&mut self) -> &mut Vec<Self::Note>;
+
+    fn base_type(&mut self, qt: &QualifiedType) -> Self::Ty;
+
+    /// Returns the type of a pointer with the given qualifiers to the given type.
+    fn pointer(&mut self, ty: Self::Ty, qualifiers: TypeQualifiers) -> Self::Ty;
+
+    /// Returns the type of a C++ reference to the given type.
+    fn reference(&mut self, ty: Self::Ty) -> Self::Ty;
+
+    /// Returns the type of a pointer with the given qualifiers which doesn't say what it points
+    /// to.
+    fn untyped_pointer(&mut self, qualifiers: TypeQualifiers) -> Self::Ty;
+
+    /// Returns the type of an array of the given length.
+    fn array(&mut self, element: Self::Ty, len: String) -> Self::Ty;
+
+    /// Returns the type of a function, given its return type and its parameters' names and
+    /// types.
+    fn function(
+        &mut self,
+        ret: Self::Ty,
+        params: Vec<(Option<&str>, Self::Ty)>,
+        variadic: bool,
+        calling_convention: Option<CallingConvention>,
+    ) -> Self::Ty;
+
+    /// Notes a difference, unless it has already been noted.
+    fn note(&mut self, note: Self::Note) {
+        let notes = self.notes();
+        if !notes.contains(&note) {
+            notes.push(note);
+        }
+    }
+
+    /// Derives the type declared by a declarator from the type it derives from. If `decay` is
+    /// true, the declarator declares a parameter, so an array or function declared directly
+    /// decays to a pointer.
+    fn derive(&mut self, ty: Self::Ty, declarator: &Declarator, decay: bool) -> Self::Ty {
+        let decays = decay
+            && matches!(
+                declarator,
+                Declarator::Array(inner, ..) | Declarator::Function { func: inner, .. }
+                    if matches!(**inner, Declarator::Anonymous | Declarator::Ident(_))
+            );
+        match declarator {
+            Declarator::Anonymous | Declarator::Ident(_) => ty,
+            Declarator::Ptr(inner, qualifiers) => {
+                let pointer = self.pointer(ty, *qualifiers);
+                self.derive(pointer, inner, decay)
+            }
+            Declarator::Reference(inner) => {
+                let reference = self.reference(ty);
+                self.derive(reference, inner, decay)
+            }
+            Declarator::Block(inner, qualifiers) => {
+                self.note(Self::BLOCK);
+                let pointer = self.untyped_pointer(*qualifiers);
+                self.derive(pointer, inner, decay)
+            }
+            Declarator::MemberPtr(inner, _, qualifiers) => {
+                self.note(Self::MEMBER_POINTER);
+                let pointer = self.untyped_pointer(*qualifiers);
+                self.derive(pointer, inner, decay)
+            }
+            Declarator::Array(inner, _, array_qualifiers) if decays => {
+                let pointer = self.pointer(ty, array_qualifiers.qualifiers);
+                self.derive(pointer, inner, decay)
+            }
+            Declarator::Array(inner, size, _) => {
+                let len = match size {
+                    ArraySize::Fixed(len) => len.to_string(),
+                    ArraySize::Expr(expr) => (*expr).to_string(),
+                    ArraySize::Variable(len) => {
+                        self.note(Self::VARIABLE_LENGTH);
+                        (*len).to_string()
+                    }
+                    ArraySize::Unspecified | ArraySize::VariableUnspecified => {
+                        self.note(Self::UNKNOWN_SIZE);
+                        "0".to_string()
+                    }
+                };
+                let array = self.array(ty, len);
+                self.derive(array, inner, decay)
+            }
+            Declarator::Function {
+                func,
+                params,
+                variadic,
+                calling_convention,
+                ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| (param.declarator.name(), self.declaration_type(param, true)))
+                    .collect();
+                let function = self.function(ty, params, *variadic, *calling_convention);
+                let ty = if decays {
+                    self.pointer(function, TypeQualifiers::default())
+                } else {
+                    function
+                };
+                self.derive(ty, func, decay)
+            }
+        }
+    }
+
+    /// Returns the type declared by a declaration. If `parameter` is true, the declaration
+    /// declares a function parameter.
+    fn declaration_type(&mut self, decl: &Declaration, parameter: bool) -> Self::Ty {
+        let base_type = self.base_type(&decl.base_type);
+        self.derive(base_type, &decl.declarator, parameter)
+    }
+}
@@ -0,0 +1,582 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Translation of C declarations to their equivalents in Go, as seen through cgo
+//!
+//! Objects are translated to variable declarations, e.g. `var p *C.int` for `int *p`, functions
+//! to function signatures, and typedefs to type aliases. C types are referred to by the names
+//! which cgo gives them, e.g. `C.int` and `C.struct_point`, so records are never defined: cgo
+//! generates their definitions itself. Declarations without a name are translated to just their
+//! type.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use super::{Translator, split_primitive};
+use crate::ast::{
+    BitInt, CallingConvention, Declaration, QualifiedType, Record, RecordKind, Type, TypeQualifier,
+    TypeQualifiers,
+};
+
+/// Difference between a C declaration and its translation, where Go has no direct equivalent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Note {
+    /// `long double`, which is translated to `float64`
+    LongDouble,
+    /// Decimal floating type, which is translated to an integer of the same size
+    Decimal,
+    /// `_BitInt` of the given width, which is translated to the smallest integer which can hold it
+    BitInt(usize),
+    /// `volatile` qualifier, which is left out
+    Volatile,
+    /// `_Atomic` qualifier, which is left out
+    Atomic,
+    /// `restrict` qualifier, which is left out
+    Restrict,
+    /// Array of unknown size, which is translated to an array of length 0
+    UnknownSize,
+    /// Variable-length array, whose length is translated as if it were a constant
+    VariableLength,
+    /// `typeof` specifier, which is translated to `_`
+    Typeof,
+    /// Record without a tag, which is translated to `_`
+    Anonymous,
+    /// Union, which cgo translates to a byte array
+    Union,
+    /// Function pointer, which cgo translates to `*[0]byte`
+    FunctionPointer,
+    /// Function type not behind a pointer, which is translated to a Go function type
+    FunctionType,
+    /// C++ reference, which is translated to a pointer
+    Reference,
+    /// Clang block pointer, which is translated to `unsafe.Pointer`
+    Block,
+    /// C++ member pointer, which is translated to `unsafe.Pointer`
+    MemberPointer,
+    /// Function using the given calling convention, which cgo can't call
+    CallingConvention(CallingConvention),
+    /// Variadic function, which cgo can't call, so its variable arguments are left out
+    Variadic,
+}
+
+/// Formats the difference described by the note, e.g. "Go has no equivalent of `restrict`, so it
+/// is left out".
+impl Display for Note {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Note::LongDouble => write!(
+                f,
+                "cgo has no equivalent of `long double`, so `float64` is used, which only matches \
+                 it where it is the same as `double`"
+            ),
+            Note::Decimal => write!(
+                f,
+                "decimal floating types have no Go equivalent, so integers of the same size are \
+                 used"
+            ),
+            Note::BitInt(width) => write!(
+                f,
+                "`_BitInt({width})` has no Go equivalent, so the smallest integer which can hold \
+                 it is used"
+            ),
+            Note::Volatile => write!(
+                f,
+                "Go has no volatile types, so volatile objects must be accessed through C helper \
+                 functions"
+            ),
+            Note::Atomic => write!(
+                f,
+                "`_Atomic` types are translated to their non-atomic equivalents, so they must be \
+                 accessed through the functions in `sync/atomic`"
+            ),
+            Note::Restrict => write!(f, "Go has no equivalent of `restrict`, so it is left out"),
+            Note::UnknownSize => write!(
+                f,
+                "arrays of unknown size are translated to arrays of length 0, so their elements \
+                 must be accessed through `unsafe.Slice()`"
+            ),
+            Note::VariableLength => write!(
+                f,
+                "the length of a variable-length array is not a constant, so it must be replaced"
+            ),
+            Note::Typeof => write!(
+                f,
+                "`typeof` has no Go equivalent, so the type must be filled in"
+            ),
+            Note::Anonymous => write!(
+                f,
+                "cgo can't name structs, unions, and enums without tags, so a typedef for one must \
+                 be used instead"
+            ),
+            Note::Union => write!(
+                f,
+                "cgo translates unions to byte arrays of the same size, so their members must be \
+                 accessed through `unsafe.Pointer`"
+            ),
+            Note::FunctionPointer => write!(
+                f,
+                "cgo translates function pointers to `*[0]byte`, so the functions they point to \
+                 must be called through C helper functions"
+            ),
+            Note::FunctionType => write!(
+                f,
+                "Go function values can't refer to C functions, so the function type only \
+                 describes the signature"
+            ),
+            Note::Reference => write!(f, "Go has no references, so a pointer is used"),
+            Note::Block => write!(
+                f,
+                "blocks have no Go equivalent, so they are translated to `unsafe.Pointer`"
+            ),
+            Note::MemberPointer => write!(
+                f,
+                "member pointers have no Go equivalent, so they are translated to `unsafe.Pointer`"
+            ),
+            Note::CallingConvention(convention) => write!(
+                f,
+                "cgo can only call functions using the platform's default calling convention, so \
+                 `{convention}` functions must be called through C helper functions"
+            ),
+            Note::Variadic => write!(
+                f,
+                "cgo can't call variadic functions, so the variable arguments are left out and \
+                 the function must be called through a C helper function"
+            ),
+        }
+    }
+}
+
+/// Go translation of a C declaration
+pub type Translation = super::Translation<Note>;
+
+/// Keywords which can't be used as Go identifiers
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// Returns the Go identifier for a C identifier. Like cgo, keywords are prefixed with an
+/// underscore.
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("_{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the Go type for a [builtin typedef](crate::builtins) with a fixed Go equivalent.
+fn builtin(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "int8_t" => "int8",
+        "int16_t" => "int16",
+        "int32_t" => "int32",
+        "int64_t" => "int64",
+        "uint8_t" => "uint8",
+        "uint16_t" => "uint16",
+        "uint32_t" => "uint32",
+        "uint64_t" => "uint64",
+        "uintptr_t" => "uintptr",
+        _ => return None,
+    })
+}
+
+/// Go type which a C type is translated to
+#[derive(Debug, Clone)]
+enum Ty {
+    /// Type of an object
+    Object(String),
+    /// `void`, which can only be pointed to or returned
+    Void,
+    /// Function type, whose parameters have optional names
+    Function {
+        params: Vec<(Option<String>, String)>,
+        /// Return type, or `None` for `void`
+        ret: Option<String>,
+    },
+}
+
+impl Ty {
+    /// Returns the Go type.
+    fn go(&self) -> String {
+        match self {
+            Ty::Object(ty) => ty.clone(),
+            Ty::Void => "unsafe.Pointer".to_string(),
+            Ty::Function { params, ret } => {
+                let params: Vec<&str> = params.iter().map(|(_, ty)| ty.as_str()).collect();
+                let ret = ret
+                    .as_ref()
+                    .map_or_else(String::new, |ret| format!(" {ret}"));
+                format!("func({}){ret}", params.join(", "))
+            }
+        }
+    }
+}
+
+/// State of the translation of a declaration to Go
+#[derive(Debug, Default)]
+struct GoTranslator {
+    notes: Vec<Note>,
+}
+
+impl GoTranslator {
+    /// Notes the qualifiers which are left out. Go has no `const`, and cgo leaves it out without
+    /// changing how the type can be used, so it isn't noted.
+    fn qualifiers(&mut self, qualifiers: TypeQualifiers) {
+        if qualifiers.contains(TypeQualifier::Volatile) {
+            self.note(Note::Volatile);
+        }
+        if qualifiers.contains(TypeQualifier::Atomic) {
+            self.note(Note::Atomic);
+        }
+        if qualifiers.contains(TypeQualifier::Restrict) {
+            self.note(Note::Restrict);
+        }
+    }
+
+    /// Returns the type of a pointer to the given type.
+    fn pointer_to(&mut self, ty: &Ty) -> String {
+        match ty {
+            Ty::Object(ty) => format!("*{ty}"),
+            Ty::Void => "unsafe.Pointer".to_string(),
+            Ty::Function { .. } => {
+                self.note(Note::FunctionPointer);
+                "*[0]byte".to_string()
+            }
+        }
+    }
+
+    fn primitive(&mut self, name: &str) -> Ty {
+        match name {
+            "float _Complex" => return Ty::Object("C.complexfloat".into()),
+            "double _Complex" => return Ty::Object("C.complexdouble".into()),
+            "long double _Complex" => {
+                self.note(Note::LongDouble);
+                return Ty::Object("complex128".into());
+            }
+            "void" => return Ty::Void,
+            _ => (),
+        }
+        let (base, unsigned) = split_primitive(name);
+        let go = match (base, unsigned) {
+            ("char", _) if name == "char" => "C.char",
+            ("char", false) => "C.schar",
+            ("char", true) => "C.uchar",
+            ("short", false) => "C.short",
+            ("short", true) => "C.ushort",
+            ("" | "int", false) => "C.int",
+            ("" | "int", true) => "C.uint",
+            ("long", false) => "C.long",
+            ("long", true) => "C.ulong",
+            ("long long", false) => "C.longlong",
+            ("long long", true) => "C.ulonglong",
+            ("float", _) => "C.float",
+            ("double", _) => "C.double",
+            ("long double", _) => {
+                self.note(Note::LongDouble);
+                "float64"
+            }
+            ("_Bool" | "bool", _) => "bool",
+            ("nullptr_t", _) => "unsafe.Pointer",
+            ("char8_t", _) => "uint8",
+            ("char16_t", _) => "uint16",
+            ("char32_t", _) => "uint32",
+            ("wchar_t", _) => "C.wchar_t",
+            ("_Decimal32" | "_Decimal64" | "_Decimal128", _) => {
+                self.note(Note::Decimal);
+                match base {
+                    "_Decimal32" => "uint32",
+                    "_Decimal64" => "uint64",
+                    _ => "[16]byte",
+                }
+            }
+            _ => name,
+        };
+        Ty::Object(go.to_string())
+    }
+
+    fn bit_int(&mut self, BitInt { width, unsigned }: BitInt) -> String {
+        let prefix = if unsigned { "uint" } else { "int" };
+        let bits = [8, 16, 32, 64].into_iter().find(|&bits| bits >= width);
+        if bits != Some(width) {
+            self.note(Note::BitInt(width));
+        }
+        bits.map_or_else(
+            || format!("[{}]byte", width.div_ceil(64) * 8),
+            |bits| format!("{prefix}{bits}"),
+        )
+    }
+
+    /// Returns the name cgo gives a record, e.g. `C.struct_point`.
+    fn record(&mut self, record: &Record) -> String {
+        let kind = match record.kind {
+            RecordKind::Struct => "struct",
+            RecordKind::Union => {
+                self.note(Note::Union);
+                "union"
+            }
+            RecordKind::Enum => "enum",
+        };
+        if let Some(name) = record.name {
+            format!("C.{kind}_{name}")
+        } else {
+            self.note(Note::Anonymous);
+            "_".to_string()
+        }
+    }
+}
+
+impl Translator for GoTranslator {
+    type Ty = Ty;
+    type Note = Note;
+
+    const UNKNOWN_SIZE: Note = Note::UnknownSize;
+    const VARIABLE_LENGTH: Note = Note::VariableLength;
+    const BLOCK: Note = Note::Block;
+    const MEMBER_POINTER: Note = Note::MemberPointer;
+
+    fn notes(&mut self) -> &mut Vec<Note> {
+        &mut self.notes
+    }
+
+    fn base_type(&mut self, qt: &QualifiedType) -> Ty {
+        self.qualifiers(qt.0);
+        match &qt.1 {
+            Type::Primitive(primitive) => self.primitive(primitive.as_ref()),
+            Type::Record(record) => Ty::Object(self.record(record)),
+            Type::Custom(name) => {
+                Ty::Object(builtin(name).map_or_else(|| format!("C.{name}"), str::to_string))
+            }
+            Type::Typeof(_) => {
+                self.note(Note::Typeof);
+                Ty::Object("_".to_string())
+            }
+            Type::BitInt(bit_int) => Ty::Object(self.bit_int(*bit_int)),
+        }
+    }
+
+    fn pointer(&mut self, ty: Ty, qualifiers: TypeQualifiers) -> Ty {
+        self.qualifiers(qualifiers);
+        Ty::Object(self.pointer_to(&ty))
+    }
+
+    fn reference(&mut self, ty: Ty) -> Ty {
+        self.note(Note::Reference);
+        Ty::Object(self.pointer_to(&ty))
+    }
+
+    fn untyped_pointer(&mut self, qualifiers: TypeQualifiers) -> Ty {
+        self.qualifiers(qualifiers);
+        Ty::Object("unsafe.Pointer".into())
+    }
+
+    fn array(&mut self, element: Ty, len: String) -> Ty {
+        Ty::Object(format!("[{len}]{}", element.go()))
+    }
+
+    fn function(
+        &mut self,
+        ret: Ty,
+        params: Vec<(Option<&str>, Ty)>,
+        variadic: bool,
+        calling_convention: Option<CallingConvention>,
+    ) -> Ty {
+        if let Some(convention) = calling_convention
+            && convention != CallingConvention::Cdecl
+        {
+            self.note(Note::CallingConvention(convention));
+        }
+        if variadic {
+            self.note(Note::Variadic);
+        }
+        let ret = match ret {
+            Ty::Void => None,
+            ty => Some(ty.go()),
+        };
+        Ty::Function {
+            params: params
+                .into_iter()
+                .map(|(name, ty)| (name.map(identifier), ty.go()))
+                .collect(),
+            ret,
+        }
+    }
+}
+
+/// Translates a declaration to its Go equivalent, along with notes on any differences.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, parser::parser, transpile::to_go::translate};
+/// let decls = parser().parse("char *names[10]; unsigned long strlen(const char *s)").unwrap();
+/// assert_eq!(translate(&decls[0]).code, "var names [10]*C.char");
+/// assert_eq!(translate(&decls[1]).code, "func strlen(s *C.char) C.ulong");
+/// ```
+#[must_use]
+pub fn translate(decl: &Declaration) -> Translation {
+    let mut translator = GoTranslator::default();
+    let ty = translator.declaration_type(decl, false);
+    let is_typedef = decl.base_type.0.contains(TypeQualifier::Typedef);
+    if matches!(ty, Ty::Function { .. }) && (is_typedef || decl.declarator.name().is_none()) {
+        translator.note(Note::FunctionType);
+    }
+    let go = match (decl.declarator.name(), ty) {
+        (None, ty) => ty.go(),
+        (Some(name), ty) if is_typedef => format!("type {} = {}", identifier(name), ty.go()),
+        (Some(name), Ty::Function { params, ret }) => {
+            let params: Vec<String> = params
+                .into_iter()
+                .map(|(name, ty)| format!("{} {ty}", name.as_deref().unwrap_or("_")))
+                .collect();
+            let ret = ret.map_or_else(String::new, |ret| format!(" {ret}"));
+            format!("func {}({}){ret}", identifier(name), params.join(", "))
+        }
+        (Some(name), ty) => format!("var {} {}", identifier(name), ty.go()),
+    };
+    Translation {
+        code: go,
+        notes: translator.notes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn run(src: &str) -> Translation {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 1, "Expected one declaration for {src}");
+        translate(&decls[0])
+    }
+
+    #[test]
+    fn objects_and_functions() {
+        for (src, expected) in [
+            ("int *p", "var p *C.int"),
+            ("const char *const s", "var s *C.char"),
+            ("char buf[10]", "var buf [10]C.char"),
+            ("unsigned long long n", "var n C.ulonglong"),
+            ("int (*grid)[3][4]", "var grid *[3][4]C.int"),
+            ("void *ptr", "var ptr unsafe.Pointer"),
+            (
+                "void *memcpy(void *dst, const void *src, uintptr_t n)",
+                "func memcpy(dst unsafe.Pointer, src unsafe.Pointer, n uintptr) unsafe.Pointer",
+            ),
+            (
+                "int main(int argc, char *argv[])",
+                "func main(argc C.int, argv **C.char) C.int",
+            ),
+            ("void exit(int)", "func exit(_ C.int)"),
+            ("uint8_t type", "var _type uint8"),
+            ("struct point origin", "var origin C.struct_point"),
+            ("struct point { int x, y; };", "C.struct_point"),
+            ("enum color c", "var c C.enum_color"),
+            ("double _Complex z", "var z C.complexdouble"),
+            ("int *", "*C.int"),
+            ("typedef unsigned char byte", "type byte = C.uchar"),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, vec![], "Unexpected notes for {src}");
+        }
+    }
+
+    #[test]
+    fn notes() {
+        for (src, expected, notes) in [
+            ("long double x", "var x float64", vec![Note::LongDouble]),
+            (
+                "int printf(const char *fmt, ...)",
+                "func printf(fmt *C.char) C.int",
+                vec![Note::Variadic],
+            ),
+            (
+                "volatile int *restrict p",
+                "var p *C.int",
+                vec![Note::Volatile, Note::Restrict],
+            ),
+            (
+                "extern int table[]",
+                "var table [0]C.int",
+                vec![Note::UnknownSize],
+            ),
+            (
+                "unsigned _BitInt(24) x",
+                "var x uint32",
+                vec![Note::BitInt(24)],
+            ),
+            ("union u value", "var value C.union_u", vec![Note::Union]),
+            ("struct { int x; } s", "var s _", vec![Note::Anonymous]),
+            (
+                "int (*fp)(int)",
+                "var fp *[0]byte",
+                vec![Note::FunctionPointer],
+            ),
+            (
+                "typedef int callback_t(int)",
+                "type callback_t = func(C.int) C.int",
+                vec![Note::FunctionType],
+            ),
+            (
+                "int __stdcall f(void)",
+                "func f() C.int",
+                vec![Note::CallingConvention(CallingConvention::Stdcall)],
+            ),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, notes, "Wrong notes for {src}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            run("restrict int *p").to_string(),
+            "var p *C.int\n// Note: Go has no equivalent of `restrict`, so it is left out"
+        );
+    }
+}
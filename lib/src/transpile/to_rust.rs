@@ -0,0 +1,714 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Translation of C declarations to their equivalents in Rust's foreign function interface
+//!
+//! Objects and functions are translated to the items which declare them in an `extern "C"`
+//! block, e.g. `pub static mut p: *mut c_int;` for `int *p`, and typedefs to type aliases.
+//! Declarations without a name are translated to just their type. Primitive C types are
+//! translated to the aliases in [`core::ffi`], e.g. `c_int`, which are assumed to be in scope.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use super::{Translator, builtin, split_primitive};
+use crate::ast::{
+    BitInt, CallingConvention, Declaration, Declarator, Enumerator, Member, QualifiedType, Record,
+    RecordBody, RecordKind, StorageClass, Type, TypeQualifier, TypeQualifiers,
+};
+
+/// Difference between a C declaration and its translation, where Rust has no direct equivalent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Note {
+    /// `long double`, which is translated to `f64`
+    LongDouble,
+    /// Complex type, which is translated to an array of its real and imaginary parts
+    Complex,
+    /// `wchar_t`, which is translated to `i32`
+    WideChar,
+    /// Decimal floating type, which is translated to an integer of the same size
+    Decimal,
+    /// `_BitInt` of the given width, which is translated to the smallest integer which can hold it
+    BitInt(usize),
+    /// `volatile` qualifier, which is left out
+    Volatile,
+    /// `_Atomic` qualifier, which is left out
+    Atomic,
+    /// `restrict` qualifier, which is left out
+    Restrict,
+    /// Array of unknown size, which is translated to an array of length 0
+    UnknownSize,
+    /// Variable-length array, whose length is translated as if it were a constant
+    VariableLength,
+    /// `typeof` specifier, which is translated to `_`
+    Typeof,
+    /// Record without a tag, which is translated to `_`
+    Anonymous,
+    /// Bit-field, which is left out of its record
+    BitField,
+    /// Clang block pointer, which is translated to `*mut c_void`
+    Block,
+    /// C++ member pointer, which is translated to `*mut c_void`
+    MemberPointer,
+    /// Function type not behind a pointer, which is translated to a function pointer type
+    FunctionType,
+    /// Function using the given calling convention, which isn't that of its `extern` block
+    CallingConvention(&'static str),
+    /// `static` declaration, whose internal linkage makes it inaccessible from Rust
+    InternalLinkage,
+}
+
+/// Formats the difference described by the note, e.g. "Rust has no equivalent of `restrict`,
+/// so it is left out".
+impl Display for Note {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Note::LongDouble => write!(
+                f,
+                "`long double` has no Rust equivalent, so `f64` is used, which only matches it \
+                 where it is the same as `double`"
+            ),
+            Note::Complex => write!(
+                f,
+                "complex numbers are translated to arrays of their real and imaginary parts, \
+                 which have the same layout but may be passed to functions differently"
+            ),
+            Note::WideChar => write!(
+                f,
+                "`wchar_t` is translated to `i32`, but it is an unsigned 16-bit integer on Windows"
+            ),
+            Note::Decimal => write!(
+                f,
+                "decimal floating types have no Rust equivalent, so integers of the same size are \
+                 used"
+            ),
+            Note::BitInt(width) => write!(
+                f,
+                "`_BitInt({width})` has no Rust equivalent, so the smallest integer which can \
+                 hold it is used"
+            ),
+            Note::Volatile => write!(
+                f,
+                "Rust has no volatile types, so volatile objects must be accessed with \
+                 `read_volatile()` and `write_volatile()`"
+            ),
+            Note::Atomic => write!(
+                f,
+                "`_Atomic` types are translated to their non-atomic equivalents, so they must be \
+                 accessed through the types in `core::sync::atomic`"
+            ),
+            Note::Restrict => write!(f, "Rust has no equivalent of `restrict`, so it is left out"),
+            Note::UnknownSize => write!(
+                f,
+                "arrays of unknown size are translated to arrays of length 0, so their elements \
+                 must be accessed through pointers"
+            ),
+            Note::VariableLength => write!(
+                f,
+                "the length of a variable-length array is not a constant, so it must be replaced"
+            ),
+            Note::Typeof => write!(
+                f,
+                "`typeof` has no Rust equivalent, so the type must be filled in"
+            ),
+            Note::Anonymous => write!(
+                f,
+                "structs, unions, and enums must have names in Rust, so one must be filled in"
+            ),
+            Note::BitField => write!(
+                f,
+                "bit-fields have no Rust equivalent, so they are left out and the layout won't \
+                 match until their storage is added"
+            ),
+            Note::Block => write!(
+                f,
+                "blocks have no Rust equivalent, so they are translated to untyped pointers"
+            ),
+            Note::MemberPointer => write!(
+                f,
+                "member pointers have no Rust equivalent, so they are translated to untyped pointers"
+            ),
+            Note::FunctionType => write!(
+                f,
+                "Rust has no function types, so a function pointer type is used"
+            ),
+            Note::CallingConvention(abi) => write!(
+                f,
+                "functions using the `{abi}` calling convention must be declared in an \
+                 `extern \"{abi}\"` block"
+            ),
+            Note::InternalLinkage => write!(
+                f,
+                "`static` declarations have internal linkage, so they can't be accessed from Rust"
+            ),
+        }
+    }
+}
+
+/// Rust translation of a C declaration, whose code consists of the definitions of any records
+/// which the declaration defines, followed by the translated declaration itself
+pub type Translation = super::Translation<Note>;
+
+/// Keywords which can't be used as Rust identifiers unless they're written as raw identifiers
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Returns the Rust identifier for a C identifier, which is a raw identifier if it's a keyword.
+fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the ABI string for a calling convention.
+fn abi(calling_convention: Option<CallingConvention>) -> &'static str {
+    match calling_convention {
+        None | Some(CallingConvention::Cdecl) => "C",
+        Some(CallingConvention::Stdcall) => "stdcall",
+        Some(CallingConvention::Fastcall) => "fastcall",
+    }
+}
+
+/// Rust type which a C type is translated to
+#[derive(Debug, Clone)]
+enum Ty {
+    /// Type of an object, along with its C qualifiers
+    Object(String, TypeQualifiers),
+    /// Function type, whose parameters have optional names
+    Function {
+        abi: &'static str,
+        params: Vec<(Option<String>, String)>,
+        /// Whether the function takes variable arguments, which are written `...` as in C
+        variadic: bool,
+        /// Return type, or `None` for `void`
+        ret: Option<String>,
+    },
+}
+
+impl Ty {
+    /// Returns the Rust type, which for a function type is a function pointer type.
+    fn rust(&self) -> String {
+        match self {
+            Ty::Object(ty, _) => ty.clone(),
+            Ty::Function {
+                abi,
+                params,
+                variadic,
+                ret,
+            } => {
+                let mut params: Vec<&str> = params.iter().map(|(_, ty)| ty.as_str()).collect();
+                if *variadic {
+                    params.push("...");
+                }
+                let ret = ret
+                    .as_ref()
+                    .map_or_else(String::new, |ret| format!(" -> {ret}"));
+                format!("extern \"{abi}\" fn({}){ret}", params.join(", "))
+            }
+        }
+    }
+
+    /// Returns the type of a pointer to this type. Function pointers may be null in C but not in
+    /// Rust, so they're optional.
+    fn pointer(&self) -> String {
+        match self {
+            Ty::Object(ty, qualifiers) if qualifiers.contains(TypeQualifier::Const) => {
+                format!("*const {ty}")
+            }
+            Ty::Object(ty, _) => format!("*mut {ty}"),
+            Ty::Function { .. } => format!("Option<{}>", self.rust()),
+        }
+    }
+
+    /// Returns the type of a reference to this type.
+    fn reference(&self) -> String {
+        match self {
+            Ty::Object(ty, qualifiers) if qualifiers.contains(TypeQualifier::Const) => {
+                format!("&{ty}")
+            }
+            Ty::Object(ty, _) => format!("&mut {ty}"),
+            Ty::Function { .. } => self.rust(),
+        }
+    }
+}
+
+/// State of the translation of a declaration to Rust
+#[derive(Debug, Default)]
+struct RustTranslator {
+    /// Definitions of the records defined by the declaration, innermost first
+    definitions: Vec<String>,
+    notes: Vec<Note>,
+}
+
+impl RustTranslator {
+    /// Notes the qualifiers which have no Rust equivalent. `const` is handled by pointers.
+    fn qualifiers(&mut self, qualifiers: TypeQualifiers) {
+        if qualifiers.contains(TypeQualifier::Volatile) {
+            self.note(Note::Volatile);
+        }
+        if qualifiers.contains(TypeQualifier::Atomic) {
+            self.note(Note::Atomic);
+        }
+        if qualifiers.contains(TypeQualifier::Restrict) {
+            self.note(Note::Restrict);
+        }
+    }
+
+    fn primitive(&mut self, name: &str) -> String {
+        if let Some(real) = name.strip_suffix(" _Complex") {
+            self.note(Note::Complex);
+            return format!("[{}; 2]", self.primitive(real));
+        }
+        let (base, unsigned) = split_primitive(name);
+        let rust = match (base, unsigned) {
+            ("char", _) if name == "char" => "c_char",
+            ("char", false) => "c_schar",
+            ("char", true) => "c_uchar",
+            ("short", false) => "c_short",
+            ("short", true) => "c_ushort",
+            ("" | "int", false) => "c_int",
+            ("" | "int", true) => "c_uint",
+            ("long", false) => "c_long",
+            ("long", true) => "c_ulong",
+            ("long long", false) => "c_longlong",
+            ("long long", true) => "c_ulonglong",
+            ("float", _) => "c_float",
+            ("double", _) => "c_double",
+            ("long double", _) => {
+                self.note(Note::LongDouble);
+                "f64"
+            }
+            ("_Bool" | "bool", _) => "bool",
+            ("void", _) => "c_void",
+            ("nullptr_t", _) => "*mut c_void",
+            ("char8_t", _) => "u8",
+            ("char16_t", _) => "u16",
+            ("char32_t", _) => "u32",
+            ("wchar_t", _) => {
+                self.note(Note::WideChar);
+                "i32"
+            }
+            ("_Decimal32" | "_Decimal64" | "_Decimal128", _) => {
+                self.note(Note::Decimal);
+                match base {
+                    "_Decimal32" => "u32",
+                    "_Decimal64" => "u64",
+                    _ => "u128",
+                }
+            }
+            _ => name,
+        };
+        rust.to_string()
+    }
+
+    fn bit_int(&mut self, BitInt { width, unsigned }: BitInt) -> String {
+        let prefix = if unsigned { "u" } else { "i" };
+        let bits = [8, 16, 32, 64, 128].into_iter().find(|&bits| bits >= width);
+        if bits != Some(width) {
+            self.note(Note::BitInt(width));
+        }
+        bits.map_or_else(|| "_".to_string(), |bits| format!("{prefix}{bits}"))
+    }
+
+    /// Returns the name of a record, adding its definition if it has a body.
+    fn record(&mut self, record: &Record) -> String {
+        if record.name.is_none() {
+            self.note(Note::Anonymous);
+        }
+        let name = record.name.map_or_else(|| "_".to_string(), identifier);
+        match &record.body {
+            None => (),
+            Some(RecordBody::Members(members)) => {
+                let keyword = if record.kind == RecordKind::Union {
+                    "union"
+                } else {
+                    "struct"
+                };
+                let fields: Vec<String> = members
+                    .iter()
+                    .filter_map(|member| self.field(member))
+                    .map(|field| format!("    pub {field},"))
+                    .collect();
+                self.definitions.push(format!(
+                    "#[repr(C)]\npub {keyword} {name} {{\n{}\n}}",
+                    fields.join("\n")
+                ));
+            }
+            Some(RecordBody::Enumerators(enumerators)) => {
+                // Enumeration constants have type `int`, as do most enums.
+                let mut definition = vec![format!("pub type {name} = c_int;")];
+                for (enumerator, value) in Enumerator::values(enumerators) {
+                    let constant = identifier(enumerator.name);
+                    definition.push(format!("pub const {constant}: {name} = {value};"));
+                }
+                self.definitions.push(definition.join("\n"));
+            }
+        }
+        name
+    }
+
+    /// Returns the field declaring a member, e.g. `x: c_int`, or `None` if it has no Rust
+    /// equivalent.
+    fn field(&mut self, member: &Member) -> Option<String> {
+        let decl = &member.declaration;
+        if member.bit_width.is_some() {
+            self.note(Note::BitField);
+            return None;
+        }
+        let ty = self.declaration_type(decl, false);
+        // Unnamed members are anonymous records, whose members Rust can't access directly.
+        let Some(name) = decl.declarator.name() else {
+            self.note(Note::Anonymous);
+            return None;
+        };
+        Some(format!("{}: {}", identifier(name), ty.rust()))
+    }
+}
+
+impl Translator for RustTranslator {
+    type Ty = Ty;
+    type Note = Note;
+
+    const UNKNOWN_SIZE: Note = Note::UnknownSize;
+    const VARIABLE_LENGTH: Note = Note::VariableLength;
+    const BLOCK: Note = Note::Block;
+    const MEMBER_POINTER: Note = Note::MemberPointer;
+
+    fn notes(&mut self) -> &mut Vec<Note> {
+        &mut self.notes
+    }
+
+    fn base_type(&mut self, qt: &QualifiedType) -> Ty {
+        let ty = match &qt.1 {
+            Type::Primitive(primitive) => self.primitive(primitive.as_ref()),
+            Type::Record(record) => self.record(record),
+            Type::Custom(name) => builtin(name).map_or_else(|| identifier(name), str::to_string),
+            Type::Typeof(_) => {
+                self.note(Note::Typeof);
+                "_".to_string()
+            }
+            Type::BitInt(bit_int) => self.bit_int(*bit_int),
+        };
+        self.qualifiers(qt.0);
+        let mut qualifiers = qt.0;
+        qualifiers.remove(TypeQualifier::Typedef);
+        Ty::Object(ty, qualifiers)
+    }
+
+    fn pointer(&mut self, ty: Ty, qualifiers: TypeQualifiers) -> Ty {
+        self.qualifiers(qualifiers);
+        Ty::Object(ty.pointer(), qualifiers)
+    }
+
+    fn reference(&mut self, ty: Ty) -> Ty {
+        Ty::Object(ty.reference(), TypeQualifiers::default())
+    }
+
+    fn untyped_pointer(&mut self, qualifiers: TypeQualifiers) -> Ty {
+        Ty::Object("*mut c_void".into(), qualifiers)
+    }
+
+    fn array(&mut self, element: Ty, len: String) -> Ty {
+        let qualifiers = match &element {
+            Ty::Object(_, qualifiers) => *qualifiers,
+            Ty::Function { .. } => TypeQualifiers::default(),
+        };
+        Ty::Object(format!("[{}; {len}]", element.rust()), qualifiers)
+    }
+
+    fn function(
+        &mut self,
+        ret: Ty,
+        params: Vec<(Option<&str>, Ty)>,
+        variadic: bool,
+        calling_convention: Option<CallingConvention>,
+    ) -> Ty {
+        let ret = match ret {
+            Ty::Object(ty, _) if ty == "c_void" => None,
+            ty => Some(ty.rust()),
+        };
+        Ty::Function {
+            abi: abi(calling_convention),
+            params: params
+                .into_iter()
+                .map(|(name, ty)| (name.map(identifier), ty.rust()))
+                .collect(),
+            variadic,
+            ret,
+        }
+    }
+}
+
+/// Translates a declaration to its Rust equivalent, along with notes on any differences.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, parser::parser, transpile::to_rust::translate};
+/// let decls = parser().parse("char *names[10]; int (*cb)(int)").unwrap();
+/// assert_eq!(translate(&decls[0]).code, "pub static mut names: [*mut c_char; 10];");
+/// assert_eq!(
+///     translate(&decls[1]).code,
+///     r#"pub static mut cb: Option<extern "C" fn(c_int) -> c_int>;"#
+/// );
+/// ```
+#[must_use]
+pub fn translate(decl: &Declaration) -> Translation {
+    let mut translator = RustTranslator::default();
+    let item = if decl.forward_declaration
+        && let Type::Record(record) = &decl.base_type.1
+    {
+        let name = translator.record(record);
+        Some(if record.kind == RecordKind::Enum {
+            format!("pub type {name} = c_int;")
+        } else {
+            // Opaque types are declared as zero-sized structs which can only be used through
+            // pointers.
+            format!("#[repr(C)]\npub struct {name} {{\n    _private: [u8; 0],\n}}")
+        })
+    } else {
+        let ty = translator.declaration_type(decl, false);
+        let is_typedef = decl.base_type.0.contains(TypeQualifier::Typedef);
+        if matches!(ty, Ty::Function { .. }) && (is_typedef || decl.declarator.name().is_none()) {
+            translator.note(Note::FunctionType);
+        }
+        match (decl.declarator.name(), ty) {
+            // A record definition on its own only defines the record.
+            (None, _)
+                if decl.declarator == Declarator::Anonymous
+                    && !translator.definitions.is_empty() =>
+            {
+                None
+            }
+            (None, ty) => Some(ty.rust()),
+            (Some(name), ty) if is_typedef => {
+                Some(format!("pub type {} = {};", identifier(name), ty.rust()))
+            }
+            (
+                Some(name),
+                Ty::Function {
+                    abi,
+                    params,
+                    variadic,
+                    ret,
+                },
+            ) => {
+                if abi != "C" {
+                    translator.note(Note::CallingConvention(abi));
+                }
+                let mut params: Vec<String> = params
+                    .into_iter()
+                    .map(|(name, ty)| format!("{}: {ty}", name.as_deref().unwrap_or("_")))
+                    .collect();
+                if variadic {
+                    params.push("...".to_string());
+                }
+                let ret = ret.map_or_else(String::new, |ret| format!(" -> {ret}"));
+                Some(format!(
+                    "pub fn {}({}){ret};",
+                    identifier(name),
+                    params.join(", ")
+                ))
+            }
+            (Some(name), Ty::Object(ty, qualifiers)) => {
+                let mutability = if qualifiers.contains(TypeQualifier::Const) {
+                    ""
+                } else {
+                    "mut "
+                };
+                Some(format!(
+                    "pub static {mutability}{}: {ty};",
+                    identifier(name)
+                ))
+            }
+        }
+    };
+    if decl.storage_class == Some(StorageClass::Static) {
+        translator.note(Note::InternalLinkage);
+    }
+    let RustTranslator {
+        mut definitions,
+        notes,
+    } = translator;
+    definitions.extend(item);
+    Translation {
+        code: definitions.join("\n"),
+        notes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn run(src: &str) -> Translation {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 1, "Expected one declaration for {src}");
+        translate(&decls[0])
+    }
+
+    #[test]
+    fn objects_and_functions() {
+        for (src, expected) in [
+            ("int *p", "pub static mut p: *mut c_int;"),
+            ("const char *const s", "pub static s: *const c_char;"),
+            ("char buf[10]", "pub static mut buf: [c_char; 10];"),
+            ("unsigned long long n", "pub static mut n: c_ulonglong;"),
+            (
+                "int (*grid)[3][4]",
+                "pub static mut grid: *mut [[c_int; 4]; 3];",
+            ),
+            (
+                "int (*fp)(int)",
+                r#"pub static mut fp: Option<extern "C" fn(c_int) -> c_int>;"#,
+            ),
+            (
+                "void *memcpy(void *dst, const void *src, uintptr_t n)",
+                "pub fn memcpy(dst: *mut c_void, src: *const c_void, n: usize) -> *mut c_void;",
+            ),
+            (
+                "void qsort(void *, uintptr_t, uintptr_t, int (*)(const void *, const void *))",
+                "pub fn qsort(_: *mut c_void, _: usize, _: usize, \
+                 _: Option<extern \"C\" fn(*const c_void, *const c_void) -> c_int>);",
+            ),
+            (
+                "int main(int argc, char *argv[])",
+                "pub fn main(argc: c_int, argv: *mut *mut c_char) -> c_int;",
+            ),
+            (
+                "void (*signal(int sig, void (*handler)(int)))(int)",
+                "pub fn signal(sig: c_int, handler: Option<extern \"C\" fn(c_int)>) \
+                 -> Option<extern \"C\" fn(c_int)>;",
+            ),
+            (
+                "int printf(const char *fmt, ...)",
+                "pub fn printf(fmt: *const c_char, ...) -> c_int;",
+            ),
+            (
+                "void (*log)(int, ...)",
+                r#"pub static mut log: Option<extern "C" fn(c_int, ...)>;"#,
+            ),
+            ("uint8_t type", "pub static mut r#type: u8;"),
+            ("int *", "*mut c_int"),
+            (
+                "typedef int (*handler_t)(int)",
+                r#"pub type handler_t = Option<extern "C" fn(c_int) -> c_int>;"#,
+            ),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, vec![], "Unexpected notes for {src}");
+        }
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            run("struct point { int x, y; struct point *next; } origin").code,
+            "#[repr(C)]\npub struct point {\n    pub x: c_int,\n    pub y: c_int,\n    \
+             pub next: *mut point,\n}\npub static mut origin: point;"
+        );
+        assert_eq!(
+            run("union u { float f; unsigned char bytes[4]; };").code,
+            "#[repr(C)]\npub union u {\n    pub f: c_float,\n    pub bytes: [c_uchar; 4],\n}"
+        );
+        assert_eq!(
+            run("enum color { RED, GREEN = 5, BLUE };").code,
+            "pub type color = c_int;\npub const RED: color = 0;\npub const GREEN: color = 5;\n\
+             pub const BLUE: color = 6;"
+        );
+        assert_eq!(
+            run("struct node;").code,
+            "#[repr(C)]\npub struct node {\n    _private: [u8; 0],\n}"
+        );
+    }
+
+    #[test]
+    fn notes() {
+        for (src, expected, notes) in [
+            (
+                "long double x",
+                "pub static mut x: f64;",
+                vec![Note::LongDouble],
+            ),
+            (
+                "double _Complex z",
+                "pub static mut z: [c_double; 2];",
+                vec![Note::Complex],
+            ),
+            (
+                "volatile int *restrict p",
+                "pub static mut p: *mut c_int;",
+                vec![Note::Volatile, Note::Restrict],
+            ),
+            (
+                "extern int table[]",
+                "pub static mut table: [c_int; 0];",
+                vec![Note::UnknownSize],
+            ),
+            (
+                "unsigned _BitInt(24) x",
+                "pub static mut x: u32;",
+                vec![Note::BitInt(24)],
+            ),
+            (
+                "static int count",
+                "pub static mut count: c_int;",
+                vec![Note::InternalLinkage],
+            ),
+            (
+                "typedef int callback_t(int)",
+                r#"pub type callback_t = extern "C" fn(c_int) -> c_int;"#,
+                vec![Note::FunctionType],
+            ),
+            (
+                "int __stdcall f(void)",
+                "pub fn f() -> c_int;",
+                vec![Note::CallingConvention("stdcall")],
+            ),
+            (
+                "struct { int x; int flags : 3; } s",
+                "#[repr(C)]\npub struct _ {\n    pub x: c_int,\n}\npub static mut s: _;",
+                vec![Note::Anonymous, Note::BitField],
+            ),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, notes, "Wrong notes for {src}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            run("restrict int *p").to_string(),
+            "pub static mut p: *mut c_int;\n// Note: Rust has no equivalent of `restrict`, so it \
+             is left out"
+        );
+    }
+}
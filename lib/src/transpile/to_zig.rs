@@ -0,0 +1,751 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Translation of C declarations to their equivalents in Zig
+//!
+//! Objects and functions are translated to `extern` declarations, e.g. `pub extern var p: [*c]c_int;`
+//! for `int *p`, and typedefs and records to constants holding their types. Declarations without
+//! a name are translated to just their type. Pointers are translated to C pointers, e.g.
+//! `[*c]c_int`, since C doesn't say whether they point to one object or many, or whether they may
+//! be null.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use super::{Translator, builtin, split_primitive};
+use crate::ast::{
+    BitInt, CallingConvention, Declaration, Declarator, Enumerator, Member, QualifiedType, Record,
+    RecordBody, RecordKind, StorageClass, Type, TypeQualifier, TypeQualifiers,
+};
+
+/// Difference between a C declaration and its translation, where Zig has no direct equivalent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Note {
+    /// Complex type, which is translated to an array of its real and imaginary parts
+    Complex,
+    /// `wchar_t`, which is translated to `i32`
+    WideChar,
+    /// Decimal floating type, which is translated to an integer of the same size
+    Decimal,
+    /// `volatile` qualifier on an object rather than on what a pointer points to, which is left
+    /// out
+    Volatile,
+    /// `_Atomic` qualifier, which is left out
+    Atomic,
+    /// `restrict` qualifier on something other than a parameter, which is left out
+    Restrict,
+    /// Array of unknown size, which is translated to an array of length 0
+    UnknownSize,
+    /// Variable-length array, whose length is translated as if it were a constant
+    VariableLength,
+    /// `typeof` specifier, which is translated to `@TypeOf()`
+    Typeof,
+    /// Member without a name, which is left out of its record
+    Anonymous,
+    /// Bit-field, which is left out of its record
+    BitField,
+    /// Clang block pointer, which is translated to `?*anyopaque`
+    Block,
+    /// C++ member pointer, which is translated to `?*anyopaque`
+    MemberPointer,
+    /// `static` declaration, whose internal linkage makes it inaccessible from Zig
+    InternalLinkage,
+}
+
+/// Formats the difference described by the note, e.g. "Zig has no equivalent of `restrict`
+/// outside of parameters, so it is left out".
+impl Display for Note {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Note::Complex => write!(
+                f,
+                "complex numbers are translated to arrays of their real and imaginary parts, \
+                 which have the same layout but may be passed to functions differently"
+            ),
+            Note::WideChar => write!(
+                f,
+                "`wchar_t` is translated to `i32`, but it is an unsigned 16-bit integer on Windows"
+            ),
+            Note::Decimal => write!(
+                f,
+                "decimal floating types have no Zig equivalent, so integers of the same size are \
+                 used"
+            ),
+            Note::Volatile => write!(
+                f,
+                "Zig only has volatile pointers, so volatile objects must be accessed through a \
+                 `*volatile` pointer"
+            ),
+            Note::Atomic => write!(
+                f,
+                "`_Atomic` types are translated to their non-atomic equivalents, so they must be \
+                 accessed with `@atomicLoad()` and `@atomicStore()`"
+            ),
+            Note::Restrict => write!(
+                f,
+                "Zig has no equivalent of `restrict` outside of parameters, so it is left out"
+            ),
+            Note::UnknownSize => write!(
+                f,
+                "arrays of unknown size are translated to arrays of length 0, so their elements \
+                 must be accessed through pointers"
+            ),
+            Note::VariableLength => write!(
+                f,
+                "the length of a variable-length array is not a constant, so it must be replaced"
+            ),
+            Note::Typeof => write!(
+                f,
+                "`typeof` is translated to `@TypeOf()`, whose operand must be rewritten as a Zig \
+                 expression"
+            ),
+            Note::Anonymous => write!(
+                f,
+                "Zig has no anonymous members, so they are left out and the layout won't match \
+                 until they are named"
+            ),
+            Note::BitField => write!(
+                f,
+                "`extern` structs can't have bit-fields, so they are left out and the layout \
+                 won't match until their storage is added"
+            ),
+            Note::Block => write!(
+                f,
+                "blocks have no Zig equivalent, so they are translated to untyped pointers"
+            ),
+            Note::MemberPointer => write!(
+                f,
+                "member pointers have no Zig equivalent, so they are translated to untyped pointers"
+            ),
+            Note::InternalLinkage => write!(
+                f,
+                "`static` declarations have internal linkage, so they can't be accessed from Zig"
+            ),
+        }
+    }
+}
+
+/// Zig translation of a C declaration, whose code consists of the definitions of any records
+/// which the declaration defines, followed by the translated declaration itself
+pub type Translation = super::Translation<Note>;
+
+/// Keywords and primitive values and types which can't be used as Zig identifiers unless they're
+/// quoted
+const KEYWORDS: &[&str] = &[
+    "addrspace",
+    "align",
+    "allowzero",
+    "and",
+    "anyframe",
+    "anyopaque",
+    "anytype",
+    "asm",
+    "bool",
+    "break",
+    "callconv",
+    "catch",
+    "comptime",
+    "const",
+    "continue",
+    "defer",
+    "else",
+    "enum",
+    "errdefer",
+    "error",
+    "export",
+    "extern",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "inline",
+    "isize",
+    "linksection",
+    "noalias",
+    "noinline",
+    "noreturn",
+    "nosuspend",
+    "null",
+    "opaque",
+    "or",
+    "orelse",
+    "packed",
+    "pub",
+    "resume",
+    "return",
+    "struct",
+    "suspend",
+    "switch",
+    "test",
+    "threadlocal",
+    "true",
+    "try",
+    "type",
+    "undefined",
+    "union",
+    "unreachable",
+    "usize",
+    "var",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// Returns the Zig identifier for a C identifier, which is quoted, e.g. `@"type"`, if it's a
+/// keyword or the name of a primitive type.
+fn identifier(name: &str) -> String {
+    let is_integer_type = name
+        .strip_prefix(['i', 'u'])
+        .is_some_and(|bits| !bits.is_empty() && bits.bytes().all(|b| b.is_ascii_digit()));
+    if KEYWORDS.contains(&name) || is_integer_type {
+        format!("@\"{name}\"")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Returns the `callconv()` argument for a calling convention, or `None` for the C calling
+/// convention.
+fn calling_convention(calling_convention: Option<CallingConvention>) -> Option<&'static str> {
+    match calling_convention {
+        None | Some(CallingConvention::Cdecl) => None,
+        Some(CallingConvention::Stdcall) => Some(".{ .x86_stdcall = .{} }"),
+        Some(CallingConvention::Fastcall) => Some(".{ .x86_fastcall = .{} }"),
+    }
+}
+
+/// Zig type which a C type is translated to
+#[derive(Debug, Clone)]
+enum Ty {
+    /// Type of an object, along with its C qualifiers
+    Object(String, TypeQualifiers),
+    /// `void`, along with its C qualifiers
+    Void(TypeQualifiers),
+    /// Function type, whose parameters have optional names
+    Function {
+        calling_convention: Option<&'static str>,
+        params: Vec<(Option<String>, String)>,
+        ret: String,
+    },
+}
+
+impl Ty {
+    /// Returns the Zig type.
+    fn zig(&self) -> String {
+        match self {
+            Ty::Object(ty, _) => ty.clone(),
+            Ty::Void(_) => "anyopaque".to_string(),
+            Ty::Function {
+                calling_convention,
+                params,
+                ret,
+            } => {
+                let params: Vec<&str> = params.iter().map(|(_, ty)| ty.as_str()).collect();
+                let calling_convention = calling_convention.unwrap_or(".c");
+                format!(
+                    "fn ({}) callconv({calling_convention}) {ret}",
+                    params.join(", ")
+                )
+            }
+        }
+    }
+
+    /// Returns the qualifiers of the type.
+    fn qualifiers(&self) -> TypeQualifiers {
+        match self {
+            Ty::Object(_, qualifiers) | Ty::Void(qualifiers) => *qualifiers,
+            Ty::Function { .. } => TypeQualifiers::default(),
+        }
+    }
+
+    /// Returns the `const` and `volatile` qualifiers of a pointer to the type, e.g. `const `.
+    fn pointee_qualifiers(&self) -> String {
+        let qualifiers = self.qualifiers();
+        let mut zig = String::new();
+        if qualifiers.contains(TypeQualifier::Const) || matches!(self, Ty::Function { .. }) {
+            zig.push_str("const ");
+        }
+        if qualifiers.contains(TypeQualifier::Volatile) {
+            zig.push_str("volatile ");
+        }
+        zig
+    }
+
+    /// Returns the type of a C pointer to this type. Pointers to `void` and to functions can't be
+    /// C pointers, so they're optional pointers instead.
+    fn pointer(&self) -> String {
+        let qualifiers = self.pointee_qualifiers();
+        match self {
+            Ty::Object(ty, _) => format!("[*c]{qualifiers}{ty}"),
+            Ty::Void(_) | Ty::Function { .. } => format!("?*{qualifiers}{}", self.zig()),
+        }
+    }
+
+    /// Returns the type of a reference to this type, which is a pointer which can't be null.
+    fn reference(&self) -> String {
+        format!("*{}{}", self.pointee_qualifiers(), self.zig())
+    }
+}
+
+/// State of the translation of a declaration to Zig
+#[derive(Debug, Default)]
+struct ZigTranslator {
+    /// Definitions of the records defined by the declaration, innermost first
+    definitions: Vec<String>,
+    notes: Vec<Note>,
+}
+
+impl ZigTranslator {
+    /// Notes the qualifiers of an object which Zig has no way to express. `const` is handled by
+    /// the declaration, and `volatile` and `restrict` only have Zig equivalents for pointees and
+    /// parameters, respectively.
+    fn object_qualifiers(&mut self, qualifiers: TypeQualifiers, parameter: bool) {
+        if qualifiers.contains(TypeQualifier::Volatile) && !parameter {
+            self.note(Note::Volatile);
+        }
+        if qualifiers.contains(TypeQualifier::Restrict) && !parameter {
+            self.note(Note::Restrict);
+        }
+    }
+
+    /// Returns the type of a pointer to the given type, noting the pointee's `restrict`
+    /// qualifier, which Zig has no way to express.
+    fn pointer_to(&mut self, ty: &Ty) -> String {
+        if ty.qualifiers().contains(TypeQualifier::Restrict) {
+            self.note(Note::Restrict);
+        }
+        ty.pointer()
+    }
+
+    fn primitive(&mut self, name: &str, qualifiers: TypeQualifiers) -> Ty {
+        if let Some(real) = name.strip_suffix(" _Complex") {
+            self.note(Note::Complex);
+            let real = self.primitive(real, qualifiers).zig();
+            return Ty::Object(format!("[2]{real}"), qualifiers);
+        }
+        let (base, unsigned) = split_primitive(name);
+        let zig = match (base, unsigned) {
+            ("void", _) => return Ty::Void(qualifiers),
+            ("char", _) if name == "char" => "c_char",
+            ("char", false) => "i8",
+            ("char", true) | ("char8_t", _) => "u8",
+            ("short", false) => "c_short",
+            ("short", true) => "c_ushort",
+            ("" | "int", false) => "c_int",
+            ("" | "int", true) => "c_uint",
+            ("long", false) => "c_long",
+            ("long", true) => "c_ulong",
+            ("long long", false) => "c_longlong",
+            ("long long", true) => "c_ulonglong",
+            ("float", _) => "f32",
+            ("double", _) => "f64",
+            ("long double", _) => "c_longdouble",
+            ("_Bool" | "bool", _) => "bool",
+            ("nullptr_t", _) => "?*anyopaque",
+            ("char16_t", _) => "u16",
+            ("char32_t", _) => "u32",
+            ("wchar_t", _) => {
+                self.note(Note::WideChar);
+                "i32"
+            }
+            ("_Decimal32" | "_Decimal64" | "_Decimal128", _) => {
+                self.note(Note::Decimal);
+                match base {
+                    "_Decimal32" => "u32",
+                    "_Decimal64" => "u64",
+                    _ => "u128",
+                }
+            }
+            _ => name,
+        };
+        Ty::Object(zig.to_string(), qualifiers)
+    }
+
+    /// Returns the name of a record, adding its definition if it has a body, or the record type
+    /// itself if it has no name.
+    fn record(&mut self, record: &Record) -> String {
+        let name = record.name.map(identifier);
+        let ty = match &record.body {
+            None => return name.unwrap_or_default(),
+            Some(RecordBody::Members(members)) => {
+                let keyword = if record.kind == RecordKind::Union {
+                    "extern union"
+                } else {
+                    "extern struct"
+                };
+                let fields: Vec<String> = members
+                    .iter()
+                    .filter_map(|member| self.field(member))
+                    .collect();
+                match &name {
+                    _ if fields.is_empty() => format!("{keyword} {{}}"),
+                    Some(_) => format!("{keyword} {{\n    {},\n}}", fields.join(",\n    ")),
+                    None => format!("{keyword} {{ {} }}", fields.join(", ")),
+                }
+            }
+            Some(RecordBody::Enumerators(enumerators)) => {
+                // Enumeration constants have type `int`, as do most enums.
+                let ty = name.as_deref().unwrap_or("c_int");
+                let mut definition = Vec::new();
+                if let Some(name) = &name {
+                    definition.push(format!("pub const {name} = c_int;"));
+                }
+                for (enumerator, value) in Enumerator::values(enumerators) {
+                    let constant = identifier(enumerator.name);
+                    definition.push(format!("pub const {constant}: {ty} = {value};"));
+                }
+                self.definitions.push(definition.join("\n"));
+                return ty.to_string();
+            }
+        };
+        match name {
+            Some(name) => {
+                self.definitions.push(format!("pub const {name} = {ty};"));
+                name
+            }
+            // Records without names are used directly, since Zig's records don't need names.
+            None => ty,
+        }
+    }
+
+    /// Returns the field declaring a member, e.g. `x: c_int`, or `None` if it has no Zig
+    /// equivalent.
+    fn field(&mut self, member: &Member) -> Option<String> {
+        let decl = &member.declaration;
+        if member.bit_width.is_some() {
+            self.note(Note::BitField);
+            return None;
+        }
+        let ty = self.declaration_type(decl, false);
+        self.object_qualifiers(ty.qualifiers(), false);
+        let Some(name) = decl.declarator.name() else {
+            self.note(Note::Anonymous);
+            return None;
+        };
+        Some(format!("{}: {}", identifier(name), ty.zig()))
+    }
+}
+
+impl Translator for ZigTranslator {
+    type Ty = Ty;
+    type Note = Note;
+
+    const UNKNOWN_SIZE: Note = Note::UnknownSize;
+    const VARIABLE_LENGTH: Note = Note::VariableLength;
+    const BLOCK: Note = Note::Block;
+    const MEMBER_POINTER: Note = Note::MemberPointer;
+
+    fn notes(&mut self) -> &mut Vec<Note> {
+        &mut self.notes
+    }
+
+    fn base_type(&mut self, qt: &QualifiedType) -> Ty {
+        let mut qualifiers = qt.0;
+        qualifiers.remove(TypeQualifier::Typedef);
+        if qualifiers.contains(TypeQualifier::Atomic) {
+            self.note(Note::Atomic);
+        }
+        let ty = match &qt.1 {
+            Type::Primitive(primitive) => return self.primitive(primitive.as_ref(), qualifiers),
+            Type::Record(record) => self.record(record),
+            Type::Custom(name) => builtin(name).map_or_else(|| identifier(name), str::to_string),
+            Type::Typeof(operand) => {
+                self.note(Note::Typeof);
+                format!("@TypeOf({operand})")
+            }
+            Type::BitInt(BitInt { width, unsigned }) => {
+                format!("{}{width}", if *unsigned { "u" } else { "i" })
+            }
+        };
+        Ty::Object(ty, qualifiers)
+    }
+
+    fn pointer(&mut self, ty: Ty, qualifiers: TypeQualifiers) -> Ty {
+        if qualifiers.contains(TypeQualifier::Atomic) {
+            self.note(Note::Atomic);
+        }
+        Ty::Object(self.pointer_to(&ty), qualifiers)
+    }
+
+    fn reference(&mut self, ty: Ty) -> Ty {
+        Ty::Object(ty.reference(), TypeQualifiers::default())
+    }
+
+    fn untyped_pointer(&mut self, qualifiers: TypeQualifiers) -> Ty {
+        Ty::Object("?*anyopaque".into(), qualifiers)
+    }
+
+    fn array(&mut self, element: Ty, len: String) -> Ty {
+        Ty::Object(format!("[{len}]{}", element.zig()), element.qualifiers())
+    }
+
+    fn function(
+        &mut self,
+        ret: Ty,
+        params: Vec<(Option<&str>, Ty)>,
+        variadic: bool,
+        convention: Option<CallingConvention>,
+    ) -> Ty {
+        let ret = match ret {
+            Ty::Void(_) => "void".to_string(),
+            ty => ty.zig(),
+        };
+        Ty::Function {
+            calling_convention: calling_convention(convention),
+            params: params
+                .into_iter()
+                .map(|(name, ty)| {
+                    let noalias = if ty.qualifiers().contains(TypeQualifier::Restrict) {
+                        "noalias "
+                    } else {
+                        ""
+                    };
+                    (name.map(identifier), format!("{noalias}{}", ty.zig()))
+                })
+                // Zig writes variable arguments the same way as C.
+                .chain(variadic.then(|| (None, "...".to_string())))
+                .collect(),
+            ret,
+        }
+    }
+}
+
+/// Translates a declaration to its Zig equivalent, along with notes on any differences.
+///
+/// # Examples
+///
+/// ```
+/// # use c2e::{chumsky::Parser, parser::parser, transpile::to_zig::translate};
+/// let decls = parser().parse("char *names[10]; int (*cb)(int)").unwrap();
+/// assert_eq!(translate(&decls[0]).code, "pub extern var names: [10][*c]c_char;");
+/// assert_eq!(
+///     translate(&decls[1]).code,
+///     "pub extern var cb: ?*const fn (c_int) callconv(.c) c_int;"
+/// );
+/// ```
+#[must_use]
+pub fn translate(decl: &Declaration) -> Translation {
+    let mut translator = ZigTranslator::default();
+    let item = if decl.forward_declaration
+        && let Type::Record(record) = &decl.base_type.1
+    {
+        let name = translator.record(record);
+        Some(if record.kind == RecordKind::Enum {
+            format!("pub const {name} = c_int;")
+        } else {
+            format!("pub const {name} = opaque {{}};")
+        })
+    } else {
+        let ty = translator.declaration_type(decl, false);
+        let is_typedef = decl.base_type.0.contains(TypeQualifier::Typedef);
+        match (decl.declarator.name(), ty) {
+            // A record definition on its own only defines the record.
+            (None, _)
+                if decl.declarator == Declarator::Anonymous
+                    && !translator.definitions.is_empty() =>
+            {
+                None
+            }
+            (None, ty) => Some(ty.zig()),
+            (Some(name), ty) if is_typedef => {
+                Some(format!("pub const {} = {};", identifier(name), ty.zig()))
+            }
+            (
+                Some(name),
+                Ty::Function {
+                    calling_convention,
+                    params,
+                    ret,
+                },
+            ) => {
+                let params: Vec<String> = params
+                    .into_iter()
+                    .map(|(name, ty)| match name {
+                        Some(name) => format!("{name}: {ty}"),
+                        None => ty,
+                    })
+                    .collect();
+                let calling_convention = calling_convention
+                    .map_or_else(String::new, |convention| format!(" callconv({convention})"));
+                Some(format!(
+                    "pub extern fn {}({}){calling_convention} {ret};",
+                    identifier(name),
+                    params.join(", ")
+                ))
+            }
+            (Some(name), ty) => {
+                let qualifiers = ty.qualifiers();
+                translator.object_qualifiers(qualifiers, false);
+                let keyword = if qualifiers.contains(TypeQualifier::Const) {
+                    "const"
+                } else {
+                    "var"
+                };
+                Some(format!(
+                    "pub extern {keyword} {}: {};",
+                    identifier(name),
+                    ty.zig()
+                ))
+            }
+        }
+    };
+    if decl.storage_class == Some(StorageClass::Static) {
+        translator.note(Note::InternalLinkage);
+    }
+    let ZigTranslator {
+        mut definitions,
+        notes,
+    } = translator;
+    definitions.extend(item);
+    Translation {
+        code: definitions.join("\n"),
+        notes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn run(src: &str) -> Translation {
+        let decls = crate::parser::parser().parse(src).unwrap();
+        assert_eq!(decls.len(), 1, "Expected one declaration for {src}");
+        translate(&decls[0])
+    }
+
+    #[test]
+    fn objects_and_functions() {
+        for (src, expected) in [
+            ("int *p", "pub extern var p: [*c]c_int;"),
+            (
+                "const char *const s",
+                "pub extern const s: [*c]const c_char;",
+            ),
+            ("volatile int *p", "pub extern var p: [*c]volatile c_int;"),
+            ("char buf[10]", "pub extern var buf: [10]c_char;"),
+            ("unsigned long long n", "pub extern var n: c_ulonglong;"),
+            ("long double x", "pub extern var x: c_longdouble;"),
+            ("unsigned _BitInt(24) x", "pub extern var x: u24;"),
+            ("int (*grid)[3][4]", "pub extern var grid: [*c][3][4]c_int;"),
+            (
+                "void *memcpy(void *restrict dst, const void *restrict src, uintptr_t n)",
+                "pub extern fn memcpy(dst: noalias ?*anyopaque, src: noalias ?*const anyopaque, \
+                 n: usize) ?*anyopaque;",
+            ),
+            (
+                "void qsort(void *, uintptr_t, uintptr_t, int (*)(const void *, const void *))",
+                "pub extern fn qsort(?*anyopaque, usize, usize, \
+                 ?*const fn (?*const anyopaque, ?*const anyopaque) callconv(.c) c_int) void;",
+            ),
+            (
+                "int main(int argc, char *argv[])",
+                "pub extern fn main(argc: c_int, argv: [*c][*c]c_char) c_int;",
+            ),
+            (
+                "int __stdcall f(void)",
+                "pub extern fn f() callconv(.{ .x86_stdcall = .{} }) c_int;",
+            ),
+            (
+                "int printf(const char *fmt, ...)",
+                "pub extern fn printf(fmt: [*c]const c_char, ...) c_int;",
+            ),
+            ("uint8_t type", "pub extern var @\"type\": u8;"),
+            ("int *", "[*c]c_int"),
+            (
+                "typedef int callback_t(int)",
+                "pub const callback_t = fn (c_int) callconv(.c) c_int;",
+            ),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, vec![], "Unexpected notes for {src}");
+        }
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            run("struct point { int x, y; struct point *next; } origin").code,
+            "pub const point = extern struct {\n    x: c_int,\n    y: c_int,\n    \
+             next: [*c]point,\n};\npub extern var origin: point;"
+        );
+        assert_eq!(
+            run("union { float f; unsigned char bytes[4]; } u").code,
+            "pub extern var u: extern union { f: f32, bytes: [4]u8 };"
+        );
+        assert_eq!(
+            run("enum color { RED, GREEN = 5, BLUE };").code,
+            "pub const color = c_int;\npub const RED: color = 0;\npub const GREEN: color = 5;\n\
+             pub const BLUE: color = 6;"
+        );
+        assert_eq!(run("struct node;").code, "pub const node = opaque {};");
+    }
+
+    #[test]
+    fn notes() {
+        for (src, expected, notes) in [
+            (
+                "double _Complex z",
+                "pub extern var z: [2]f64;",
+                vec![Note::Complex],
+            ),
+            (
+                "volatile int x",
+                "pub extern var x: c_int;",
+                vec![Note::Volatile],
+            ),
+            (
+                "int *restrict p",
+                "pub extern var p: [*c]c_int;",
+                vec![Note::Restrict],
+            ),
+            (
+                "extern int table[]",
+                "pub extern var table: [0]c_int;",
+                vec![Note::UnknownSize],
+            ),
+            (
+                "static int count",
+                "pub extern var count: c_int;",
+                vec![Note::InternalLinkage],
+            ),
+            (
+                "struct s { int x; int flags : 3; };",
+                "pub const s = extern struct {\n    x: c_int,\n};",
+                vec![Note::BitField],
+            ),
+        ] {
+            let translation = run(src);
+            assert_eq!(translation.code, expected, "Wrong translation of {src}");
+            assert_eq!(translation.notes, notes, "Wrong notes for {src}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            run("volatile int x").to_string(),
+            "pub extern var x: c_int;\n// Note: Zig only has volatile pointers, so volatile \
+             objects must be accessed through a `*volatile` pointer"
+        );
+    }
+}
@@ -0,0 +1,449 @@
+/*
+ * This program is free software: you can redistribute it and/or modify it under the terms of
+ * the GNU General Public License as published by the Free Software Foundation, either version
+ * 3 of the License, or (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY;
+ * without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License along with this program. If
+ * not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Generation of example code showing how to use a declared identifier
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    ast::{Declaration, Declarator, Type, TypeQualifier, TypeQualifiers},
+    c_syntax::{c_declarator, c_type},
+};
+
+/// Name used for the variable which receives the value read in the example
+const RESULT: &str = "result";
+
+/// Name used for the object whose members are accessed through a C++ member pointer
+const OBJECT: &str = "object";
+
+/// Returns whether the type is `void`.
+fn is_void(ty: &Type) -> bool {
+    matches!(ty, Type::Primitive(primitive) if primitive.as_ref() == "void")
+}
+
+/// Returns the parts of a declarator, from the name outwards.
+fn parts<'a, 'src>(declarator: &'a Declarator<'src>) -> Vec<&'a Declarator<'src>> {
+    let mut parts = Vec::new();
+    let mut current = declarator;
+    loop {
+        match current {
+            Declarator::Anonymous | Declarator::Ident(_) => break,
+            Declarator::Ptr(inner, _)
+            | Declarator::Reference(inner)
+            | Declarator::Block(inner, _)
+            | Declarator::MemberPtr(inner, ..)
+            | Declarator::Array(inner, ..)
+            | Declarator::Function { func: inner, .. } => {
+                parts.push(current);
+                current = inner;
+            }
+        }
+    }
+    parts.reverse();
+    parts
+}
+
+/// Returns a copy of the declarator with its innermost `consumed` parts replaced by the given
+/// name, i.e. the declarator of the type which remains after using those parts.
+fn remaining<'src>(
+    declarator: &Declarator<'src>,
+    consumed: usize,
+    name: &'src str,
+) -> Declarator<'src> {
+    if parts(declarator).len() <= consumed {
+        return Declarator::Ident(name);
+    }
+    let inner = |inner: &Declarator<'src>| Box::new(remaining(inner, consumed, name));
+    match declarator {
+        Declarator::Anonymous | Declarator::Ident(_) => Declarator::Ident(name),
+        Declarator::Ptr(decl, qualifiers) => Declarator::Ptr(inner(decl), *qualifiers),
+        Declarator::Reference(decl) => Declarator::Reference(inner(decl)),
+        Declarator::Block(decl, qualifiers) => Declarator::Block(inner(decl), *qualifiers),
+        Declarator::MemberPtr(decl, class, qualifiers) => {
+            Declarator::MemberPtr(inner(decl), class, *qualifiers)
+        }
+        Declarator::Array(decl, size, qualifiers) => {
+            Declarator::Array(inner(decl), *size, *qualifiers)
+        }
+        Declarator::Function {
+            func,
+            params,
+            variadic,
+            calling_convention,
+            member_qualifiers,
+        } => Declarator::Function {
+            func: inner(func),
+            params: params.clone(),
+            variadic: *variadic,
+            calling_convention: *calling_convention,
+            member_qualifiers: *member_qualifiers,
+        },
+    }
+}
+
+/// Returns the arguments for a call to a function with the given parameters, using the
+/// parameters' names or letters for unnamed parameters, starting from `first` for the first
+/// parameter, e.g. `a`, `b`, and so on.
+fn arguments(params: &[Declaration], first: char) -> String {
+    let args: Vec<String> = params
+        .iter()
+        .zip(first..)
+        .map(|(param, letter)| {
+            param
+                .declarator
+                .name()
+                .map_or_else(|| letter.to_string(), ToString::to_string)
+        })
+        .collect();
+    args.join(", ")
+}
+
+/// Returns the statement assigning a value to the declared object, if it can be assigned.
+fn assignment(decl: &Declaration, name: &str, parts: &[&Declarator]) -> Option<String> {
+    let value = match parts {
+        [] if decl.base_type.0.contains(TypeQualifier::Const) || is_void(&decl.base_type.1) => {
+            return None;
+        }
+        [] => "value".to_string(),
+        [
+            Declarator::Ptr(_, qualifiers) | Declarator::Block(_, qualifiers),
+            ..,
+        ] if qualifiers.contains(TypeQualifier::Const) => {
+            return None;
+        }
+        [
+            Declarator::Ptr(..) | Declarator::Block(..),
+            Declarator::Function { .. },
+            ..,
+        ] => format!("my_{name}"),
+        [Declarator::Ptr(..), ..] => "&value".to_string(),
+        [Declarator::MemberPtr(_, class, qualifiers), ..] => {
+            if qualifiers.contains(TypeQualifier::Const) {
+                return None;
+            }
+            format!("&{class}::member")
+        }
+        _ => return None,
+    };
+    Some(format!("{name} = {value};"))
+}
+
+/// Returns the statement using the declared identifier, e.g. `int result = cmp(a, b);`.
+/// Pointers are dereferenced and arrays are indexed until a function is called or a value
+/// which isn't a pointer or array is reached.
+fn use_statement(decl: &Declaration, name: &str, parts: &[&Declarator]) -> String {
+    let mut expr = name.to_string();
+    // Whether the expression ends with a prefix operator, so a postfix operator following it must
+    // be parenthesized
+    let mut is_prefix = false;
+    let mut consumed = 0;
+    for (i, part) in parts.iter().enumerate() {
+        let next = parts.get(i + 1);
+        match part {
+            // Functions can be called through pointers without dereferencing them.
+            Declarator::Ptr(..) | Declarator::Block(..)
+                if matches!(next, Some(Declarator::Function { .. })) => {}
+            // `void` can't be dereferenced.
+            Declarator::Ptr(..) if next.is_none() && is_void(&decl.base_type.1) => {
+                break;
+            }
+            Declarator::Ptr(..) | Declarator::Block(..) => {
+                expr = format!("*{expr}");
+                is_prefix = true;
+            }
+            // Members are accessed through an object of the class.
+            Declarator::MemberPtr(..) => {
+                expr = format!("{OBJECT}.*{expr}");
+                is_prefix = true;
+            }
+            Declarator::Reference(_) | Declarator::Anonymous | Declarator::Ident(_) => {}
+            Declarator::Array(..) | Declarator::Function { .. } => {
+                if is_prefix {
+                    expr = format!("({expr})");
+                    is_prefix = false;
+                }
+                if let Declarator::Function { params, .. } = part {
+                    expr = format!("{expr}({})", arguments(params, 'a'));
+                    consumed = i + 1;
+                    break;
+                }
+                expr = format!("{expr}[0]");
+            }
+        }
+        consumed = i + 1;
+    }
+
+    let remaining = remaining(&decl.declarator, consumed, RESULT);
+    let mut ty = decl.base_type.clone();
+    if parts.len() == consumed {
+        if is_void(&ty.1) {
+            return format!("{expr};");
+        }
+        // The copy needn't have the qualifiers of the value it's copied from.
+        ty.0 = TypeQualifiers::default();
+    }
+    format!("{} {} = {expr};", c_type(&ty), c_declarator(&remaining).0)
+}
+
+/// Generates a snippet of C code showing how to use the identifier declared by a declaration,
+/// e.g. for `int (*cmp)(const void *, const void *)`:
+///
+/// ```c
+/// cmp = my_cmp;
+/// int result = cmp(a, b);
+/// ```
+///
+/// Values are assigned to objects which can be assigned to, pointers are dereferenced, arrays
+/// are indexed, and functions are called. For a typedef, a variable named `value` of the defined
+/// type is declared, e.g. `T value;`, and used if its type is derived from another. Unnamed
+/// declarations are used through a variable named `x`.
+#[must_use]
+pub fn generate_usage(decl: &Declaration) -> String {
+    let mut lines = Vec::new();
+    let mut name = decl.declarator.name().unwrap_or("x");
+    let mut decl = decl.clone();
+    let typedef = decl.base_type.0.contains(TypeQualifier::Typedef);
+    if typedef {
+        decl.base_type.0.remove(TypeQualifier::Typedef);
+        lines.push(format!("{name} value;"));
+        name = "value";
+    }
+    let parts = parts(&decl.declarator);
+    // A variable which is itself named `value`, like the one declared for a typedef, isn't
+    // usefully assigned `value` or `&value`.
+    if let Some(assignment) = assignment(&decl, name, &parts)
+        .filter(|assignment| !matches!(assignment.as_str(), "value = value;" | "value = &value;"))
+    {
+        lines.push(assignment);
+    }
+    // Reading a variable which was only just declared shows nothing about its type.
+    if !typedef || !parts.is_empty() {
+        lines.push(use_statement(&decl, name, &parts));
+    }
+    lines.join("\n")
+}
+
+/// Ways to call a function through a pointer declared by a declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSyntax {
+    /// Call which explicitly dereferences the function pointer, e.g. `(*handler)(code)`
+    pub explicit: String,
+    /// Call which relies on function pointers being callable without dereferencing them, e.g.
+    /// `handler(code)`
+    pub implicit: String,
+    /// Statement assigning a value to the declared object, if it can be assigned, e.g.
+    /// `handler = my_handler;`
+    pub assignment: Option<String>,
+}
+
+/// Returns the expression which calls the last function reached through a pointer, indexing
+/// arrays, dereferencing other pointers, and calling other functions along the way, so that
+/// functions returning function pointers are called in a chain, e.g. `(*(*f)(a))(b)`. Pointers
+/// to functions are dereferenced if `explicit` is set. Returns `None` if no function is reached
+/// through a pointer.
+fn call_through_pointer(name: &str, parts: &[&Declarator], explicit: bool) -> Option<String> {
+    let mut expr = name.to_string();
+    // Whether the expression ends with a prefix operator, so a postfix operator following it must
+    // be parenthesized
+    let mut is_prefix = false;
+    let mut through_pointer = false;
+    let mut call = None;
+    // Letter for the first unnamed argument of the next call, so that the arguments of chained
+    // calls are distinct
+    let mut first_letter = 'a';
+    for (i, part) in parts.iter().enumerate() {
+        let next = parts.get(i + 1);
+        match part {
+            Declarator::Ptr(..) if matches!(next, Some(Declarator::Function { .. })) => {
+                through_pointer = true;
+                if explicit {
+                    expr = format!("*{expr}");
+                    is_prefix = true;
+                }
+            }
+            // Blocks are always called directly.
+            Declarator::Block(..) if matches!(next, Some(Declarator::Function { .. })) => {}
+            Declarator::Ptr(..) | Declarator::Block(..) => {
+                expr = format!("*{expr}");
+                is_prefix = true;
+            }
+            // Members are accessed through an object of the class.
+            Declarator::MemberPtr(..) => {
+                expr = format!("{OBJECT}.*{expr}");
+                is_prefix = true;
+            }
+            Declarator::Reference(_) | Declarator::Anonymous | Declarator::Ident(_) => {}
+            Declarator::Array(..) | Declarator::Function { .. } => {
+                if is_prefix {
+                    expr = format!("({expr})");
+                    is_prefix = false;
+                }
+                if let Declarator::Function { params, .. } = part {
+                    expr = format!("{expr}({})", arguments(params, first_letter));
+                    if params.iter().any(|param| param.declarator.name().is_none()) {
+                        first_letter = (first_letter..).nth(params.len()).unwrap_or(first_letter);
+                    }
+                    if through_pointer {
+                        call = Some(expr.clone());
+                    }
+                } else {
+                    expr = format!("{expr}[0]");
+                }
+            }
+        }
+    }
+    call
+}
+
+/// Returns the ways to call a function through a pointer declared by a declaration, e.g.
+/// `(*handler)(code)` and `handler(code)` for `void (*handler)(int code)`, or `None` if the
+/// declaration doesn't declare a pointer to a function, directly or through other pointers,
+/// arrays, and functions. Typedefs don't declare anything which can be called.
+#[must_use]
+pub fn call_syntax(decl: &Declaration) -> Option<CallSyntax> {
+    if decl.base_type.0.contains(TypeQualifier::Typedef) {
+        return None;
+    }
+    let name = decl.declarator.name().unwrap_or("x");
+    let parts = parts(&decl.declarator);
+    Some(CallSyntax {
+        explicit: call_through_pointer(name, &parts, true)?,
+        implicit: call_through_pointer(name, &parts, false)?,
+        assignment: assignment(decl, name, &parts),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn usage() {
+        for (src, expected) in [
+            (
+                "int (*cmp)(const void *, const void *)",
+                "cmp = my_cmp;\nint result = cmp(a, b);",
+            ),
+            ("int x", "x = value;\nint result = x;"),
+            ("const int limit", "int result = limit;"),
+            ("int f(int n, char c)", "int result = f(n, c);"),
+            ("void g(void)", "g();"),
+            ("char *strs[4]", "char result = *strs[0];"),
+            ("int (*p)[10]", "p = &value;\nint result = (*p)[0];"),
+            ("int m[2][3]", "int result = m[0][0];"),
+            ("void *mem", "mem = &value;\nvoid *result = mem;"),
+            ("const char *const s", "char result = *s;"),
+            ("char *(*get)(int)", "get = my_get;\nchar *result = get(a);"),
+            ("int (**fpp)(void)", "fpp = &value;\nint result = (*fpp)();"),
+            ("int (*(*f)(void))[3]", "f = my_f;\nint (*result)[3] = f();"),
+            (
+                "typedef int (*handler_t)(int code)",
+                "handler_t value;\nvalue = my_value;\nint result = value(code);",
+            ),
+            ("unsigned long *", "x = &value;\nunsigned long result = *x;"),
+            ("typedef int T", "T value;"),
+            ("typedef struct point point_t", "point_t value;"),
+            (
+                "typedef int *int_ptr",
+                "int_ptr value;\nint result = *value;",
+            ),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            assert_eq!(
+                generate_usage(&decls[0]),
+                expected,
+                "Wrong output for {src}"
+            );
+        }
+    }
+
+    #[test]
+    fn calls() {
+        for (src, expected) in [
+            (
+                "void (*handler)(int code)",
+                Some((
+                    "(*handler)(code)",
+                    "handler(code)",
+                    Some("handler = my_handler;"),
+                )),
+            ),
+            (
+                "int (**fpp)(void)",
+                Some(("(**fpp)()", "(*fpp)()", Some("fpp = &value;"))),
+            ),
+            (
+                "int (*ops[4])(int, int)",
+                Some(("(*ops[0])(a, b)", "ops[0](a, b)", None)),
+            ),
+            (
+                "void (*signal(int sig, void (*handler)(int)))(int)",
+                Some((
+                    "(*signal(sig, handler))(a)",
+                    "signal(sig, handler)(a)",
+                    None,
+                )),
+            ),
+            (
+                "int (*(*f)(int))(char)",
+                Some(("(*(*f)(a))(b)", "f(a)(b)", Some("f = my_f;"))),
+            ),
+            (
+                "int (*(*f)(void))[3]",
+                Some(("(*f)()", "f()", Some("f = my_f;"))),
+            ),
+            (
+                "int (*const cb)(void *ctx)",
+                Some(("(*cb)(ctx)", "cb(ctx)", None)),
+            ),
+            ("int f(int n)", None),
+            ("int *p", None),
+            ("void (^block)(void)", None),
+            ("typedef void (*handler_t)(int)", None),
+        ] {
+            let decls = crate::parser::parser().parse(src).unwrap();
+            let expected = expected.map(|(explicit, implicit, assignment)| CallSyntax {
+                explicit: explicit.to_string(),
+                implicit: implicit.to_string(),
+                assignment: assignment.map(ToString::to_string),
+            });
+            assert_eq!(call_syntax(&decls[0]), expected, "Wrong output for {src}");
+        }
+    }
+
+    #[test]
+    fn member_pointers() {
+        let mut state = crate::parser::State::default().with_cpp(true);
+        let decls = crate::parser::parser()
+            .parse_with_state("int C::*m; int (C::*f)(int n) const", &mut state)
+            .unwrap();
+        assert_eq!(
+            generate_usage(&decls[0]),
+            "m = &C::member;\nint result = object.*m;"
+        );
+        assert_eq!(
+            generate_usage(&decls[1]),
+            "f = &C::member;\nint result = (object.*f)(n);"
+        );
+        // Member functions can't be called without an object.
+        assert_eq!(call_syntax(&decls[1]), None);
+    }
+}
@@ -1,7 +1,9 @@
-use c2e::color::{Highlight, HighlightedText, fmt::Formatter};
+use c2e::color::{
+    Group, Highlight, HighlightedText, HighlightedTextSegment, StyleMap, fmt::Formatter,
+};
 use wasm_bindgen::prelude::wasm_bindgen;
 
-/// Data structure which maps [`Highlight`]s to class names.
+/// Data structure which maps [`Highlight`]s to class names and text styles.
 #[derive(Debug, Clone)]
 #[wasm_bindgen(getter_with_clone)]
 pub struct ClassMapping {
@@ -11,6 +13,8 @@ pub struct ClassMapping {
     pub identifier: Option<String>,
     pub number: Option<String>,
     pub quasi_keyword: Option<String>,
+    #[wasm_bindgen(skip)]
+    pub styles: StyleMap,
 }
 
 #[wasm_bindgen]
@@ -31,8 +35,35 @@ impl ClassMapping {
             identifier,
             number,
             quasi_keyword,
+            styles: StyleMap::PLAIN,
         }
     }
+
+    /// Sets the style of text with the given highlight, named in `snake_case` (e.g.
+    /// `quasi_keyword`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the highlight name is invalid or names a highlight which can't be
+    /// styled.
+    #[wasm_bindgen(js_name = "setStyle")]
+    pub fn set_style(
+        &mut self,
+        highlight: &str,
+        bold: bool,
+        italic: bool,
+        underline: bool,
+    ) -> Result<(), String> {
+        let style = highlight
+            .parse()
+            .ok()
+            .and_then(|highlight| self.styles.style_for_highlight_mut(highlight))
+            .ok_or_else(|| format!("Invalid highlight: {highlight}"))?;
+        style.bold = bold;
+        style.italic = italic;
+        style.underline = underline;
+        Ok(())
+    }
 }
 
 /// Formatter which formats [`HighlightedText`] into HTML, using `<span>` elements with classes for
@@ -41,7 +72,11 @@ impl ClassMapping {
 /// Text with [`Highlight::None`] will not be wrapped in a `<span>` element. Text with other
 /// highlights will be wrapped in a `<span>` element with a class corresponding to the highlight
 /// type according to this formatter's `class_mapping`. If the class mapping contains `None`, the
-/// text will not be wrapped in a `<span>` element.
+/// text will not be wrapped in a `<span>` element. Styled text is additionally wrapped in `<b>`,
+/// `<i>`, and `<u>` elements inside the `<span>`.
+///
+/// Each [`Group`] of segments is wrapped in a `<span>` element with a `data-level` or
+/// `data-parameter` attribute containing its index, e.g. `<span data-parameter="0">`.
 #[derive(Debug, Clone)]
 #[wasm_bindgen]
 pub struct HtmlFormatter {
@@ -59,31 +94,73 @@ impl HtmlFormatter {
 
 impl Formatter for HtmlFormatter {
     fn format(&self, dst: &mut impl core::fmt::Write, text: &HighlightedText) -> core::fmt::Result {
-        text.0
-            .iter()
-            .filter(|segment| !segment.text.is_empty())
-            .try_for_each(|segment| {
-                let class = match segment.highlight {
-                    Highlight::Qualifier => self.colors.qualifier.as_deref(),
-                    Highlight::PrimitiveType => self.colors.primitive_type.as_deref(),
-                    Highlight::UserDefinedType => self.colors.user_defined_type.as_deref(),
-                    Highlight::Ident => self.colors.identifier.as_deref(),
-                    Highlight::Number => self.colors.number.as_deref(),
-                    Highlight::QuasiKeyword => self.colors.quasi_keyword.as_deref(),
-                    _ => None,
-                };
-
-                if let Some(class_name) = class {
-                    write!(
-                        dst,
-                        r#"<span class="{}">{}</span>"#,
-                        html_escape::encode_quoted_attribute(class_name),
-                        html_escape::encode_text(&segment.text)
-                    )
-                } else {
-                    write!(dst, "{}", html_escape::encode_text(&segment.text))
+        let mut open_groups: &[Group] = &[];
+        for segment in text.0.iter().filter(|segment| !segment.text.is_empty()) {
+            let common = open_groups
+                .iter()
+                .zip(&segment.groups)
+                .take_while(|(open, group)| open == group)
+                .count();
+            for _ in common..open_groups.len() {
+                dst.write_str("</span>")?;
+            }
+            for group in &segment.groups[common..] {
+                match group {
+                    Group::Level(index) => write!(dst, r#"<span data-level="{index}">"#)?,
+                    Group::Parameter(index) => write!(dst, r#"<span data-parameter="{index}">"#)?,
                 }
-            })
+            }
+            open_groups = &segment.groups;
+            self.format_segment(dst, segment)?;
+        }
+        for _ in open_groups {
+            dst.write_str("</span>")?;
+        }
+        Ok(())
+    }
+}
+
+impl HtmlFormatter {
+    /// Writes a single segment, wrapped in elements for its class and style.
+    fn format_segment(
+        &self,
+        dst: &mut impl core::fmt::Write,
+        segment: &HighlightedTextSegment,
+    ) -> core::fmt::Result {
+        let class = match segment.highlight {
+            Highlight::Qualifier => self.colors.qualifier.as_deref(),
+            Highlight::PrimitiveType => self.colors.primitive_type.as_deref(),
+            Highlight::UserDefinedType => self.colors.user_defined_type.as_deref(),
+            Highlight::Ident => self.colors.identifier.as_deref(),
+            Highlight::Number => self.colors.number.as_deref(),
+            Highlight::QuasiKeyword => self.colors.quasi_keyword.as_deref(),
+            _ => None,
+        };
+
+        let style = self.colors.styles.style_for_highlight(segment.highlight);
+        let tags = [
+            (style.bold, "b"),
+            (style.italic, "i"),
+            (style.underline, "u"),
+        ];
+        if let Some(class_name) = class {
+            write!(
+                dst,
+                r#"<span class="{}">"#,
+                html_escape::encode_quoted_attribute(class_name)
+            )?;
+        }
+        for (_, tag) in tags.iter().filter(|(enabled, _)| *enabled) {
+            write!(dst, "<{tag}>")?;
+        }
+        write!(dst, "{}", html_escape::encode_text(&segment.text))?;
+        for (_, tag) in tags.iter().rev().filter(|(enabled, _)| *enabled) {
+            write!(dst, "</{tag}>")?;
+        }
+        if class.is_some() {
+            dst.write_str("</span>")?;
+        }
+        Ok(())
     }
 }
 
@@ -136,4 +213,67 @@ id
 "#
         );
     }
+
+    #[test]
+    fn test_html_formatter_groups() {
+        let formatter = HtmlFormatter::new(ClassMapping::new(
+            None,
+            None,
+            None,
+            Some("ident".to_string()),
+            None,
+            None,
+        ));
+        let mut text = HighlightedText(vec![
+            HighlightedTextSegment::new("a function that takes (", Highlight::None),
+            HighlightedTextSegment::new("x", Highlight::Ident),
+            HighlightedTextSegment::new(" and ", Highlight::None),
+            HighlightedTextSegment::new("y", Highlight::Ident),
+            HighlightedTextSegment::new(")", Highlight::None),
+            HighlightedTextSegment::new(" returning void", Highlight::None),
+        ]);
+        text[0].groups = vec![Group::Level(0)];
+        text[1].groups = vec![Group::Level(0), Group::Parameter(0)];
+        text[2].groups = vec![Group::Level(0)];
+        text[3].groups = vec![Group::Level(0), Group::Parameter(1)];
+        text[4].groups = vec![Group::Level(0)];
+
+        let mut output = String::new();
+        formatter.format(&mut output, &text).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"<span data-level="0">a function that takes ("#,
+                r#"<span data-parameter="0"><span class="ident">x</span></span> and "#,
+                r#"<span data-parameter="1"><span class="ident">y</span></span>)</span>"#,
+                " returning void",
+            )
+        );
+    }
+
+    #[test]
+    fn test_html_formatter_styles() {
+        let mut mapping =
+            ClassMapping::new(None, None, None, Some("ident".to_string()), None, None);
+        mapping.set_style("ident", true, true, false).unwrap();
+        mapping
+            .set_style("quasi_keyword", false, false, true)
+            .unwrap();
+        assert!(mapping.set_style("none", true, false, false).is_err());
+        assert!(mapping.set_style("bogus", true, false, false).is_err());
+
+        let text = HighlightedText(vec![
+            HighlightedTextSegment::new("x", Highlight::Ident),
+            HighlightedTextSegment::new(" ", Highlight::None),
+            HighlightedTextSegment::new("returning", Highlight::QuasiKeyword),
+        ]);
+        let mut output = String::new();
+        HtmlFormatter::new(mapping)
+            .format(&mut output, &text)
+            .unwrap();
+        assert_eq!(
+            output,
+            r#"<span class="ident"><b><i>x</i></b></span> <u>returning</u>"#
+        );
+    }
 }
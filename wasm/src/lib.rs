@@ -15,43 +15,346 @@
 
 use std::fmt::Write;
 
-use c2e::{ast::Declaration, chumsky::Parser};
+use c2e::{
+    ast::Declaration,
+    builtins::Profile,
+    chumsky::Parser,
+    color::{
+        HighlightedText,
+        fmt::{JsonFormatter, MarkdownFormatter, SsmlFormatter},
+    },
+    explainer::{DiagramStyle, Options, draw_diagram, explain_difference},
+    layout::{DataModel, describe_layout},
+    lint::lint,
+    parser::State,
+    transpile::Target,
+};
 use fmt::HtmlFormatter;
 use wasm_bindgen::prelude::*;
 
 mod fmt;
 
+/// Explanation of the declarations which parsed successfully, along with the errors for those
+/// which didn't.
+#[derive(Debug, Clone)]
+#[wasm_bindgen(getter_with_clone)]
+pub struct PartialExplanation {
+    pub html: String,
+    pub errors: Vec<String>,
+}
+
+/// Options for explaining declarations, which start out as the defaults and are changed with
+/// setters, so they can be combined, e.g. terse explanations in Spanish.
+#[derive(Debug, Clone, Default)]
+#[wasm_bindgen]
+pub struct ExplainOptions {
+    state: State,
+    options: Options,
+}
+
+#[wasm_bindgen]
+impl ExplainOptions {
+    /// Creates the default options.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the builtin typedefs of the profile with the given name (e.g. `"posix"` or
+    /// `"windows"`) available in addition to the default ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no profile with the given name.
+    #[wasm_bindgen(js_name = "addProfile")]
+    pub fn add_profile(&mut self, name: &str) -> Result<(), String> {
+        let profile: Profile = name
+            .parse()
+            .map_err(|_| format!("unknown profile '{name}'"))?;
+        self.state = self.state.clone().with_profile(profile);
+        Ok(())
+    }
+
+    /// Sets how much detail to include in explanations (`"terse"`, `"normal"`, or `"verbose"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the verbosity is invalid.
+    #[wasm_bindgen(js_name = "setVerbosity")]
+    pub fn set_verbosity(&mut self, verbosity: &str) -> Result<(), String> {
+        self.options.verbosity = verbosity
+            .parse()
+            .map_err(|_| format!("unknown verbosity '{verbosity}'"))?;
+        Ok(())
+    }
+
+    /// Sets the language to explain declarations in, by its ISO 639-1 code (`"en"` or `"es"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the language isn't supported.
+    #[wasm_bindgen(js_name = "setLanguage")]
+    pub fn set_language(&mut self, language: &str) -> Result<(), String> {
+        self.options.language = language
+            .parse()
+            .map_err(|_| format!("unknown language '{language}'"))?;
+        Ok(())
+    }
+
+    /// Sets whether to wrap the explanation of each declarator level and function parameter in
+    /// a `<span>` element with a `data-level` or `data-parameter` attribute.
+    #[wasm_bindgen(js_name = "setGroups")]
+    pub fn set_groups(&mut self, groups: bool) {
+        self.options.groups = groups;
+    }
+}
+
 /// Explain the given C source code declaration.
 #[wasm_bindgen]
-pub fn explain(formatter: &HtmlFormatter, src: &str) -> Result<String, Vec<String>> {
+pub fn explain(
+    formatter: &HtmlFormatter,
+    src: &str,
+    options: &ExplainOptions,
+) -> Result<String, Vec<String>> {
+    let explanation = explain_partial(formatter, src, options);
+    if explanation.errors.is_empty() {
+        Ok(explanation.html)
+    } else {
+        Err(explanation.errors)
+    }
+}
+
+/// Explain the valid declarations in the given C source code, and report errors for the rest.
+#[wasm_bindgen]
+pub fn explain_partial(
+    formatter: &HtmlFormatter,
+    src: &str,
+    options: &ExplainOptions,
+) -> PartialExplanation {
+    let (decls, errs) = c2e::parser::parser()
+        .parse_with_state(src, &mut options.state.clone())
+        .into_output_errors();
+    PartialExplanation {
+        html: explain_declarations(
+            formatter,
+            decls.as_deref().unwrap_or_default(),
+            &options.options,
+        ),
+        errors: messages(errs),
+    }
+}
+
+/// Describe the sizes and alignments of the types declared by the given C source code under the
+/// given data model (`"ILP32"`, `"LP64"`, or `"LLP64"`), one declaration per line.
+#[wasm_bindgen]
+pub fn describe_sizes(src: &str, model: &str) -> Result<String, Vec<String>> {
+    let model: DataModel = model
+        .to_uppercase()
+        .parse()
+        .map_err(|_| vec![format!("unknown data model '{model}'")])?;
+    let decls = parse(src)?;
+    let descriptions: Vec<String> = decls
+        .iter()
+        .map(|decl| describe_layout(decl, model))
+        .collect::<Result<_, _>>()
+        .map_err(|err| vec![err.to_string()])?;
+    Ok(descriptions.join("\n"))
+}
+
+/// Explain how two C source code declarations differ.
+#[wasm_bindgen]
+pub fn explain_differences(
+    formatter: &HtmlFormatter,
+    a: &str,
+    b: &str,
+) -> Result<String, Vec<String>> {
+    let parse_one = |src| {
+        parse(src).and_then(|mut decls| match decls.len() {
+            1 => Ok(decls.remove(0)),
+            _ => Err(vec![format!("expected exactly one declaration in '{src}'")]),
+        })
+    };
+    let (a, b) = (parse_one(a)?, parse_one(b)?);
+    Ok(explain_difference(&a, &b, &Options::default()).format_to_string(formatter))
+}
+
+/// Report suspicious declarations in the given C source code, each with a suggestion for fixing
+/// it, e.g. "at 0..17: `const` on a return type has no effect; remove `const` from the return
+/// type". Declarations which don't parse are ignored.
+#[wasm_bindgen]
+pub fn lint_declarations(src: &str) -> Vec<String> {
+    let (decls, _) =
+        c2e::parser::parse_translation_unit(src, &mut State::default()).into_output_errors();
+    lint(src, decls.as_deref().unwrap_or_default())
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Generate example code showing how to use the identifiers declared by the given C source code,
+/// with a blank line between the examples for each declaration.
+#[wasm_bindgen]
+pub fn generate_usage(src: &str) -> Result<String, Vec<String>> {
+    let decls = parse(src)?;
+    let examples: Vec<String> = decls.iter().map(c2e::usage::generate_usage).collect();
+    Ok(examples.join("\n\n"))
+}
+
+/// Translate declarations to Rust's foreign function interface, with a blank line between the
+/// translations of each declaration.
+#[wasm_bindgen]
+pub fn translate_to_rust(src: &str) -> Result<String, Vec<String>> {
+    translate_to(src, "rust")
+}
+
+/// Translate declarations to the given language (`"rust"`, `"go"`, or `"zig"`), with a blank line
+/// between the translations of each declaration.
+#[wasm_bindgen]
+pub fn translate_to(src: &str, language: &str) -> Result<String, Vec<String>> {
+    let target: Target = language
+        .parse()
+        .map_err(|_| vec![format!("unknown language '{language}'")])?;
+    let decls = parse(src)?;
+    let translations: Vec<String> = decls.iter().map(|decl| target.translate(decl)).collect();
+    Ok(translations.join("\n\n"))
+}
+
+/// Explain the given C source code declarations as SSML, for reading them aloud with a speech
+/// synthesizer or screen reader.
+#[wasm_bindgen]
+pub fn explain_as_ssml(src: &str) -> Result<String, Vec<String>> {
+    let decls = parse(src)?;
+    let mut text = HighlightedText::new();
+    for (i, decl) in decls.iter().enumerate() {
+        if i > 0 {
+            text.push_str(";\n");
+        }
+        text.extend(c2e::explainer::explain_declaration(decl).0);
+    }
+    Ok(text.format_to_string(&SsmlFormatter::new()))
+}
+
+/// Explain the given C source code declarations as Markdown, with the source code in a fenced code
+/// block followed by the explanations, for pasting into issues, chat messages, or notes.
+#[wasm_bindgen]
+pub fn explain_as_markdown(src: &str) -> Result<String, Vec<String>> {
+    let decls = parse(src)?;
+    let mut text = HighlightedText::new();
+    for (i, decl) in decls.iter().enumerate() {
+        if i > 0 {
+            text.push_str(";\n");
+        }
+        text.extend(c2e::explainer::explain_declaration(decl).0);
+    }
+    Ok(text.format_to_string(&MarkdownFormatter::with_declaration(src.trim())))
+}
+
+/// Explain the given C source code declarations as a JSON array of highlighted segments, for
+/// applying custom styling.
+#[wasm_bindgen]
+pub fn explain_as_json(src: &str) -> Result<String, Vec<String>> {
+    let decls = parse(src)?;
+    let mut text = HighlightedText::new();
+    for (i, decl) in decls.iter().enumerate() {
+        if i > 0 {
+            text.push_str(";\n");
+        }
+        text.extend(c2e::explainer::explain_declaration(decl).0);
+    }
+    Ok(text.format_to_string(&JsonFormatter::new()))
+}
+
+/// Explain the given C source code declarations using the built-in formatter with the given name,
+/// e.g. `"markdown"` or `"bbcode"`, one declaration per line.
+#[wasm_bindgen]
+pub fn explain_in_format(src: &str, format: &str) -> Result<String, Vec<String>> {
+    let formatter = c2e::color::fmt::by_name(format)
+        .ok_or_else(|| vec![format!("unknown format '{format}'")])?;
+    let decls = parse(src)?;
+    let explanations: Vec<String> = decls
+        .iter()
+        .map(|decl| c2e::explainer::explain_declaration(decl).format_to_string(&*formatter))
+        .collect();
+    Ok(explanations.join("\n"))
+}
+
+/// Draw a diagram of the structure of each of the given C source code declarations in the given
+/// style (`"unicode"` or `"ascii"`), separated by blank lines. The diagrams are meant to be shown
+/// in a `<pre>` element.
+#[wasm_bindgen]
+pub fn diagram(formatter: &HtmlFormatter, src: &str, style: &str) -> Result<String, Vec<String>> {
+    let style: DiagramStyle = style
+        .parse()
+        .map_err(|_| vec![format!("unknown diagram style '{style}'")])?;
+    let decls = parse(src)?;
+    let diagrams: Vec<String> = decls
+        .iter()
+        .map(|decl| draw_diagram(decl, style, &Options::default()).format_to_string(formatter))
+        .collect();
+    Ok(diagrams.join("\n\n"))
+}
+
+/// Describe the types of the given C source code declarations in compact notation, one per line,
+/// e.g. `p: ptr -> array[10] -> int`.
+#[wasm_bindgen]
+pub fn compact_notation(src: &str) -> Result<String, Vec<String>> {
+    let decls = parse(src)?;
+    let notations: Vec<String> = decls.iter().map(c2e::notation::arrow_notation).collect();
+    Ok(notations.join("\n"))
+}
+
+/// Compose a C declaration from an English description of a type, like
+/// "pointer to array of 10 const char".
+#[wasm_bindgen]
+pub fn english_to_c(phrase: &str) -> Result<String, Vec<String>> {
+    c2e::en2c::english_to_c(phrase).map_err(|err| vec![err.to_string()])
+}
+
+/// Returns the messages of the given errors.
+fn messages(errs: impl IntoIterator<Item = impl ToString>) -> Vec<String> {
+    errs.into_iter().map(|err| err.to_string()).collect()
+}
+
+/// Parses the given C source code, returning the messages of the errors if it's invalid.
+fn parse(src: &str) -> Result<Vec<Declaration<'_>>, Vec<String>> {
     c2e::parser::parser()
         .parse(src)
         .into_result()
-        .map(|decls| explain_declarations(formatter, &decls))
-        .map_err(|errs| errs.into_iter().map(|err| err.to_string()).collect())
+        .map_err(messages)
 }
 
-fn explain_declarations(formatter: &HtmlFormatter, decls: &[Declaration<'_>]) -> String {
+fn explain_declarations(
+    formatter: &HtmlFormatter,
+    decls: &[Declaration<'_>],
+    options: &Options,
+) -> String {
     match decls {
         [] => String::new(),
-        [decl] => explain_to_html(formatter, decl),
+        [decl] => explain_to_html(formatter, decl, options),
         [decls @ .., last] => {
             let mut s = String::new();
             for decl in decls {
-                write!(&mut s, "{};\n\n", explain_to_html(formatter, decl)).unwrap();
+                write!(&mut s, "{};\n\n", explain_to_html(formatter, decl, options)).unwrap();
             }
-            write!(&mut s, "{};", explain_to_html(formatter, last)).unwrap();
+            write!(&mut s, "{};", explain_to_html(formatter, last, options)).unwrap();
             s
         }
     }
 }
 
-fn explain_to_html(formatter: &HtmlFormatter, declaration: &Declaration<'_>) -> String {
-    c2e::explainer::explain_declaration(declaration).format_to_string(formatter)
+fn explain_to_html(
+    formatter: &HtmlFormatter,
+    declaration: &Declaration<'_>,
+    options: &Options,
+) -> String {
+    c2e::explainer::explain_declaration_with_options(declaration, options)
+        .format_to_string(formatter)
 }
 
 #[cfg(test)]
 mod tests {
+    use c2e::color::StyleMap;
+
     use crate::fmt::ClassMapping;
 
     use super::*;
@@ -66,19 +369,20 @@ mod tests {
             identifier: Some("i".to_string()),
             number: Some("n".to_string()),
             quasi_keyword: Some("qk".to_string()),
+            styles: StyleMap::PLAIN,
         };
         HtmlFormatter::new(mapping)
     }
 
     #[test]
     fn explain_empty() {
-        let output = explain(&get_formatter(), "").unwrap();
+        let output = explain(&get_formatter(), "", &ExplainOptions::new()).unwrap();
         assert_eq!(output, "");
     }
 
     #[test]
     fn explain_success_single() {
-        let output = explain(&get_formatter(), "int main()").unwrap();
+        let output = explain(&get_formatter(), "int main()", &ExplainOptions::new()).unwrap();
         assert_eq!(
             output,
             r#"a <span class="qk">function</span> named <span class="i">main</span> that takes no parameters and returns an <span class="p">int</span>"#
@@ -87,7 +391,12 @@ mod tests {
 
     #[test]
     fn explain_success_multiple() {
-        let output = explain(&get_formatter(), "int main(); int foo(int a);").unwrap();
+        let output = explain(
+            &get_formatter(),
+            "int main(); int foo(int a);",
+            &ExplainOptions::new(),
+        )
+        .unwrap();
         assert_eq!(
             output,
             r#"a <span class="qk">function</span> named <span class="i">main</span> that takes no parameters and returns an <span class="p">int</span>;
@@ -96,9 +405,249 @@ a <span class="qk">function</span> named <span class="i">foo</span> that takes (
         );
     }
 
+    #[test]
+    fn explain_with_profile() {
+        let mut options = ExplainOptions::new();
+        assert!(explain(&get_formatter(), "DWORD flags", &options).is_err());
+        options.add_profile("windows").unwrap();
+        let output = explain(&get_formatter(), "DWORD flags", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"a <span class="u">DWORD</span> named <span class="i">flags</span>"#
+        );
+
+        assert_eq!(
+            options.add_profile("plan9").unwrap_err(),
+            "unknown profile 'plan9'"
+        );
+    }
+
+    #[test]
+    fn explain_verbosity() {
+        let mut options = ExplainOptions::new();
+        options.set_verbosity("terse").unwrap();
+        let output = explain(&get_formatter(), "int *p", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"<span class="qk">pointer</span> to <span class="p">int</span>"#
+        );
+
+        assert_eq!(
+            options.set_verbosity("chatty").unwrap_err(),
+            "unknown verbosity 'chatty'"
+        );
+    }
+
+    #[test]
+    fn explain_formats() {
+        assert_eq!(
+            explain_in_format("int *p; char c", "bbcode").unwrap(),
+            "a [color=#008700]pointer[/color] named [b][color=#d70000]p[/color][/b] to an \
+             [color=#af8700]int[/color]\na [color=#af8700]char[/color] named \
+             [b][color=#d70000]c[/color][/b]"
+        );
+        assert_eq!(
+            explain_in_format("int n", "rtf").unwrap_err(),
+            vec!["unknown format 'rtf'".to_string()]
+        );
+    }
+
+    #[test]
+    fn diagrams() {
+        assert_eq!(
+            diagram(&get_formatter(), "char *s", "ascii").unwrap(),
+            r#"char *s
+      ^ <span class="i">s</span> is
+     ^- a <span class="qk">pointer</span> to
+^^^^--- a <span class="p">char</span>"#
+        );
+        assert_eq!(
+            diagram(&get_formatter(), "int n", "boxes").unwrap_err(),
+            vec!["unknown diagram style 'boxes'".to_string()]
+        );
+    }
+
+    #[test]
+    fn explain_groups() {
+        let mut options = ExplainOptions::new();
+        options.set_groups(true);
+        let output = explain(&get_formatter(), "int (*fp)(char c)", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"<span data-level="0">a <span class="qk">pointer</span> named <span class="i">fp</span> to </span><span data-level="1">a <span class="qk">function</span> that takes (<span data-parameter="0">a <span class="p">char</span> named <span class="i">c</span></span>) and returns </span>an <span class="p">int</span>"#
+        );
+    }
+
+    #[test]
+    fn explain_language() {
+        let mut options = ExplainOptions::new();
+        options.set_language("es").unwrap();
+        let output = explain(&get_formatter(), "int *p", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"un <span class="qk">puntero</span> llamado <span class="i">p</span> a un <span class="p">int</span>"#
+        );
+
+        // Options can be combined.
+        options.set_verbosity("terse").unwrap();
+        let output = explain(&get_formatter(), "int *p", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"<span class="qk">puntero</span> a <span class="p">int</span>"#
+        );
+
+        assert_eq!(
+            options.set_language("tlh").unwrap_err(),
+            "unknown language 'tlh'"
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(
+            describe_sizes("int *arr[10]; char c", "lp64").unwrap(),
+            "arr occupies 80 bytes and is aligned to 8 bytes on LP64\n\
+             c occupies 1 byte and is aligned to 1 byte on LP64"
+        );
+        assert_eq!(
+            describe_sizes("long l", "ILP32").unwrap(),
+            "l occupies 4 bytes and is aligned to 4 bytes on ILP32"
+        );
+        assert_eq!(
+            describe_sizes("void v", "lp64").unwrap_err(),
+            vec!["void is an incomplete type".to_string()]
+        );
+        assert_eq!(
+            describe_sizes("int x", "lp32").unwrap_err(),
+            vec!["unknown data model 'lp32'".to_string()]
+        );
+    }
+
+    #[test]
+    fn differences() {
+        assert_eq!(
+            explain_differences(&get_formatter(), "int *a[10]", "int (*a)[10]").unwrap(),
+            r#"the first is an <span class="qk">array</span> of <span class="qk">pointers</span>; the second is a <span class="qk">pointer</span> to an <span class="qk">array</span>"#
+        );
+        assert_eq!(
+            explain_differences(&get_formatter(), "int x; int y", "int z").unwrap_err(),
+            vec!["expected exactly one declaration in 'int x; int y'".to_string()]
+        );
+    }
+
+    #[test]
+    fn rust() {
+        assert_eq!(
+            translate_to_rust("int *p; typedef unsigned char byte").unwrap(),
+            "pub static mut p: *mut c_int;\n\npub type byte = c_uchar;"
+        );
+        assert!(translate_to_rust("int (").is_err());
+    }
+
+    #[test]
+    fn translate() {
+        assert_eq!(
+            translate_to("int *p; unsigned char buf[4]", "go").unwrap(),
+            "var p *C.int\n\nvar buf [4]C.uchar"
+        );
+        assert_eq!(
+            translate_to("int *p", "zig").unwrap(),
+            "pub extern var p: [*c]c_int;"
+        );
+        assert_eq!(
+            translate_to("int *p", "cobol").unwrap_err(),
+            vec!["unknown language 'cobol'"]
+        );
+    }
+
+    #[test]
+    fn ssml() {
+        assert_eq!(
+            explain_as_ssml("int x; char *s").unwrap(),
+            "<speak>an int named <emphasis>x</emphasis>;<break strength=\"weak\"/>a pointer named \
+             <emphasis>s</emphasis> to a char</speak>"
+        );
+        assert!(explain_as_ssml("int (").is_err());
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            explain_as_markdown("int x; char *s\n").unwrap(),
+            "```c\nint x; char *s\n```\n\nan `int` named `x`;\na pointer named `s` to a `char`"
+        );
+        assert!(explain_as_markdown("int (").is_err());
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            explain_as_json("int x; char c").unwrap(),
+            r#"[{"text":"an ","highlight":"none"},{"text":"int","highlight":"primitive_type"},{"text":" named ","highlight":"none"},{"text":"x","highlight":"ident"},{"text":";\n","highlight":"none"},{"text":"a ","highlight":"none"},{"text":"char","highlight":"primitive_type"},{"text":" named ","highlight":"none"},{"text":"c","highlight":"ident"}]"#
+        );
+        assert!(explain_as_json("int (").is_err());
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(
+            compact_notation("int (*p)[10]; const char *s").unwrap(),
+            "p: ptr -> array[10] -> int\ns: ptr -> const char"
+        );
+        assert!(compact_notation("int (").is_err());
+    }
+
+    #[test]
+    fn usage() {
+        assert_eq!(
+            generate_usage("int (*cmp)(const void *, const void *); void f(int n)").unwrap(),
+            "cmp = my_cmp;\nint result = cmp(a, b);\n\nf(n);"
+        );
+        assert!(generate_usage("int (").is_err());
+    }
+
+    #[test]
+    fn en2c() {
+        assert_eq!(
+            english_to_c("function taking int and char pointer returning void").unwrap(),
+            "void (int, char *)"
+        );
+        assert_eq!(
+            english_to_c("pointer int").unwrap_err(),
+            vec!["expected 'to', but found 'int'".to_string()]
+        );
+    }
+
+    #[test]
+    fn lints() {
+        assert_eq!(
+            lint_declarations("char* a, b; int x y;"),
+            vec![
+                "at 9..10: `b` is not a pointer, unlike `a`; write `*b` if it should also be a \
+                 pointer, or declare it separately"
+                    .to_string()
+            ]
+        );
+        assert!(lint_declarations("int *p;").is_empty());
+    }
+
+    #[test]
+    fn explain_partial_error() {
+        let output = explain_partial(
+            &get_formatter(),
+            "int x y; float z;",
+            &ExplainOptions::new(),
+        );
+        assert_eq!(
+            output.html,
+            r#"a <span class="p">float</span> named <span class="i">z</span>"#
+        );
+        assert_eq!(output.errors.len(), 1);
+    }
+
     #[test]
     fn explain_error() {
-        let output = explain(&get_formatter(), "int main(");
+        let output = explain(&get_formatter(), "int main(", &ExplainOptions::new());
         let errors = output.unwrap_err();
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("expected"));
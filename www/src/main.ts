@@ -1,5 +1,10 @@
 import "./style.css";
-import initExplainer, { ClassMapping, explain, HtmlFormatter } from "c2e-wasm";
+import initExplainer, {
+    ClassMapping,
+    explain_partial,
+    ExplainOptions,
+    HtmlFormatter,
+} from "c2e-wasm";
 
 const input = document.getElementById("input")! as HTMLTextAreaElement;
 const output = document.getElementById("output")!;
@@ -18,6 +23,15 @@ function showOutputHTML(html: string) {
     output.classList.remove(errorColorClass);
 }
 
+// Shows the explanations of the valid declarations followed by the errors for the rest
+function showPartialOutputHTML(html: string, errors: string[]) {
+    showOutputHTML(html);
+    const errorDiv = document.createElement("div");
+    errorDiv.classList.add(errorColorClass);
+    errorDiv.textContent = errors.join("\n");
+    output.append("\n\n", errorDiv);
+}
+
 function showError(text: string) {
     output.textContent = text;
     output.classList.add(errorColorClass);
@@ -58,6 +72,7 @@ Promise.race([initExplainer(), rejectAfter(wasmLoadTimeoutMS)])
         );
         // Create a formatter
         const formatter = new HtmlFormatter(mapping);
+        const options = new ExplainOptions();
 
         function processInput() {
             url.searchParams.set("code", input.value);
@@ -65,11 +80,15 @@ Promise.race([initExplainer(), rejectAfter(wasmLoadTimeoutMS)])
             if (input.value.trim() === "") {
                 return;
             }
-            try {
-                showOutputHTML(explain(formatter, input.value));
-            } catch (err) {
-                let errors = err as string[];
+            const explanation = explain_partial(formatter, input.value, options);
+            const { html, errors } = explanation;
+            explanation.free();
+            if (errors.length === 0) {
+                showOutputHTML(html);
+            } else if (html === "") {
                 showError(errors.join("\n"));
+            } else {
+                showPartialOutputHTML(html, errors);
             }
         }
 